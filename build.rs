@@ -1,12 +1,45 @@
 #[cfg(target_os = "dragonfly")]
 extern crate gcc;
 
-#[cfg(target_os = "dragonfly")]
+use std::env;
+use std::process::Command;
+
+// The minor version of the compiler, or None if it can't be told
+fn rustc_minor_version() -> Option<u32> {
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let output = match Command::new(rustc).arg("--version").output() {
+        Ok(output) => output,
+        Err(_) => return None,
+    };
+    let version = match String::from_utf8(output.stdout) {
+        Ok(version) => version,
+        Err(_) => return None,
+    };
+    // Like "rustc 1.36.0 (a53f9df32 2019-07-03)"
+    let mut pieces = version.split_whitespace().nth(1).unwrap_or("").split('.');
+    if pieces.next() != Some("1") {
+        return None;
+    }
+    pieces.next().and_then(|minor| minor.parse().ok())
+}
+
 fn main() {
+    // Older versions of Cargo take this as metadata and ignore it
+    println!("cargo:rustc-check-cfg=cfg(io_slice)");
+    // std::io::IoSlice and IoSliceMut were stabilized in Rust 1.36
+    if rustc_minor_version().map_or(false, |minor| minor >= 36) {
+        println!("cargo:rustc-cfg=io_slice");
+    }
+
+    build_errno();
+}
+
+#[cfg(target_os = "dragonfly")]
+fn build_errno() {
     gcc::Build::new()
         .file("src/errno_dragonfly.c")
         .compile("liberrno_dragonfly.a");
 }
 
 #[cfg(not(target_os = "dragonfly"))]
-fn main() {}
+fn build_errno() {}
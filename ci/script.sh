@@ -12,16 +12,16 @@ main() {
     IFS=';' read -ra TARGET_ARRAY <<< "$TARGET"
     for t in "${TARGET_ARRAY[@]}"; do
 	# Build debug and release targets
-	cross build --target $t
-	cross build --target $t --release
+	cross build --target $t $FEATURES
+	cross build --target $t --release $FEATURES
 
 	if [ ! -z $DISABLE_TESTS ]; then
 	    continue
 	fi
 
 	# Run tests on debug and release targets.
-	cross test --target $t
-	cross test --target $t --release
+	cross test --target $t $FEATURES
+	cross test --target $t --release $FEATURES
     done
 }
 
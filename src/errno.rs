@@ -1,7 +1,9 @@
 #[cfg(not(target_os = "dragonfly"))]
 use libc;
 use libc::c_int;
-use std::{fmt, io, error};
+use std::fmt;
+#[cfg(feature = "std")]
+use std::{io, error};
 use {Error, Result};
 
 pub use self::consts::*;
@@ -83,6 +85,16 @@ impl Errno {
             Ok(value)
         }
     }
+
+    /// Returns `Ok(())` if `code` is zero, or the error it holds otherwise.
+    /// This is for the functions, like most of pthreads, that return the
+    /// error number rather than setting `errno`.
+    pub(crate) fn result_from_code(code: c_int) -> Result<()> {
+        match code {
+            0 => Ok(()),
+            err => Err(Error::Sys(from_i32(err))),
+        }
+    }
 }
 
 /// The sentinel value indicates that a function failed and more detailed
@@ -107,6 +119,7 @@ impl ErrnoSentinel for *mut libc::c_void {
     fn sentinel() -> Self { (-1 as isize) as *mut libc::c_void }
 }
 
+#[cfg(feature = "std")]
 impl error::Error for Errno {
     fn description(&self) -> &str {
         self.desc()
@@ -115,16 +128,66 @@ impl error::Error for Errno {
 
 impl fmt::Display for Errno {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}: {}", self, self.desc())
+        try!(write!(f, "{:?}: ", self));
+        write_strerror(f, *self)
     }
 }
 
+#[cfg(feature = "std")]
 impl From<Errno> for io::Error {
     fn from(err: Errno) -> Self {
         io::Error::from_raw_os_error(err as i32)
     }
 }
 
+#[cfg(feature = "std")]
+impl From<io::Error> for Errno {
+    /// Take the errno of an error from the OS, or else the one closest to its
+    /// kind, falling back to `EIO`.
+    fn from(err: io::Error) -> Self {
+        use std::io::ErrorKind::*;
+
+        if let Some(errno) = err.raw_os_error() {
+            return Errno::from_i32(errno);
+        }
+        match err.kind() {
+            NotFound => Errno::ENOENT,
+            PermissionDenied => Errno::EACCES,
+            ConnectionRefused => Errno::ECONNREFUSED,
+            ConnectionReset => Errno::ECONNRESET,
+            ConnectionAborted => Errno::ECONNABORTED,
+            NotConnected => Errno::ENOTCONN,
+            AddrInUse => Errno::EADDRINUSE,
+            AddrNotAvailable => Errno::EADDRNOTAVAIL,
+            BrokenPipe => Errno::EPIPE,
+            AlreadyExists => Errno::EEXIST,
+            WouldBlock => Errno::EAGAIN,
+            InvalidInput => Errno::EINVAL,
+            InvalidData => Errno::EINVAL,
+            TimedOut => Errno::ETIMEDOUT,
+            Interrupted => Errno::EINTR,
+            _ => Errno::EIO,
+        }
+    }
+}
+
+// Write the message of the C library for `errno`, which may be translated to
+// the locale, or `desc` if it has none
+fn write_strerror(f: &mut fmt::Formatter, errno: Errno) -> fmt::Result {
+    use std::ffi::CStr;
+
+    if errno == Errno::UnknownErrno {
+        return f.write_str(desc(errno));
+    }
+    let mut buf = [0 as ::libc::c_char; 256];
+    let res = unsafe { ::libc::strerror_r(errno as c_int, buf.as_mut_ptr(), buf.len()) };
+    if res != 0 {
+        return f.write_str(desc(errno));
+    }
+
+    ::fmt_lossy(f, unsafe { CStr::from_ptr(buf.as_ptr()) }.to_bytes())
+}
+
 fn last() -> Errno {
     Errno::from_i32(errno())
 }
@@ -534,6 +597,7 @@ mod consts {
     use libc;
 
     #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     #[repr(i32)]
     pub enum Errno {
         UnknownErrno    = 0,
@@ -822,6 +886,7 @@ mod consts {
     use libc;
 
     #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     #[repr(i32)]
     pub enum Errno {
         UnknownErrno    = 0,
@@ -1059,6 +1124,7 @@ mod consts {
     use libc;
 
     #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     #[repr(i32)]
     pub enum Errno {
         UnknownErrno    = 0,
@@ -1277,6 +1343,7 @@ mod consts {
     use libc;
 
     #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     #[repr(i32)]
     pub enum Errno {
         UnknownErrno    = 0,
@@ -1502,6 +1569,7 @@ mod consts {
     use libc;
 
     #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     #[repr(i32)]
     pub enum Errno {
         UnknownErrno    = 0,
@@ -1708,6 +1776,7 @@ mod consts {
     use libc;
 
     #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     #[repr(i32)]
     pub enum Errno {
         UnknownErrno    = 0,
@@ -1,13 +1,20 @@
 use {Error, Result, NixPath};
 use errno::Errno;
-use libc::{self, c_int, c_uint, c_char, size_t, ssize_t};
+use libc::{self, c_int, c_uint};
+#[cfg(feature = "std")]
+use libc::{c_char, size_t, ssize_t};
 use sys::stat::Mode;
-use std::os::unix::io::RawFd;
-use std::ffi::OsStr;
-use std::os::unix::ffi::OsStrExt;
+use unistd::Fd;
+use std::os::unix::io::{FromRawFd, RawFd};
+#[cfg(feature = "std")]
+use std::ffi::{OsStr, OsString};
+#[cfg(feature = "std")]
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
 
 #[cfg(any(target_os = "android", target_os = "linux"))]
 use sys::uio::IoVec;  // For vmsplice
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+use std::ptr;
 
 libc_bitflags!{
     pub struct AtFlags: c_int {
@@ -145,6 +152,11 @@ pub fn open<P: ?Sized + NixPath>(path: &P, oflag: OFlag, mode: Mode) -> Result<R
     Errno::result(fd)
 }
 
+/// Like [`open`](fn.open.html), but returns an owned file descriptor.
+pub fn open_owned<P: ?Sized + NixPath>(path: &P, oflag: OFlag, mode: Mode) -> Result<Fd> {
+    open(path, oflag, mode).map(|fd| unsafe { Fd::from_raw_fd(fd) })
+}
+
 pub fn openat<P: ?Sized + NixPath>(dirfd: RawFd, path: &P, oflag: OFlag, mode: Mode) -> Result<RawFd> {
     let fd = try!(path.with_nix_path(|cstr| {
         unsafe { libc::openat(dirfd, cstr.as_ptr(), oflag.bits(), mode.bits() as c_uint) }
@@ -152,6 +164,66 @@ pub fn openat<P: ?Sized + NixPath>(dirfd: RawFd, path: &P, oflag: OFlag, mode: M
     Errno::result(fd)
 }
 
+/// Like [`openat`](fn.openat.html), but returns an owned file descriptor.
+pub fn openat_owned<P: ?Sized + NixPath>(dirfd: RawFd, path: &P, oflag: OFlag, mode: Mode)
+                                         -> Result<Fd> {
+    openat(dirfd, path, oflag, mode).map(|fd| unsafe { Fd::from_raw_fd(fd) })
+}
+
+/// Flags which are meaningful in combination with `O_PATH`.
+///
+/// The kernel silently ignores any others, which would hide mistakes like expecting an `O_PATH`
+/// descriptor to be readable.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn o_path_flags(oflag: OFlag) -> Result<OFlag> {
+    let allowed = OFlag::O_CLOEXEC | OFlag::O_DIRECTORY | OFlag::O_NOFOLLOW;
+    if allowed.contains(oflag) {
+        Ok(oflag | OFlag::O_PATH)
+    } else {
+        Err(Error::invalid_argument())
+    }
+}
+
+/// Obtain a file descriptor which only refers to a location in the filesystem (see
+/// [open(2)](http://man7.org/linux/man-pages/man2/open.2.html), `O_PATH`).
+///
+/// The file itself is not opened, so no permissions on it are required, and symbolic links may
+/// be opened themselves when `O_NOFOLLOW` is given. Only `O_CLOEXEC`, `O_DIRECTORY` and
+/// `O_NOFOLLOW` may be passed in `oflag`; anything else fails with `EINVAL` rather than being
+/// silently dropped by the kernel.
+///
+/// Descriptors obtained this way may be used with:
+///
+/// * the `dirfd` argument of the `*at` functions, such as
+///   [`openat`](fn.openat.html), [`openat_path`](fn.openat_path.html),
+///   [`readlinkat`](fn.readlinkat.html) and [`fstatat`](../sys/stat/fn.fstatat.html);
+/// * `readlinkat(fd, "", ..)` to read the target of a symbolic link opened with `O_NOFOLLOW`;
+/// * `fstatat(fd, "", AtFlags::AT_EMPTY_PATH)` and [`fstat`](../sys/stat/fn.fstat.html)
+///   (the latter since Linux 3.6);
+/// * [`fchdir`](../unistd/fn.fchdir.html) if the descriptor refers to a directory;
+/// * [`close`](../unistd/fn.close.html), [`dup`](../unistd/fn.dup.html) and its variants, and
+///   the `F_GETFD`, `F_SETFD` and `F_GETFL` operations of [`fcntl`](fn.fcntl.html).
+///
+/// All other operations, such as `read`, `write`, `fchmod` or `ioctl`, fail with `EBADF`.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn open_path<P: ?Sized + NixPath>(path: &P, oflag: OFlag) -> Result<RawFd> {
+    let oflag = try!(o_path_flags(oflag));
+    open(path, oflag, Mode::empty())
+}
+
+/// Obtain a file descriptor which only refers to a location in the filesystem, relative to a
+/// directory file descriptor.
+///
+/// `dirfd` may itself have been obtained with `O_PATH`, which allows walking a directory tree one
+/// component at a time without ever following a path that could be swapped out underneath. See
+/// [`open_path`](fn.open_path.html) for the operations the returned descriptor supports.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn openat_path<P: ?Sized + NixPath>(dirfd: RawFd, path: &P, oflag: OFlag) -> Result<RawFd> {
+    let oflag = try!(o_path_flags(oflag));
+    openat(dirfd, path, oflag, Mode::empty())
+}
+
+#[cfg(feature = "std")]
 fn wrap_readlink_result(buffer: &mut[u8], res: ssize_t) -> Result<&OsStr> {
     match Errno::result(res) {
         Err(err) => Err(err),
@@ -165,6 +237,13 @@ fn wrap_readlink_result(buffer: &mut[u8], res: ssize_t) -> Result<&OsStr> {
     }
 }
 
+/// Read the target of a symbolic link into a caller-provided buffer (see
+/// [readlink(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/readlink.html)).
+///
+/// No allocation is performed. If the target doesn't fit in `buffer` it would be silently
+/// truncated by the kernel, so this fails with `ENAMETOOLONG` instead. Use
+/// [`readlink_owned`](fn.readlink_owned.html) when the length of the target isn't known.
+#[cfg(feature = "std")]
 pub fn readlink<'a, P: ?Sized + NixPath>(path: &P, buffer: &'a mut [u8]) -> Result<&'a OsStr> {
     let res = try!(path.with_nix_path(|cstr| {
         unsafe { libc::readlink(cstr.as_ptr(), buffer.as_mut_ptr() as *mut c_char, buffer.len() as size_t) }
@@ -174,6 +253,12 @@ pub fn readlink<'a, P: ?Sized + NixPath>(path: &P, buffer: &'a mut [u8]) -> Resu
 }
 
 
+/// Read the target of a symbolic link relative to a directory file descriptor into a
+/// caller-provided buffer (see
+/// [readlinkat(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/readlinkat.html)).
+///
+/// Behaves like [`readlink`](fn.readlink.html) otherwise.
+#[cfg(feature = "std")]
 pub fn readlinkat<'a, P: ?Sized + NixPath>(dirfd: RawFd, path: &P, buffer: &'a mut [u8]) -> Result<&'a OsStr> {
     let res = try!(path.with_nix_path(|cstr| {
         unsafe { libc::readlinkat(dirfd, cstr.as_ptr(), buffer.as_mut_ptr() as *mut c_char, buffer.len() as size_t) }
@@ -182,6 +267,53 @@ pub fn readlinkat<'a, P: ?Sized + NixPath>(dirfd: RawFd, path: &P, buffer: &'a m
     wrap_readlink_result(buffer, res)
 }
 
+/// Calls `f` with successively larger buffers, starting from `len` bytes, until the link target
+/// fits.
+#[cfg(feature = "std")]
+fn readlink_grow<F>(len: usize, mut f: F) -> Result<OsString>
+    where F: FnMut(*mut c_char, size_t) -> ssize_t
+{
+    let mut buf = Vec::<u8>::with_capacity(len);
+    loop {
+        let cap = buf.capacity();
+        let len = try!(Errno::result(f(buf.as_mut_ptr() as *mut c_char, cap as size_t))) as usize;
+        if len < cap {
+            unsafe { buf.set_len(len) };
+            buf.shrink_to_fit();
+            return Ok(OsString::from_vec(buf));
+        }
+        // The target may have been truncated, so retry with twice the space.
+        match cap.checked_mul(2) {
+            Some(new_cap) => buf.reserve_exact(new_cap),
+            None => return Err(Error::Sys(Errno::ENAMETOOLONG)),
+        }
+    }
+}
+
+/// Read the target of a symbolic link, allocating as much space as needed.
+///
+/// Starts with a `PATH_MAX`-sized buffer and retries with a growing buffer whenever the target
+/// might have been truncated, so arbitrarily long targets are returned in full.
+#[cfg(feature = "std")]
+pub fn readlink_owned<P: ?Sized + NixPath>(path: &P) -> Result<OsString> {
+    try!(path.with_nix_path(|cstr| {
+        readlink_grow(libc::PATH_MAX as usize,
+                      |buf, len| unsafe { libc::readlink(cstr.as_ptr(), buf, len) })
+    }))
+}
+
+/// Read the target of a symbolic link relative to a directory file descriptor, allocating as
+/// much space as needed.
+///
+/// Behaves like [`readlink_owned`](fn.readlink_owned.html) otherwise.
+#[cfg(feature = "std")]
+pub fn readlinkat_owned<P: ?Sized + NixPath>(dirfd: RawFd, path: &P) -> Result<OsString> {
+    try!(path.with_nix_path(|cstr| {
+        readlink_grow(libc::PATH_MAX as usize,
+                      |buf, len| unsafe { libc::readlinkat(dirfd, cstr.as_ptr(), buf, len) })
+    }))
+}
+
 #[cfg(any(target_os = "android", target_os = "linux"))]
 libc_bitflags!(
     /// Additional flags for file sealing, which allows for limiting operations on a file.
@@ -225,8 +357,19 @@ pub enum FcntlArg<'a> {
     F_ADD_SEALS(SealFlag),
     #[cfg(any(target_os = "android", target_os = "linux"))]
     F_GET_SEALS,
+    /// Flush all data of the file to the drive, and ask the drive to write its
+    /// cache to permanent storage.  `fsync` doesn't do the latter on macOS and
+    /// iOS, so the data may still be lost on a power failure.
     #[cfg(any(target_os = "macos", target_os = "ios"))]
     F_FULLFSYNC,
+    /// Preallocate storage for the file, as described by the `fstore_t`.  Its
+    /// `fst_flags` are `F_ALLOCATECONTIG` and `F_ALLOCATEALL`, and its
+    /// `fst_posmode` is `F_PEOFPOSMODE`, to allocate from the end of the file,
+    /// or `F_VOLPOSMODE`, from the given offset on the volume.  On success,
+    /// `fst_bytesalloc` is set to the number of bytes allocated.  This doesn't
+    /// change the size of the file.
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    F_PREALLOCATE(&'a mut libc::fstore_t),
     #[cfg(any(target_os = "linux", target_os = "android"))]
     F_GETPIPE_SZ,
     #[cfg(any(target_os = "linux", target_os = "android"))]
@@ -255,6 +398,8 @@ pub fn fcntl(fd: RawFd, arg: FcntlArg) -> Result<c_int> {
             F_GET_SEALS => libc::fcntl(fd, libc::F_GET_SEALS),
             #[cfg(any(target_os = "macos", target_os = "ios"))]
             F_FULLFSYNC => libc::fcntl(fd, libc::F_FULLFSYNC),
+            #[cfg(any(target_os = "macos", target_os = "ios"))]
+            F_PREALLOCATE(fstore) => libc::fcntl(fd, libc::F_PREALLOCATE, fstore),
             #[cfg(any(target_os = "linux", target_os = "android"))]
             F_GETPIPE_SZ => libc::fcntl(fd, libc::F_GETPIPE_SZ),
             #[cfg(any(target_os = "linux", target_os = "android"))]
@@ -267,6 +412,47 @@ pub fn fcntl(fd: RawFd, arg: FcntlArg) -> Result<c_int> {
     Errno::result(res)
 }
 
+/// Set or clear `O_NONBLOCK` on `fd`, keeping its other file status flags.
+///
+/// The flag belongs to the open file description, so it also changes for the
+/// file descriptors duplicated from `fd`, in this process or others.  Flags that
+/// nix doesn't know about, like `O_LARGEFILE` on 32-bit Linux, are kept too.
+pub fn set_nonblocking(fd: RawFd, nonblocking: bool) -> Result<()> {
+    let flags = try!(fcntl(fd, F_GETFL));
+    let new_flags = if nonblocking {
+        flags | libc::O_NONBLOCK
+    } else {
+        flags & !libc::O_NONBLOCK
+    };
+    if new_flags == flags {
+        return Ok(());
+    }
+    let res = unsafe { libc::fcntl(fd, libc::F_SETFL, new_flags) };
+
+    Errno::result(res).map(drop)
+}
+
+/// Set or clear `FD_CLOEXEC` on `fd`, keeping its other file descriptor flags.
+///
+/// Unlike `O_NONBLOCK`, the flag belongs to `fd` alone.  Setting it after
+/// creating `fd` leaves a window where another thread may `fork` and `exec`
+/// with `fd` open, so prefer the `O_CLOEXEC` or `SOCK_CLOEXEC` flags where
+/// `fd` is created.
+pub fn set_cloexec(fd: RawFd, cloexec: bool) -> Result<()> {
+    let flags = try!(fcntl(fd, F_GETFD));
+    let new_flags = if cloexec {
+        flags | libc::FD_CLOEXEC
+    } else {
+        flags & !libc::FD_CLOEXEC
+    };
+    if new_flags == flags {
+        return Ok(());
+    }
+    let res = unsafe { libc::fcntl(fd, libc::F_SETFD, new_flags) };
+
+    Errno::result(res).map(drop)
+}
+
 pub enum FlockArg {
     LockShared,
     LockExclusive,
@@ -380,3 +566,144 @@ pub fn fallocate(fd: RawFd, mode: FallocateFlags, offset: libc::off_t, len: libc
     let res = unsafe { libc::fallocate(fd, mode.bits(), offset, len) };
     Errno::result(res)
 }
+
+// libc doesn't export these, so their values are taken from sys/clonefile.h
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+bitflags! {
+    /// Flags for [`clonefileat`](fn.clonefileat.html) and
+    /// [`fclonefileat`](fn.fclonefileat.html)
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct CloneFileFlags: u32 {
+        /// Clone a symbolic link itself, rather than the file it refers to
+        const CLONE_NOFOLLOW = 0x0001;
+        /// Don't copy the ownership of the source, so that the clone is owned
+        /// by the caller
+        const CLONE_NOOWNERCOPY = 0x0002;
+    }
+}
+
+/// Create a copy-on-write clone of a file or a directory tree (see
+/// [`clonefileat(2)`](https://www.manpagez.com/man/2/clonefileat/)).
+///
+/// The clone shares its data blocks with the source until either is modified,
+/// so it is created in constant time and takes no space.  The source and the
+/// destination must be on the same APFS volume, or this fails with `EXDEV` or
+/// `ENOTSUP`.  The destination must not exist.  As for `openat`, relative
+/// paths are resolved from the directory file descriptors, which may be
+/// `libc::AT_FDCWD`.
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+pub fn clonefileat<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(src_dirfd: RawFd, src: &P1,
+                                                               dst_dirfd: RawFd, dst: &P2,
+                                                               flags: CloneFileFlags)
+                                                               -> Result<()> {
+    let res = try!(try!(src.with_nix_path(|src| {
+        dst.with_nix_path(|dst| {
+            unsafe {
+                libc::clonefileat(src_dirfd, src.as_ptr(), dst_dirfd, dst.as_ptr(), flags.bits())
+            }
+        })
+    })));
+
+    Errno::result(res).map(drop)
+}
+
+/// Like [`clonefileat`](fn.clonefileat.html), but clones the file open as
+/// `srcfd`.
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+pub fn fclonefileat<P: ?Sized + NixPath>(srcfd: RawFd, dst_dirfd: RawFd, dst: &P,
+                                         flags: CloneFileFlags) -> Result<()> {
+    let res = try!(dst.with_nix_path(|dst| {
+        unsafe { libc::fclonefileat(srcfd, dst_dirfd, dst.as_ptr(), flags.bits()) }
+    }));
+
+    Errno::result(res).map(drop)
+}
+
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+libc_bitflags! {
+    /// What [`copyfile`](fn.copyfile.html) and [`fcopyfile`](fn.fcopyfile.html)
+    /// copy, and how
+    pub struct CopyFileFlags: u32 {
+        /// Copy the access control list
+        COPYFILE_ACL;
+        /// Copy the POSIX information: the mode, ownership and times
+        COPYFILE_STAT;
+        /// Copy the extended attributes
+        COPYFILE_XATTR;
+        /// Copy the data
+        COPYFILE_DATA;
+        /// Copy the access control list and the POSIX information
+        COPYFILE_SECURITY;
+        /// Copy everything but the data
+        COPYFILE_METADATA;
+        /// Copy a directory tree
+        COPYFILE_RECURSIVE;
+        /// Fail with `EEXIST` if the destination exists
+        COPYFILE_EXCL;
+        /// Copy a symbolic link source itself, rather than the file it refers to
+        COPYFILE_NOFOLLOW_SRC;
+        /// Don't follow a symbolic link destination
+        COPYFILE_NOFOLLOW_DST;
+        /// Both `COPYFILE_NOFOLLOW_SRC` and `COPYFILE_NOFOLLOW_DST`
+        COPYFILE_NOFOLLOW;
+        /// Remove the source once it is copied
+        COPYFILE_MOVE;
+        /// Remove the destination before copying
+        COPYFILE_UNLINK;
+        /// Try to clone the file, as with `clonefile`, and copy it as asked
+        /// otherwise.  A clone copies everything, whatever the other flags.
+        COPYFILE_CLONE;
+        /// Clone the file, or fail with `ENOTSUP`
+        COPYFILE_CLONE_FORCE;
+        /// Don't copy the holes of a sparse file
+        COPYFILE_DATA_SPARSE;
+    }
+}
+
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+impl CopyFileFlags {
+    /// Copy everything: `COPYFILE_METADATA` and `COPYFILE_DATA`.  This one
+    /// isn't in libc.
+    pub const COPYFILE_ALL: CopyFileFlags = CopyFileFlags {
+        bits: libc::COPYFILE_METADATA | libc::COPYFILE_DATA,
+    };
+}
+
+/// Copy a file, or the parts of it given by `flags` (see
+/// [`copyfile(3)`](https://www.manpagez.com/man/3/copyfile/)).
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+pub fn copyfile<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(from: &P1, to: &P2,
+                                                            flags: CopyFileFlags) -> Result<()> {
+    let res = try!(try!(from.with_nix_path(|from| {
+        to.with_nix_path(|to| {
+            unsafe { libc::copyfile(from.as_ptr(), to.as_ptr(), ptr::null_mut(), flags.bits()) }
+        })
+    })));
+
+    Errno::result(res).map(drop)
+}
+
+/// Like [`copyfile`](fn.copyfile.html), but copies between open files.
+/// `COPYFILE_RECURSIVE` and the flags that need paths have no effect.
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+pub fn fcopyfile(from: RawFd, to: RawFd, flags: CopyFileFlags) -> Result<()> {
+    let res = unsafe { libc::fcopyfile(from, to, ptr::null_mut(), flags.bits()) };
+
+    Errno::result(res).map(drop)
+}
+
+#[cfg(all(test, feature = "std", any(target_os = "android", target_os = "linux")))]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_readlink_grows_buffer() {
+        let path = b"/proc/self/exe\0";
+        // Start from a buffer too small for any target, so it must be grown
+        let target = readlink_grow(1, |buf, len| unsafe {
+            libc::readlink(path.as_ptr() as *const c_char, buf, len)
+        }).unwrap();
+        assert_eq!(target, fs::read_link("/proc/self/exe").unwrap().into_os_string());
+    }
+}
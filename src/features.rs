@@ -1,8 +1,19 @@
+//! Detect at runtime which features the operating system supports.
+//!
+//! Each probe runs once, the first time it's asked for, and its result is
+//! cached for the rest of the process.  Wrappers of the features that can do
+//! without them fall back on an equivalent; their docs say how.
 pub use self::os::*;
 
 #[cfg(any(target_os = "linux", target_os = "android"))]
 mod os {
+    use libc::{self, c_int};
+    use std::ptr;
+    #[allow(deprecated)]
+    use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+    use errno::{Errno, ErrnoSentinel};
     use sys::utsname::uname;
+    use Error;
 
     // Features:
     // * atomic cloexec on socket: 2.6.27
@@ -82,19 +93,168 @@ mod os {
         }
     }
 
+    /// Whether `socket` and `socketpair` can set `SOCK_CLOEXEC` and
+    /// `SOCK_NONBLOCK` atomically (Linux 2.6.27).  Otherwise they set them with
+    /// `fcntl` after creating the sockets.
     pub fn socket_atomic_cloexec() -> bool {
         kernel_version() >= VERS_2_6_27
     }
 
+    const PROBE_UNKNOWN: usize = 0;
+    const PROBE_MISSING: usize = 1;
+    const PROBE_AVAILABLE: usize = 2;
+
+    // Run `probe` the first time, then return what it found
+    fn cached(cache: &AtomicUsize, probe: fn() -> bool) -> bool {
+        match cache.load(Ordering::Relaxed) {
+            PROBE_UNKNOWN => {
+                let available = probe();
+                cache.store(if available { PROBE_AVAILABLE } else { PROBE_MISSING },
+                            Ordering::Relaxed);
+                available
+            },
+            state => state == PROBE_AVAILABLE,
+        }
+    }
+
+    // Whether a call returning `res` got past the kernel's syscall table.  The
+    // probes pass invalid arguments, so that an existing syscall fails without
+    // doing anything, with an error other than ENOSYS.
+    fn implemented<S: ErrnoSentinel + PartialEq<S>>(res: S) -> bool {
+        Errno::result(res) != Err(Error::Sys(Errno::ENOSYS))
+    }
+
+    macro_rules! probe {
+        ($(#[$attr:meta])* fn $name:ident() $probe:block) => {
+            $(#[$attr])*
+            pub fn $name() -> bool {
+                #[allow(deprecated)]
+                static CACHE: AtomicUsize = ATOMIC_USIZE_INIT;
+
+                fn probe() -> bool $probe
+
+                cached(&CACHE, probe)
+            }
+        }
+    }
+
+    probe! {
+        /// Whether the kernel supports `pipe2` (Linux 2.6.27).
+        ///
+        /// Without it, [`unistd::pipe2`](../unistd/fn.pipe2.html) falls back on
+        /// `pipe` and sets the flags it can with `fcntl` afterwards.
+        fn pipe2_available() {
+            implemented(unsafe { libc::pipe2(ptr::null_mut(), 0) })
+        }
+    }
+
+    probe! {
+        /// Whether the kernel supports `accept4` (Linux 2.6.28).
+        ///
+        /// Without it, [`accept4`](../sys/socket/fn.accept4.html) falls back on
+        /// `accept` and sets the flags with `fcntl` afterwards.
+        fn accept4_available() {
+            implemented(unsafe { libc::accept4(-1, ptr::null_mut(), ptr::null_mut(), 0) })
+        }
+    }
+
+    probe! {
+        /// Whether the kernel supports `copy_file_range` (Linux 4.5).
+        ///
+        /// Without it, copy the data through a buffer with `read` and `write`,
+        /// or with `sendfile`.
+        fn copy_file_range_available() {
+            implemented(unsafe {
+                libc::syscall(libc::SYS_copy_file_range, -1 as c_int, ptr::null_mut::<i64>(),
+                              -1 as c_int, ptr::null_mut::<i64>(), 0usize, 0 as c_int)
+            })
+        }
+    }
+
+    probe! {
+        /// Whether the kernel supports `statx` (Linux 4.11).
+        ///
+        /// Without it, use [`fstatat`](../sys/stat/fn.fstatat.html), which
+        /// can't tell the birth time of files.
+        fn statx_available() {
+            implemented(unsafe {
+                libc::syscall(libc::SYS_statx, -1 as c_int, ptr::null::<libc::c_char>(),
+                              0 as c_int, 0 as c_int, ptr::null_mut::<libc::c_void>())
+            })
+        }
+    }
+
+    probe! {
+        /// Whether the kernel supports `pidfd_open` (Linux 5.3).
+        ///
+        /// Without it, there's no file descriptor to wait for a process with;
+        /// use `waitpid` or `SIGCHLD` instead.
+        fn pidfd_open_available() {
+            // No process has the ID 0, so this fails with EINVAL
+            implemented(unsafe { libc::syscall(libc::SYS_pidfd_open, 0 as libc::pid_t, 0 as c_int) })
+        }
+    }
+
+    probe! {
+        /// Whether the kernel supports `epoll_pwait2` (Linux 5.11).
+        ///
+        /// Without it, [`Epoll::wait_timeout`](../sys/epoll/struct.Epoll.html#method.wait_timeout)
+        /// falls back on `epoll_pwait`, with the timeout rounded up to whole
+        /// milliseconds.
+        fn epoll_pwait2_available() {
+            // An invalid epoll file descriptor fails with EBADF
+            implemented(unsafe {
+                libc::syscall(libc::SYS_epoll_pwait2, -1 as c_int, ptr::null_mut::<libc::c_void>(),
+                              0 as c_int, ptr::null::<libc::c_void>(),
+                              ptr::null::<libc::sigset_t>(), 0usize)
+            })
+        }
+    }
+
     #[test]
     pub fn test_parsing_kernel_version() {
         assert!(kernel_version() > 0);
     }
+
+    #[test]
+    pub fn test_probes() {
+        // Older kernels than these aren't supported by Rust's standard library
+        assert!(pipe2_available());
+        assert!(accept4_available());
+
+        // Whatever the answer, it stays the same once cached
+        assert_eq!(copy_file_range_available(), copy_file_range_available());
+        assert_eq!(statx_available(), statx_available());
+        assert_eq!(pidfd_open_available(), pidfd_open_available());
+        assert_eq!(epoll_pwait2_available(), epoll_pwait2_available());
+    }
 }
 
 #[cfg(any(target_os = "macos", target_os = "freebsd", target_os = "dragonfly", target_os = "ios", target_os = "openbsd", target_os = "netbsd"))]
 mod os {
+    /// Whether `socket` and `socketpair` can set `SOCK_CLOEXEC` and
+    /// `SOCK_NONBLOCK` atomically.  Otherwise they set them with `fcntl` after
+    /// creating the sockets.
     pub fn socket_atomic_cloexec() -> bool {
         false
     }
+
+    /// Whether the system has `pipe2`.
+    ///
+    /// It doesn't on iOS and macOS, where
+    /// [`unistd::pipe2`](../unistd/fn.pipe2.html) calls `pipe` and sets the
+    /// flags with `fcntl` afterwards.
+    pub fn pipe2_available() -> bool {
+        !cfg!(any(target_os = "ios", target_os = "macos"))
+    }
+
+    /// Whether the system has `accept4`.
+    ///
+    /// It doesn't on iOS and macOS, where
+    /// [`accept4`](../sys/socket/fn.accept4.html) just calls `accept`:
+    /// `SockFlag` has no flags there, since these systems lack
+    /// `SOCK_CLOEXEC` and `SOCK_NONBLOCK`.
+    pub fn accept4_available() -> bool {
+        !cfg!(any(target_os = "ios", target_os = "macos"))
+    }
 }
@@ -29,6 +29,76 @@ pub struct InterfaceAddress {
     pub broadcast: Option<SockAddr>,
     /// Point-to-point destination address
     pub destination: Option<SockAddr>,
+    /// Traffic statistics of the interface, only given with its link-layer
+    /// address
+    #[cfg(target_os = "linux")]
+    pub stats: Option<InterfaceStats>,
+}
+
+/// Traffic statistics of an interface, as given by `getifaddrs`
+///
+/// These are 32-bit counters, which wrap around on busy interfaces.
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy, Eq, Hash, PartialEq, Debug)]
+pub struct InterfaceStats {
+    /// Packets received
+    pub rx_packets: u32,
+    /// Packets sent
+    pub tx_packets: u32,
+    /// Bytes received
+    pub rx_bytes: u32,
+    /// Bytes sent
+    pub tx_bytes: u32,
+    /// Bad packets received
+    pub rx_errors: u32,
+    /// Packets that couldn't be sent
+    pub tx_errors: u32,
+    /// Packets received but dropped, e.g. for lack of buffer space
+    pub rx_dropped: u32,
+    /// Packets dropped before being sent
+    pub tx_dropped: u32,
+    /// Multicast packets received
+    pub multicast: u32,
+    /// Collisions while sending
+    pub collisions: u32,
+}
+
+// The start of struct rtnl_link_stats from linux/if_link.h, which glibc and
+// musl point ifa_data to for AF_PACKET entries
+#[cfg(target_os = "linux")]
+#[repr(C)]
+struct rtnl_link_stats {
+    rx_packets: u32,
+    tx_packets: u32,
+    rx_bytes: u32,
+    tx_bytes: u32,
+    rx_errors: u32,
+    tx_errors: u32,
+    rx_dropped: u32,
+    tx_dropped: u32,
+    multicast: u32,
+    collisions: u32,
+}
+
+#[cfg(target_os = "linux")]
+fn get_stats(info: &libc::ifaddrs) -> Option<InterfaceStats> {
+    if info.ifa_addr.is_null() || info.ifa_data.is_null() ||
+        unsafe { (*info.ifa_addr).sa_family } as libc::c_int != libc::AF_PACKET {
+        return None;
+    }
+    let stats = unsafe { &*(info.ifa_data as *const rtnl_link_stats) };
+    Some(InterfaceStats {
+        rx_packets: stats.rx_packets,
+        tx_packets: stats.tx_packets,
+        rx_bytes: stats.rx_bytes,
+        tx_bytes: stats.tx_bytes,
+        rx_errors: stats.rx_errors,
+        tx_errors: stats.tx_errors,
+        rx_dropped: stats.rx_dropped,
+        tx_dropped: stats.tx_dropped,
+        multicast: stats.multicast,
+        collisions: stats.collisions,
+    })
 }
 
 cfg_if! {
@@ -56,6 +126,8 @@ impl InterfaceAddress {
             netmask: netmask,
             broadcast: None,
             destination: None,
+            #[cfg(target_os = "linux")]
+            stats: get_stats(info),
         };
 
         let ifu = get_ifu_from_sockaddr(info);
@@ -143,4 +215,16 @@ mod tests {
     fn test_getifaddrs() {
         let _ = getifaddrs();
     }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_getifaddrs_stats() {
+        use sys::socket::AddressFamily;
+
+        // Only the link-layer entries have statistics
+        for ifaddr in getifaddrs().unwrap() {
+            let link = ifaddr.address.map_or(false, |a| a.family() == AddressFamily::Packet);
+            assert_eq!(ifaddr.stats.is_some(), link, "{}", ifaddr.interface_name);
+        }
+    }
 }
@@ -0,0 +1,77 @@
+//! Load and unload Linux kernel modules (see
+//! [init_module(2)](http://man7.org/linux/man-pages/man2/init_module.2.html) and
+//! [delete_module(2)](http://man7.org/linux/man-pages/man2/delete_module.2.html)).
+//!
+//! These only do what the kernel does: unlike `modprobe`, they don't look
+//! modules up by name, decompress them, or load their dependencies first.  They
+//! all require `CAP_SYS_MODULE`.
+use libc::{self, c_uint, c_void};
+use std::os::unix::io::RawFd;
+use errno::Errno;
+use {NixPath, Result};
+
+/// Load the ELF image of a module from a buffer.
+///
+/// `params` holds the module parameters separated by spaces, like
+/// `"debug=1 mode=fast"`.  Fails with `EEXIST` if the module is already loaded,
+/// and with `ENOEXEC` if the image isn't a valid module.
+pub fn init_module<P: ?Sized + NixPath>(image: &[u8], params: &P) -> Result<()> {
+    let res = try!(params.with_nix_path(|params| unsafe {
+        libc::syscall(libc::SYS_init_module, image.as_ptr() as *const c_void, image.len(),
+                      params.as_ptr())
+    }));
+
+    Errno::result(res).map(drop)
+}
+
+libc_bitflags!{
+    /// Flags for [`finit_module`](fn.finit_module.html)
+    pub struct ModuleInitFlags: c_uint {
+        /// Load the module even if the hashes of its symbol versions don't
+        /// match the kernel
+        MODULE_INIT_IGNORE_MODVERSIONS;
+        /// Load the module even if it was built for another kernel version
+        MODULE_INIT_IGNORE_VERMAGIC;
+    }
+}
+
+/// Load a module from the file open as `fd`.
+///
+/// Like [`init_module`](fn.init_module.html), except that the kernel can tell
+/// which file the module came from, for instance to check it with a security
+/// module.  The flags skip the checks that the module fits the kernel, which
+/// will crash if it doesn't.  Fails with `ENOSYS` on kernels older than 3.8.
+pub fn finit_module<P: ?Sized + NixPath>(fd: RawFd, params: &P, flags: ModuleInitFlags)
+                                         -> Result<()> {
+    let res = try!(params.with_nix_path(|params| unsafe {
+        libc::syscall(libc::SYS_finit_module, fd, params.as_ptr(), flags.bits())
+    }));
+
+    Errno::result(res).map(drop)
+}
+
+libc_bitflags!{
+    /// Flags for [`delete_module`](fn.delete_module.html)
+    pub struct DeleteModuleFlags: libc::c_int {
+        /// Fail with `EWOULDBLOCK` if the module is in use, instead of waiting
+        /// until it isn't.  Kernels since 3.13 never wait, and fail like this
+        /// even without the flag.
+        O_NONBLOCK;
+        /// Unload the module even if it's in use or can't be unloaded, if the
+        /// kernel was built with `CONFIG_MODULE_FORCE_UNLOAD`.  This may crash
+        /// it.
+        O_TRUNC;
+    }
+}
+
+/// Unload the module named `name`, like `ext4`.
+///
+/// Fails with `ENOENT` if no such module is loaded, and with `EWOULDBLOCK` if
+/// other modules or processes still use it.
+pub fn delete_module<P: ?Sized + NixPath>(name: &P, flags: DeleteModuleFlags) -> Result<()> {
+    let res = try!(name.with_nix_path(|name| unsafe {
+        libc::syscall(libc::SYS_delete_module, name.as_ptr(), flags.bits())
+    }));
+
+    Errno::result(res).map(drop)
+}
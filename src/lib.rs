@@ -2,8 +2,18 @@
 //!
 //! Modules are structured according to the C header file that they would be
 //! defined in.
+//!
+//! # Without std
+//!
+//! With the default `std` feature disabled, nix is `#![no_std]` and only
+//! builds its core: `Errno`, `Error` and `NixPath`, the flags and enums, and
+//! the wrappers that need neither allocation nor `std`'s types, like `open`,
+//! `read`, `write` and `fork`.  `RawFd` and the traits for file descriptors
+//! are then nix's own, at the crate root.  The `alloc` feature adds `NixPath`
+//! for owned strings and lets paths be longer than `PATH_MAX`.
 #![crate_name = "nix"]
 #![cfg(unix)]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![allow(non_camel_case_types)]
 // latest bitflags triggers a rustc bug with cross-crate macro expansions causing dead_code
 // warnings even though the macro expands into something with allow(dead_code)
@@ -11,6 +21,9 @@
 #![cfg_attr(test, deny(warnings))]
 #![recursion_limit = "500"]
 
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+extern crate alloc;
+#[cfg(feature = "std")]
 extern crate bytes;
 #[macro_use]
 extern crate bitflags;
@@ -18,45 +31,101 @@ extern crate bitflags;
 #[macro_use]
 extern crate cfg_if;
 extern crate void;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+
+#[cfg(all(feature = "serde", not(feature = "std")))]
+compile_error!("the serde feature requires the std feature");
 
 #[macro_use] mod macros;
 
 pub extern crate libc;
 
+// Without std, `std::` paths lead here instead: to `core`, and to nix's own
+// file descriptor types
+#[cfg(not(feature = "std"))]
+mod std {
+    pub use core::*;
+
+    pub mod os {
+        pub mod unix {
+            pub mod io {
+                /// A raw file descriptor
+                pub type RawFd = ::libc::c_int;
+
+                /// Borrow the raw file descriptor of an object
+                pub trait AsRawFd {
+                    fn as_raw_fd(&self) -> RawFd;
+                }
+
+                /// Construct an object from a raw file descriptor, which it
+                /// then owns
+                pub trait FromRawFd {
+                    unsafe fn from_raw_fd(fd: RawFd) -> Self;
+                }
+
+                /// Take the raw file descriptor of an object, which no
+                /// longer owns it
+                pub trait IntoRawFd {
+                    fn into_raw_fd(self) -> RawFd;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+pub use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+
+// Used by the exported macros, which must also work in `#![no_std]` crates.
+// This is `core::mem` either way, through the `std` shim without std.
+#[doc(hidden)]
+pub use std::mem as __mem;
+
 use errno::Errno;
 
 pub mod errno;
 pub mod features;
 pub mod fcntl;
 
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub mod kmod;
+
 #[cfg(any(target_os = "linux", target_os = "android"))]
 pub mod mount;
 
-#[cfg(any(target_os = "dragonfly",
-          target_os = "freebsd",
-          target_os = "fushsia",
-          target_os = "linux",
-          target_os = "netbsd"))]
+#[cfg(all(feature = "std",
+          any(target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "fushsia",
+              target_os = "linux",
+              target_os = "netbsd")))]
 pub mod mqueue;
 
+#[cfg(feature = "std")]
 pub mod pty;
 
 pub mod poll;
 
 pub mod net;
 
-#[cfg(any(target_os = "dragonfly",
-          target_os = "freebsd",
-          target_os = "ios",
-          target_os = "linux",
-          target_os = "macos",
-          target_os = "netbsd",
-          target_os = "openbsd"))]
+#[cfg(all(feature = "std",
+          any(target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "linux",
+              target_os = "macos",
+              target_os = "netbsd",
+              target_os = "openbsd")))]
 pub mod ifaddrs;
 
 #[cfg(any(target_os = "linux", target_os = "android"))]
 pub mod sched;
 
+#[cfg(feature = "std")]
+pub mod semaphore;
+
 pub mod sys;
 
 // This can be implemented for other platforms as soon as libc
@@ -74,12 +143,21 @@ pub mod unistd;
  */
 
 use libc::c_char;
-use std::{ptr, result};
-use std::ffi::{CStr, OsStr};
+use std::{ptr, result, str};
+use std::ffi::CStr;
+#[cfg(feature = "std")]
+use std::ffi::{CString, OsStr, OsString};
+#[cfg(feature = "std")]
 use std::path::{Path, PathBuf};
+#[cfg(feature = "std")]
 use std::os::unix::ffi::OsStrExt;
 use std::fmt;
+#[cfg(feature = "std")]
 use std::error;
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::ffi::CString;
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::{string::String, vec::Vec};
 use libc::PATH_MAX;
 
 /// Nix Result Type
@@ -90,17 +168,20 @@ pub type Result<T> = result::Result<T, Error>;
 /// The nix error type provides a common way of dealing with
 /// various system system/libc calls that might fail.  Each
 /// error has a corresponding errno (usually the one from the
-/// underlying OS) to which it can be mapped in addition to
-/// implementing other common traits.
+/// underlying OS), returned by [`as_errno`](#method.as_errno),
+/// which is also kept when converting it into a `std::io::Error`.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Error {
     Sys(errno::Errno),
-    InvalidPath,
     /// The operation involved a conversion to Rust's native String type, which failed because the
-    /// string did not contain all valid UTF-8.
+    /// string did not contain all valid UTF-8.  Its errno is `EILSEQ`.
     InvalidUtf8,
+    /// A path or a name contained a nul byte, so it couldn't be passed to C.  Its errno is
+    /// `EINVAL`.
+    InteriorNul,
     /// The operation is not supported by Nix, in this instance either use the libc bindings or
     /// consult the module documentation to see if there is a more appropriate interface available.
+    /// Its errno is `EOPNOTSUPP`.
     UnsupportedOperation,
 }
 
@@ -121,21 +202,54 @@ impl Error {
         Error::Sys(Errno::EINVAL)
     }
 
+    /// The errno of the error
+    pub fn as_errno(&self) -> Errno {
+        match *self {
+            Error::Sys(errno) => errno,
+            Error::InvalidUtf8 => Errno::EILSEQ,
+            Error::InteriorNul => Errno::EINVAL,
+            Error::UnsupportedOperation => Errno::EOPNOTSUPP,
+        }
+    }
+
 }
 
 impl From<Errno> for Error {
     fn from(errno: Errno) -> Error { Error::from_errno(errno) }
 }
 
+#[cfg(feature = "std")]
 impl From<std::string::FromUtf8Error> for Error {
     fn from(_: std::string::FromUtf8Error) -> Error { Error::InvalidUtf8 }
 }
 
+#[cfg(feature = "std")]
+impl From<std::ffi::NulError> for Error {
+    fn from(_: std::ffi::NulError) -> Error { Error::InteriorNul }
+}
+
+#[cfg(feature = "std")]
+impl From<Error> for std::io::Error {
+    /// Convert into the error of the OS with the errno of the error
+    fn from(err: Error) -> Self {
+        err.as_errno().into()
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    /// Convert from the errno of the error, as for `Errno`
+    fn from(err: std::io::Error) -> Self {
+        Error::Sys(err.into())
+    }
+}
+
+#[cfg(feature = "std")]
 impl error::Error for Error {
     fn description(&self) -> &str {
         match *self {
-            Error::InvalidPath => "Invalid path",
             Error::InvalidUtf8 => "Invalid UTF-8 string",
+            Error::InteriorNul => "Nul byte in string",
             Error::UnsupportedOperation => "Unsupported Operation",
             Error::Sys(ref errno) => errno.desc(),
         }
@@ -145,10 +259,28 @@ impl error::Error for Error {
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            Error::InvalidPath => write!(f, "Invalid path"),
             Error::InvalidUtf8 => write!(f, "Invalid UTF-8 string"),
+            Error::InteriorNul => write!(f, "Nul byte in string"),
             Error::UnsupportedOperation => write!(f, "Unsupported Operation"),
-            Error::Sys(errno) => write!(f, "{:?}: {}", errno, errno.desc()),
+            Error::Sys(errno) => write!(f, "{}", errno),
+        }
+    }
+}
+
+// Write `bytes` as `String::from_utf8_lossy` would make them, without allocating
+fn fmt_lossy(f: &mut fmt::Formatter, mut bytes: &[u8]) -> fmt::Result {
+    loop {
+        match str::from_utf8(bytes) {
+            Ok(valid) => return f.write_str(valid),
+            Err(e) => {
+                let (valid, rest) = bytes.split_at(e.valid_up_to());
+                try!(f.write_str(unsafe { str::from_utf8_unchecked(valid) }));
+                try!(f.write_str("\u{FFFD}"));
+                match e.error_len() {
+                    Some(len) => bytes = &rest[len..],
+                    None => return Ok(()),
+                }
+            }
         }
     }
 }
@@ -162,15 +294,16 @@ pub trait NixPath {
 
 impl NixPath for str {
     fn len(&self) -> usize {
-        NixPath::len(OsStr::new(self))
+        NixPath::len(self.as_bytes())
     }
 
     fn with_nix_path<T, F>(&self, f: F) -> Result<T>
         where F: FnOnce(&CStr) -> T {
-            OsStr::new(self).with_nix_path(f)
+            self.as_bytes().with_nix_path(f)
         }
 }
 
+#[cfg(feature = "std")]
 impl NixPath for OsStr {
     fn len(&self) -> usize {
         self.as_bytes().len()
@@ -189,11 +322,18 @@ impl NixPath for CStr {
 
     fn with_nix_path<T, F>(&self, f: F) -> Result<T>
             where F: FnOnce(&CStr) -> T {
-        // Equivalence with the [u8] impl.
-        if self.len() >= PATH_MAX as usize {
-            return Err(Error::InvalidPath);
-        }
+        Ok(f(self))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl NixPath for CString {
+    fn len(&self) -> usize {
+        NixPath::len(self.as_c_str())
+    }
 
+    fn with_nix_path<T, F>(&self, f: F) -> Result<T>
+            where F: FnOnce(&CStr) -> T {
         Ok(f(self))
     }
 }
@@ -205,26 +345,69 @@ impl NixPath for [u8] {
 
     fn with_nix_path<T, F>(&self, f: F) -> Result<T>
             where F: FnOnce(&CStr) -> T {
-        let mut buf = [0u8; PATH_MAX as usize];
-
-        if self.len() >= PATH_MAX as usize {
-            return Err(Error::InvalidPath);
+        if self.contains(&0) {
+            return Err(Error::InteriorNul);
         }
 
-        match self.iter().position(|b| *b == 0) {
-            Some(_) => Err(Error::InvalidPath),
-            None => {
-                unsafe {
-                    // TODO: Replace with bytes::copy_memory. rust-lang/rust#24028
-                    ptr::copy_nonoverlapping(self.as_ptr(), buf.as_mut_ptr(), self.len());
-                    Ok(f(CStr::from_ptr(buf.as_ptr() as *const c_char)))
-                }
-
+        // Paths that don't fit on the stack are still passed on, to let the OS
+        // decide whether they're too long, like for relative paths
+        #[cfg(feature = "alloc")]
+        {
+            if self.len() >= PATH_MAX as usize {
+                let path = unsafe { CString::from_vec_unchecked(self.to_vec()) };
+                return Ok(f(&path));
+            }
+        }
+        #[cfg(not(feature = "alloc"))]
+        {
+            if self.len() >= PATH_MAX as usize {
+                return Err(Error::Sys(Errno::ENAMETOOLONG));
             }
         }
+
+        let mut buf = [0u8; PATH_MAX as usize];
+        unsafe {
+            // TODO: Replace with bytes::copy_memory. rust-lang/rust#24028
+            ptr::copy_nonoverlapping(self.as_ptr(), buf.as_mut_ptr(), self.len());
+            Ok(f(CStr::from_ptr(buf.as_ptr() as *const c_char)))
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl NixPath for Vec<u8> {
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    fn with_nix_path<T, F>(&self, f: F) -> Result<T> where F: FnOnce(&CStr) -> T {
+        self.as_slice().with_nix_path(f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl NixPath for String {
+    fn len(&self) -> usize {
+        NixPath::len(self.as_str())
+    }
+
+    fn with_nix_path<T, F>(&self, f: F) -> Result<T> where F: FnOnce(&CStr) -> T {
+        self.as_str().with_nix_path(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl NixPath for OsString {
+    fn len(&self) -> usize {
+        NixPath::len(self.as_os_str())
+    }
+
+    fn with_nix_path<T, F>(&self, f: F) -> Result<T> where F: FnOnce(&CStr) -> T {
+        self.as_os_str().with_nix_path(f)
     }
 }
 
+#[cfg(feature = "std")]
 impl NixPath for Path {
     fn len(&self) -> usize {
         NixPath::len(self.as_os_str())
@@ -235,6 +418,7 @@ impl NixPath for Path {
     }
 }
 
+#[cfg(feature = "std")]
 impl NixPath for PathBuf {
     fn len(&self) -> usize {
         NixPath::len(self.as_os_str())
@@ -1,6 +1,7 @@
 /// The `libc_bitflags!` macro helps with a common use case of defining a public bitflags type
 /// with values from the libc crate. It is used the same way as the `bitflags!` macro, except
-/// that only the name of the flag value has to be given.
+/// that only the name of the flag value has to be given.  With the `serde` feature, the type
+/// also implements `Serialize` and `Deserialize`.
 ///
 /// The `libc` crate must be in scope with the name `libc`.
 ///
@@ -50,6 +51,7 @@ macro_rules! libc_bitflags {
     ) => {
         bitflags! {
             $(#[$outer])*
+            #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
             pub struct $BitFlags: $T {
                 $(
                     $(#[$inner $($args)*])*
@@ -2,13 +2,23 @@
 //!
 //! [Further reading and details on the C API](http://man7.org/linux/man-pages/man7/mq_overview.7.html)
 
-use Result;
+use {NixPath, Result};
 use errno::Errno;
 
 use libc::{self, c_char, c_long, mqd_t, size_t};
 use std::ffi::CString;
+#[cfg(any(all(target_os = "linux", target_env = "gnu"),
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "netbsd"))]
+use sys::signal::{SigEvent, SigevNotify};
 use sys::stat::Mode;
+use sys::time::TimeSpec;
 use std::mem;
+#[cfg(target_os = "linux")]
+use std::os::unix::io::{AsRawFd, IntoRawFd, RawFd};
+#[cfg(target_os = "linux")]
+use std::ptr;
 
 libc_bitflags!{
     pub struct MQ_OFlag: libc::c_int {
@@ -61,18 +71,33 @@ impl MqAttr {
     pub fn flags(&self) -> c_long {
         self.mq_attr.mq_flags
     }
+
+    /// The maximum number of messages in the queue
+    pub fn maxmsg(&self) -> c_long {
+        self.mq_attr.mq_maxmsg
+    }
+
+    /// The maximum size of a message, in bytes
+    pub fn msgsize(&self) -> c_long {
+        self.mq_attr.mq_msgsize
+    }
+
+    /// The number of messages currently in the queue
+    pub fn curmsgs(&self) -> c_long {
+        self.mq_attr.mq_curmsgs
+    }
 }
 
 
 /// Open a message queue
 ///
 /// See also [`mq_open(2)`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/mq_open.html)
-pub fn mq_open(name: &CString,
-               oflag: MQ_OFlag,
-               mode: Mode,
-               attr: Option<&MqAttr>)
-               -> Result<mqd_t> {
-    let res = match attr {
+pub fn mq_open<P: ?Sized + NixPath>(name: &P,
+                                    oflag: MQ_OFlag,
+                                    mode: Mode,
+                                    attr: Option<&MqAttr>)
+                                    -> Result<mqd_t> {
+    let res = try!(name.with_nix_path(|name| match attr {
         Some(mq_attr) => unsafe {
             libc::mq_open(name.as_ptr(),
                           oflag.bits(),
@@ -80,15 +105,15 @@ pub fn mq_open(name: &CString,
                           &mq_attr.mq_attr as *const libc::mq_attr)
         },
         None => unsafe { libc::mq_open(name.as_ptr(), oflag.bits()) },
-    };
+    }));
     Errno::result(res)
 }
 
 /// Remove a message queue
 ///
 /// See also [`mq_unlink(2)`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/mq_unlink.html)
-pub fn mq_unlink(name: &CString) -> Result<()> {
-    let res = unsafe { libc::mq_unlink(name.as_ptr()) };
+pub fn mq_unlink<P: ?Sized + NixPath>(name: &P) -> Result<()> {
+    let res = try!(name.with_nix_path(|name| unsafe { libc::mq_unlink(name.as_ptr()) }));
     Errno::result(res).map(drop)
 }
 
@@ -127,6 +152,74 @@ pub fn mq_send(mqdes: mqd_t, message: &[u8], msq_prio: u32) -> Result<()> {
     Errno::result(res).map(drop)
 }
 
+/// Receive a message from a message queue, waiting no later than `abs_timeout`
+///
+/// Like [`mq_receive`](fn.mq_receive.html), but if the queue is empty and the message queue
+/// descriptor isn't in non-blocking mode, fails with `ETIMEDOUT` once `CLOCK_REALTIME` reaches the
+/// absolute time `abs_timeout`.
+///
+/// See also [`mq_timedreceive(2)`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/mq_timedreceive.html)
+pub fn mq_timedreceive(mqdes: mqd_t,
+                       message: &mut [u8],
+                       msg_prio: &mut u32,
+                       abs_timeout: &TimeSpec)
+                       -> Result<usize> {
+    let len = message.len() as size_t;
+    let res = unsafe {
+        libc::mq_timedreceive(mqdes,
+                              message.as_mut_ptr() as *mut c_char,
+                              len,
+                              msg_prio as *mut u32,
+                              abs_timeout.as_ref())
+    };
+    Errno::result(res).map(|r| r as usize)
+}
+
+/// Send a message to a message queue, waiting no later than `abs_timeout`
+///
+/// Like [`mq_send`](fn.mq_send.html), but if the queue is full and the message queue descriptor
+/// isn't in non-blocking mode, fails with `ETIMEDOUT` once `CLOCK_REALTIME` reaches the absolute
+/// time `abs_timeout`.
+///
+/// See also [`mq_timedsend(2)`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/mq_timedsend.html)
+pub fn mq_timedsend(mqdes: mqd_t, message: &[u8], msq_prio: u32, abs_timeout: &TimeSpec)
+                    -> Result<()> {
+    let res = unsafe {
+        libc::mq_timedsend(mqdes,
+                           message.as_ptr() as *const c_char,
+                           message.len(),
+                           msq_prio,
+                           abs_timeout.as_ref())
+    };
+    Errno::result(res).map(drop)
+}
+
+/// Register for notification when a message arrives on an empty message queue
+///
+/// When a message is sent to the queue while it's empty, and no other process is blocked in
+/// `mq_receive` on it, the process is notified as described by `notification`, and the
+/// registration is removed.  Only one process can be registered on a queue at a time; the others
+/// fail with `EBUSY`.
+///
+/// A `notification` of `None` removes the registration of the calling process, so that the
+/// queue is free for others.  `SigevNone` on the other hand keeps it registered, without
+/// delivering any notification.
+///
+/// See also [`mq_notify(2)`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/mq_notify.html)
+#[cfg(any(all(target_os = "linux", target_env = "gnu"),
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "netbsd"))]
+pub fn mq_notify(mqdes: mqd_t, notification: Option<SigevNotify>) -> Result<()> {
+    let sigevent = notification.map(|n| SigEvent::new(n).sigevent());
+    let sigevent = match sigevent {
+        Some(ref sigevent) => sigevent as *const libc::sigevent,
+        None => ::std::ptr::null(),
+    };
+    let res = unsafe { libc::mq_notify(mqdes, sigevent) };
+    Errno::result(res).map(drop)
+}
+
 /// Get message queue attributes
 ///
 /// See also [`mq_getattr(2)`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/mq_getattr.html)
@@ -170,3 +263,153 @@ pub fn mq_remove_nonblock(mqd: mqd_t) -> Result<(MqAttr)> {
                               oldattr.mq_attr.mq_curmsgs);
     mq_setattr(mqd, &newattr)
 }
+
+/// An owned message queue descriptor, which is closed when dropped.
+///
+/// On Linux, message queue descriptors are file descriptors, which can be
+/// watched with `poll` or `epoll`, so `MqD` implements `AsRawFd` and
+/// `IntoRawFd` there.  It can't be made from a file descriptor, since the
+/// queue's name isn't known.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::mqueue::{MqAttr, MqD, MQ_OFlag};
+/// # use nix::sys::stat::Mode;
+/// # use std::ffi::CString;
+/// let name = CString::new("/nix_mqd_doctest").unwrap();
+/// let attr = MqAttr::new(0, 10, 32, 0);
+/// let mut mq = MqD::create(&name, MQ_OFlag::O_RDWR, Mode::S_IRUSR | Mode::S_IWUSR, &attr)
+///     .unwrap();
+/// mq.unlink_on_drop();
+///
+/// mq.send(b"hello", 1).unwrap();
+/// let mut buf = [0u8; 32];
+/// let mut prio = 0;
+/// let len = mq.receive(&mut buf, &mut prio).unwrap();
+/// assert_eq!(&buf[..len], b"hello");
+/// ```
+#[derive(Debug)]
+pub struct MqD {
+    mqd: mqd_t,
+    // The name can't be retrieved from the descriptor, so it's kept for unlinking
+    name: CString,
+    unlink: bool,
+}
+
+impl MqD {
+    /// Open an existing message queue, or create one if `oflag` contains `O_CREAT`.
+    ///
+    /// See [`mq_open`](fn.mq_open.html).
+    pub fn open<P: ?Sized + NixPath>(name: &P,
+                                     oflag: MQ_OFlag,
+                                     mode: Mode,
+                                     attr: Option<&MqAttr>)
+                                     -> Result<MqD> {
+        let name = try!(name.with_nix_path(|name| name.to_owned()));
+        mq_open(&name, oflag, mode, attr).map(|mqd| {
+            MqD { mqd: mqd, name: name, unlink: false }
+        })
+    }
+
+    /// Create a new message queue with the given attributes, failing with `EEXIST` if one with
+    /// that name already exists.
+    ///
+    /// `O_CREAT` and `O_EXCL` are added to `oflag`, which must still contain the access mode.
+    pub fn create<P: ?Sized + NixPath>(name: &P, oflag: MQ_OFlag, mode: Mode, attr: &MqAttr)
+                                       -> Result<MqD> {
+        MqD::open(name, oflag | MQ_OFlag::O_CREAT | MQ_OFlag::O_EXCL, mode, Some(attr))
+    }
+
+    /// Remove the message queue's name when this descriptor is dropped.
+    ///
+    /// The queue itself is destroyed once all descriptors referring to it are closed.
+    pub fn unlink_on_drop(&mut self) {
+        self.unlink = true;
+    }
+
+    /// Receive a message, see [`mq_receive`](fn.mq_receive.html).
+    pub fn receive(&self, message: &mut [u8], msg_prio: &mut u32) -> Result<usize> {
+        mq_receive(self.mqd, message, msg_prio)
+    }
+
+    /// Receive a message with a deadline, see [`mq_timedreceive`](fn.mq_timedreceive.html).
+    pub fn timedreceive(&self, message: &mut [u8], msg_prio: &mut u32, abs_timeout: &TimeSpec)
+                        -> Result<usize> {
+        mq_timedreceive(self.mqd, message, msg_prio, abs_timeout)
+    }
+
+    /// Send a message, see [`mq_send`](fn.mq_send.html).
+    pub fn send(&self, message: &[u8], msg_prio: u32) -> Result<()> {
+        mq_send(self.mqd, message, msg_prio)
+    }
+
+    /// Send a message with a deadline, see [`mq_timedsend`](fn.mq_timedsend.html).
+    pub fn timedsend(&self, message: &[u8], msg_prio: u32, abs_timeout: &TimeSpec) -> Result<()> {
+        mq_timedsend(self.mqd, message, msg_prio, abs_timeout)
+    }
+
+    /// Register for notification of message arrival, see [`mq_notify`](fn.mq_notify.html).
+    #[cfg(any(all(target_os = "linux", target_env = "gnu"),
+              target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "netbsd"))]
+    pub fn notify(&self, notification: Option<SigevNotify>) -> Result<()> {
+        mq_notify(self.mqd, notification)
+    }
+
+    /// Get the attributes of the message queue.
+    pub fn getattr(&self) -> Result<MqAttr> {
+        mq_getattr(self.mqd)
+    }
+
+    /// Set the attributes of the message queue, returning the old ones.
+    ///
+    /// Only `O_NONBLOCK` in the flags can be changed, see [`mq_setattr`](fn.mq_setattr.html).
+    pub fn setattr(&self, attr: &MqAttr) -> Result<MqAttr> {
+        mq_setattr(self.mqd, attr)
+    }
+
+    /// Set or clear `O_NONBLOCK`, returning the old attributes.
+    pub fn set_nonblock(&self, nonblock: bool) -> Result<MqAttr> {
+        if nonblock {
+            mq_set_nonblock(self.mqd)
+        } else {
+            mq_remove_nonblock(self.mqd)
+        }
+    }
+
+    /// Get the raw message queue descriptor.
+    pub fn as_raw(&self) -> mqd_t {
+        self.mqd
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl AsRawFd for MqD {
+    fn as_raw_fd(&self) -> RawFd {
+        self.mqd
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl IntoRawFd for MqD {
+    /// The queue isn't unlinked anymore, even after
+    /// [`unlink_on_drop`](#method.unlink_on_drop).
+    fn into_raw_fd(self) -> RawFd {
+        let fd = self.mqd;
+        let name = unsafe { ptr::read(&self.name) };
+        mem::forget(self);
+        drop(name);
+        fd
+    }
+}
+
+impl Drop for MqD {
+    fn drop(&mut self) {
+        let _ = mq_close(self.mqd);
+        if self.unlink {
+            let _ = mq_unlink(&self.name);
+        }
+    }
+}
@@ -5,8 +5,20 @@
 
 use libc;
 use libc::c_uint;
+#[cfg(feature = "std")]
+use std::ffi::{CStr, CString};
 use {Result, Error, NixPath};
 
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+pub use self::nameindex::*;
+
 /// Resolve an interface into a interface number.
 pub fn if_nametoindex<P: ?Sized + NixPath>(name: &P) -> Result<c_uint> {
     let if_index = try!(name.with_nix_path(|name| unsafe { libc::if_nametoindex(name.as_ptr()) }));
@@ -18,6 +30,135 @@ pub fn if_nametoindex<P: ?Sized + NixPath>(name: &P) -> Result<c_uint> {
     }
 }
 
+/// Resolve an interface number into the name of the interface (see
+/// [if_indextoname(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/if_indextoname.html)).
+#[cfg(feature = "std")]
+pub fn if_indextoname(index: c_uint) -> Result<CString> {
+    let mut buf = [0u8; libc::IF_NAMESIZE];
+    let res = unsafe { libc::if_indextoname(index, buf.as_mut_ptr() as *mut libc::c_char) };
+
+    if res.is_null() {
+        Err(Error::last())
+    } else {
+        let name = unsafe { CStr::from_ptr(buf.as_ptr() as *const libc::c_char) };
+        Ok(name.to_owned())
+    }
+}
+
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+mod nameindex {
+    use libc::{self, c_uint};
+    use std::ffi::CStr;
+    use std::marker::PhantomData;
+    use {Error, Result};
+
+    /// The list of network interfaces, as returned by [`if_nameindex`](fn.if_nameindex.html)
+    ///
+    /// It's freed when dropped.
+    #[derive(Debug)]
+    pub struct Interfaces {
+        ptr: *mut libc::if_nameindex,
+    }
+
+    impl Interfaces {
+        /// Iterate over the interfaces
+        pub fn iter<'a>(&'a self) -> InterfacesIter<'a> {
+            InterfacesIter {
+                ptr: self.ptr,
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl Drop for Interfaces {
+        fn drop(&mut self) {
+            unsafe { libc::if_freenameindex(self.ptr) };
+        }
+    }
+
+    impl<'a> IntoIterator for &'a Interfaces {
+        type Item = Interface<'a>;
+        type IntoIter = InterfacesIter<'a>;
+
+        fn into_iter(self) -> InterfacesIter<'a> {
+            self.iter()
+        }
+    }
+
+    /// A network interface, as yielded by iterating over [`Interfaces`](struct.Interfaces.html)
+    #[derive(Clone, Copy, Debug)]
+    pub struct Interface<'a> {
+        index: c_uint,
+        name: &'a CStr,
+    }
+
+    impl<'a> Interface<'a> {
+        /// The number of the interface
+        pub fn index(&self) -> c_uint {
+            self.index
+        }
+
+        /// The name of the interface
+        pub fn name(&self) -> &'a CStr {
+            self.name
+        }
+    }
+
+    /// An iterator over the interfaces of an [`Interfaces`](struct.Interfaces.html)
+    #[derive(Debug)]
+    pub struct InterfacesIter<'a> {
+        ptr: *const libc::if_nameindex,
+        _marker: PhantomData<&'a Interfaces>,
+    }
+
+    impl<'a> Iterator for InterfacesIter<'a> {
+        type Item = Interface<'a>;
+
+        fn next(&mut self) -> Option<Interface<'a>> {
+            // The list ends with an entry whose index is 0 and name is null
+            let entry = unsafe { &*self.ptr };
+            if entry.if_index == 0 {
+                None
+            } else {
+                self.ptr = unsafe { self.ptr.offset(1) };
+                Some(Interface {
+                    index: entry.if_index,
+                    name: unsafe { CStr::from_ptr(entry.if_name) },
+                })
+            }
+        }
+    }
+
+    /// Get the number and name of every network interface (see
+    /// [if_nameindex(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/if_nameindex.html)).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nix::net::if_::if_nameindex;
+    ///
+    /// for interface in &if_nameindex().unwrap() {
+    ///     println!("{}: {}", interface.index(), interface.name().to_string_lossy());
+    /// }
+    /// ```
+    pub fn if_nameindex() -> Result<Interfaces> {
+        let ptr = unsafe { libc::if_nameindex() };
+
+        if ptr.is_null() {
+            Err(Error::last())
+        } else {
+            Ok(Interfaces { ptr: ptr })
+        }
+    }
+}
+
 libc_bitflags!(
     /// Standard interface flags, used by `getifaddrs`
     pub struct InterfaceFlags: libc::c_int {
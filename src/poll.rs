@@ -1,6 +1,16 @@
-#[cfg(any(target_os = "android", target_os = "dragonfly", target_os = "freebsd", target_os = "linux"))]
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
 use sys::time::TimeSpec;
-#[cfg(any(target_os = "android", target_os = "dragonfly", target_os = "freebsd", target_os = "linux"))]
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
 use sys::signal::SigSet;
 use std::os::unix::io::RawFd;
 
@@ -15,7 +25,9 @@ use errno::Errno;
 /// for a specific file descriptor.
 ///
 /// After a call to `poll` or `ppoll`, the events that occured can be
-/// retrieved by calling [`revents()`](#method.revents) on the `PollFd`.
+/// retrieved by calling [`revents()`](#method.revents) on the `PollFd`,
+/// and the events of interest can be changed for the next call with
+/// [`set_events()`](#method.set_events).
 #[repr(C)]
 #[derive(Clone, Copy)]
 pub struct PollFd {
@@ -35,6 +47,25 @@ impl PollFd {
         }
     }
 
+    /// Returns the file descriptor to poll.
+    pub fn fd(&self) -> RawFd {
+        self.pollfd.fd
+    }
+
+    /// Returns the events of interest.
+    pub fn events(&self) -> EventFlags {
+        EventFlags::from_bits_truncate(self.pollfd.events)
+    }
+
+    /// Replaces the events of interest, so the same `PollFd` can be reused for
+    /// the next call to `poll` or `ppoll`.
+    ///
+    /// The events that occured in the last call are cleared.
+    pub fn set_events(&mut self, events: EventFlags) {
+        self.pollfd.events = events.bits();
+        self.pollfd.revents = 0;
+    }
+
     /// Returns the events that occured in the last call to `poll` or `ppoll`.
     pub fn revents(&self) -> Option<EventFlags> {
         EventFlags::from_bits(self.pollfd.revents)
@@ -70,6 +101,14 @@ libc_bitflags! {
         POLLRDBAND;
         /// Priority data may be written.
         POLLWRBAND;
+        /// The peer of a stream socket closed the connection, or shut down
+        /// writing half of it.  Unlike `POLLHUP` this must be requested, and
+        /// lets the reader tell that the end of the data has been reached
+        /// without reading it.
+        #[cfg(any(target_os = "android",
+                  target_os = "freebsd",
+                  target_os = "linux"))]
+        POLLRDHUP;
         /// Error condition (only returned in
         /// [`PollFd::revents`](struct.PollFd.html#method.revents);
         /// ignored in [`PollFd::new`](struct.PollFd.html#method.new)).
@@ -126,16 +165,28 @@ pub fn poll(fds: &mut [PollFd], timeout: libc::c_int) -> Result<libc::c_int> {
 /// ([`poll(2)`](http://man7.org/linux/man-pages/man2/poll.2.html))
 ///
 /// `ppoll` behaves like `poll`, but let you specify what signals may interrupt it
-/// with the `sigmask` argument.
+/// with the `sigmask` argument.  The signal mask of the calling thread is
+/// replaced by `sigmask` for the duration of the call, atomically, so a signal
+/// that is otherwise blocked can't arrive between unblocking it and starting
+/// to wait.
 ///
-#[cfg(any(target_os = "android", target_os = "dragonfly", target_os = "freebsd", target_os = "linux"))]
-pub fn ppoll(fds: &mut [PollFd], timeout: TimeSpec, sigmask: SigSet) -> Result<libc::c_int> {
-
-
+/// Unlike `poll`, the timeout has nanosecond resolution, and a `timeout` of
+/// `None` means an infinite timeout.
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+pub fn ppoll(fds: &mut [PollFd], timeout: Option<TimeSpec>, sigmask: SigSet) -> Result<libc::c_int> {
+    let timeout = match timeout {
+        Some(ref t) => t.as_ref() as *const libc::timespec,
+        None => ::std::ptr::null(),
+    };
     let res = unsafe {
         libc::ppoll(fds.as_mut_ptr() as *mut libc::pollfd,
                     fds.len() as libc::nfds_t,
-                    timeout.as_ref(),
+                    timeout,
                     sigmask.as_ref())
     };
     Errno::result(res)
@@ -8,8 +8,16 @@ pub use libc::winsize as Winsize;
 use std::ffi::CStr;
 use std::mem;
 use std::os::unix::prelude::*;
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd"))]
+use std::path::PathBuf;
 
 use sys::termios::Termios;
+use unistd::{ForkResult, Pid};
 use {Result, Error, fcntl};
 use errno::Errno;
 
@@ -22,6 +30,18 @@ pub struct OpenptyResult {
     pub slave: RawFd,
 }
 
+/// Representation of a master with a forked pty
+///
+/// This is returned by `forkpty`. Note that this type does *not* implement `Drop`, so the user
+/// must manually close the file descriptors.
+#[derive(Clone, Copy)]
+pub struct ForkptyResult {
+    /// The master port in a virtual pty pair
+    pub master: RawFd,
+    /// Metadata about forked process
+    pub fork_result: ForkResult,
+}
+
 
 /// Representation of the Master device in a master/slave pty pair
 ///
@@ -31,6 +51,52 @@ pub struct OpenptyResult {
 #[derive(Debug)]
 pub struct PtyMaster(RawFd);
 
+impl PtyMaster {
+    /// Allocate a new pseudoterminal, ready for its slave to be opened.
+    ///
+    /// This performs the whole `posix_openpt()`, `grantpt()`, `unlockpt()` sequence. `flags` is
+    /// passed to `posix_openpt()` and should usually be `O_RDWR | O_NOCTTY`.
+    pub fn open(flags: fcntl::OFlag) -> Result<PtyMaster> {
+        let master = posix_openpt(flags)?;
+        grantpt(&master)?;
+        unlockpt(&master)?;
+        Ok(master)
+    }
+
+    /// Return the path of the slave device corresponding to this master.
+    ///
+    /// Unlike `ptsname()`, this is threadsafe.
+    #[cfg(any(target_os = "android",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "linux",
+              target_os = "macos",
+              target_os = "netbsd"))]
+    pub fn slave_name(&self) -> Result<PathBuf> {
+        use std::ffi::OsStr;
+
+        slave_name_bytes(self).map(|name| PathBuf::from(OsStr::from_bytes(&name)))
+    }
+
+    /// Open the slave device corresponding to this master.
+    ///
+    /// `flags` is passed to `open()` and should usually be `O_RDWR`, plus `O_NOCTTY` unless the
+    /// slave is meant to become the controlling terminal of the calling process. The returned
+    /// file descriptor is not closed automatically.
+    #[cfg(any(target_os = "android",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "linux",
+              target_os = "macos",
+              target_os = "netbsd"))]
+    pub fn open_slave(&self, flags: fcntl::OFlag) -> Result<RawFd> {
+        use sys::stat::Mode;
+
+        let name = self.slave_name()?;
+        fcntl::open(&name, flags, Mode::empty())
+    }
+}
+
 impl AsRawFd for PtyMaster {
     fn as_raw_fd(&self) -> RawFd {
         self.0
@@ -45,6 +111,14 @@ impl IntoRawFd for PtyMaster {
     }
 }
 
+impl FromRawFd for PtyMaster {
+    /// Take ownership of `fd`, which must be the open master of a
+    /// pseudoterminal, not owned by anything else.
+    unsafe fn from_raw_fd(fd: RawFd) -> PtyMaster {
+        PtyMaster(fd)
+    }
+}
+
 impl Drop for PtyMaster {
     fn drop(&mut self) {
         // On drop, we ignore errors like EINTR and EIO because there's no clear
@@ -154,17 +228,33 @@ pub unsafe fn ptsname(fd: &PtyMaster) -> Result<String> {
 ///
 /// `ptsname_r()` returns the name of the slave pseudoterminal device corresponding to the master
 /// referred to by `fd`. This is the threadsafe version of `ptsname()`, but it is not part of the
-/// POSIX standard and is instead a Linux-specific extension.
+/// POSIX standard and is instead an extension available on Linux, FreeBSD and NetBSD.
 ///
 /// This value is useful for opening the slave ptty once the master has already been opened with
 /// `posix_openpt()`.
-#[cfg(any(target_os = "android", target_os = "linux"))]
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd"))]
 #[inline]
 pub fn ptsname_r(fd: &PtyMaster) -> Result<String> {
+    let name = slave_name_bytes(fd)?;
+    let name = String::from_utf8(name)?;
+    Ok(name)
+}
+
+/// Get the name of the slave pseudoterminal as raw bytes, in a threadsafe manner.
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd"))]
+fn slave_name_bytes(fd: &PtyMaster) -> Result<Vec<u8>> {
     let mut name_buf = vec![0u8; 64];
     let name_buf_ptr = name_buf.as_mut_ptr() as *mut libc::c_char;
-    if unsafe { libc::ptsname_r(fd.as_raw_fd(), name_buf_ptr, name_buf.capacity()) } != 0 {
-        return Err(Error::last());
+    let res = unsafe { libc::ptsname_r(fd.as_raw_fd(), name_buf_ptr, name_buf.len()) };
+    if res != 0 {
+        // glibc sets errno while other implementations return the error instead.
+        return Err(Error::Sys(if res < 0 { Errno::last() } else { Errno::from_i32(res) }));
     }
 
     // Find the first null-character terminating this string. This is guaranteed to succeed if the
@@ -172,8 +262,23 @@ pub fn ptsname_r(fd: &PtyMaster) -> Result<String> {
     let null_index = name_buf.iter().position(|c| *c == b'\0').unwrap();
     name_buf.truncate(null_index);
 
-    let name = String::from_utf8(name_buf)?;
-    Ok(name)
+    Ok(name_buf)
+}
+
+/// Get the name of the slave pseudoterminal as raw bytes, in a threadsafe manner.
+///
+/// Darwin lacks `ptsname_r()`, but its `ptsname()` is itself implemented with this `ioctl`.
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+fn slave_name_bytes(fd: &PtyMaster) -> Result<Vec<u8>> {
+    // The name is written to a buffer of 128 bytes, the size encoded in the request
+    let mut name_buf = [0u8; 128];
+    let res = unsafe {
+        libc::ioctl(fd.as_raw_fd(), libc::TIOCPTYGNAME as libc::c_ulong, name_buf.as_mut_ptr())
+    };
+    Errno::result(res)?;
+
+    let null_index = name_buf.iter().position(|c| *c == b'\0').unwrap_or(name_buf.len());
+    Ok(name_buf[..null_index].to_vec())
 }
 
 /// Unlock a pseudoterminal master/slave pseudoterminal pair (see
@@ -263,3 +368,44 @@ pub fn openpty<'a, 'b, T: Into<Option<&'a Winsize>>, U: Into<Option<&'b Termios>
         slave: slave,
     })
 }
+
+/// Create a new pseudoterminal and fork a child process with the slave as its controlling
+/// terminal (see [`forkpty`](http://man7.org/linux/man-pages/man3/forkpty.3.html)).
+///
+/// In the child, the slave side of the pseudoterminal becomes standard input, output and error,
+/// and the child is made the leader of a new session. In the parent, `master` refers to the
+/// master side of the pseudoterminal; in the child it is not valid.
+///
+/// If `winsize` is not `None`, the window size of the slave will be set to the values in
+/// `winsize`. If `termios` is not `None`, the pseudoterminal's terminal settings of the slave will
+/// be set to the values in `termios`.
+///
+/// The same restrictions as for [`fork`](../unistd/fn.fork.html) apply to the code that may run
+/// in the child.
+pub fn forkpty<'a, 'b, T: Into<Option<&'a Winsize>>, U: Into<Option<&'b Termios>>>(winsize: T, termios: U) -> Result<ForkptyResult> {
+    use std::ptr;
+
+    let mut master: libc::c_int = -1;
+
+    let termios = termios.into().map(|termios| termios.get_libc_termios());
+    let term = match termios {
+        Some(ref inner_termios) => &**inner_termios as *const libc::termios as *mut _,
+        None => ptr::null_mut(),
+    };
+    let win = winsize
+        .into()
+        .map(|winsize| winsize as *const Winsize as *mut _)
+        .unwrap_or(ptr::null_mut());
+
+    let res = unsafe { libc::forkpty(&mut master, ptr::null_mut(), term, win) };
+
+    let fork_result = Errno::result(res).map(|res| match res {
+        0 => ForkResult::Child,
+        res => ForkResult::Parent { child: Pid::from_raw(res) },
+    })?;
+
+    Ok(ForkptyResult {
+        master: master,
+        fork_result: fork_result,
+    })
+}
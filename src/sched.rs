@@ -1,10 +1,12 @@
 use std::mem;
 use std::os::unix::io::RawFd;
-use std::option::Option;
-use libc::{self, c_int, c_void};
+use libc;
+#[cfg(feature = "std")]
+use libc::{c_int, c_void};
 use {Error, Result};
 use errno::Errno;
 use ::unistd::Pid;
+use sys::time::TimeSpec;
 
 // For some functions taking with a parameter of type CloneFlags,
 // only a subset of these flags have an effect.
@@ -36,6 +38,7 @@ libc_bitflags!{
     }
 }
 
+#[cfg(feature = "std")]
 pub type CloneCb<'a> = Box<FnMut() -> isize + 'a>;
 
 #[repr(C)]
@@ -74,6 +77,65 @@ impl CpuSet {
     }
 }
 
+/// A set of CPUs, sized when it's created (see
+/// [`CPU_ALLOC(3)`](http://man7.org/linux/man-pages/man3/CPU_ALLOC.3.html)).
+///
+/// Unlike a `CpuSet`, which holds the first 1024 CPUs, it can hold every CPU
+/// of hosts with more.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DynCpuSet {
+    // The kernel's cpumask: bit `n % BITS` of word `n / BITS` is CPU `n`
+    words: Vec<libc::c_ulong>,
+}
+
+#[cfg(feature = "std")]
+impl DynCpuSet {
+    const BITS: usize = 8 * mem::size_of::<libc::c_ulong>();
+
+    /// An empty set, with room for at least `cpus` CPUs.
+    pub fn new(cpus: usize) -> DynCpuSet {
+        DynCpuSet { words: vec![0; (cpus + Self::BITS - 1) / Self::BITS] }
+    }
+
+    /// The number of CPUs the set has room for.
+    pub fn cpus(&self) -> usize {
+        self.words.len() * Self::BITS
+    }
+
+    /// Fails with `EINVAL` if `cpu` is beyond the room of the set.
+    pub fn is_set(&self, cpu: usize) -> Result<bool> {
+        let word = try!(self.words.get(cpu / Self::BITS).ok_or(Error::Sys(Errno::EINVAL)));
+        Ok(word & (1 << (cpu % Self::BITS)) != 0)
+    }
+
+    /// Fails with `EINVAL` if `cpu` is beyond the room of the set.
+    pub fn set(&mut self, cpu: usize) -> Result<()> {
+        let word = try!(self.words.get_mut(cpu / Self::BITS).ok_or(Error::Sys(Errno::EINVAL)));
+        *word |= 1 << (cpu % Self::BITS);
+        Ok(())
+    }
+
+    /// Fails with `EINVAL` if `cpu` is beyond the room of the set.
+    pub fn unset(&mut self, cpu: usize) -> Result<()> {
+        let word = try!(self.words.get_mut(cpu / Self::BITS).ok_or(Error::Sys(Errno::EINVAL)));
+        *word &= !(1 << (cpu % Self::BITS));
+        Ok(())
+    }
+
+    pub(crate) fn size(&self) -> libc::size_t {
+        (self.words.len() * mem::size_of::<libc::c_ulong>()) as libc::size_t
+    }
+
+    pub(crate) fn as_ptr(&self) -> *const libc::cpu_set_t {
+        self.words.as_ptr() as *const libc::cpu_set_t
+    }
+
+    pub(crate) fn as_mut_ptr(&mut self) -> *mut libc::cpu_set_t {
+        self.words.as_mut_ptr() as *mut libc::cpu_set_t
+    }
+}
+
 pub fn sched_setaffinity(pid: Pid, cpuset: &CpuSet) -> Result<()> {
     let res = unsafe {
         libc::sched_setaffinity(pid.into(),
@@ -84,6 +146,32 @@ pub fn sched_setaffinity(pid: Pid, cpuset: &CpuSet) -> Result<()> {
     Errno::result(res).map(drop)
 }
 
+/// Relinquish the CPU, so that another thread may run before the calling one
+/// (see
+/// [`sched_yield(2)`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/sched_yield.html)).
+///
+/// The calling thread is moved to the end of the queue of threads of its
+/// priority, and another one is run if there is any.
+pub fn sched_yield() -> Result<()> {
+    let res = unsafe { libc::sched_yield() };
+
+    Errno::result(res).map(drop)
+}
+
+/// Get the time slice of a process under the `SCHED_RR` policy (see
+/// [`sched_rr_get_interval(2)`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/sched_rr_get_interval.html)).
+///
+/// A `pid` of 0 means the calling thread.  On Linux, the interval of a process
+/// under another policy is returned too: zero for `SCHED_FIFO`, and the time
+/// slice it would have otherwise.
+pub fn sched_rr_get_interval(pid: Pid) -> Result<TimeSpec> {
+    let mut interval: libc::timespec = unsafe { mem::zeroed() };
+    let res = unsafe { libc::sched_rr_get_interval(pid.into(), &mut interval) };
+
+    Errno::result(res).map(|_| TimeSpec::from(interval))
+}
+
+#[cfg(feature = "std")]
 pub fn clone(mut cb: CloneCb,
              stack: &mut [u8],
              flags: CloneFlags,
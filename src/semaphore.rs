@@ -0,0 +1,334 @@
+//! POSIX semaphores
+//!
+//! A named semaphore is created or opened by name with
+//! [`NamedSemaphore::open`](struct.NamedSemaphore.html#method.open), so that
+//! unrelated processes can synchronize through it.  An
+//! [`UnnamedSemaphore`](struct.UnnamedSemaphore.html) is placed in memory
+//! shared by the processes instead.
+//!
+//! [Further reading and details on the C API](http://man7.org/linux/man-pages/man7/sem_overview.7.html)
+//!
+//! # Examples
+//!
+//! ```
+//! # use nix::fcntl::OFlag;
+//! # use nix::semaphore::NamedSemaphore;
+//! # use nix::sys::stat::Mode;
+//! let mut sem = NamedSemaphore::open("/nix-doc-semaphore", OFlag::O_CREAT,
+//!                                    Mode::S_IRUSR | Mode::S_IWUSR, 1).unwrap();
+//! sem.unlink_on_drop();
+//!
+//! sem.wait().unwrap();
+//! // ... critical section ...
+//! sem.post().unwrap();
+//! ```
+use libc::{self, c_uint};
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+use libc::{c_int, c_void};
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+use std::cell::UnsafeCell;
+use std::ffi::CString;
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+use std::mem;
+use errno::Errno;
+use fcntl::OFlag;
+use sys::stat::Mode;
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+use sys::time::TimeSpec;
+use {Error, NixPath, Result};
+
+/// A named semaphore, which is closed when dropped.
+///
+/// See [sem_overview(7)](http://man7.org/linux/man-pages/man7/sem_overview.7.html).
+#[derive(Debug)]
+pub struct NamedSemaphore {
+    sem: *mut libc::sem_t,
+    // The name can't be retrieved from the semaphore, so it's kept for unlinking
+    name: CString,
+    unlink: bool,
+}
+
+// The semaphore functions are thread-safe
+unsafe impl Send for NamedSemaphore {}
+unsafe impl Sync for NamedSemaphore {}
+
+impl NamedSemaphore {
+    /// Open an existing named semaphore, or create one if `oflag` contains
+    /// `O_CREAT` (see
+    /// [sem_open(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/sem_open.html)).
+    ///
+    /// The name should start with a slash and contain no other.  `O_CREAT`
+    /// and `O_EXCL` are the only meaningful flags; a new semaphore gets the
+    /// permissions in `mode` and the initial `value`, which are ignored
+    /// otherwise.
+    pub fn open<P: ?Sized + NixPath>(name: &P, oflag: OFlag, mode: Mode, value: c_uint)
+                                     -> Result<NamedSemaphore> {
+        let (sem, name) = try!(name.with_nix_path(|cstr| {
+            let sem = unsafe {
+                libc::sem_open(cstr.as_ptr(), oflag.bits(), mode.bits() as c_uint, value)
+            };
+            (sem, cstr.to_owned())
+        }));
+
+        if sem == libc::SEM_FAILED {
+            Err(Error::Sys(Errno::last()))
+        } else {
+            Ok(NamedSemaphore { sem: sem, name: name, unlink: false })
+        }
+    }
+
+    /// Create a new named semaphore, failing with `EEXIST` if one with that
+    /// name already exists.
+    ///
+    /// This is [`open`](#method.open) with `O_CREAT` and `O_EXCL`.
+    pub fn create<P: ?Sized + NixPath>(name: &P, mode: Mode, value: c_uint)
+                                       -> Result<NamedSemaphore> {
+        NamedSemaphore::open(name, OFlag::O_CREAT | OFlag::O_EXCL, mode, value)
+    }
+
+    /// Remove the semaphore's name when it is dropped.
+    ///
+    /// The semaphore itself is destroyed once all processes have closed it.
+    pub fn unlink_on_drop(&mut self) {
+        self.unlink = true;
+    }
+
+    /// Decrement the semaphore, blocking while its value is zero (see
+    /// [sem_wait(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/sem_wait.html)).
+    ///
+    /// Fails with `EINTR` if a signal handler interrupts the wait.
+    pub fn wait(&self) -> Result<()> {
+        sem_wait(self.sem)
+    }
+
+    /// Decrement the semaphore, or fail with `EAGAIN` if its value is zero
+    /// (see
+    /// [sem_trywait(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/sem_trywait.html)).
+    pub fn try_wait(&self) -> Result<()> {
+        sem_trywait(self.sem)
+    }
+
+    /// Decrement the semaphore, blocking while its value is zero until the
+    /// absolute time `abs_timeout` of `CLOCK_REALTIME`, then failing with
+    /// `ETIMEDOUT` (see
+    /// [sem_timedwait(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/sem_timedwait.html)).
+    #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+    pub fn timed_wait(&self, abs_timeout: &TimeSpec) -> Result<()> {
+        sem_timedwait(self.sem, abs_timeout)
+    }
+
+    /// Increment the semaphore, waking up a waiter (see
+    /// [sem_post(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/sem_post.html)).
+    pub fn post(&self) -> Result<()> {
+        sem_post(self.sem)
+    }
+
+    /// Get the current value of the semaphore (see
+    /// [sem_getvalue(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/sem_getvalue.html)).
+    #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+    pub fn value(&self) -> Result<c_int> {
+        sem_getvalue(self.sem)
+    }
+}
+
+impl Drop for NamedSemaphore {
+    fn drop(&mut self) {
+        unsafe { libc::sem_close(self.sem) };
+        if self.unlink {
+            let _ = sem_unlink(self.name.as_c_str());
+        }
+    }
+}
+
+/// Remove a named semaphore (see
+/// [sem_unlink(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/sem_unlink.html)).
+///
+/// Processes that have it open can keep using it.
+pub fn sem_unlink<P: ?Sized + NixPath>(name: &P) -> Result<()> {
+    let res = try!(name.with_nix_path(|cstr| {
+        unsafe { libc::sem_unlink(cstr.as_ptr()) }
+    }));
+
+    Errno::result(res).map(drop)
+}
+
+// The operations shared by named and unnamed semaphores.  The semaphore functions
+// are thread-safe, so they only need a pointer to a valid semaphore.
+fn sem_wait(sem: *mut libc::sem_t) -> Result<()> {
+    let res = unsafe { libc::sem_wait(sem) };
+
+    Errno::result(res).map(drop)
+}
+
+fn sem_trywait(sem: *mut libc::sem_t) -> Result<()> {
+    let res = unsafe { libc::sem_trywait(sem) };
+
+    Errno::result(res).map(drop)
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+fn sem_timedwait(sem: *mut libc::sem_t, abs_timeout: &TimeSpec) -> Result<()> {
+    let res = unsafe { libc::sem_timedwait(sem, abs_timeout.as_ref()) };
+
+    Errno::result(res).map(drop)
+}
+
+fn sem_post(sem: *mut libc::sem_t) -> Result<()> {
+    let res = unsafe { libc::sem_post(sem) };
+
+    Errno::result(res).map(drop)
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+fn sem_getvalue(sem: *mut libc::sem_t) -> Result<c_int> {
+    let mut value = 0;
+    let res = unsafe { libc::sem_getvalue(sem, &mut value) };
+
+    Errno::result(res).map(|_| value)
+}
+
+/// An unnamed semaphore, which lives in memory provided by the caller.
+///
+/// Placed in memory shared between processes, such as a `MAP_SHARED` mapping
+/// or a System V shared memory segment, and initialized with `pshared`, it
+/// synchronizes those processes.  Initialized to 1, it serves as a mutex
+/// between them; see also
+/// [`PthreadMutex`](../sys/pthread/struct.PthreadMutex.html).
+///
+/// It must not be moved after it's initialized, so it's only ever accessed by
+/// reference.
+///
+/// # Examples
+///
+/// Share a semaphore with a child process:
+///
+/// ```
+/// # extern crate libc;
+/// # extern crate nix;
+/// # use std::ptr;
+/// # use nix::semaphore::UnnamedSemaphore;
+/// # use nix::sys::mman::*;
+/// # use nix::sys::wait::waitpid;
+/// # use nix::unistd::{fork, ForkResult};
+/// # fn main() {
+/// let region = unsafe {
+///     mmap(ptr::null_mut(), UnnamedSemaphore::size(), ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+///          MapFlags::MAP_SHARED | MapFlags::MAP_ANONYMOUS, -1, 0).unwrap()
+/// };
+/// let sem = unsafe { UnnamedSemaphore::init(region, true, 0) }.unwrap();
+///
+/// match fork().unwrap() {
+///     ForkResult::Child => {
+///         sem.post().unwrap();
+///         unsafe { libc::_exit(0) };
+///     }
+///     ForkResult::Parent { child } => {
+///         sem.wait().unwrap();
+///         waitpid(child, None).unwrap();
+///     }
+/// }
+///
+/// unsafe {
+///     sem.destroy().unwrap();
+///     munmap(region, UnnamedSemaphore::size()).unwrap();
+/// }
+/// # }
+/// ```
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+#[repr(C)]
+pub struct UnnamedSemaphore(UnsafeCell<libc::sem_t>);
+
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+unsafe impl Send for UnnamedSemaphore {}
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+unsafe impl Sync for UnnamedSemaphore {}
+
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+impl UnnamedSemaphore {
+    /// The number of bytes a semaphore occupies in memory
+    pub fn size() -> usize {
+        mem::size_of::<UnnamedSemaphore>()
+    }
+
+    /// The alignment a semaphore requires in memory
+    pub fn align() -> usize {
+        mem::align_of::<UnnamedSemaphore>()
+    }
+
+    /// Initialize a semaphore with the given `value` at `addr` (see
+    /// [sem_init(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/sem_init.html)).
+    ///
+    /// With `pshared`, the semaphore can be used by every process that can
+    /// access the memory; otherwise only by the threads of this process.  Fails
+    /// with `EINVAL` if `addr` isn't suitably aligned.
+    ///
+    /// # Safety
+    ///
+    /// `addr` must point to [`size`](#method.size) writable bytes that stay
+    /// valid for `'a`, and no semaphore must already be in use there.
+    pub unsafe fn init<'a>(addr: *mut c_void, pshared: bool, value: c_uint)
+                           -> Result<&'a UnnamedSemaphore> {
+        if addr as usize % UnnamedSemaphore::align() != 0 {
+            return Err(Error::invalid_argument());
+        }
+        let sem = addr as *mut UnnamedSemaphore;
+        let res = libc::sem_init((*sem).0.get(), pshared as c_int, value);
+
+        Errno::result(res).map(|_| &*sem)
+    }
+
+    /// Get a reference to a semaphore that was already initialized at `addr`,
+    /// for instance by another process.
+    ///
+    /// # Safety
+    ///
+    /// `addr` must point to a semaphore initialized with
+    /// [`init`](#method.init), which stays valid for `'a`.
+    pub unsafe fn from_ptr<'a>(addr: *mut c_void) -> &'a UnnamedSemaphore {
+        &*(addr as *const UnnamedSemaphore)
+    }
+
+    /// Destroy the semaphore (see
+    /// [sem_destroy(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/sem_destroy.html)).
+    ///
+    /// # Safety
+    ///
+    /// No thread or process may use the semaphore afterwards, unless it's
+    /// initialized again.
+    pub unsafe fn destroy(&self) -> Result<()> {
+        let res = libc::sem_destroy(self.0.get());
+
+        Errno::result(res).map(drop)
+    }
+
+    /// Decrement the semaphore, blocking while its value is zero (see
+    /// [`NamedSemaphore::wait`](struct.NamedSemaphore.html#method.wait)).
+    pub fn wait(&self) -> Result<()> {
+        sem_wait(self.0.get())
+    }
+
+    /// Decrement the semaphore, or fail with `EAGAIN` if its value is zero
+    /// (see [`NamedSemaphore::try_wait`](struct.NamedSemaphore.html#method.try_wait)).
+    pub fn try_wait(&self) -> Result<()> {
+        sem_trywait(self.0.get())
+    }
+
+    /// Decrement the semaphore, blocking while its value is zero until an
+    /// absolute deadline (see
+    /// [`NamedSemaphore::timed_wait`](struct.NamedSemaphore.html#method.timed_wait)).
+    pub fn timed_wait(&self, abs_timeout: &TimeSpec) -> Result<()> {
+        sem_timedwait(self.0.get(), abs_timeout)
+    }
+
+    /// Increment the semaphore, waking up a waiter (see
+    /// [`NamedSemaphore::post`](struct.NamedSemaphore.html#method.post)).
+    pub fn post(&self) -> Result<()> {
+        sem_post(self.0.get())
+    }
+
+    /// Get the current value of the semaphore (see
+    /// [`NamedSemaphore::value`](struct.NamedSemaphore.html#method.value)).
+    pub fn value(&self) -> Result<c_int> {
+        sem_getvalue(self.0.get())
+    }
+}
@@ -23,7 +23,7 @@
 use {Error, Result};
 use bytes::{Bytes, BytesMut};
 use errno::Errno;
-use std::os::unix::io::RawFd;
+use std::os::unix::io::{AsRawFd, RawFd};
 use libc::{c_void, off_t, size_t};
 use libc;
 use std::fmt;
@@ -43,7 +43,8 @@ libc_enum! {
         /// do it like `fsync`
         O_SYNC,
         /// on supported operating systems only, do it like `fdatasync`
-        #[cfg(any(target_os = "ios",
+        #[cfg(any(target_os = "freebsd",
+                  target_os = "ios",
                   target_os = "linux",
                   target_os = "macos",
                   target_os = "netbsd",
@@ -1001,7 +1002,7 @@ pub fn aio_suspend(list: &[&AioCb], timeout: Option<TimeSpec>) -> Result<()> {
 ///     SigevNotify::SigevNone,
 ///     LioOpcode::LIO_WRITE);
 /// lio_listio(LioMode::LIO_WAIT,
-///            &[&mut aiocb],
+///            &mut [&mut aiocb],
 ///            SigevNotify::SigevNone).unwrap();
 /// assert_eq!(aiocb.aio_return().unwrap() as usize, WBUF.len());
 /// # }
@@ -1011,15 +1012,35 @@ pub fn aio_suspend(list: &[&AioCb], timeout: Option<TimeSpec>) -> Result<()> {
 ///
 /// [`lio_listio`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/lio_listio.html)
 #[cfg(not(any(target_os = "ios", target_os = "macos")))]
-pub fn lio_listio(mode: LioMode, list: &[&mut AioCb],
+pub fn lio_listio(mode: LioMode, list: &mut [&mut AioCb],
                   sigev_notify: SigevNotify) -> Result<()> {
-    let sigev = SigEvent::new(sigev_notify);
-    let sigevp = &mut sigev.sigevent() as *mut libc::sigevent;
-    let plist = list as *const [&mut AioCb] as *const [*mut libc::aiocb];
-    let p = plist as *const *mut libc::aiocb;
-    Errno::result(unsafe {
-        libc::lio_listio(mode as i32, p, list.len() as i32, sigevp)
-    }).map(drop)
+    let mut sigevent = SigEvent::new(sigev_notify).sigevent();
+    let mut plist: Vec<*mut libc::aiocb> = list.iter_mut()
+        .map(|aiocb| &mut aiocb.aiocb as *mut libc::aiocb)
+        .collect();
+    let res = unsafe {
+        libc::lio_listio(mode as i32, plist.as_mut_ptr(), plist.len() as i32,
+                         &mut sigevent)
+    };
+    match Errno::result(res) {
+        // None of the requests were queued
+        Err(Error::Sys(Errno::EAGAIN)) | Err(Error::Sys(Errno::EINVAL)) => (),
+        // Otherwise the requests must be completed with aio_return, even if
+        // some of them failed
+        _ => for aiocb in list.iter_mut() {
+            if aiocb.aiocb.aio_lio_opcode != libc::LIO_NOP {
+                aiocb.in_progress = true;
+            }
+        }
+    }
+    Errno::result(res).map(drop)
+}
+
+impl<'a> AsRawFd for AioCb<'a> {
+    /// The file descriptor the `AioCb` operates on, which it doesn't own
+    fn as_raw_fd(&self) -> RawFd {
+        self.aiocb.aio_fildes
+    }
 }
 
 impl<'a> Debug for AioCb<'a> {
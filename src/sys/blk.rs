@@ -0,0 +1,85 @@
+//! Query and manipulate block devices (see
+//! [`linux/fs.h`](https://elixir.bootlin.com/linux/latest/source/include/uapi/linux/fs.h)).
+//!
+//! These wrappers operate on a file descriptor referring to an open block
+//! device, such as `/dev/sda` or `/dev/loop0`.  Calling them on any other kind
+//! of file fails with `ENOTTY`.
+use libc::{c_int, c_uint};
+use std::mem;
+use std::os::unix::io::RawFd;
+use Result;
+
+/// The `ioctl` group used by the block layer.
+const BLK_IOC_MAGIC: u8 = 0x12;
+
+mod ioctls {
+    use libc::{c_int, c_uint, size_t};
+    use super::BLK_IOC_MAGIC;
+
+    // BLKSSZGET and friends predate the encoded ioctl numbers, so although
+    // they're declared with _IO they still transfer data through a pointer.
+    ioctl_read_bad!(blksszget, request_code_none!(BLK_IOC_MAGIC, 104), c_int);
+    ioctl_read_bad!(blkpbszget, request_code_none!(BLK_IOC_MAGIC, 123), c_uint);
+    ioctl_write_ptr_bad!(blkdiscard, request_code_none!(BLK_IOC_MAGIC, 119), [u64; 2]);
+    ioctl_write_ptr_bad!(blksecdiscard, request_code_none!(BLK_IOC_MAGIC, 125), [u64; 2]);
+    ioctl_write_ptr_bad!(blkzeroout, request_code_none!(BLK_IOC_MAGIC, 127), [u64; 2]);
+    // The kernel encodes BLKGETSIZE64 with the size of a `size_t`, but always
+    // writes back a 64-bit value.
+    ioctl_read_bad!(blkgetsize64, request_code_read!(BLK_IOC_MAGIC, 114, ::std::mem::size_of::<size_t>()), u64);
+}
+
+/// Return the size of the block device in bytes (`BLKGETSIZE64`).
+pub fn size(fd: RawFd) -> Result<u64> {
+    let mut size: u64 = 0;
+    unsafe { ioctls::blkgetsize64(fd, &mut size) }.map(|_| size)
+}
+
+/// Return the logical sector size of the block device in bytes (`BLKSSZGET`).
+///
+/// This is the smallest unit the device can address, and the granularity
+/// required for offsets and lengths passed to [`discard`](fn.discard.html) and
+/// friends.
+pub fn logical_sector_size(fd: RawFd) -> Result<c_int> {
+    let mut size: c_int = 0;
+    unsafe { ioctls::blksszget(fd, &mut size) }.map(|_| size)
+}
+
+/// Return the physical sector size of the block device in bytes (`BLKPBSZGET`).
+///
+/// This is the smallest unit the device can write without a read-modify-write
+/// cycle, and may be larger than the logical sector size.
+pub fn physical_sector_size(fd: RawFd) -> Result<c_uint> {
+    let mut size: c_uint = 0;
+    unsafe { ioctls::blkpbszget(fd, &mut size) }.map(|_| size)
+}
+
+/// Discard the given byte range of the block device (`BLKDISCARD`).
+///
+/// This tells the device that the range no longer holds useful data, which on
+/// SSDs and thinly-provisioned storage frees the underlying space. The
+/// contents of the range are undefined afterwards. Both `offset` and `len` must
+/// be multiples of the logical sector size.
+pub fn discard(fd: RawFd, offset: u64, len: u64) -> Result<()> {
+    let range = [offset, len];
+    unsafe { ioctls::blkdiscard(fd, &range) }.map(mem::drop)
+}
+
+/// Securely discard the given byte range of the block device (`BLKSECDISCARD`).
+///
+/// Like [`discard`](fn.discard.html), but additionally requires the device to
+/// erase any copies of the data it may hold, e.g. in remapped blocks. Devices
+/// without secure discard support fail with `EOPNOTSUPP`.
+pub fn secure_discard(fd: RawFd, offset: u64, len: u64) -> Result<()> {
+    let range = [offset, len];
+    unsafe { ioctls::blksecdiscard(fd, &range) }.map(mem::drop)
+}
+
+/// Zero the given byte range of the block device (`BLKZEROOUT`).
+///
+/// Unlike [`discard`](fn.discard.html), subsequent reads of the range are
+/// guaranteed to return zeroes. Both `offset` and `len` must be multiples of
+/// the logical sector size.
+pub fn zero_out(fd: RawFd, offset: u64, len: u64) -> Result<()> {
+    let range = [offset, len];
+    unsafe { ioctls::blkzeroout(fd, &range) }.map(mem::drop)
+}
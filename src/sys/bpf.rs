@@ -0,0 +1,397 @@
+//! Load eBPF programs and manage eBPF maps (see
+//! [bpf(2)](http://man7.org/linux/man-pages/man2/bpf.2.html)).
+//!
+//! These are the basic commands, enough to load compiled programs and share
+//! data with them through maps.  Other commands can be issued with the raw
+//! [`bpf`](fn.bpf.html).  Programs and maps are file descriptors, which keep
+//! them alive until closed, unless they're pinned to the BPF file system.
+//!
+//! Unless `/proc/sys/kernel/unprivileged_bpf_disabled` is 0, every command
+//! requires `CAP_BPF` or `CAP_SYS_ADMIN`, and fails with `EPERM` otherwise.
+use libc::{self, c_int, c_uint, c_void};
+use std::os::unix::io::RawFd;
+use std::{cmp, mem, ptr};
+use errno::Errno;
+use {Error, NixPath, Result};
+
+// libc doesn't export bpf, so these values are taken from linux/bpf.h
+
+/// Commands of [`bpf`](fn.bpf.html)
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BpfCmd {
+    /// Create a map
+    BPF_MAP_CREATE = 0,
+    /// Look up an element of a map
+    BPF_MAP_LOOKUP_ELEM = 1,
+    /// Add or replace an element of a map
+    BPF_MAP_UPDATE_ELEM = 2,
+    /// Remove an element of a map
+    BPF_MAP_DELETE_ELEM = 3,
+    /// Get the key after another one in a map
+    BPF_MAP_GET_NEXT_KEY = 4,
+    /// Verify and load a program
+    BPF_PROG_LOAD = 5,
+    /// Pin a program or map to a path on a BPF file system
+    BPF_OBJ_PIN = 6,
+    /// Open a pinned program or map
+    BPF_OBJ_GET = 7,
+    /// Attach a program to a cgroup, socket map or other hook
+    BPF_PROG_ATTACH = 8,
+    /// Detach a program attached with `BPF_PROG_ATTACH`
+    BPF_PROG_DETACH = 9,
+    /// Run a program on test input
+    BPF_PROG_TEST_RUN = 10,
+    /// Get the ID of the next loaded program
+    BPF_PROG_GET_NEXT_ID = 11,
+    /// Get the ID of the next map
+    BPF_MAP_GET_NEXT_ID = 12,
+    /// Open the program with an ID
+    BPF_PROG_GET_FD_BY_ID = 13,
+    /// Open the map with an ID
+    BPF_MAP_GET_FD_BY_ID = 14,
+    /// Get information about a program or map
+    BPF_OBJ_GET_INFO_BY_FD = 15,
+}
+
+/// Types of maps, for [`map_create`](fn.map_create.html)
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MapType {
+    /// A hash table
+    BPF_MAP_TYPE_HASH = 1,
+    /// An array, whose elements are indexed by a `u32` and can't be removed
+    BPF_MAP_TYPE_ARRAY = 2,
+    /// An array of programs, for tail calls from one program to another
+    BPF_MAP_TYPE_PROG_ARRAY = 3,
+    /// An array of perf event file descriptors, for programs to send events to
+    BPF_MAP_TYPE_PERF_EVENT_ARRAY = 4,
+    /// A hash table with a value for every CPU
+    BPF_MAP_TYPE_PERCPU_HASH = 5,
+    /// An array with a value for every CPU
+    BPF_MAP_TYPE_PERCPU_ARRAY = 6,
+    /// Stack traces, recorded by programs
+    BPF_MAP_TYPE_STACK_TRACE = 7,
+    /// An array of cgroup file descriptors
+    BPF_MAP_TYPE_CGROUP_ARRAY = 8,
+    /// A hash table removing the least recently used element when full
+    BPF_MAP_TYPE_LRU_HASH = 9,
+    /// An LRU hash table with a value for every CPU
+    BPF_MAP_TYPE_LRU_PERCPU_HASH = 10,
+    /// A trie, matching keys by their longest prefix, like IP addresses
+    BPF_MAP_TYPE_LPM_TRIE = 11,
+    /// An array of other maps
+    BPF_MAP_TYPE_ARRAY_OF_MAPS = 12,
+    /// A hash table of other maps
+    BPF_MAP_TYPE_HASH_OF_MAPS = 13,
+    /// Network devices, for XDP programs to redirect packets to
+    BPF_MAP_TYPE_DEVMAP = 14,
+    /// An array of sockets, for programs to redirect messages to
+    BPF_MAP_TYPE_SOCKMAP = 15,
+    /// CPUs, for XDP programs to redirect packets to
+    BPF_MAP_TYPE_CPUMAP = 16,
+    /// AF_XDP sockets, for XDP programs to redirect packets to
+    BPF_MAP_TYPE_XSKMAP = 17,
+    /// A hash table of sockets, for programs to redirect messages to
+    BPF_MAP_TYPE_SOCKHASH = 18,
+}
+
+/// Types of programs, for [`prog_load`](fn.prog_load.html)
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ProgType {
+    /// Filter the packets received by a socket
+    BPF_PROG_TYPE_SOCKET_FILTER = 1,
+    /// Run on a kprobe or uprobe
+    BPF_PROG_TYPE_KPROBE = 2,
+    /// Classify packets in the traffic control layer
+    BPF_PROG_TYPE_SCHED_CLS = 3,
+    /// Act on packets in the traffic control layer
+    BPF_PROG_TYPE_SCHED_ACT = 4,
+    /// Run on a tracepoint
+    BPF_PROG_TYPE_TRACEPOINT = 5,
+    /// Process packets in the driver, before the network stack (XDP)
+    BPF_PROG_TYPE_XDP = 6,
+    /// Run on a perf event
+    BPF_PROG_TYPE_PERF_EVENT = 7,
+    /// Filter the packets of the processes in a cgroup
+    BPF_PROG_TYPE_CGROUP_SKB = 8,
+    /// Run when the processes in a cgroup create sockets
+    BPF_PROG_TYPE_CGROUP_SOCK = 9,
+    /// Process incoming packets of a lightweight tunnel
+    BPF_PROG_TYPE_LWT_IN = 10,
+    /// Process outgoing packets of a lightweight tunnel
+    BPF_PROG_TYPE_LWT_OUT = 11,
+    /// Encapsulate the packets transmitted by a lightweight tunnel
+    BPF_PROG_TYPE_LWT_XMIT = 12,
+    /// Set the options of TCP connections
+    BPF_PROG_TYPE_SOCK_OPS = 13,
+    /// Process the packets of the sockets in a socket map
+    BPF_PROG_TYPE_SK_SKB = 14,
+    /// Control access to devices for the processes in a cgroup
+    BPF_PROG_TYPE_CGROUP_DEVICE = 15,
+    /// Process the messages sent on the sockets in a socket map
+    BPF_PROG_TYPE_SK_MSG = 16,
+    /// Run on a tracepoint, with its raw arguments
+    BPF_PROG_TYPE_RAW_TRACEPOINT = 17,
+    /// Run when the processes in a cgroup bind or connect sockets
+    BPF_PROG_TYPE_CGROUP_SOCK_ADDR = 18,
+}
+
+bitflags! {
+    /// Flags for [`map_create`](fn.map_create.html)
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct MapFlags: u32 {
+        /// Allocate the elements of a hash map on demand, instead of all at
+        /// creation
+        const BPF_F_NO_PREALLOC = 1 << 0;
+        /// Give every CPU its own list of the least recently used elements of
+        /// an LRU map
+        const BPF_F_NO_COMMON_LRU = 1 << 1;
+        /// Only allow programs to read the map
+        const BPF_F_RDONLY = 1 << 3;
+        /// Only allow programs to write the map
+        const BPF_F_WRONLY = 1 << 4;
+    }
+}
+
+bitflags! {
+    /// Flags for [`map_update_elem`](fn.map_update_elem.html)
+    ///
+    /// Without flags (`BPF_ANY`), the element is added or replaced.
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct UpdateFlags: u64 {
+        /// Fail with `EEXIST` if the element already exists
+        const BPF_NOEXIST = 1;
+        /// Fail with `ENOENT` if the element doesn't exist yet
+        const BPF_EXIST = 2;
+        /// Take the spin lock of the value while updating it
+        const BPF_F_LOCK = 4;
+    }
+}
+
+// The parts of the kernel's union bpf_attr for each command
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct MapCreateAttr {
+    map_type: u32,
+    key_size: u32,
+    value_size: u32,
+    max_entries: u32,
+    map_flags: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct MapElemAttr {
+    map_fd: u32,
+    pad: u32,
+    key: u64,
+    // Also next_key
+    value: u64,
+    flags: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct ProgLoadAttr {
+    prog_type: u32,
+    insn_cnt: u32,
+    insns: u64,
+    license: u64,
+    log_level: u32,
+    log_size: u32,
+    log_buf: u64,
+    kern_version: u32,
+    prog_flags: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct ObjAttr {
+    pathname: u64,
+    bpf_fd: u32,
+    file_flags: u32,
+}
+
+// Newer kernels accept larger attributes, as long as the rest is zeroed
+#[repr(C)]
+#[derive(Clone, Copy)]
+union BpfAttr {
+    map_create: MapCreateAttr,
+    map_elem: MapElemAttr,
+    prog_load: ProgLoadAttr,
+    obj: ObjAttr,
+    pad: [u64; 16],
+}
+
+impl BpfAttr {
+    fn new() -> BpfAttr {
+        BpfAttr { pad: [0; 16] }
+    }
+}
+
+/// Issue a command to the BPF subsystem (see
+/// [bpf(2)](http://man7.org/linux/man-pages/man2/bpf.2.html)).
+///
+/// # Safety
+///
+/// `attr` must point to the `size` bytes of the `union bpf_attr` for `cmd`, and
+/// the pointers in it must be valid for what the command does with them.
+pub unsafe fn bpf(cmd: BpfCmd, attr: *mut c_void, size: u32) -> Result<c_int> {
+    let res = libc::syscall(libc::SYS_bpf, cmd as c_int, attr, size as c_uint);
+
+    Errno::result(res).map(|r| r as c_int)
+}
+
+fn bpf_attr(cmd: BpfCmd, attr: &mut BpfAttr) -> Result<c_int> {
+    unsafe { bpf(cmd, attr as *mut BpfAttr as *mut c_void, mem::size_of::<BpfAttr>() as u32) }
+}
+
+/// Create a map with `max_entries` elements of `value_size` bytes, indexed by
+/// keys of `key_size` bytes (`BPF_MAP_CREATE`).
+///
+/// Array maps are indexed by `u32`s, so `key_size` must be 4.
+pub fn map_create(map_type: MapType, key_size: u32, value_size: u32, max_entries: u32,
+                  flags: MapFlags) -> Result<RawFd> {
+    let mut attr = BpfAttr::new();
+    attr.map_create = MapCreateAttr {
+        map_type: map_type as u32,
+        key_size: key_size,
+        value_size: value_size,
+        max_entries: max_entries,
+        map_flags: flags.bits(),
+    };
+    bpf_attr(BpfCmd::BPF_MAP_CREATE, &mut attr)
+}
+
+fn map_elem(cmd: BpfCmd, fd: RawFd, key: *const u8, value: *mut u8, flags: u64) -> Result<()> {
+    let mut attr = BpfAttr::new();
+    attr.map_elem = MapElemAttr {
+        map_fd: fd as u32,
+        pad: 0,
+        key: key as u64,
+        value: value as u64,
+        flags: flags,
+    };
+    bpf_attr(cmd, &mut attr).map(drop)
+}
+
+/// Copy the value of the element of the map `fd` with the key `key` into
+/// `value` (`BPF_MAP_LOOKUP_ELEM`).
+///
+/// Fails with `ENOENT` if there's no such element.
+///
+/// # Safety
+///
+/// `key` must be as long as the keys of the map, and `value` as its values.
+/// The values of per-CPU maps are an array of values, each padded to a multiple
+/// of 8 bytes, for every possible CPU.
+pub unsafe fn map_lookup_elem(fd: RawFd, key: &[u8], value: &mut [u8]) -> Result<()> {
+    map_elem(BpfCmd::BPF_MAP_LOOKUP_ELEM, fd, key.as_ptr(), value.as_mut_ptr(), 0)
+}
+
+/// Add or replace the element of the map `fd` with the key `key`
+/// (`BPF_MAP_UPDATE_ELEM`).
+///
+/// Fails with `E2BIG` if the map is full.
+///
+/// # Safety
+///
+/// `key` must be as long as the keys of the map, and `value` as its values,
+/// or the kernel reads past them.
+pub unsafe fn map_update_elem(fd: RawFd, key: &[u8], value: &[u8], flags: UpdateFlags)
+                              -> Result<()> {
+    map_elem(BpfCmd::BPF_MAP_UPDATE_ELEM, fd, key.as_ptr(), value.as_ptr() as *mut u8,
+             flags.bits())
+}
+
+/// Remove the element of the map `fd` with the key `key`
+/// (`BPF_MAP_DELETE_ELEM`).
+///
+/// Fails with `ENOENT` if there's no such element, and with `EINVAL` for
+/// array maps, whose elements can't be removed.
+///
+/// # Safety
+///
+/// `key` must be as long as the keys of the map, or the kernel reads past it.
+pub unsafe fn map_delete_elem(fd: RawFd, key: &[u8]) -> Result<()> {
+    map_elem(BpfCmd::BPF_MAP_DELETE_ELEM, fd, key.as_ptr(), ptr::null_mut(), 0)
+}
+
+/// Copy the key after `key` in the map `fd` into `next_key`, or the first key
+/// if `key` is `None` or not in the map (`BPF_MAP_GET_NEXT_KEY`).
+///
+/// Fails with `ENOENT` after the last key, so this iterates over the keys of
+/// the map.
+///
+/// # Safety
+///
+/// `key` and `next_key` must be as long as the keys of the map.
+pub unsafe fn map_get_next_key(fd: RawFd, key: Option<&[u8]>, next_key: &mut [u8])
+                               -> Result<()> {
+    let key = key.map(|key| key.as_ptr()).unwrap_or(ptr::null());
+    map_elem(BpfCmd::BPF_MAP_GET_NEXT_KEY, fd, key, next_key.as_mut_ptr(), 0)
+}
+
+/// Load the program made of the eBPF instructions `insns` (`BPF_PROG_LOAD`).
+///
+/// The instructions are the bytecode that compilers emit, 8 bytes each.  The
+/// kernel verifies the program before loading it, and fails with `EACCES` or
+/// `EINVAL` if it's rejected.  With `log`, the verifier explains why there, as
+/// a nul-terminated string.  Some helper functions require a GPL compatible
+/// `license`.
+pub fn prog_load<P: ?Sized + NixPath>(prog_type: ProgType, insns: &[u8], license: &P,
+                                      log: Option<&mut [u8]>) -> Result<RawFd> {
+    if insns.len() % 8 != 0 {
+        return Err(Error::invalid_argument());
+    }
+    let (log_level, log_buf, log_size) = match log {
+        // The kernel fails with EINVAL on logs larger than u32::MAX >> 2
+        Some(log) => (1, log.as_mut_ptr(), cmp::min(log.len(), (u32::max_value() >> 2) as usize)),
+        None => (0, ptr::null_mut(), 0),
+    };
+    try!(license.with_nix_path(|license| {
+        let mut attr = BpfAttr::new();
+        attr.prog_load = ProgLoadAttr {
+            prog_type: prog_type as u32,
+            insn_cnt: (insns.len() / 8) as u32,
+            insns: insns.as_ptr() as u64,
+            license: license.as_ptr() as u64,
+            log_level: log_level,
+            log_size: log_size as u32,
+            log_buf: log_buf as u64,
+            kern_version: 0,
+            prog_flags: 0,
+        };
+        bpf_attr(BpfCmd::BPF_PROG_LOAD, &mut attr)
+    }))
+}
+
+/// Pin the program or map `fd` to `path`, on a BPF file system, so that it
+/// stays loaded after `fd` is closed (`BPF_OBJ_PIN`).
+pub fn obj_pin<P: ?Sized + NixPath>(fd: RawFd, path: &P) -> Result<()> {
+    try!(path.with_nix_path(|path| {
+        let mut attr = BpfAttr::new();
+        attr.obj = ObjAttr {
+            pathname: path.as_ptr() as u64,
+            bpf_fd: fd as u32,
+            file_flags: 0,
+        };
+        bpf_attr(BpfCmd::BPF_OBJ_PIN, &mut attr)
+    })).map(drop)
+}
+
+/// Open the program or map pinned to `path` (`BPF_OBJ_GET`).
+pub fn obj_get<P: ?Sized + NixPath>(path: &P) -> Result<RawFd> {
+    try!(path.with_nix_path(|path| {
+        let mut attr = BpfAttr::new();
+        attr.obj = ObjAttr {
+            pathname: path.as_ptr() as u64,
+            bpf_fd: 0,
+            file_flags: 0,
+        };
+        bpf_attr(BpfCmd::BPF_OBJ_GET, &mut attr)
+    }))
+}
@@ -0,0 +1,251 @@
+//! Capability mode and capability rights (see
+//! [capsicum(4)](https://www.freebsd.org/cgi/man.cgi?query=capsicum&sektion=4)).
+//!
+//! A process in capability mode can't reach global namespaces, like the file
+//! system or the process IDs, but only the objects it holds file descriptors
+//! for.  The rights of each descriptor can be limited further, so that a
+//! compromised process can do no more with it than what it needs.
+//!
+//! # Examples
+//!
+//! Only allow reading standard input, then enter capability mode:
+//!
+//! ```no_run
+//! # extern crate nix;
+//! # use nix::sys::capsicum::*;
+//! # fn main() {
+//! let mut rights = CapRights::new();
+//! rights.add(CapRight::CAP_READ);
+//! rights.add(CapRight::CAP_FSTAT);
+//! cap_rights_limit(0, &rights).unwrap();
+//! cap_enter().unwrap();
+//! # }
+//! ```
+use libc::{self, c_uint};
+use std::mem;
+use std::os::unix::io::RawFd;
+use errno::Errno;
+use Result;
+
+libc_enum! {
+    /// A right on a file descriptor.  Some rights are made of others:
+    /// `CAP_PREAD` is `CAP_SEEK` and `CAP_READ`, for instance.
+    #[repr(u64)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub enum CapRight {
+        /// `read`, `recv` and the like, without seeking
+        CAP_READ,
+        /// `write`, `send` and the like, without seeking, except with
+        /// `O_APPEND`
+        CAP_WRITE,
+        /// `lseek` to get the offset only
+        CAP_SEEK_TELL,
+        /// `lseek`
+        CAP_SEEK,
+        /// `pread` and `preadv`
+        CAP_PREAD,
+        /// `pwrite` and `pwritev`
+        CAP_PWRITE,
+        /// `mmap` with `PROT_NONE`
+        CAP_MMAP,
+        /// `mmap` with `PROT_READ`
+        CAP_MMAP_R,
+        /// `mmap` with `PROT_WRITE`
+        CAP_MMAP_W,
+        /// `mmap` with `PROT_EXEC`
+        CAP_MMAP_X,
+        /// `openat` with `O_CREAT`
+        CAP_CREATE,
+        /// `fexecve`
+        CAP_FEXECVE,
+        /// `fsync` and `aio_fsync`
+        CAP_FSYNC,
+        /// `ftruncate`
+        CAP_FTRUNCATE,
+        /// Use as the directory of `openat` and the other `*at` functions
+        CAP_LOOKUP,
+        /// `fchdir`
+        CAP_FCHDIR,
+        /// `fchflags`
+        CAP_FCHFLAGS,
+        /// `fchmod`
+        CAP_FCHMOD,
+        /// `fchown`
+        CAP_FCHOWN,
+        /// `fcntl`, for the commands allowed by
+        /// [`cap_fcntls_limit`](fn.cap_fcntls_limit.html)
+        CAP_FCNTL,
+        /// `flock`, and `fcntl` with `F_GETLK`, `F_SETLK` and `F_SETLKW`
+        CAP_FLOCK,
+        /// `fpathconf`
+        CAP_FPATHCONF,
+        /// `fstat`
+        CAP_FSTAT,
+        /// `fstatfs`
+        CAP_FSTATFS,
+        /// `futimens` and `futimes`
+        CAP_FUTIMES,
+        /// `accept`
+        CAP_ACCEPT,
+        /// `bind`
+        CAP_BIND,
+        /// `connect`
+        CAP_CONNECT,
+        /// `getpeername`
+        CAP_GETPEERNAME,
+        /// `getsockname`
+        CAP_GETSOCKNAME,
+        /// `getsockopt`
+        CAP_GETSOCKOPT,
+        /// `listen`
+        CAP_LISTEN,
+        /// `setsockopt`
+        CAP_SETSOCKOPT,
+        /// `shutdown`
+        CAP_SHUTDOWN,
+        /// The rights needed by a connected socket
+        CAP_SOCK_CLIENT,
+        /// The rights needed by a listening socket
+        CAP_SOCK_SERVER,
+        /// Register the descriptor in a kqueue
+        CAP_EVENT,
+        /// `kevent` to wait for events
+        CAP_KQUEUE_EVENT,
+        /// `kevent` to change the events
+        CAP_KQUEUE_CHANGE,
+        /// `ioctl`, for the commands allowed by `cap_ioctls_limit`
+        CAP_IOCTL,
+        /// `pdgetpid`
+        CAP_PDGETPID,
+        /// `pdwait4`
+        CAP_PDWAIT,
+        /// `pdkill`
+        CAP_PDKILL,
+        /// `extattr_delete_fd`
+        CAP_EXTATTR_DELETE,
+        /// `extattr_get_fd`
+        CAP_EXTATTR_GET,
+        /// `extattr_list_fd`
+        CAP_EXTATTR_LIST,
+        /// `extattr_set_fd`
+        CAP_EXTATTR_SET,
+    }
+}
+
+/// A set of rights on a file descriptor, as limited by
+/// [`cap_rights_limit`](fn.cap_rights_limit.html)
+#[derive(Clone, Copy)]
+pub struct CapRights {
+    rights: libc::cap_rights_t,
+}
+
+impl CapRights {
+    /// Create an empty set of rights
+    pub fn new() -> CapRights {
+        let mut rights: libc::cap_rights_t = unsafe { mem::zeroed() };
+        unsafe { libc::__cap_rights_init(libc::CAP_RIGHTS_VERSION, &mut rights, 0u64) };
+        CapRights { rights }
+    }
+
+    /// Add a right to the set
+    pub fn add(&mut self, right: CapRight) {
+        unsafe { libc::__cap_rights_set(&mut self.rights, right as u64, 0u64) };
+    }
+
+    /// Remove a right from the set
+    pub fn remove(&mut self, right: CapRight) {
+        unsafe { libc::__cap_rights_clear(&mut self.rights, right as u64, 0u64) };
+    }
+
+    /// Whether the set has a right
+    pub fn contains(&self, right: CapRight) -> bool {
+        unsafe { libc::__cap_rights_is_set(&self.rights, right as u64, 0u64) }
+    }
+
+    /// Whether the set has all the rights of `other`
+    pub fn contains_all(&self, other: &CapRights) -> bool {
+        unsafe { libc::cap_rights_contains(&self.rights, &other.rights) }
+    }
+
+    /// Add the rights of `other` to the set
+    pub fn extend(&mut self, other: &CapRights) {
+        unsafe { libc::cap_rights_merge(&mut self.rights, &other.rights) };
+    }
+}
+
+impl AsRef<libc::cap_rights_t> for CapRights {
+    fn as_ref(&self) -> &libc::cap_rights_t {
+        &self.rights
+    }
+}
+
+libc_bitflags! {
+    /// The `fcntl` commands allowed with `CAP_FCNTL`, as limited by
+    /// [`cap_fcntls_limit`](fn.cap_fcntls_limit.html)
+    pub struct CapFcntlRights: u32 {
+        CAP_FCNTL_GETFL;
+        CAP_FCNTL_SETFL;
+        CAP_FCNTL_GETOWN;
+        CAP_FCNTL_SETOWN;
+    }
+}
+
+/// Enter capability mode (see
+/// [cap_enter(2)](https://www.freebsd.org/cgi/man.cgi?query=cap_enter&sektion=2)).
+///
+/// This can't be undone, and is inherited by the children of the process.
+pub fn cap_enter() -> Result<()> {
+    let res = unsafe { libc::cap_enter() };
+
+    Errno::result(res).map(drop)
+}
+
+/// Whether the process is in capability mode (see
+/// [cap_getmode(2)](https://www.freebsd.org/cgi/man.cgi?query=cap_getmode&sektion=2)).
+pub fn cap_getmode() -> Result<bool> {
+    let mut mode: c_uint = 0;
+    let res = unsafe { libc::cap_getmode(&mut mode) };
+
+    Errno::result(res).map(|_| mode != 0)
+}
+
+/// Limit the rights of a file descriptor (see
+/// [cap_rights_limit(2)](https://www.freebsd.org/cgi/man.cgi?query=cap_rights_limit&sektion=2)).
+///
+/// Rights can only be removed, so `rights` must be a subset of the current
+/// rights of `fd`, or this fails with `ENOTCAPABLE`.  Operations needing the
+/// removed rights then fail with `ENOTCAPABLE` too.
+pub fn cap_rights_limit(fd: RawFd, rights: &CapRights) -> Result<()> {
+    let res = unsafe { libc::cap_rights_limit(fd, &rights.rights) };
+
+    Errno::result(res).map(drop)
+}
+
+/// Get the rights of a file descriptor (see
+/// [cap_rights_get(3)](https://www.freebsd.org/cgi/man.cgi?query=cap_rights_get&sektion=3)).
+pub fn cap_rights_get(fd: RawFd) -> Result<CapRights> {
+    let mut rights = CapRights::new();
+    let res = unsafe {
+        libc::__cap_rights_get(libc::CAP_RIGHTS_VERSION, fd, &mut rights.rights)
+    };
+
+    Errno::result(res).map(|_| rights)
+}
+
+/// Limit the `fcntl` commands allowed on a file descriptor with `CAP_FCNTL`
+/// (see
+/// [cap_fcntls_limit(2)](https://www.freebsd.org/cgi/man.cgi?query=cap_fcntls_limit&sektion=2)).
+pub fn cap_fcntls_limit(fd: RawFd, fcntlrights: CapFcntlRights) -> Result<()> {
+    let res = unsafe { libc::cap_fcntls_limit(fd, fcntlrights.bits()) };
+
+    Errno::result(res).map(drop)
+}
+
+/// Get the `fcntl` commands allowed on a file descriptor (see
+/// [cap_fcntls_get(2)](https://www.freebsd.org/cgi/man.cgi?query=cap_fcntls_get&sektion=2)).
+pub fn cap_fcntls_get(fd: RawFd) -> Result<CapFcntlRights> {
+    let mut fcntlrights: u32 = 0;
+    let res = unsafe { libc::cap_fcntls_get(fd, &mut fcntlrights) };
+
+    Errno::result(res).map(|_| CapFcntlRights::from_bits_truncate(fcntlrights))
+}
@@ -1,10 +1,13 @@
 use Result;
 use errno::Errno;
 use libc::{self, c_int};
-use std::os::unix::io::RawFd;
-use std::ptr;
-use std::mem;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::{cmp, mem, ptr};
 use ::Error;
+use features;
+use sys::signal::{KERNEL_SIGSET_SIZE, SigSet};
+use sys::time::{TimeSpec, TimeValLike};
+use unistd::{self, Fd};
 
 libc_bitflags!(
     pub struct EpollFlags: libc::c_int {
@@ -73,6 +76,11 @@ pub fn epoll_create() -> Result<RawFd> {
     Errno::result(res)
 }
 
+/// Like [`epoll_create`](fn.epoll_create.html), but returns an owned file descriptor.
+pub fn epoll_create_owned() -> Result<Fd> {
+    epoll_create().map(|fd| unsafe { Fd::from_raw_fd(fd) })
+}
+
 #[inline]
 pub fn epoll_create1(flags: EpollCreateFlags) -> Result<RawFd> {
     let res = unsafe { libc::epoll_create1(flags.bits()) };
@@ -80,6 +88,11 @@ pub fn epoll_create1(flags: EpollCreateFlags) -> Result<RawFd> {
     Errno::result(res)
 }
 
+/// Like [`epoll_create1`](fn.epoll_create1.html), but returns an owned file descriptor.
+pub fn epoll_create1_owned(flags: EpollCreateFlags) -> Result<Fd> {
+    epoll_create1(flags).map(|fd| unsafe { Fd::from_raw_fd(fd) })
+}
+
 #[inline]
 pub fn epoll_ctl<'a, T>(epfd: RawFd, op: EpollOp, fd: RawFd, event: T) -> Result<()>
     where T: Into<Option<&'a mut EpollEvent>>
@@ -107,3 +120,179 @@ pub fn epoll_wait(epfd: RawFd, events: &mut [EpollEvent], timeout_ms: isize) ->
 
     Errno::result(res).map(|r| r as usize)
 }
+
+/// Like `epoll_wait`, but atomically replaces the signal mask of the calling thread with
+/// `sigmask` while waiting (see
+/// [epoll_pwait(2)](http://man7.org/linux/man-pages/man2/epoll_pwait.2.html)).
+///
+/// This lets a thread block signals except while it waits, without the race between unblocking
+/// them and calling `epoll_wait`.  With `None`, it's the same as `epoll_wait`.
+#[inline]
+pub fn epoll_pwait(epfd: RawFd, events: &mut [EpollEvent], timeout_ms: isize,
+                   sigmask: Option<&SigSet>) -> Result<usize> {
+    let sigmask = match sigmask {
+        Some(sigmask) => sigmask.as_ref() as *const libc::sigset_t,
+        None => ptr::null(),
+    };
+    let res = unsafe {
+        libc::epoll_pwait(epfd, events.as_mut_ptr() as *mut libc::epoll_event, events.len() as c_int, timeout_ms as c_int, sigmask)
+    };
+
+    Errno::result(res).map(|r| r as usize)
+}
+
+// epoll_pwait2 always takes the 64-bit kernel timespec, even where `libc::timespec` has a 32-bit
+// `tv_sec`, and the size of the kernel's sigset rather than of `libc::sigset_t`.
+#[repr(C)]
+struct KernelTimespec {
+    tv_sec: i64,
+    tv_nsec: i64,
+}
+
+/// Like `epoll_pwait`, but with a timeout of nanosecond resolution (see
+/// [epoll_pwait2(2)](http://man7.org/linux/man-pages/man2/epoll_pwait2.2.html)).
+///
+/// A `timeout` of `None` waits forever.  `epoll_pwait2` was added in Linux 5.11, and fails with
+/// `ENOSYS` on older kernels; see [`epoll_pwait2_available`](fn.epoll_pwait2_available.html).
+pub fn epoll_pwait2(epfd: RawFd, events: &mut [EpollEvent], timeout: Option<&TimeSpec>,
+                    sigmask: Option<&SigSet>) -> Result<usize> {
+    let timeout = timeout.map(|t| KernelTimespec {
+        tv_sec: t.tv_sec() as i64,
+        tv_nsec: t.tv_nsec() as i64,
+    });
+    let timeout = match timeout {
+        Some(ref timeout) => timeout as *const KernelTimespec,
+        None => ptr::null(),
+    };
+    let sigmask = match sigmask {
+        Some(sigmask) => sigmask.as_ref() as *const libc::sigset_t,
+        None => ptr::null(),
+    };
+    let res = unsafe {
+        libc::syscall(libc::SYS_epoll_pwait2, epfd, events.as_mut_ptr(), events.len() as c_int,
+                      timeout, sigmask, KERNEL_SIGSET_SIZE)
+    };
+
+    Errno::result(res).map(|r| r as usize)
+}
+
+/// Check whether the running kernel supports [`epoll_pwait2`](fn.epoll_pwait2.html).
+///
+/// The answer is cached; this is the same as
+/// [`features::epoll_pwait2_available`](../../features/fn.epoll_pwait2_available.html).
+pub fn epoll_pwait2_available() -> bool {
+    features::epoll_pwait2_available()
+}
+
+/// An owned epoll instance, which is closed when dropped.
+///
+/// The epoll file descriptor is created with `EPOLL_CLOEXEC`, so it isn't leaked to child
+/// processes either.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::epoll::{Epoll, EpollEvent, EpollFlags};
+/// # use nix::unistd::{pipe, write};
+/// let (r, w) = pipe().unwrap();
+/// let epoll = Epoll::new().unwrap();
+/// epoll.add(r, EpollFlags::EPOLLIN, 42).unwrap();
+/// write(w, b"x").unwrap();
+///
+/// let mut events = [EpollEvent::empty(); 8];
+/// assert_eq!(epoll.wait(&mut events, -1).unwrap(), 1);
+/// assert_eq!(events[0].data(), 42);
+/// ```
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct Epoll(RawFd);
+
+impl Epoll {
+    /// Create a new epoll instance.
+    pub fn new() -> Result<Epoll> {
+        epoll_create1(EpollCreateFlags::EPOLL_CLOEXEC).map(Epoll)
+    }
+
+    /// Start watching `fd` for `events`.  `token` is returned with each of its events.
+    pub fn add(&self, fd: RawFd, events: EpollFlags, token: u64) -> Result<()> {
+        let mut event = EpollEvent::new(events, token);
+        epoll_ctl(self.0, EpollOp::EpollCtlAdd, fd, &mut event)
+    }
+
+    /// Change the events and token of a watched `fd`.
+    pub fn modify(&self, fd: RawFd, events: EpollFlags, token: u64) -> Result<()> {
+        let mut event = EpollEvent::new(events, token);
+        epoll_ctl(self.0, EpollOp::EpollCtlMod, fd, &mut event)
+    }
+
+    /// Stop watching `fd`.
+    pub fn delete(&self, fd: RawFd) -> Result<()> {
+        epoll_ctl(self.0, EpollOp::EpollCtlDel, fd, None)
+    }
+
+    /// Wait for events, filling `events` with up to `events.len()` of them and returning how many
+    /// there are.
+    ///
+    /// A `timeout_ms` of -1 waits forever, while 0 returns immediately.
+    pub fn wait(&self, events: &mut [EpollEvent], timeout_ms: isize) -> Result<usize> {
+        epoll_wait(self.0, events, timeout_ms)
+    }
+
+    /// Like [`wait`](#method.wait), but with the signal mask of the calling thread replaced by
+    /// `sigmask` while waiting.  See [`epoll_pwait`](fn.epoll_pwait.html).
+    pub fn pwait(&self, events: &mut [EpollEvent], timeout_ms: isize, sigmask: &SigSet)
+        -> Result<usize>
+    {
+        epoll_pwait(self.0, events, timeout_ms, Some(sigmask))
+    }
+
+    /// Wait for events with a timeout of nanosecond resolution, optionally with the signal mask of
+    /// the calling thread replaced by `sigmask` while waiting.  A `timeout` of `None` waits
+    /// forever.
+    ///
+    /// This uses [`epoll_pwait2`](fn.epoll_pwait2.html) where the kernel supports it, and falls
+    /// back to `epoll_pwait` otherwise, with the timeout rounded up to whole milliseconds.
+    pub fn wait_timeout(&self, events: &mut [EpollEvent], timeout: Option<&TimeSpec>,
+                        sigmask: Option<&SigSet>) -> Result<usize> {
+        if features::epoll_pwait2_available() {
+            return epoll_pwait2(self.0, events, timeout, sigmask);
+        }
+
+        let timeout_ms = match timeout {
+            Some(timeout) if *timeout < TimeSpec::zero() => return Err(Error::Sys(Errno::EINVAL)),
+            Some(timeout) => {
+                let ms = (timeout.num_nanoseconds() + 999_999) / 1_000_000;
+                cmp::min(ms, c_int::max_value() as i64) as isize
+            },
+            None => -1,
+        };
+        epoll_pwait(self.0, events, timeout_ms, sigmask)
+    }
+}
+
+impl Drop for Epoll {
+    fn drop(&mut self) {
+        let _ = unistd::close(self.0);
+    }
+}
+
+impl AsRawFd for Epoll {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+impl IntoRawFd for Epoll {
+    fn into_raw_fd(self) -> RawFd {
+        let fd = self.0;
+        mem::forget(self);
+        fd
+    }
+}
+
+impl FromRawFd for Epoll {
+    /// Take ownership of `fd`, which must be an open epoll instance not owned by anything
+    /// else.
+    unsafe fn from_raw_fd(fd: RawFd) -> Epoll {
+        Epoll(fd)
+    }
+}
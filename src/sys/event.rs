@@ -7,9 +7,12 @@ use libc::{timespec, time_t, c_int, c_long, intptr_t, uintptr_t};
 #[cfg(target_os = "netbsd")]
 use libc::{timespec, time_t, c_long, intptr_t, uintptr_t, size_t};
 use libc;
-use std::os::unix::io::RawFd;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 use std::ptr;
 use std::mem;
+use sys::time::TimeSpec;
+use sys::wait::WaitStatus;
+use unistd::{self, Pid};
 
 // Redefine kevent in terms of programmer-friendly enums and bitfields.
 #[derive(Clone, Copy)]
@@ -308,6 +311,412 @@ pub fn ev_set(ev: &mut KEvent,
     ev.kevent.udata  = udata as type_of_udata;
 }
 
+/// A builder for [`KEvent`](struct.KEvent.html)s
+///
+/// Every field that isn't set explicitly is zero, and flags accumulate, so only the interesting
+/// parts of an event need to be spelled out.
+///
+/// # Examples
+///
+/// Register a user event, and then trigger it from anywhere to wake up the thread waiting on the
+/// kqueue:
+///
+/// ```
+/// # use nix::sys::event::*;
+/// # fn main() {
+/// # #[cfg(any(target_os = "dragonfly", target_os = "freebsd",
+/// #           target_os = "ios", target_os = "macos"))]
+/// # {
+/// let kq = kqueue().unwrap();
+/// let register = KEventBuilder::user(1).add().clear().build();
+/// let trigger = KEventBuilder::user(1).trigger().build();
+/// kevent(kq, &[register, trigger], &mut [], 0).unwrap();
+///
+/// let mut events = [KEventBuilder::new(0, EventFilter::EVFILT_READ).build()];
+/// assert_eq!(kevent(kq, &[], &mut events, 0).unwrap(), 1);
+/// assert_eq!(events[0].ident(), 1);
+/// # }
+/// # }
+/// ```
+#[derive(Clone, Copy)]
+pub struct KEventBuilder {
+    kevent: KEvent,
+}
+
+impl KEventBuilder {
+    /// Start building an event for `ident` on `filter`
+    pub fn new(ident: uintptr_t, filter: EventFilter) -> KEventBuilder {
+        let mut kevent: KEvent = unsafe { mem::zeroed() };
+        kevent.kevent.ident = ident;
+        kevent.kevent.filter = filter as type_of_event_filter;
+        KEventBuilder { kevent: kevent }
+    }
+
+    /// Start building a user event (`EVFILT_USER`) for `ident`
+    #[cfg(any(target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "macos"))]
+    pub fn user(ident: uintptr_t) -> KEventBuilder {
+        KEventBuilder::new(ident, EventFilter::EVFILT_USER)
+    }
+
+    /// Add `flags` to the event's flags
+    pub fn flags(mut self, flags: EventFlag) -> KEventBuilder {
+        self.kevent.kevent.flags |= flags.bits();
+        self
+    }
+
+    /// Add `fflags` to the event's filter flags
+    pub fn fflags(mut self, fflags: FilterFlag) -> KEventBuilder {
+        self.kevent.kevent.fflags |= fflags.bits();
+        self
+    }
+
+    /// Set the event's filter data
+    pub fn data(mut self, data: intptr_t) -> KEventBuilder {
+        self.kevent.kevent.data = data as type_of_data;
+        self
+    }
+
+    /// Set the opaque user data that is returned with the event
+    pub fn udata(mut self, udata: intptr_t) -> KEventBuilder {
+        self.kevent.kevent.udata = udata as type_of_udata;
+        self
+    }
+
+    /// Add the event to the kqueue, or modify it if it's already there (`EV_ADD`)
+    pub fn add(self) -> KEventBuilder {
+        self.flags(EventFlag::EV_ADD)
+    }
+
+    /// Remove the event from the kqueue (`EV_DELETE`)
+    pub fn delete(self) -> KEventBuilder {
+        self.flags(EventFlag::EV_DELETE)
+    }
+
+    /// Remove the event from the kqueue after it's returned for the first time (`EV_ONESHOT`)
+    pub fn oneshot(self) -> KEventBuilder {
+        self.flags(EventFlag::EV_ONESHOT)
+    }
+
+    /// Reset the state of the event after it's returned (`EV_CLEAR`).  User events need this to
+    /// be triggered more than once.
+    pub fn clear(self) -> KEventBuilder {
+        self.flags(EventFlag::EV_CLEAR)
+    }
+
+    /// Trigger a user event (`NOTE_TRIGGER`)
+    #[cfg(any(target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "macos"))]
+    pub fn trigger(self) -> KEventBuilder {
+        self.fflags(FilterFlag::NOTE_TRIGGER)
+    }
+
+    /// Finish building the event
+    pub fn build(self) -> KEvent {
+        self.kevent
+    }
+}
+
+libc_bitflags!(
+    /// Changes to a file or directory reported by a [`VnodeWatcher`](struct.VnodeWatcher.html)
+    pub struct VnodeEvents: u32 {
+        /// The file was unlinked
+        NOTE_DELETE;
+        /// The file was written to, or for a directory, an entry was added or removed
+        NOTE_WRITE;
+        /// The file grew
+        NOTE_EXTEND;
+        /// The file's attributes, such as its permissions, changed
+        NOTE_ATTRIB;
+        /// The file's link count changed
+        NOTE_LINK;
+        /// The file was renamed
+        NOTE_RENAME;
+        /// Access to the file was revoked, for example because its file system was unmounted
+        NOTE_REVOKE;
+    }
+);
+
+/// An event returned by [`VnodeWatcher::read_events`](struct.VnodeWatcher.html#method.read_events)
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct VnodeEvent {
+    fd: RawFd,
+    events: VnodeEvents,
+}
+
+impl VnodeEvent {
+    /// The watched file descriptor that the events happened to
+    pub fn fd(&self) -> RawFd {
+        self.fd
+    }
+
+    /// What happened to the file since it was last reported
+    pub fn events(&self) -> VnodeEvents {
+        self.events
+    }
+}
+
+/// Watches files and directories for changes with `EVFILT_VNODE`
+///
+/// Files are watched through open file descriptors, which must stay open for as long as they're
+/// watched; closing one removes it from the watcher.  The watcher owns its kqueue, which is closed
+/// when it's dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use nix::fcntl::{open, OFlag};
+/// # use nix::sys::event::{VnodeEvents, VnodeWatcher};
+/// # use nix::sys::stat::Mode;
+/// let fd = open("/etc/hosts", OFlag::O_RDONLY, Mode::empty()).unwrap();
+/// let watcher = VnodeWatcher::new().unwrap();
+/// watcher.add(fd, VnodeEvents::NOTE_WRITE | VnodeEvents::NOTE_DELETE).unwrap();
+/// for event in watcher.read_events(None).unwrap() {
+///     println!("{:?} happened to {}", event.events(), event.fd());
+/// }
+/// ```
+#[derive(Debug)]
+pub struct VnodeWatcher {
+    kq: RawFd,
+}
+
+impl VnodeWatcher {
+    /// The maximum number of events returned by a single call to
+    /// [`read_events`](#method.read_events)
+    pub const MAX_EVENTS: usize = 32;
+
+    /// Create a new watcher that isn't watching anything yet.
+    pub fn new() -> Result<VnodeWatcher> {
+        kqueue().map(|kq| VnodeWatcher { kq: kq })
+    }
+
+    /// Start watching `fd` for `events`, or change the events it's watched for.
+    pub fn add(&self, fd: RawFd, events: VnodeEvents) -> Result<()> {
+        let kev = KEventBuilder::new(fd as uintptr_t, EventFilter::EVFILT_VNODE)
+            .add()
+            .clear()
+            .fflags(FilterFlag::from_bits_truncate(events.bits()))
+            .build();
+        kevent_ts(self.kq, &[kev], &mut [], None).map(drop)
+    }
+
+    /// Stop watching `fd`.
+    pub fn remove(&self, fd: RawFd) -> Result<()> {
+        let kev = KEventBuilder::new(fd as uintptr_t, EventFilter::EVFILT_VNODE)
+            .delete()
+            .build();
+        kevent_ts(self.kq, &[kev], &mut [], None).map(drop)
+    }
+
+    /// Wait until at least one watched file changes, and return what happened.
+    ///
+    /// Changes to the same file are combined into a single event until it's returned.  A
+    /// `timeout` of `None` waits forever, and an empty vector is returned if the timeout expires.
+    pub fn read_events(&self, timeout: Option<TimeSpec>) -> Result<Vec<VnodeEvent>> {
+        let empty = KEventBuilder::new(0, EventFilter::EVFILT_VNODE).build();
+        let mut kevs = vec![empty; VnodeWatcher::MAX_EVENTS];
+        let n = try!(kevent_ts(self.kq, &[], &mut kevs, timeout.map(|t| *t.as_ref())));
+
+        Ok(kevs[..n].iter().map(|kev| VnodeEvent {
+            fd: kev.ident() as RawFd,
+            events: VnodeEvents::from_bits_truncate(kev.fflags().bits()),
+        }).collect())
+    }
+}
+
+impl Drop for VnodeWatcher {
+    fn drop(&mut self) {
+        let _ = unistd::close(self.kq);
+    }
+}
+
+impl AsRawFd for VnodeWatcher {
+    fn as_raw_fd(&self) -> RawFd {
+        self.kq
+    }
+}
+
+impl IntoRawFd for VnodeWatcher {
+    fn into_raw_fd(self) -> RawFd {
+        let fd = self.kq;
+        mem::forget(self);
+        fd
+    }
+}
+
+impl FromRawFd for VnodeWatcher {
+    /// Take ownership of `fd`, which must be an open kqueue not owned by anything
+    /// else.
+    unsafe fn from_raw_fd(fd: RawFd) -> VnodeWatcher {
+        VnodeWatcher { kq: fd }
+    }
+}
+
+libc_bitflags!(
+    /// Process events reported by a [`ProcessWatcher`](struct.ProcessWatcher.html)
+    pub struct ProcEvents: u32 {
+        /// The process exited
+        NOTE_EXIT;
+        /// Report the exit status along with `NOTE_EXIT`.  Other systems always report it.
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        NOTE_EXITSTATUS;
+        /// The process forked
+        NOTE_FORK;
+        /// The process executed a new program
+        NOTE_EXEC;
+        /// Also watch the children the process forks, for the same events.  Each new child is
+        /// first reported with `NOTE_CHILD`.
+        NOTE_TRACK;
+        /// A child of a tracked process couldn't be watched (read-only)
+        NOTE_TRACKERR;
+        /// The process is a new child of a tracked process (read-only)
+        NOTE_CHILD;
+    }
+);
+
+/// An event returned by
+/// [`ProcessWatcher::read_events`](struct.ProcessWatcher.html#method.read_events)
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ProcEvent {
+    pid: Pid,
+    events: ProcEvents,
+    data: intptr_t,
+}
+
+impl ProcEvent {
+    /// The process that the events happened to
+    pub fn pid(&self) -> Pid {
+        self.pid
+    }
+
+    /// What happened to the process since it was last reported
+    pub fn events(&self) -> ProcEvents {
+        self.events
+    }
+
+    /// How the process exited, for a `NOTE_EXIT` event.
+    ///
+    /// On macOS and iOS, the exit status is only reported if the process was watched with
+    /// `NOTE_EXITSTATUS`.
+    pub fn exit_status(&self) -> Option<WaitStatus> {
+        if self.events.contains(ProcEvents::NOTE_EXIT) {
+            WaitStatus::from_raw(self.pid, self.data as i32).ok()
+        } else {
+            None
+        }
+    }
+
+    /// The parent of the process, for a `NOTE_CHILD` event.
+    pub fn parent(&self) -> Option<Pid> {
+        if self.events.contains(ProcEvents::NOTE_CHILD) {
+            Some(Pid::from_raw(self.data as libc::pid_t))
+        } else {
+            None
+        }
+    }
+}
+
+/// Watches processes for exits, forks and execs with `EVFILT_PROC`
+///
+/// Unlike `waitpid`, any process that the caller may signal can be watched, not only its own
+/// children.  A process stops being watched once it exits.  The watcher owns its kqueue, which
+/// is closed when it's dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use nix::sys::event::{ProcEvents, ProcessWatcher};
+/// # use nix::unistd::Pid;
+/// let watcher = ProcessWatcher::new().unwrap();
+/// watcher.add(Pid::from_raw(1234), ProcEvents::NOTE_EXIT | ProcEvents::NOTE_EXEC).unwrap();
+/// for event in watcher.read_events(None).unwrap() {
+///     println!("{:?} happened to {}", event.events(), event.pid());
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ProcessWatcher {
+    kq: RawFd,
+}
+
+impl ProcessWatcher {
+    /// The maximum number of events returned by a single call to
+    /// [`read_events`](#method.read_events)
+    pub const MAX_EVENTS: usize = 32;
+
+    /// Create a new watcher that isn't watching anything yet.
+    pub fn new() -> Result<ProcessWatcher> {
+        kqueue().map(|kq| ProcessWatcher { kq: kq })
+    }
+
+    /// Start watching `pid` for `events`, or change the events it's watched for.
+    ///
+    /// Fails with `ESRCH` if the process doesn't exist, including if it has already exited.
+    pub fn add(&self, pid: Pid, events: ProcEvents) -> Result<()> {
+        let kev = KEventBuilder::new(libc::pid_t::from(pid) as uintptr_t, EventFilter::EVFILT_PROC)
+            .add()
+            .clear()
+            .fflags(FilterFlag::from_bits_truncate(events.bits()))
+            .build();
+        kevent_ts(self.kq, &[kev], &mut [], None).map(drop)
+    }
+
+    /// Stop watching `pid`.
+    pub fn remove(&self, pid: Pid) -> Result<()> {
+        let kev = KEventBuilder::new(libc::pid_t::from(pid) as uintptr_t, EventFilter::EVFILT_PROC)
+            .delete()
+            .build();
+        kevent_ts(self.kq, &[kev], &mut [], None).map(drop)
+    }
+
+    /// Wait until at least one watched process has an event, and return what happened.
+    ///
+    /// A `timeout` of `None` waits forever, and an empty vector is returned if the timeout
+    /// expires.
+    pub fn read_events(&self, timeout: Option<TimeSpec>) -> Result<Vec<ProcEvent>> {
+        let empty = KEventBuilder::new(0, EventFilter::EVFILT_PROC).build();
+        let mut kevs = vec![empty; ProcessWatcher::MAX_EVENTS];
+        let n = try!(kevent_ts(self.kq, &[], &mut kevs, timeout.map(|t| *t.as_ref())));
+
+        Ok(kevs[..n].iter().map(|kev| ProcEvent {
+            pid: Pid::from_raw(kev.ident() as libc::pid_t),
+            events: ProcEvents::from_bits_truncate(kev.fflags().bits()),
+            data: kev.data(),
+        }).collect())
+    }
+}
+
+impl Drop for ProcessWatcher {
+    fn drop(&mut self) {
+        let _ = unistd::close(self.kq);
+    }
+}
+
+impl AsRawFd for ProcessWatcher {
+    fn as_raw_fd(&self) -> RawFd {
+        self.kq
+    }
+}
+
+impl IntoRawFd for ProcessWatcher {
+    fn into_raw_fd(self) -> RawFd {
+        let fd = self.kq;
+        mem::forget(self);
+        fd
+    }
+}
+
+impl FromRawFd for ProcessWatcher {
+    /// Take ownership of `fd`, which must be an open kqueue not owned by anything
+    /// else.
+    unsafe fn from_raw_fd(fd: RawFd) -> ProcessWatcher {
+        ProcessWatcher { kq: fd }
+    }
+}
+
 #[test]
 fn test_struct_kevent() {
     let udata : intptr_t = 12345;
@@ -332,3 +741,20 @@ fn test_struct_kevent() {
     assert!(expected.udata == actual.udata() as type_of_udata);
     assert!(mem::size_of::<libc::kevent>() == mem::size_of::<KEvent>());
 }
+
+#[test]
+fn test_kevent_builder() {
+    let kev = KEventBuilder::new(7, EventFilter::EVFILT_READ)
+        .add()
+        .oneshot()
+        .fflags(FilterFlag::NOTE_LOWAT)
+        .data(16)
+        .udata(42)
+        .build();
+    assert_eq!(kev.ident(), 7);
+    assert_eq!(kev.filter(), EventFilter::EVFILT_READ);
+    assert_eq!(kev.flags(), EventFlag::EV_ADD | EventFlag::EV_ONESHOT);
+    assert_eq!(kev.fflags(), FilterFlag::NOTE_LOWAT);
+    assert_eq!(kev.data(), 16);
+    assert_eq!(kev.udata(), 42);
+}
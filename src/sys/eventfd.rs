@@ -1,7 +1,9 @@
 use libc;
-use std::os::unix::io::RawFd;
-use Result;
+use std::{mem, slice};
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use {Error, Result};
 use errno::Errno;
+use unistd::{self, Fd};
 
 libc_bitflags! {
     pub struct EfdFlags: libc::c_int {
@@ -16,3 +18,96 @@ pub fn eventfd(initval: libc::c_uint, flags: EfdFlags) -> Result<RawFd> {
 
     Errno::result(res).map(|r| r as RawFd)
 }
+
+/// Like [`eventfd`](fn.eventfd.html), but returns an owned file descriptor.
+pub fn eventfd_owned(initval: libc::c_uint, flags: EfdFlags) -> Result<Fd> {
+    eventfd(initval, flags).map(|fd| unsafe { Fd::from_raw_fd(fd) })
+}
+
+/// An owned `eventfd` instance, which is closed when dropped (see
+/// [eventfd(2)](http://man7.org/linux/man-pages/man2/eventfd.2.html)).
+///
+/// An `EventFd` holds a 64-bit counter.  Writing adds to it, and reading returns it and resets it
+/// to zero, or with `EFD_SEMAPHORE`, returns 1 and decrements it.  It's readable whenever the
+/// counter is nonzero, which makes it a cheap way to wake a thread blocked in `poll` or `epoll`.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::eventfd::{EfdFlags, EventFd};
+/// let efd = EventFd::new(0, EfdFlags::EFD_CLOEXEC).unwrap();
+/// efd.write(2).unwrap();
+/// efd.write(3).unwrap();
+/// assert_eq!(efd.read().unwrap(), Some(5));
+/// ```
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct EventFd(RawFd);
+
+impl EventFd {
+    /// Create a new `EventFd` with its counter set to `initval`.
+    pub fn new(initval: libc::c_uint, flags: EfdFlags) -> Result<EventFd> {
+        eventfd(initval, flags).map(EventFd)
+    }
+
+    /// Read the counter.
+    ///
+    /// Blocks while the counter is zero, unless the `EventFd` was created with `EFD_NONBLOCK`, in
+    /// which case `None` is returned.
+    pub fn read(&self) -> Result<Option<u64>> {
+        let mut value: u64 = 0;
+        let res = {
+            let buffer = unsafe {
+                slice::from_raw_parts_mut(&mut value as *mut u64 as *mut u8,
+                                          mem::size_of::<u64>())
+            };
+            unistd::read(self.0, buffer)
+        };
+
+        match res {
+            Ok(8) => Ok(Some(value)),
+            Ok(_) => unreachable!("partial read on eventfd"),
+            Err(Error::Sys(Errno::EAGAIN)) => Ok(None),
+            Err(error) => Err(error)
+        }
+    }
+
+    /// Add `value` to the counter.
+    ///
+    /// If that would overflow the counter, blocks until it's read, unless the `EventFd` was
+    /// created with `EFD_NONBLOCK`, in which case it fails with `EAGAIN`.  Writing
+    /// `u64::max_value()` always fails with `EINVAL`.
+    pub fn write(&self, value: u64) -> Result<()> {
+        let buffer = unsafe {
+            slice::from_raw_parts(&value as *const u64 as *const u8, mem::size_of::<u64>())
+        };
+        unistd::write(self.0, buffer).map(drop)
+    }
+}
+
+impl Drop for EventFd {
+    fn drop(&mut self) {
+        let _ = unistd::close(self.0);
+    }
+}
+
+impl AsRawFd for EventFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+impl IntoRawFd for EventFd {
+    fn into_raw_fd(self) -> RawFd {
+        let fd = self.0;
+        mem::forget(self);
+        fd
+    }
+}
+
+impl FromRawFd for EventFd {
+    /// Take ownership of `fd`, which must be an open eventfd not owned by anything
+    /// else.
+    unsafe fn from_raw_fd(fd: RawFd) -> EventFd {
+        EventFd(fd)
+    }
+}
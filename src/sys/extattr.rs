@@ -0,0 +1,240 @@
+//! Extended attributes of files (see
+//! [extattr(2)](https://www.freebsd.org/cgi/man.cgi?query=extattr&sektion=2)).
+//!
+//! Extended attributes are named pieces of data attached to files, in a
+//! namespace: the user namespace is for anyone with access to the file, and
+//! the system namespace is for the kernel and privileged processes.  Each
+//! operation exists for a path (`_file`), for a path without following a
+//! final symbolic link (`_link`), and for an open file descriptor (`_fd`).
+//!
+//! The `get` and `list` operations read into a buffer and return the number
+//! of bytes read.  With an empty buffer, they return the size the buffer
+//! needs instead.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use nix::sys::extattr::*;
+//! extattr_set_file("/tmp/file", AttrNamespace::EXTATTR_NAMESPACE_USER, "origin", b"backup")
+//!     .unwrap();
+//! let mut buf = [0u8; 64];
+//! let len = extattr_get_file("/tmp/file", AttrNamespace::EXTATTR_NAMESPACE_USER, "origin",
+//!                            &mut buf).unwrap();
+//! assert_eq!(&buf[..len], b"backup");
+//! ```
+use libc::{self, c_void, size_t};
+use std::os::unix::io::RawFd;
+use std::ptr;
+use errno::Errno;
+use {NixPath, Result};
+
+libc_enum! {
+    /// The namespace of an extended attribute
+    #[repr(i32)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub enum AttrNamespace {
+        /// Attributes anyone with access to the file may use
+        EXTATTR_NAMESPACE_USER,
+        /// Attributes only the kernel and privileged processes may use
+        EXTATTR_NAMESPACE_SYSTEM,
+    }
+}
+
+// An empty buffer asks for the size of the value, which needs a null pointer
+fn buf_ptr(buf: &mut [u8]) -> *mut c_void {
+    if buf.is_empty() {
+        ptr::null_mut()
+    } else {
+        buf.as_mut_ptr() as *mut c_void
+    }
+}
+
+/// Read the value of an extended attribute of a file
+pub fn extattr_get_file<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(
+        path: &P1, namespace: AttrNamespace, name: &P2, buf: &mut [u8]) -> Result<usize> {
+    let len = buf.len() as size_t;
+    let res = try!(try!(path.with_nix_path(|path| {
+        name.with_nix_path(|name| unsafe {
+            libc::extattr_get_file(path.as_ptr(), namespace as i32, name.as_ptr(), buf_ptr(buf),
+                                   len)
+        })
+    })));
+
+    Errno::result(res).map(|r| r as usize)
+}
+
+/// Read the value of an extended attribute of a file, without following a
+/// final symbolic link
+pub fn extattr_get_link<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(
+        path: &P1, namespace: AttrNamespace, name: &P2, buf: &mut [u8]) -> Result<usize> {
+    let len = buf.len() as size_t;
+    let res = try!(try!(path.with_nix_path(|path| {
+        name.with_nix_path(|name| unsafe {
+            libc::extattr_get_link(path.as_ptr(), namespace as i32, name.as_ptr(), buf_ptr(buf),
+                                   len)
+        })
+    })));
+
+    Errno::result(res).map(|r| r as usize)
+}
+
+/// Read the value of an extended attribute of an open file
+pub fn extattr_get_fd<P: ?Sized + NixPath>(fd: RawFd, namespace: AttrNamespace, name: &P,
+                                           buf: &mut [u8]) -> Result<usize> {
+    let len = buf.len() as size_t;
+    let res = try!(name.with_nix_path(|name| unsafe {
+        libc::extattr_get_fd(fd, namespace as i32, name.as_ptr(), buf_ptr(buf), len)
+    }));
+
+    Errno::result(res).map(|r| r as usize)
+}
+
+/// Set the value of an extended attribute of a file, creating it if needed
+pub fn extattr_set_file<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(
+        path: &P1, namespace: AttrNamespace, name: &P2, value: &[u8]) -> Result<()> {
+    let res = try!(try!(path.with_nix_path(|path| {
+        name.with_nix_path(|name| unsafe {
+            libc::extattr_set_file(path.as_ptr(), namespace as i32, name.as_ptr(),
+                                   value.as_ptr() as *const c_void, value.len() as size_t)
+        })
+    })));
+
+    Errno::result(res).map(drop)
+}
+
+/// Set the value of an extended attribute of a file, without following a
+/// final symbolic link
+pub fn extattr_set_link<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(
+        path: &P1, namespace: AttrNamespace, name: &P2, value: &[u8]) -> Result<()> {
+    let res = try!(try!(path.with_nix_path(|path| {
+        name.with_nix_path(|name| unsafe {
+            libc::extattr_set_link(path.as_ptr(), namespace as i32, name.as_ptr(),
+                                   value.as_ptr() as *const c_void, value.len() as size_t)
+        })
+    })));
+
+    Errno::result(res).map(drop)
+}
+
+/// Set the value of an extended attribute of an open file
+pub fn extattr_set_fd<P: ?Sized + NixPath>(fd: RawFd, namespace: AttrNamespace, name: &P,
+                                           value: &[u8]) -> Result<()> {
+    let res = try!(name.with_nix_path(|name| unsafe {
+        libc::extattr_set_fd(fd, namespace as i32, name.as_ptr(),
+                             value.as_ptr() as *const c_void, value.len() as size_t)
+    }));
+
+    Errno::result(res).map(drop)
+}
+
+/// Remove an extended attribute of a file
+pub fn extattr_delete_file<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(
+        path: &P1, namespace: AttrNamespace, name: &P2) -> Result<()> {
+    let res = try!(try!(path.with_nix_path(|path| {
+        name.with_nix_path(|name| unsafe {
+            libc::extattr_delete_file(path.as_ptr(), namespace as i32, name.as_ptr())
+        })
+    })));
+
+    Errno::result(res).map(drop)
+}
+
+/// Remove an extended attribute of a file, without following a final
+/// symbolic link
+pub fn extattr_delete_link<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(
+        path: &P1, namespace: AttrNamespace, name: &P2) -> Result<()> {
+    let res = try!(try!(path.with_nix_path(|path| {
+        name.with_nix_path(|name| unsafe {
+            libc::extattr_delete_link(path.as_ptr(), namespace as i32, name.as_ptr())
+        })
+    })));
+
+    Errno::result(res).map(drop)
+}
+
+/// Remove an extended attribute of an open file
+pub fn extattr_delete_fd<P: ?Sized + NixPath>(fd: RawFd, namespace: AttrNamespace, name: &P)
+                                              -> Result<()> {
+    let res = try!(name.with_nix_path(|name| unsafe {
+        libc::extattr_delete_fd(fd, namespace as i32, name.as_ptr())
+    }));
+
+    Errno::result(res).map(drop)
+}
+
+/// List the names of the extended attributes of a file in a namespace.  Use
+/// [`ExtattrNames`](struct.ExtattrNames.html) to read them from the buffer.
+pub fn extattr_list_file<P: ?Sized + NixPath>(path: &P, namespace: AttrNamespace,
+                                              buf: &mut [u8]) -> Result<usize> {
+    let len = buf.len() as size_t;
+    let res = try!(path.with_nix_path(|path| unsafe {
+        libc::extattr_list_file(path.as_ptr(), namespace as i32, buf_ptr(buf), len)
+    }));
+
+    Errno::result(res).map(|r| r as usize)
+}
+
+/// List the names of the extended attributes of a file in a namespace,
+/// without following a final symbolic link
+pub fn extattr_list_link<P: ?Sized + NixPath>(path: &P, namespace: AttrNamespace,
+                                              buf: &mut [u8]) -> Result<usize> {
+    let len = buf.len() as size_t;
+    let res = try!(path.with_nix_path(|path| unsafe {
+        libc::extattr_list_link(path.as_ptr(), namespace as i32, buf_ptr(buf), len)
+    }));
+
+    Errno::result(res).map(|r| r as usize)
+}
+
+/// List the names of the extended attributes of an open file in a namespace
+pub fn extattr_list_fd(fd: RawFd, namespace: AttrNamespace, buf: &mut [u8]) -> Result<usize> {
+    let len = buf.len() as size_t;
+    let res = unsafe { libc::extattr_list_fd(fd, namespace as i32, buf_ptr(buf), len) };
+
+    Errno::result(res).map(|r| r as usize)
+}
+
+/// An iterator over the names listed by the `extattr_list_*` functions.  The
+/// list has a byte with the length of each name before the name, which isn't
+/// terminated by a nul.
+///
+/// ```no_run
+/// # use nix::sys::extattr::*;
+/// let mut buf = [0u8; 1024];
+/// let len = extattr_list_file("/tmp/file", AttrNamespace::EXTATTR_NAMESPACE_USER, &mut buf)
+///     .unwrap();
+/// for name in ExtattrNames::new(&buf[..len]) {
+///     println!("{}", String::from_utf8_lossy(name));
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ExtattrNames<'a> {
+    list: &'a [u8],
+}
+
+impl<'a> ExtattrNames<'a> {
+    /// Iterate over the names in the part of the buffer filled by an
+    /// `extattr_list_*` function
+    pub fn new(list: &'a [u8]) -> ExtattrNames<'a> {
+        ExtattrNames { list: list }
+    }
+}
+
+impl<'a> Iterator for ExtattrNames<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        let len = match self.list.first() {
+            Some(&len) => len as usize,
+            None => return None,
+        };
+        if self.list.len() < 1 + len {
+            // A truncated list, if the buffer was too small
+            self.list = &[];
+            return None;
+        }
+        let name = &self.list[1..1 + len];
+        self.list = &self.list[1 + len..];
+        Some(name)
+    }
+}
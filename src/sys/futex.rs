@@ -0,0 +1,195 @@
+//! Fast user-space locking (see
+//! [futex(2)](http://man7.org/linux/man-pages/man2/futex.2.html)).
+//!
+//! A futex is a 32-bit word, usually in memory shared between threads or
+//! processes, which the uncontended path of a synchronization primitive
+//! manipulates with atomic instructions alone.  These functions only serve to
+//! sleep until the word changes and to wake up the sleepers.
+//!
+//! The kernel identifies a futex by its address.  Unless `FUTEX_PRIVATE_FLAG`
+//! is given, which is faster but restricted to the threads of one process, it
+//! works across processes that map the same memory, even at different
+//! addresses.
+//!
+//! The operations that only read the futex words are safe: an invalid address
+//! just makes them fail with `EFAULT`.  The priority-inheritance operations
+//! write the thread ID of the owner into the word, so they are unsafe.
+use libc::{self, c_int, c_long, timespec};
+use std::ptr;
+use errno::Errno;
+use sys::time::TimeSpec;
+use Result;
+
+libc_bitflags!{
+    /// Flags modifying a futex operation
+    pub struct FutexFlags: c_int {
+        /// The futex is only used by the threads of this process
+        FUTEX_PRIVATE_FLAG;
+        /// Measure absolute timeouts against `CLOCK_REALTIME` instead of
+        /// `CLOCK_MONOTONIC` (since Linux 4.5 for `futex_wait`)
+        FUTEX_CLOCK_REALTIME;
+    }
+}
+
+/// The bitset that matches every waiter in
+/// [`futex_wait_bitset`](fn.futex_wait_bitset.html) and
+/// [`futex_wake_bitset`](fn.futex_wake_bitset.html)
+pub const FUTEX_BITSET_MATCH_ANY: u32 = 0xffff_ffff;
+
+/// Set in a priority-inheritance futex when other threads wait for it
+pub const FUTEX_WAITERS: u32 = 0x8000_0000;
+/// Set in a priority-inheritance futex when its owner died without unlocking it
+pub const FUTEX_OWNER_DIED: u32 = 0x4000_0000;
+/// The bits of a priority-inheritance futex that hold the thread ID of its owner
+pub const FUTEX_TID_MASK: u32 = 0x3fff_ffff;
+
+// The kernel checks the addresses, so only the operations that write to the
+// futex words are unsafe
+fn futex(uaddr: *const u32, op: c_int, flags: FutexFlags, val: u32, timeout: *const timespec,
+         uaddr2: *const u32, val3: u32) -> Result<c_int> {
+    let res = unsafe {
+        libc::syscall(libc::SYS_futex, uaddr, op | flags.bits(), val, timeout, uaddr2, val3)
+    };
+
+    Errno::result(res).map(|r| r as c_int)
+}
+
+fn timeout_ptr(timeout: Option<&TimeSpec>) -> *const timespec {
+    timeout.map_or(ptr::null(), |t| t.as_ref())
+}
+
+// The requeue operations pass a count where the timeout usually goes
+fn count_ptr(count: c_int) -> *const timespec {
+    count as c_long as *const timespec
+}
+
+/// Sleep until the futex is woken up, if it still holds `val` (`FUTEX_WAIT`).
+///
+/// `timeout` is relative, and measured against `CLOCK_MONOTONIC`.  Fails with
+/// `EAGAIN` if the futex doesn't hold `val`, with `ETIMEDOUT` if the timeout
+/// expires, and with `EINTR` if a signal handler interrupts the wait.  A
+/// successful return may be spurious, so callers must check the futex again.
+pub fn futex_wait(uaddr: *const u32, val: u32, timeout: Option<&TimeSpec>, flags: FutexFlags)
+                  -> Result<()> {
+    futex(uaddr, libc::FUTEX_WAIT, flags, val, timeout_ptr(timeout), ptr::null(), 0).map(drop)
+}
+
+/// Wake up at most `nwake` threads waiting on the futex (`FUTEX_WAKE`).
+///
+/// Returns the number of threads woken up.
+pub fn futex_wake(uaddr: *const u32, nwake: c_int, flags: FutexFlags) -> Result<c_int> {
+    futex(uaddr, libc::FUTEX_WAKE, flags, nwake as u32, ptr::null(), ptr::null(), 0)
+}
+
+/// Like [`futex_wait`](fn.futex_wait.html), but with an absolute timeout, and
+/// only woken up by wakes whose bitset shares a bit with `bitset`
+/// (`FUTEX_WAIT_BITSET`).
+///
+/// `abs_timeout` is measured against `CLOCK_MONOTONIC`, or `CLOCK_REALTIME` with
+/// `FUTEX_CLOCK_REALTIME`.  `bitset` must not be zero.
+pub fn futex_wait_bitset(uaddr: *const u32, val: u32, abs_timeout: Option<&TimeSpec>, bitset: u32,
+                         flags: FutexFlags) -> Result<()> {
+    futex(uaddr, libc::FUTEX_WAIT_BITSET, flags, val, timeout_ptr(abs_timeout), ptr::null(),
+          bitset).map(drop)
+}
+
+/// Like [`futex_wake`](fn.futex_wake.html), but only waking up waiters whose
+/// bitset shares a bit with `bitset` (`FUTEX_WAKE_BITSET`).
+pub fn futex_wake_bitset(uaddr: *const u32, nwake: c_int, bitset: u32, flags: FutexFlags)
+                         -> Result<c_int> {
+    futex(uaddr, libc::FUTEX_WAKE_BITSET, flags, nwake as u32, ptr::null(), ptr::null(),
+          bitset)
+}
+
+/// Wake up at most `nwake` threads waiting on the futex at `uaddr`, and move at
+/// most `nrequeue` of the remaining ones to the futex at `uaddr2`
+/// (`FUTEX_REQUEUE`).
+///
+/// Returns the number of threads woken up.  This is racy, and
+/// [`futex_cmp_requeue`](fn.futex_cmp_requeue.html) should be used instead.
+pub fn futex_requeue(uaddr: *const u32, nwake: c_int, uaddr2: *const u32, nrequeue: c_int,
+                     flags: FutexFlags) -> Result<c_int> {
+    futex(uaddr, libc::FUTEX_REQUEUE, flags, nwake as u32, count_ptr(nrequeue), uaddr2, 0)
+}
+
+/// Like [`futex_requeue`](fn.futex_requeue.html), but failing with `EAGAIN`
+/// unless the futex at `uaddr` still holds `val` (`FUTEX_CMP_REQUEUE`).
+///
+/// Returns the number of threads woken up or moved.
+pub fn futex_cmp_requeue(uaddr: *const u32, nwake: c_int, uaddr2: *const u32, nrequeue: c_int,
+                         val: u32, flags: FutexFlags) -> Result<c_int> {
+    futex(uaddr, libc::FUTEX_CMP_REQUEUE, flags, nwake as u32, count_ptr(nrequeue), uaddr2,
+          val)
+}
+
+/// Acquire a priority-inheritance futex whose fast path failed, sleeping until
+/// it's unlocked (`FUTEX_LOCK_PI`).
+///
+/// The futex holds 0 when unlocked, and the thread ID of its owner otherwise.
+/// `abs_timeout` is measured against `CLOCK_REALTIME`.  Fails with `EDEADLK`
+/// if this thread already owns the futex.
+///
+/// # Safety
+///
+/// `uaddr` must point to a futex word that is only modified with atomic
+/// operations.
+pub unsafe fn futex_lock_pi(uaddr: *mut u32, abs_timeout: Option<&TimeSpec>, flags: FutexFlags)
+                            -> Result<()> {
+    futex(uaddr, libc::FUTEX_LOCK_PI, flags, 0, timeout_ptr(abs_timeout), ptr::null(), 0)
+        .map(drop)
+}
+
+/// Try to acquire a priority-inheritance futex, failing with `EAGAIN` if it's
+/// owned by another thread (`FUTEX_TRYLOCK_PI`).
+///
+/// # Safety
+///
+/// See [`futex_lock_pi`](fn.futex_lock_pi.html).
+pub unsafe fn futex_trylock_pi(uaddr: *mut u32, flags: FutexFlags) -> Result<()> {
+    futex(uaddr, libc::FUTEX_TRYLOCK_PI, flags, 0, ptr::null(), ptr::null(), 0).map(drop)
+}
+
+/// Release a priority-inheritance futex owned by this thread, handing it to
+/// the highest-priority waiter (`FUTEX_UNLOCK_PI`).
+///
+/// Fails with `EPERM` if this thread doesn't own the futex.
+///
+/// # Safety
+///
+/// See [`futex_lock_pi`](fn.futex_lock_pi.html).
+pub unsafe fn futex_unlock_pi(uaddr: *mut u32, flags: FutexFlags) -> Result<()> {
+    futex(uaddr, libc::FUTEX_UNLOCK_PI, flags, 0, ptr::null(), ptr::null(), 0).map(drop)
+}
+
+/// Wait on the non-PI futex at `uaddr` if it still holds `val`, expecting to be
+/// moved to the priority-inheritance futex at `uaddr2` with
+/// [`futex_cmp_requeue_pi`](fn.futex_cmp_requeue_pi.html)
+/// (`FUTEX_WAIT_REQUEUE_PI`).
+///
+/// On success, this thread owns the futex at `uaddr2`.  `abs_timeout` is
+/// measured against `CLOCK_MONOTONIC`, or `CLOCK_REALTIME` with
+/// `FUTEX_CLOCK_REALTIME`.
+///
+/// # Safety
+///
+/// See [`futex_lock_pi`](fn.futex_lock_pi.html).
+pub unsafe fn futex_wait_requeue_pi(uaddr: *const u32, val: u32, abs_timeout: Option<&TimeSpec>,
+                                    uaddr2: *mut u32, flags: FutexFlags) -> Result<()> {
+    futex(uaddr, libc::FUTEX_WAIT_REQUEUE_PI, flags, val, timeout_ptr(abs_timeout), uaddr2, 0)
+        .map(drop)
+}
+
+/// Wake up one thread waiting on the futex at `uaddr` by making it acquire the
+/// priority-inheritance futex at `uaddr2`, if possible, and move at most
+/// `nrequeue` of the others to `uaddr2` (`FUTEX_CMP_REQUEUE_PI`).
+///
+/// Fails with `EAGAIN` unless the futex at `uaddr` still holds `val`.  Returns
+/// the number of threads woken up or moved.
+///
+/// # Safety
+///
+/// See [`futex_lock_pi`](fn.futex_lock_pi.html).
+pub unsafe fn futex_cmp_requeue_pi(uaddr: *const u32, uaddr2: *mut u32, nrequeue: c_int, val: u32,
+                                   flags: FutexFlags) -> Result<c_int> {
+    futex(uaddr, libc::FUTEX_CMP_REQUEUE_PI, flags, 1, count_ptr(nrequeue), uaddr2, val)
+}
@@ -0,0 +1,557 @@
+//! Asynchronous I/O through submission and completion rings shared with the kernel (see
+//! [io_uring(7)](http://man7.org/linux/man-pages/man7/io_uring.7.html)).
+//!
+//! Requests are described by submission queue entries ([`Sqe`](struct.Sqe.html)), queued on the
+//! submission ring and handed to the kernel in a batch with a single system call.  The result of
+//! each request is later posted on the completion ring as a [`Cqe`](struct.Cqe.html), carrying the
+//! `user_data` of the request it completes.
+//!
+//! [`IoUring`](struct.IoUring.html) owns an instance and its mapped rings.  The raw system calls
+//! are also available for callers that manage the rings themselves.  io_uring was added in Linux
+//! 5.1, and may also be disabled by the administrator, in which case creating an instance fails
+//! with `ENOSYS` or `EPERM`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use nix::sys::io_uring::{IoUring, Sqe};
+//! let mut ring = IoUring::new(8).unwrap();
+//! unsafe { ring.push(&Sqe::nop().user_data(42)) }.unwrap();
+//! ring.submit_and_wait(1).unwrap();
+//! let cqe = ring.pop_completion().unwrap();
+//! assert_eq!(cqe.user_data(), 42);
+//! assert_eq!(cqe.result(), Ok(0));
+//! ```
+use libc::{self, c_void};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::sync::atomic::{self, Ordering};
+use std::{mem, ptr};
+use errno::Errno;
+use sys::mman::{self, MapFlags, ProtFlags};
+use sys::signal::{KERNEL_SIGSET_SIZE, SigSet};
+use sys::socket::SockFlag;
+use sys::uio::IoVec;
+use unistd;
+use Result;
+
+// libc doesn't export io_uring, so these values are taken from linux/io_uring.h
+
+/// Operation codes of submission queue entries
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Opcode {
+    IORING_OP_NOP = 0,
+    IORING_OP_READV = 1,
+    IORING_OP_WRITEV = 2,
+    IORING_OP_FSYNC = 3,
+    IORING_OP_ACCEPT = 13,
+}
+
+bitflags! {
+    /// Flags for [`io_uring_setup`](fn.io_uring_setup.html)
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct SetupFlags: u32 {
+        /// Busy-wait for completions instead of using interrupts.  Only supported on files
+        /// opened with `O_DIRECT`.
+        const IORING_SETUP_IOPOLL = 1 << 0;
+        /// Let a kernel thread poll the submission ring, so submitting doesn't require a
+        /// system call while the thread is awake
+        const IORING_SETUP_SQPOLL = 1 << 1;
+        /// Bind the polling thread to the CPU in `sq_thread_cpu`
+        const IORING_SETUP_SQ_AFF = 1 << 2;
+        /// Size the completion ring with `cq_entries` instead of twice the submission ring
+        const IORING_SETUP_CQSIZE = 1 << 3;
+        /// Clamp out of range ring sizes instead of failing with `EINVAL`
+        const IORING_SETUP_CLAMP = 1 << 4;
+        /// Share the asynchronous workers of the instance in `wq_fd`
+        const IORING_SETUP_ATTACH_WQ = 1 << 5;
+    }
+}
+
+bitflags! {
+    /// Flags for [`io_uring_enter`](fn.io_uring_enter.html)
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct EnterFlags: u32 {
+        /// Wait for `min_complete` completions before returning
+        const IORING_ENTER_GETEVENTS = 1 << 0;
+        /// Wake up the polling thread of an `IORING_SETUP_SQPOLL` instance
+        const IORING_ENTER_SQ_WAKEUP = 1 << 1;
+        /// Wait until the submission ring has free entries
+        const IORING_ENTER_SQ_WAIT = 1 << 2;
+    }
+}
+
+bitflags! {
+    /// Flags of a submission queue entry, see [`Sqe::flags`](struct.Sqe.html#method.flags)
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct SqeFlags: u8 {
+        /// `fd` is an index into the files registered with `IORING_REGISTER_FILES`
+        const IOSQE_FIXED_FILE = 1 << 0;
+        /// Don't start this request until all previous ones have completed
+        const IOSQE_IO_DRAIN = 1 << 1;
+        /// Don't start the next request until this one has completed successfully
+        const IOSQE_IO_LINK = 1 << 2;
+        /// Like `IOSQE_IO_LINK`, but the next request is started even if this one fails
+        const IOSQE_IO_HARDLINK = 1 << 3;
+        /// Always execute the request asynchronously, rather than trying non-blocking first
+        const IOSQE_ASYNC = 1 << 4;
+    }
+}
+
+bitflags! {
+    /// Flags for [`Sqe::fsync`](struct.Sqe.html#method.fsync)
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct FsyncFlags: u32 {
+        /// Only flush the data, like `fdatasync`
+        const IORING_FSYNC_DATASYNC = 1 << 0;
+    }
+}
+
+/// Operations of [`io_uring_register`](fn.io_uring_register.html)
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RegisterOp {
+    /// Register an array of `iovec`s as fixed buffers
+    IORING_REGISTER_BUFFERS = 0,
+    IORING_UNREGISTER_BUFFERS = 1,
+    /// Register an array of file descriptors, for use with `IOSQE_FIXED_FILE`
+    IORING_REGISTER_FILES = 2,
+    IORING_UNREGISTER_FILES = 3,
+    /// Register an eventfd that is signaled on completions
+    IORING_REGISTER_EVENTFD = 4,
+    IORING_UNREGISTER_EVENTFD = 5,
+}
+
+/// Offsets of the rings for `mmap`
+const IORING_OFF_SQ_RING: libc::off_t = 0;
+const IORING_OFF_CQ_RING: libc::off_t = 0x800_0000;
+const IORING_OFF_SQES: libc::off_t = 0x1000_0000;
+
+/// Set in the submission ring flags when the polling thread has gone to sleep
+const IORING_SQ_NEED_WAKEUP: u32 = 1 << 0;
+
+/// Offsets of the fields of the submission ring, filled in by `io_uring_setup`
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SqringOffsets {
+    pub head: u32,
+    pub tail: u32,
+    pub ring_mask: u32,
+    pub ring_entries: u32,
+    pub flags: u32,
+    pub dropped: u32,
+    pub array: u32,
+    pub resv1: u32,
+    pub resv2: u64,
+}
+
+/// Offsets of the fields of the completion ring, filled in by `io_uring_setup`
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CqringOffsets {
+    pub head: u32,
+    pub tail: u32,
+    pub ring_mask: u32,
+    pub ring_entries: u32,
+    pub overflow: u32,
+    pub cqes: u32,
+    pub flags: u32,
+    pub resv1: u32,
+    pub resv2: u64,
+}
+
+/// Parameters of [`io_uring_setup`](fn.io_uring_setup.html)
+///
+/// The caller sets `flags` and the fields they enable, and the kernel fills in the rest: the
+/// actual sizes of the rings, the features it supports and the offsets needed to map the rings.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct IoUringParams {
+    pub sq_entries: u32,
+    pub cq_entries: u32,
+    pub flags: u32,
+    pub sq_thread_cpu: u32,
+    pub sq_thread_idle: u32,
+    pub features: u32,
+    pub wq_fd: u32,
+    pub resv: [u32; 3],
+    pub sq_off: SqringOffsets,
+    pub cq_off: CqringOffsets,
+}
+
+/// A submission queue entry, describing one request
+///
+/// Entries are created with one of the constructors for the supported operations, and can be
+/// customized with [`user_data`](#method.user_data) and [`flags`](#method.flags).  An entry doesn't
+/// borrow the buffers it refers to, which the kernel uses until the request completes; see
+/// [`IoUring::push`](struct.IoUring.html#method.push).
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Sqe {
+    opcode: u8,
+    flags: u8,
+    ioprio: u16,
+    fd: i32,
+    off: u64,
+    addr: u64,
+    len: u32,
+    op_flags: u32,
+    user_data: u64,
+    buf_index: u16,
+    personality: u16,
+    splice_fd_in: i32,
+    addr3: u64,
+    pad: [u64; 1],
+}
+
+impl Sqe {
+    fn new(opcode: Opcode, fd: RawFd) -> Sqe {
+        let mut sqe: Sqe = unsafe { mem::zeroed() };
+        sqe.opcode = opcode as u8;
+        sqe.fd = fd;
+        sqe
+    }
+
+    /// A request that does nothing, and completes with a result of 0
+    pub fn nop() -> Sqe {
+        Sqe::new(Opcode::IORING_OP_NOP, -1)
+    }
+
+    /// Read from `fd` at `offset` into the buffers of `iov`, like `preadv`.
+    ///
+    /// An `offset` of -1 reads from the current file position.
+    pub fn readv(fd: RawFd, iov: &[IoVec<&mut [u8]>], offset: i64) -> Sqe {
+        let mut sqe = Sqe::new(Opcode::IORING_OP_READV, fd);
+        sqe.addr = iov.as_ptr() as u64;
+        sqe.len = iov.len() as u32;
+        sqe.off = offset as u64;
+        sqe
+    }
+
+    /// Write the buffers of `iov` to `fd` at `offset`, like `pwritev`.
+    ///
+    /// An `offset` of -1 writes at the current file position.
+    pub fn writev(fd: RawFd, iov: &[IoVec<&[u8]>], offset: i64) -> Sqe {
+        let mut sqe = Sqe::new(Opcode::IORING_OP_WRITEV, fd);
+        sqe.addr = iov.as_ptr() as u64;
+        sqe.len = iov.len() as u32;
+        sqe.off = offset as u64;
+        sqe
+    }
+
+    /// Flush `fd` to its storage device, like `fsync` or, with `IORING_FSYNC_DATASYNC`,
+    /// `fdatasync`.
+    pub fn fsync(fd: RawFd, flags: FsyncFlags) -> Sqe {
+        let mut sqe = Sqe::new(Opcode::IORING_OP_FSYNC, fd);
+        sqe.op_flags = flags.bits();
+        sqe
+    }
+
+    /// Accept a connection on the listening socket `fd`, like `accept4`.
+    ///
+    /// The request completes with the file descriptor of the new connection.
+    pub fn accept(fd: RawFd, flags: SockFlag) -> Sqe {
+        let mut sqe = Sqe::new(Opcode::IORING_OP_ACCEPT, fd);
+        sqe.op_flags = flags.bits() as u32;
+        sqe
+    }
+
+    /// Set the value that identifies the completion of this request
+    pub fn user_data(mut self, user_data: u64) -> Sqe {
+        self.user_data = user_data;
+        self
+    }
+
+    /// Set the flags of this request
+    pub fn flags(mut self, flags: SqeFlags) -> Sqe {
+        self.flags = flags.bits();
+        self
+    }
+}
+
+/// A completion queue entry, describing the result of one request
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Cqe {
+    user_data: u64,
+    res: i32,
+    flags: u32,
+}
+
+impl Cqe {
+    /// The `user_data` of the completed request
+    pub fn user_data(&self) -> u64 {
+        self.user_data
+    }
+
+    /// The result of the request, like the return value of the equivalent system call
+    pub fn result(&self) -> Result<u32> {
+        if self.res < 0 {
+            Err(::Error::Sys(Errno::from_i32(-self.res)))
+        } else {
+            Ok(self.res as u32)
+        }
+    }
+
+    /// Operation specific flags
+    pub fn flags(&self) -> u32 {
+        self.flags
+    }
+}
+
+/// Create an io_uring instance with at least `entries` submission queue entries (see
+/// [io_uring_setup(2)](http://man7.org/linux/man-pages/man2/io_uring_setup.2.html)).
+///
+/// Returns the file descriptor of the instance, whose rings must then be mapped with `mmap` using
+/// the offsets returned in `params`.
+pub fn io_uring_setup(entries: u32, params: &mut IoUringParams) -> Result<RawFd> {
+    let res = unsafe {
+        libc::syscall(libc::SYS_io_uring_setup, entries, params as *mut IoUringParams)
+    };
+
+    Errno::result(res).map(|fd| fd as RawFd)
+}
+
+/// Submit `to_submit` entries of the submission ring, and optionally wait for `min_complete`
+/// completions (see
+/// [io_uring_enter(2)](http://man7.org/linux/man-pages/man2/io_uring_enter.2.html)).
+///
+/// While waiting, the signal mask is replaced with `sigmask` if given.  Returns the number of
+/// entries that were consumed from the submission ring.
+///
+/// # Safety
+///
+/// The submitted entries must be valid, and the memory they refer to must remain valid until
+/// their requests complete.
+pub unsafe fn io_uring_enter(fd: RawFd, to_submit: u32, min_complete: u32, flags: EnterFlags,
+                             sigmask: Option<&SigSet>) -> Result<u32> {
+    let sigmask = match sigmask {
+        Some(sigmask) => sigmask.as_ref() as *const libc::sigset_t,
+        None => ptr::null(),
+    };
+    let res = libc::syscall(libc::SYS_io_uring_enter, fd, to_submit, min_complete, flags.bits(),
+                            sigmask, KERNEL_SIGSET_SIZE);
+
+    Errno::result(res).map(|r| r as u32)
+}
+
+/// Register resources, such as buffers or files, with an io_uring instance (see
+/// [io_uring_register(2)](http://man7.org/linux/man-pages/man2/io_uring_register.2.html)).
+///
+/// # Safety
+///
+/// `arg` must point to `nr_args` elements of the type expected by `opcode`.  Registered buffers
+/// must remain valid until they are unregistered.
+pub unsafe fn io_uring_register(fd: RawFd, opcode: RegisterOp, arg: *const c_void,
+                                nr_args: u32) -> Result<u32> {
+    let res = libc::syscall(libc::SYS_io_uring_register, fd, opcode as u32, arg, nr_args);
+
+    Errno::result(res).map(|r| r as u32)
+}
+
+/// A shared mapping of one of the rings, which is unmapped when dropped
+#[derive(Debug)]
+struct Mmap {
+    addr: *mut c_void,
+    len: usize,
+}
+
+impl Mmap {
+    fn new(fd: RawFd, len: usize, offset: libc::off_t) -> Result<Mmap> {
+        let addr = try!(unsafe {
+            mman::mmap(ptr::null_mut(), len, ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+                       MapFlags::MAP_SHARED | MapFlags::MAP_POPULATE, fd, offset)
+        });
+        Ok(Mmap { addr: addr, len: len })
+    }
+
+    /// A pointer to the field at `offset` bytes from the start of the mapping
+    fn field<T>(&self, offset: u32) -> *mut T {
+        unsafe { (self.addr as *mut u8).offset(offset as isize) as *mut T }
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        let _ = unsafe { mman::munmap(self.addr, self.len) };
+    }
+}
+
+/// An owned io_uring instance with its rings mapped, which is closed when dropped.
+///
+/// Entries are queued with [`push`](#method.push), handed to the kernel with
+/// [`submit`](#method.submit) or [`submit_and_wait`](#method.submit_and_wait), and their
+/// completions are collected with [`pop_completion`](#method.pop_completion).
+///
+/// Only `AsRawFd` is implemented: the rings are mapped along with the file descriptor, so it can't
+/// be handed over alone.
+#[derive(Debug)]
+pub struct IoUring {
+    fd: RawFd,
+    flags: SetupFlags,
+    sq_head: *const u32,
+    sq_tail: *mut u32,
+    sq_mask: u32,
+    sq_entries: u32,
+    sq_flags: *const u32,
+    sq_array: *mut u32,
+    sqes: *mut Sqe,
+    /// Entries that were pushed but not submitted yet
+    sq_pending: u32,
+    cq_head: *mut u32,
+    cq_tail: *const u32,
+    cq_mask: u32,
+    cqes: *const Cqe,
+    // The rings the pointers above point into
+    _sq_ring: Mmap,
+    _cq_ring: Mmap,
+    _sqe_array: Mmap,
+}
+
+impl IoUring {
+    /// Create an instance with at least `entries` submission queue entries.
+    pub fn new(entries: u32) -> Result<IoUring> {
+        IoUring::with_params(entries, &mut IoUringParams::default())
+    }
+
+    /// Create an instance with the given parameters.
+    ///
+    /// The kernel fills in the rest of `params`, which tells the actual sizes of the rings and
+    /// the supported features.
+    pub fn with_params(entries: u32, params: &mut IoUringParams) -> Result<IoUring> {
+        let fd = try!(io_uring_setup(entries, params));
+        IoUring::map_rings(fd, params).map_err(|e| {
+            let _ = unistd::close(fd);
+            e
+        })
+    }
+
+    fn map_rings(fd: RawFd, params: &IoUringParams) -> Result<IoUring> {
+        let sq_len = params.sq_off.array as usize
+            + params.sq_entries as usize * mem::size_of::<u32>();
+        let cq_len = params.cq_off.cqes as usize
+            + params.cq_entries as usize * mem::size_of::<Cqe>();
+        let sqes_len = params.sq_entries as usize * mem::size_of::<Sqe>();
+
+        let sq_ring = try!(Mmap::new(fd, sq_len, IORING_OFF_SQ_RING));
+        let cq_ring = try!(Mmap::new(fd, cq_len, IORING_OFF_CQ_RING));
+        let sqe_array = try!(Mmap::new(fd, sqes_len, IORING_OFF_SQES));
+
+        let sq_off = &params.sq_off;
+        let cq_off = &params.cq_off;
+        unsafe {
+            Ok(IoUring {
+                fd: fd,
+                flags: SetupFlags::from_bits_truncate(params.flags),
+                sq_head: sq_ring.field(sq_off.head),
+                sq_tail: sq_ring.field(sq_off.tail),
+                sq_mask: *sq_ring.field::<u32>(sq_off.ring_mask),
+                sq_entries: *sq_ring.field::<u32>(sq_off.ring_entries),
+                sq_flags: sq_ring.field(sq_off.flags),
+                sq_array: sq_ring.field(sq_off.array),
+                sqes: sqe_array.field(0),
+                sq_pending: 0,
+                cq_head: cq_ring.field(cq_off.head),
+                cq_tail: cq_ring.field(cq_off.tail),
+                cq_mask: *cq_ring.field::<u32>(cq_off.ring_mask),
+                cqes: cq_ring.field(cq_off.cqes),
+                _sq_ring: sq_ring,
+                _cq_ring: cq_ring,
+                _sqe_array: sqe_array,
+            })
+        }
+    }
+
+    /// Queue an entry on the submission ring.
+    ///
+    /// The entry isn't seen by the kernel until it's submitted.  Fails with `EBUSY` if the ring
+    /// is full.
+    ///
+    /// # Safety
+    ///
+    /// The memory the entry refers to, such as the buffers of a `readv`, must remain valid until
+    /// its completion has been collected.
+    pub unsafe fn push(&mut self, sqe: &Sqe) -> Result<()> {
+        // Nothing but the kernel moves the head, and nothing but us moves the tail.  The
+        // ring indices would be atomics, but volatile accesses and fences do the same for
+        // aligned u32s.
+        let head = ptr::read_volatile(self.sq_head);
+        atomic::fence(Ordering::Acquire);
+        let tail = *self.sq_tail;
+        if tail.wrapping_sub(head) == self.sq_entries {
+            return Err(::Error::Sys(Errno::EBUSY));
+        }
+
+        let index = tail & self.sq_mask;
+        ptr::write(self.sqes.offset(index as isize), *sqe);
+        ptr::write(self.sq_array.offset(index as isize), index);
+        // Publish the entry before the new tail
+        atomic::fence(Ordering::Release);
+        ptr::write_volatile(self.sq_tail, tail.wrapping_add(1));
+        self.sq_pending += 1;
+        Ok(())
+    }
+
+    /// Submit the queued entries to the kernel, returning how many were submitted.
+    pub fn submit(&mut self) -> Result<u32> {
+        self.enter(0, EnterFlags::empty())
+    }
+
+    /// Submit the queued entries to the kernel and wait until at least `want` completions are
+    /// available, returning how many entries were submitted.
+    pub fn submit_and_wait(&mut self, want: u32) -> Result<u32> {
+        self.enter(want, EnterFlags::IORING_ENTER_GETEVENTS)
+    }
+
+    fn enter(&mut self, min_complete: u32, mut flags: EnterFlags) -> Result<u32> {
+        if self.flags.contains(SetupFlags::IORING_SETUP_SQPOLL) {
+            // The polling thread picks up new entries by itself, unless it has gone to sleep
+            let to_submit = self.sq_pending;
+            self.sq_pending = 0;
+            atomic::fence(Ordering::SeqCst);
+            if unsafe { ptr::read_volatile(self.sq_flags) } & IORING_SQ_NEED_WAKEUP != 0 {
+                flags |= EnterFlags::IORING_ENTER_SQ_WAKEUP;
+            } else if min_complete == 0 {
+                return Ok(to_submit);
+            }
+            try!(unsafe { io_uring_enter(self.fd, to_submit, min_complete, flags, None) });
+            return Ok(to_submit);
+        }
+
+        let submitted = try!(unsafe {
+            io_uring_enter(self.fd, self.sq_pending, min_complete, flags, None)
+        });
+        self.sq_pending -= submitted;
+        Ok(submitted)
+    }
+
+    /// Collect the next completion, if any.
+    pub fn pop_completion(&mut self) -> Option<Cqe> {
+        unsafe {
+            let head = *self.cq_head;
+            let tail = ptr::read_volatile(self.cq_tail);
+            atomic::fence(Ordering::Acquire);
+            if head == tail {
+                return None;
+            }
+
+            let cqe = ptr::read(self.cqes.offset((head & self.cq_mask) as isize));
+            // Don't let the kernel reuse the entry before it has been read
+            atomic::fence(Ordering::Release);
+            ptr::write_volatile(self.cq_head, head.wrapping_add(1));
+            Some(cqe)
+        }
+    }
+}
+
+impl Drop for IoUring {
+    fn drop(&mut self) {
+        let _ = unistd::close(self.fd);
+    }
+}
+
+impl AsRawFd for IoUring {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
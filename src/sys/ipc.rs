@@ -0,0 +1,142 @@
+//! Definitions shared by the System V IPC mechanisms (see
+//! [svipc(7)](http://man7.org/linux/man-pages/man7/svipc.7.html)).
+//!
+//! Message queues, semaphore sets and shared memory segments are all looked up
+//! by a [`Key`](struct.Key.html), created with the same [`IpcFlags`](struct.IpcFlags.html)
+//! and permissions, and described by an [`IpcPerm`](struct.IpcPerm.html).
+use libc::{self, c_int, key_t, mode_t};
+use errno::Errno;
+use sys::stat::Mode;
+use unistd::{Gid, Uid};
+use {NixPath, Result};
+
+/// Key of a System V IPC object
+///
+/// Newtype pattern around `key_t`.  Processes agree on a key to refer to the
+/// same object, which is usually derived from a file with [`ftok`](fn.ftok.html).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Key(key_t);
+
+impl Key {
+    /// Creates `Key` from raw `key_t`.
+    pub fn from_raw(key: key_t) -> Self {
+        Key(key)
+    }
+}
+
+impl From<Key> for key_t {
+    fn from(key: Key) -> Self {
+        key.0
+    }
+}
+
+/// The key that always creates a new object, which can only be shared by
+/// passing on its identifier
+pub const IPC_PRIVATE: Key = Key(libc::IPC_PRIVATE);
+
+/// Derive a key from the identity of an existing file and `proj_id` (see
+/// [ftok(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/ftok.html)).
+///
+/// Only the low 8 bits of `proj_id` are used, and they must not all be zero.
+pub fn ftok<P: ?Sized + NixPath>(path: &P, proj_id: u8) -> Result<Key> {
+    let res = try!(path.with_nix_path(|cstr| {
+        unsafe { libc::ftok(cstr.as_ptr(), proj_id as c_int) }
+    }));
+
+    Errno::result(res).map(Key)
+}
+
+libc_bitflags!{
+    /// Flags for creating or looking up a System V IPC object
+    pub struct IpcFlags: c_int {
+        /// Create the object if it doesn't exist
+        IPC_CREAT;
+        /// Together with `IPC_CREAT`, fail with `EEXIST` if the object exists
+        IPC_EXCL;
+        /// Fail with `EAGAIN` (or `ENOMSG`) instead of blocking
+        IPC_NOWAIT;
+    }
+}
+
+/// Combine the flags and permissions passed to the `*get` functions.
+// FIXME: Switch this over to use pub(crate)
+#[doc(hidden)]
+pub fn get_flags(flags: c_int, mode: Mode) -> c_int {
+    flags | (mode.bits() & 0o777) as c_int
+}
+
+/// The owner and permissions of a System V IPC object
+// FIXME: Change to repr(transparent)
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct IpcPerm(libc::ipc_perm);
+
+impl IpcPerm {
+    /// The key the object was created with
+    // libc keeps the field private on these glibc targets
+    #[cfg(all(target_os = "linux",
+              not(all(target_env = "gnu",
+                      any(target_arch = "m68k", target_arch = "powerpc")))))]
+    pub fn key(&self) -> Key {
+        Key(self.0.__key)
+    }
+
+    /// The key the object was created with
+    #[cfg(target_os = "freebsd")]
+    pub fn key(&self) -> Key {
+        Key(self.0.key)
+    }
+
+    /// The owner of the object
+    pub fn uid(&self) -> Uid {
+        Uid::from_raw(self.0.uid)
+    }
+
+    /// Change the owner, which takes effect with the `*ctl_set` function
+    pub fn set_uid(&mut self, uid: Uid) {
+        self.0.uid = uid.into();
+    }
+
+    /// The group of the object
+    pub fn gid(&self) -> Gid {
+        Gid::from_raw(self.0.gid)
+    }
+
+    /// Change the group, which takes effect with the `*ctl_set` function
+    pub fn set_gid(&mut self, gid: Gid) {
+        self.0.gid = gid.into();
+    }
+
+    /// The user that created the object
+    pub fn cuid(&self) -> Uid {
+        Uid::from_raw(self.0.cuid)
+    }
+
+    /// The group of the user that created the object
+    pub fn cgid(&self) -> Gid {
+        Gid::from_raw(self.0.cgid)
+    }
+
+    /// The permissions of the object
+    pub fn mode(&self) -> Mode {
+        Mode::from_bits_truncate(self.0.mode as mode_t & 0o777)
+    }
+
+    /// Change the permissions, which takes effect with the `*ctl_set` function
+    pub fn set_mode(&mut self, mode: Mode) {
+        let other = self.0.mode as mode_t & !0o777;
+        self.0.mode = (other | mode.bits() & 0o777) as _;
+    }
+}
+
+impl From<libc::ipc_perm> for IpcPerm {
+    fn from(perm: libc::ipc_perm) -> IpcPerm {
+        IpcPerm(perm)
+    }
+}
+
+impl AsRef<libc::ipc_perm> for IpcPerm {
+    fn as_ref(&self) -> &libc::ipc_perm {
+        &self.0
+    }
+}
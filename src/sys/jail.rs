@@ -0,0 +1,201 @@
+//! Create, query, enter and remove jails (see
+//! [jail(2)](https://www.freebsd.org/cgi/man.cgi?query=jail&sektion=2)).
+//!
+//! A jail is described by named parameters, like `name`, `path`,
+//! `host.hostname` or `persist`, which are listed in
+//! [jail(8)](https://www.freebsd.org/cgi/man.cgi?query=jail&sektion=8).
+//! [`JailParams`](struct.JailParams.html) holds the parameters passed to
+//! [`jail_set`](fn.jail_set.html), and the buffers filled by
+//! [`jail_get`](fn.jail_get.html).
+//!
+//! # Examples
+//!
+//! Create a persistent jail, then run in it:
+//!
+//! ```no_run
+//! # use nix::sys::jail::*;
+//! let mut params = JailParams::new();
+//! params.string("name", "build").unwrap()
+//!       .string("path", "/jails/build").unwrap()
+//!       .string("host.hostname", "build.example.org").unwrap()
+//!       .flag("persist", true).unwrap();
+//! let jid = jail_set(&mut params, JailFlags::JAIL_CREATE).unwrap();
+//! jail_attach(jid).unwrap();
+//! ```
+use libc::{self, c_int, c_uint, c_void};
+use std::ffi::{CStr, CString};
+use std::{mem, ptr};
+use errno::Errno;
+use Result;
+
+/// The ID of a jail
+pub type Jid = c_int;
+
+libc_bitflags! {
+    /// Flags for [`jail_set`](fn.jail_set.html) and
+    /// [`jail_get`](fn.jail_get.html)
+    pub struct JailFlags: c_int {
+        /// Create a jail
+        JAIL_CREATE;
+        /// Change an existing jail
+        JAIL_UPDATE;
+        /// Attach the calling process to the jail once it's set
+        JAIL_ATTACH;
+        /// Also match a jail that is being removed
+        JAIL_DYING;
+    }
+}
+
+/// The parameters of a jail, passed to [`jail_set`](fn.jail_set.html) or
+/// [`jail_get`](fn.jail_get.html) as an array of `iovec`s, with a name and a
+/// value for each parameter.
+///
+/// For `jail_get`, the parameters other than the key (`jid` or `name`) are
+/// buffers the kernel fills: integers are read into the value given to
+/// [`int`](#method.int), and strings into the buffers added by
+/// [`string_buffer`](#method.string_buffer).  Adding a `string_buffer` named
+/// `errmsg` to either call gets a description of what went wrong.
+#[derive(Clone, Debug, Default)]
+pub struct JailParams {
+    params: Vec<(CString, Option<Vec<u8>>)>,
+}
+
+impl JailParams {
+    /// Create an empty set of parameters
+    pub fn new() -> JailParams {
+        JailParams { params: Vec::new() }
+    }
+
+    fn push(&mut self, name: &str, value: Option<Vec<u8>>) -> Result<&mut JailParams> {
+        let name = try!(CString::new(name));
+        self.params.push((name, value));
+        Ok(self)
+    }
+
+    /// Add a string parameter
+    pub fn string(&mut self, name: &str, value: &str) -> Result<&mut JailParams> {
+        let value = try!(CString::new(value));
+        self.push(name, Some(value.into_bytes_with_nul()))
+    }
+
+    /// Add a buffer of `len` bytes, in which `jail_get` reads a string
+    /// parameter
+    pub fn string_buffer(&mut self, name: &str, len: usize) -> Result<&mut JailParams> {
+        self.push(name, Some(vec![0; len]))
+    }
+
+    /// Add an integer parameter, like `jid`, `securelevel` or
+    /// `children.max`
+    pub fn int(&mut self, name: &str, value: c_int) -> Result<&mut JailParams> {
+        let bytes: [u8; 4] = unsafe { mem::transmute(value) };
+        self.push(name, Some(bytes.to_vec()))
+    }
+
+    /// Add a boolean parameter, like `persist`.  A false one is passed with
+    /// the `no` prefix, as in `nopersist`.
+    pub fn flag(&mut self, name: &str, value: bool) -> Result<&mut JailParams> {
+        if value {
+            self.push(name, None)
+        } else {
+            self.push(&format!("no{}", name), None)
+        }
+    }
+
+    /// Add a parameter with a raw value, like an array of `in_addr` for
+    /// `ip4.addr`
+    pub fn bytes(&mut self, name: &str, value: &[u8]) -> Result<&mut JailParams> {
+        self.push(name, Some(value.to_vec()))
+    }
+
+    fn value(&self, name: &str) -> Option<&[u8]> {
+        self.params.iter()
+            .find(|&&(ref n, _)| n.as_bytes() == name.as_bytes())
+            .and_then(|&(_, ref value)| value.as_ref().map(|v| &v[..]))
+    }
+
+    /// Get the value of a string parameter, as filled by `jail_get`
+    pub fn get_string(&self, name: &str) -> Option<&CStr> {
+        self.value(name).and_then(|value| {
+            value.iter().position(|&b| b == 0)
+                .map(|nul| unsafe { CStr::from_bytes_with_nul_unchecked(&value[..nul + 1]) })
+        })
+    }
+
+    /// Get the value of an integer parameter, as filled by `jail_get`
+    pub fn get_int(&self, name: &str) -> Option<c_int> {
+        self.value(name).and_then(|value| {
+            if value.len() == mem::size_of::<c_int>() {
+                Some(unsafe { ptr::read_unaligned(value.as_ptr() as *const c_int) })
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Get the raw value of a parameter, as filled by `jail_get`
+    pub fn get_bytes(&self, name: &str) -> Option<&[u8]> {
+        self.value(name)
+    }
+
+    fn iovecs(&mut self) -> Vec<libc::iovec> {
+        let mut iov = Vec::with_capacity(2 * self.params.len());
+        for &mut (ref name, ref mut value) in &mut self.params {
+            let name = name.as_bytes_with_nul();
+            iov.push(libc::iovec {
+                iov_base: name.as_ptr() as *mut c_void,
+                iov_len: name.len(),
+            });
+            iov.push(match *value {
+                Some(ref mut value) => libc::iovec {
+                    iov_base: value.as_mut_ptr() as *mut c_void,
+                    iov_len: value.len(),
+                },
+                None => libc::iovec { iov_base: ptr::null_mut(), iov_len: 0 },
+            });
+        }
+        iov
+    }
+}
+
+/// Create or change a jail, returning its ID (see
+/// [jail_set(2)](https://www.freebsd.org/cgi/man.cgi?query=jail_set&sektion=2)).
+///
+/// An existing jail is given by its `jid` or `name` parameter.
+pub fn jail_set(params: &mut JailParams, flags: JailFlags) -> Result<Jid> {
+    let mut iov = params.iovecs();
+    let res = unsafe { libc::jail_set(iov.as_mut_ptr(), iov.len() as c_uint, flags.bits()) };
+
+    Errno::result(res)
+}
+
+/// Read the parameters of a jail into `params`, returning its ID (see
+/// [jail_get(2)](https://www.freebsd.org/cgi/man.cgi?query=jail_get&sektion=2)).
+///
+/// The jail is given by its `jid` or `name` parameter.  A `lastjid`
+/// parameter instead gets the first jail whose ID is greater, to iterate over
+/// all the jails.
+pub fn jail_get(params: &mut JailParams, flags: JailFlags) -> Result<Jid> {
+    let mut iov = params.iovecs();
+    let res = unsafe { libc::jail_get(iov.as_mut_ptr(), iov.len() as c_uint, flags.bits()) };
+
+    Errno::result(res)
+}
+
+/// Attach the calling process to a jail (see
+/// [jail_attach(2)](https://www.freebsd.org/cgi/man.cgi?query=jail_attach&sektion=2)).
+///
+/// The process then runs in the jail, with its root directory, until it
+/// exits.
+pub fn jail_attach(jid: Jid) -> Result<()> {
+    let res = unsafe { libc::jail_attach(jid) };
+
+    Errno::result(res).map(drop)
+}
+
+/// Remove a jail, killing all of its processes (see
+/// [jail_remove(2)](https://www.freebsd.org/cgi/man.cgi?query=jail_remove&sektion=2)).
+pub fn jail_remove(jid: Jid) -> Result<()> {
+    let res = unsafe { libc::jail_remove(jid) };
+
+    Errno::result(res).map(drop)
+}
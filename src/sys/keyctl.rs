@@ -0,0 +1,281 @@
+//! Store secrets in the kernel key retention service (see
+//! [keyrings(7)](http://man7.org/linux/man-pages/man7/keyrings.7.html)).
+//!
+//! A key has a type, such as `user` for arbitrary data or `logon` for data
+//! that can't be read back from user space, a description by which it's
+//! searched, and a payload.  Keys are held by keyrings, which are keys
+//! themselves, and a process possesses the keys in its thread, process and
+//! session keyrings, and in the keyrings they hold.  Possessing a key grants
+//! the `KEY_POS_*` permissions, on top of the ones for its owner, group and
+//! others.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use nix::sys::keyctl::*;
+//! let key = add_key("user", "app:token", b"secret", KEY_SPEC_SESSION_KEYRING).unwrap();
+//! assert_eq!(request_key("user", "app:token", None).unwrap(), key);
+//! assert_eq!(read(key).unwrap(), b"secret");
+//! ```
+use libc::{self, c_int, c_long, c_ulong};
+use std::{fmt, ptr};
+use errno::Errno;
+use unistd::{Gid, Uid};
+use {NixPath, Result};
+
+// libc doesn't export the keyring API, so these values are taken from
+// linux/keyctl.h
+const KEYCTL_GET_KEYRING_ID: c_int = 0;
+const KEYCTL_UPDATE: c_int = 2;
+const KEYCTL_REVOKE: c_int = 3;
+const KEYCTL_CHOWN: c_int = 4;
+const KEYCTL_SETPERM: c_int = 5;
+const KEYCTL_LINK: c_int = 8;
+const KEYCTL_UNLINK: c_int = 9;
+const KEYCTL_SEARCH: c_int = 10;
+const KEYCTL_READ: c_int = 11;
+const KEYCTL_SET_TIMEOUT: c_int = 15;
+const KEYCTL_INVALIDATE: c_int = 21;
+
+/// The serial number of a key or keyring
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct KeySerial(i32);
+
+impl KeySerial {
+    /// Creates `KeySerial` from a raw serial number.
+    pub fn from_raw(serial: i32) -> Self {
+        KeySerial(serial)
+    }
+}
+
+impl From<KeySerial> for i32 {
+    fn from(serial: KeySerial) -> Self {
+        serial.0
+    }
+}
+
+impl fmt::Display for KeySerial {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// The keyring of the calling thread
+pub const KEY_SPEC_THREAD_KEYRING: KeySerial = KeySerial(-1);
+/// The keyring of the calling process, shared by its threads
+pub const KEY_SPEC_PROCESS_KEYRING: KeySerial = KeySerial(-2);
+/// The keyring of the session, inherited by child processes
+pub const KEY_SPEC_SESSION_KEYRING: KeySerial = KeySerial(-3);
+/// The keyring of the real user ID of the process
+pub const KEY_SPEC_USER_KEYRING: KeySerial = KeySerial(-4);
+/// The default session keyring of the real user ID of the process
+pub const KEY_SPEC_USER_SESSION_KEYRING: KeySerial = KeySerial(-5);
+
+bitflags! {
+    /// The permissions of a key, see [`setperm`](fn.setperm.html)
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct KeyPerm: u32 {
+        /// Possessors can view the attributes of the key
+        const KEY_POS_VIEW = 0x0100_0000;
+        /// Possessors can read the payload of the key
+        const KEY_POS_READ = 0x0200_0000;
+        /// Possessors can update the key, or add keys to the keyring
+        const KEY_POS_WRITE = 0x0400_0000;
+        /// Possessors can find the key, or search the keyring
+        const KEY_POS_SEARCH = 0x0800_0000;
+        /// Possessors can link the key into keyrings
+        const KEY_POS_LINK = 0x1000_0000;
+        /// Possessors can change the owner, permissions and timeout of the key
+        const KEY_POS_SETATTR = 0x2000_0000;
+        /// All the permissions of possessors
+        const KEY_POS_ALL = 0x3f00_0000;
+        /// The owner can view the attributes of the key
+        const KEY_USR_VIEW = 0x0001_0000;
+        /// The owner can read the payload of the key
+        const KEY_USR_READ = 0x0002_0000;
+        /// The owner can update the key, or add keys to the keyring
+        const KEY_USR_WRITE = 0x0004_0000;
+        /// The owner can find the key, or search the keyring
+        const KEY_USR_SEARCH = 0x0008_0000;
+        /// The owner can link the key into keyrings
+        const KEY_USR_LINK = 0x0010_0000;
+        /// The owner can change the owner, permissions and timeout of the key
+        const KEY_USR_SETATTR = 0x0020_0000;
+        /// All the permissions of the owner
+        const KEY_USR_ALL = 0x003f_0000;
+        /// The group can view the attributes of the key
+        const KEY_GRP_VIEW = 0x0000_0100;
+        /// The group can read the payload of the key
+        const KEY_GRP_READ = 0x0000_0200;
+        /// The group can update the key, or add keys to the keyring
+        const KEY_GRP_WRITE = 0x0000_0400;
+        /// The group can find the key, or search the keyring
+        const KEY_GRP_SEARCH = 0x0000_0800;
+        /// The group can link the key into keyrings
+        const KEY_GRP_LINK = 0x0000_1000;
+        /// The group can change the owner, permissions and timeout of the key
+        const KEY_GRP_SETATTR = 0x0000_2000;
+        /// All the permissions of the group
+        const KEY_GRP_ALL = 0x0000_3f00;
+        /// Others can view the attributes of the key
+        const KEY_OTH_VIEW = 0x0000_0001;
+        /// Others can read the payload of the key
+        const KEY_OTH_READ = 0x0000_0002;
+        /// Others can update the key, or add keys to the keyring
+        const KEY_OTH_WRITE = 0x0000_0004;
+        /// Others can find the key, or search the keyring
+        const KEY_OTH_SEARCH = 0x0000_0008;
+        /// Others can link the key into keyrings
+        const KEY_OTH_LINK = 0x0000_0010;
+        /// Others can change the owner, permissions and timeout of the key
+        const KEY_OTH_SETATTR = 0x0000_0020;
+        /// All the permissions of others
+        const KEY_OTH_ALL = 0x0000_003f;
+    }
+}
+
+fn keyctl(cmd: c_int, arg2: c_ulong, arg3: c_ulong, arg4: c_ulong, arg5: c_ulong)
+          -> Result<c_long> {
+    let res = unsafe { libc::syscall(libc::SYS_keyctl, cmd, arg2, arg3, arg4, arg5) };
+
+    Errno::result(res)
+}
+
+fn serial_arg(serial: KeySerial) -> c_ulong {
+    serial.0 as c_ulong
+}
+
+/// Add a key to `keyring`, or update the payload of the key with the same
+/// type and description in it (see
+/// [add_key(2)](http://man7.org/linux/man-pages/man2/add_key.2.html)).
+///
+/// A `keyring` type creates a keyring, whose payload must be empty.
+pub fn add_key<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(key_type: &P1, description: &P2,
+                                                           payload: &[u8], keyring: KeySerial)
+                                                           -> Result<KeySerial> {
+    let res = try!(try!(key_type.with_nix_path(|key_type| {
+        description.with_nix_path(|description| unsafe {
+            libc::syscall(libc::SYS_add_key, key_type.as_ptr(), description.as_ptr(),
+                          payload.as_ptr(), payload.len(), keyring.0)
+        })
+    })));
+
+    Errno::result(res).map(|serial| KeySerial(serial as i32))
+}
+
+/// Find a key by type and description in the keyrings of the process (see
+/// [request_key(2)](http://man7.org/linux/man-pages/man2/request_key.2.html)).
+///
+/// The key is also linked into `dest_keyring`, if given.  This never asks
+/// user space to create the key, so it fails with `ENOKEY` if there's none.
+pub fn request_key<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(key_type: &P1, description: &P2,
+                                                               dest_keyring: Option<KeySerial>)
+                                                               -> Result<KeySerial> {
+    let dest_keyring = dest_keyring.map_or(0, |k| k.0);
+    let res = try!(try!(key_type.with_nix_path(|key_type| {
+        description.with_nix_path(|description| unsafe {
+            libc::syscall(libc::SYS_request_key, key_type.as_ptr(), description.as_ptr(),
+                          ptr::null::<libc::c_char>(), dest_keyring)
+        })
+    })));
+
+    Errno::result(res).map(|serial| KeySerial(serial as i32))
+}
+
+/// Get the serial number of a keyring, given one of the `KEY_SPEC_*` ones
+/// (`KEYCTL_GET_KEYRING_ID`).
+///
+/// Fails with `ENOKEY` if the keyring doesn't exist yet, unless `create` is
+/// true.
+pub fn get_keyring_id(keyring: KeySerial, create: bool) -> Result<KeySerial> {
+    keyctl(KEYCTL_GET_KEYRING_ID, serial_arg(keyring), create as c_ulong, 0, 0)
+        .map(|serial| KeySerial(serial as i32))
+}
+
+/// Search `keyring`, and the keyrings it holds, for a key by type and
+/// description (`KEYCTL_SEARCH`).
+///
+/// The key is also linked into `dest_keyring`, if given.  Fails with `ENOKEY`
+/// if there's none.
+pub fn search<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(keyring: KeySerial, key_type: &P1,
+                                                          description: &P2,
+                                                          dest_keyring: Option<KeySerial>)
+                                                          -> Result<KeySerial> {
+    let dest_keyring = dest_keyring.map_or(0, serial_arg);
+    let res = try!(key_type.with_nix_path(|key_type| {
+        description.with_nix_path(|description| {
+            keyctl(KEYCTL_SEARCH, serial_arg(keyring), key_type.as_ptr() as c_ulong,
+                   description.as_ptr() as c_ulong, dest_keyring)
+        })
+    }));
+
+    try!(res).map(|serial| KeySerial(serial as i32))
+}
+
+/// Read the payload of a key, or the serial numbers of the keys held by a
+/// keyring, as native-endian `i32`s (`KEYCTL_READ`).
+#[cfg(feature = "std")]
+pub fn read(key: KeySerial) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    loop {
+        // Returns the size of the payload, which may change between calls
+        let len = try!(keyctl(KEYCTL_READ, serial_arg(key), buf.as_mut_ptr() as c_ulong,
+                              buf.len() as c_ulong, 0)) as usize;
+        if len <= buf.len() {
+            buf.truncate(len);
+            return Ok(buf);
+        }
+        buf.resize(len, 0);
+    }
+}
+
+/// Replace the payload of a key (`KEYCTL_UPDATE`).
+pub fn update(key: KeySerial, payload: &[u8]) -> Result<()> {
+    keyctl(KEYCTL_UPDATE, serial_arg(key), payload.as_ptr() as c_ulong, payload.len() as c_ulong,
+           0).map(drop)
+}
+
+/// Revoke a key, making all operations on it but unlinking fail with
+/// `EKEYREVOKED` (`KEYCTL_REVOKE`).
+pub fn revoke(key: KeySerial) -> Result<()> {
+    keyctl(KEYCTL_REVOKE, serial_arg(key), 0, 0, 0).map(drop)
+}
+
+/// Destroy a key, unlinking it from all keyrings (`KEYCTL_INVALIDATE`).
+pub fn invalidate(key: KeySerial) -> Result<()> {
+    keyctl(KEYCTL_INVALIDATE, serial_arg(key), 0, 0, 0).map(drop)
+}
+
+/// Change the owner and group of a key, leaving them unchanged if `None`
+/// (`KEYCTL_CHOWN`).
+///
+/// Changing the owner requires `CAP_SYS_ADMIN`.
+pub fn chown(key: KeySerial, owner: Option<Uid>, group: Option<Gid>) -> Result<()> {
+    // -1 means unchanged
+    let owner = owner.map_or(-1, |u| libc::uid_t::from(u) as c_int);
+    let group = group.map_or(-1, |g| libc::gid_t::from(g) as c_int);
+    keyctl(KEYCTL_CHOWN, serial_arg(key), owner as c_ulong, group as c_ulong, 0).map(drop)
+}
+
+/// Change the permissions of a key (`KEYCTL_SETPERM`).
+pub fn setperm(key: KeySerial, perm: KeyPerm) -> Result<()> {
+    keyctl(KEYCTL_SETPERM, serial_arg(key), perm.bits() as c_ulong, 0, 0).map(drop)
+}
+
+/// Make a key expire `timeout` seconds from now, or never if 0
+/// (`KEYCTL_SET_TIMEOUT`).
+pub fn set_timeout(key: KeySerial, timeout: u32) -> Result<()> {
+    keyctl(KEYCTL_SET_TIMEOUT, serial_arg(key), timeout as c_ulong, 0, 0).map(drop)
+}
+
+/// Add a key to `keyring`, replacing any key with the same type and
+/// description (`KEYCTL_LINK`).
+pub fn link(key: KeySerial, keyring: KeySerial) -> Result<()> {
+    keyctl(KEYCTL_LINK, serial_arg(key), serial_arg(keyring), 0, 0).map(drop)
+}
+
+/// Remove a key from `keyring`, which destroys it if no other keyring holds it
+/// (`KEYCTL_UNLINK`).
+pub fn unlink(key: KeySerial, keyring: KeySerial) -> Result<()> {
+    keyctl(KEYCTL_UNLINK, serial_arg(key), serial_arg(keyring), 0, 0).map(drop)
+}
@@ -0,0 +1,78 @@
+//! Read and control the kernel log buffer (see
+//! [syslog(2)](http://man7.org/linux/man-pages/man2/syslog.2.html)).
+//!
+//! This is what `dmesg` reads.  The buffer is a ring holding the most recent
+//! messages, each starting with its level as `<N>`.  Unless
+//! `/proc/sys/kernel/dmesg_restrict` is 0, every action requires `CAP_SYSLOG`.
+use libc::{self, c_char, c_int};
+use std::{cmp, ptr};
+use errno::Errno;
+use Result;
+
+// Not in libc, from linux/syslog.h
+const SYSLOG_ACTION_READ: c_int = 2;
+const SYSLOG_ACTION_READ_ALL: c_int = 3;
+const SYSLOG_ACTION_READ_CLEAR: c_int = 4;
+const SYSLOG_ACTION_CLEAR: c_int = 5;
+const SYSLOG_ACTION_CONSOLE_OFF: c_int = 6;
+const SYSLOG_ACTION_CONSOLE_ON: c_int = 7;
+const SYSLOG_ACTION_CONSOLE_LEVEL: c_int = 8;
+const SYSLOG_ACTION_SIZE_UNREAD: c_int = 9;
+const SYSLOG_ACTION_SIZE_BUFFER: c_int = 10;
+
+/// An action of [`klogctl`](fn.klogctl.html)
+#[derive(Debug, Eq, PartialEq)]
+pub enum KlogAction<'a> {
+    /// Wait until there are unread messages, and move as many as fit in the
+    /// buffer out of the log (`SYSLOG_ACTION_READ`)
+    Read(&'a mut [u8]),
+    /// Copy the last messages in the log that fit in the buffer, without
+    /// waiting (`SYSLOG_ACTION_READ_ALL`)
+    ReadAll(&'a mut [u8]),
+    /// Like `ReadAll`, then clear the log (`SYSLOG_ACTION_READ_CLEAR`)
+    ReadClear(&'a mut [u8]),
+    /// Clear the log, so that `ReadAll` only returns newer messages
+    /// (`SYSLOG_ACTION_CLEAR`)
+    Clear,
+    /// Stop printing messages to the console, except for panics
+    /// (`SYSLOG_ACTION_CONSOLE_OFF`)
+    ConsoleOff,
+    /// Restore the level of messages printed to the console from before
+    /// `ConsoleOff` (`SYSLOG_ACTION_CONSOLE_ON`)
+    ConsoleOn,
+    /// Only print messages more urgent than the level, from 1 to 8, to the
+    /// console (`SYSLOG_ACTION_CONSOLE_LEVEL`)
+    ConsoleLevel(c_int),
+    /// Get the number of unread bytes in the log (`SYSLOG_ACTION_SIZE_UNREAD`)
+    SizeUnread,
+    /// Get the size of the log buffer (`SYSLOG_ACTION_SIZE_BUFFER`)
+    SizeBuffer,
+}
+
+/// Perform an action on the kernel log buffer.
+///
+/// The read actions return the number of bytes copied, the size actions the
+/// size, and the others 0.
+pub fn klogctl(action: KlogAction) -> Result<usize> {
+    // Lengths over c_int::MAX would turn negative, so only use that much
+    fn buffer(buf: &mut [u8]) -> (*mut c_char, c_int) {
+        let len = cmp::min(buf.len(), c_int::max_value() as usize);
+        (buf.as_mut_ptr() as *mut c_char, len as c_int)
+    }
+
+    let (action, (buf, len)) = match action {
+        KlogAction::Read(buf) => (SYSLOG_ACTION_READ, buffer(buf)),
+        KlogAction::ReadAll(buf) => (SYSLOG_ACTION_READ_ALL, buffer(buf)),
+        KlogAction::ReadClear(buf) => (SYSLOG_ACTION_READ_CLEAR, buffer(buf)),
+        KlogAction::Clear => (SYSLOG_ACTION_CLEAR, (ptr::null_mut(), 0)),
+        KlogAction::ConsoleOff => (SYSLOG_ACTION_CONSOLE_OFF, (ptr::null_mut(), 0)),
+        KlogAction::ConsoleOn => (SYSLOG_ACTION_CONSOLE_ON, (ptr::null_mut(), 0)),
+        // The level is passed as the length
+        KlogAction::ConsoleLevel(level) => (SYSLOG_ACTION_CONSOLE_LEVEL, (ptr::null_mut(), level)),
+        KlogAction::SizeUnread => (SYSLOG_ACTION_SIZE_UNREAD, (ptr::null_mut(), 0)),
+        KlogAction::SizeBuffer => (SYSLOG_ACTION_SIZE_BUFFER, (ptr::null_mut(), 0)),
+    };
+    let res = unsafe { libc::klogctl(action, buf, len) };
+
+    Errno::result(res).map(|r| r as usize)
+}
@@ -0,0 +1,193 @@
+//! Sandbox the calling thread with Landlock (see
+//! [landlock(7)](http://man7.org/linux/man-pages/man7/landlock.7.html)).
+//!
+//! A ruleset lists the kinds of access it handles and the rules that allow
+//! them, for files beneath some directories or for some TCP ports.  Once the
+//! thread restricts itself with the ruleset, handled accesses that no rule
+//! allows fail with `EACCES`.  Unhandled ones stay allowed.  This doesn't
+//! require privileges, and the restrictions can't be lifted, only stacked.
+//!
+//! Each version of the ABI handles more kinds of access, and rulesets handling
+//! kinds unknown to the kernel fail with `EINVAL`, so programs should only
+//! handle the ones of [`landlock_abi_version`](fn.landlock_abi_version.html).
+//!
+//! # Examples
+//!
+//! Forbid writing any file:
+//!
+//! ```no_run
+//! # extern crate libc;
+//! # extern crate nix;
+//! # use nix::sys::landlock::*;
+//! # fn main() {
+//! let ruleset = landlock_create_ruleset(AccessFs::LANDLOCK_ACCESS_FS_WRITE_FILE,
+//!                                       AccessNet::empty()).unwrap();
+//! // Unless the thread has CAP_SYS_ADMIN
+//! unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) };
+//! landlock_restrict_self(ruleset).unwrap();
+//! # }
+//! ```
+use libc::{self, c_int, c_void};
+use std::os::unix::io::RawFd;
+use std::{mem, ptr};
+use errno::Errno;
+use Result;
+
+// libc doesn't export landlock, so these values are taken from linux/landlock.h
+
+bitflags! {
+    /// Kinds of access to files
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct AccessFs: u64 {
+        /// Execute a file
+        const LANDLOCK_ACCESS_FS_EXECUTE = 1 << 0;
+        /// Open a file for writing
+        const LANDLOCK_ACCESS_FS_WRITE_FILE = 1 << 1;
+        /// Open a file for reading
+        const LANDLOCK_ACCESS_FS_READ_FILE = 1 << 2;
+        /// List a directory
+        const LANDLOCK_ACCESS_FS_READ_DIR = 1 << 3;
+        /// Remove or rename an empty directory
+        const LANDLOCK_ACCESS_FS_REMOVE_DIR = 1 << 4;
+        /// Remove or rename a file
+        const LANDLOCK_ACCESS_FS_REMOVE_FILE = 1 << 5;
+        /// Create, rename or link a character device
+        const LANDLOCK_ACCESS_FS_MAKE_CHAR = 1 << 6;
+        /// Create or rename a directory
+        const LANDLOCK_ACCESS_FS_MAKE_DIR = 1 << 7;
+        /// Create, rename or link a regular file
+        const LANDLOCK_ACCESS_FS_MAKE_REG = 1 << 8;
+        /// Create, rename or link a Unix socket
+        const LANDLOCK_ACCESS_FS_MAKE_SOCK = 1 << 9;
+        /// Create, rename or link a named pipe
+        const LANDLOCK_ACCESS_FS_MAKE_FIFO = 1 << 10;
+        /// Create, rename or link a block device
+        const LANDLOCK_ACCESS_FS_MAKE_BLOCK = 1 << 11;
+        /// Create, rename or link a symbolic link
+        const LANDLOCK_ACCESS_FS_MAKE_SYM = 1 << 12;
+        /// Rename or link a file into another directory (ABI 2).  Without it,
+        /// that's always forbidden.
+        const LANDLOCK_ACCESS_FS_REFER = 1 << 13;
+        /// Truncate a file (ABI 3)
+        const LANDLOCK_ACCESS_FS_TRUNCATE = 1 << 14;
+        /// Use `ioctl` on a device (ABI 5)
+        const LANDLOCK_ACCESS_FS_IOCTL_DEV = 1 << 15;
+    }
+}
+
+bitflags! {
+    /// Kinds of access to the network (ABI 4)
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct AccessNet: u64 {
+        /// Bind a TCP socket to a port
+        const LANDLOCK_ACCESS_NET_BIND_TCP = 1 << 0;
+        /// Connect a TCP socket to a port
+        const LANDLOCK_ACCESS_NET_CONNECT_TCP = 1 << 1;
+    }
+}
+
+const LANDLOCK_CREATE_RULESET_VERSION: u32 = 1 << 0;
+
+const LANDLOCK_RULE_PATH_BENEATH: c_int = 1;
+const LANDLOCK_RULE_NET_PORT: c_int = 2;
+
+#[repr(C)]
+struct RulesetAttr {
+    handled_access_fs: u64,
+    handled_access_net: u64,
+}
+
+#[repr(C, packed)]
+struct PathBeneathAttr {
+    allowed_access: u64,
+    parent_fd: i32,
+}
+
+#[repr(C)]
+struct NetPortAttr {
+    allowed_access: u64,
+    port: u64,
+}
+
+/// Get the version of the Landlock ABI that the kernel supports.
+///
+/// Fails with `ENOSYS` on kernels older than 5.13, or built without Landlock,
+/// and with `EOPNOTSUPP` if it was disabled at boot.
+pub fn landlock_abi_version() -> Result<c_int> {
+    let res = unsafe {
+        libc::syscall(libc::SYS_landlock_create_ruleset, ptr::null::<c_void>(), 0,
+                      LANDLOCK_CREATE_RULESET_VERSION)
+    };
+
+    Errno::result(res).map(|version| version as c_int)
+}
+
+/// Create a ruleset that handles the accesses `fs` and `net`, without rules
+/// allowing them yet.
+///
+/// `net` must be empty before ABI 4.
+pub fn landlock_create_ruleset(fs: AccessFs, net: AccessNet) -> Result<RawFd> {
+    let attr = RulesetAttr {
+        handled_access_fs: fs.bits(),
+        handled_access_net: net.bits(),
+    };
+    let res = unsafe {
+        libc::syscall(libc::SYS_landlock_create_ruleset, &attr as *const RulesetAttr,
+                      mem::size_of::<RulesetAttr>(), 0)
+    };
+
+    Errno::result(res).map(|fd| fd as RawFd)
+}
+
+/// A rule, for [`landlock_add_rule`](fn.landlock_add_rule.html)
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum LandlockRule {
+    /// Allow the accesses to the directory or file open as the file
+    /// descriptor, and to everything beneath it.  It may be opened with
+    /// `O_PATH`.
+    PathBeneath(RawFd, AccessFs),
+    /// Allow the accesses to the TCP port (ABI 4)
+    NetPort(u16, AccessNet),
+}
+
+/// Add a rule to the ruleset `ruleset_fd`.
+///
+/// The accesses the rule allows must be handled by the ruleset, or this fails
+/// with `EINVAL`.
+pub fn landlock_add_rule(ruleset_fd: RawFd, rule: LandlockRule) -> Result<()> {
+    let res = match rule {
+        LandlockRule::PathBeneath(parent_fd, access) => {
+            let attr = PathBeneathAttr {
+                allowed_access: access.bits(),
+                parent_fd: parent_fd,
+            };
+            unsafe {
+                libc::syscall(libc::SYS_landlock_add_rule, ruleset_fd, LANDLOCK_RULE_PATH_BENEATH,
+                              &attr as *const PathBeneathAttr, 0)
+            }
+        },
+        LandlockRule::NetPort(port, access) => {
+            let attr = NetPortAttr {
+                allowed_access: access.bits(),
+                port: port as u64,
+            };
+            unsafe {
+                libc::syscall(libc::SYS_landlock_add_rule, ruleset_fd, LANDLOCK_RULE_NET_PORT,
+                              &attr as *const NetPortAttr, 0)
+            }
+        },
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Restrict the calling thread, and the threads and processes it creates from
+/// now on, with the ruleset `ruleset_fd`.
+///
+/// Unless the thread has `CAP_SYS_ADMIN`, it must have set `no_new_privs` with
+/// `prctl`, or this fails with `EPERM`.  Other threads aren't restricted.
+pub fn landlock_restrict_self(ruleset_fd: RawFd) -> Result<()> {
+    let res = unsafe { libc::syscall(libc::SYS_landlock_restrict_self, ruleset_fd, 0) };
+
+    Errno::result(res).map(drop)
+}
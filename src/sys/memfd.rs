@@ -1,6 +1,7 @@
 use libc;
-use std::os::unix::io::RawFd;
+use std::os::unix::io::{FromRawFd, RawFd};
 use Result;
+use unistd::Fd;
 use errno::Errno;
 use std::ffi::CStr;
 
@@ -18,3 +19,8 @@ pub fn memfd_create(name: &CStr, flags: MemFdCreateFlag) -> Result<RawFd> {
 
     Errno::result(res).map(|r| r as RawFd)
 }
+
+/// Like [`memfd_create`](fn.memfd_create.html), but returns an owned file descriptor.
+pub fn memfd_create_owned(name: &CStr, flags: MemFdCreateFlag) -> Result<Fd> {
+    memfd_create(name, flags).map(|fd| unsafe { Fd::from_raw_fd(fd) })
+}
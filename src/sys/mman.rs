@@ -4,6 +4,10 @@ use fcntl::OFlag;
 use libc::{self, c_int, c_void, size_t, off_t};
 use sys::stat::Mode;
 use std::os::unix::io::RawFd;
+#[cfg(not(target_os = "android"))]
+use std::os::unix::io::FromRawFd;
+#[cfg(not(target_os = "android"))]
+use unistd::Fd;
 
 libc_bitflags!{
     /// Desired memory protection of a memory mapping.
@@ -249,6 +253,12 @@ pub fn shm_open<P: ?Sized + NixPath>(name: &P, flag: OFlag, mode: Mode) -> Resul
     Errno::result(ret)
 }
 
+/// Like [`shm_open`](fn.shm_open.html), but returns an owned file descriptor.
+#[cfg(not(target_os = "android"))]
+pub fn shm_open_owned<P: ?Sized + NixPath>(name: &P, flag: OFlag, mode: Mode) -> Result<Fd> {
+    shm_open(name, flag, mode).map(|fd| unsafe { Fd::from_raw_fd(fd) })
+}
+
 #[cfg(not(target_os = "android"))]
 pub fn shm_unlink<P: ?Sized + NixPath>(name: &P) -> Result<()> {
     let ret = try!(name.with_nix_path(|cstr| {
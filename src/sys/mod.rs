@@ -1,14 +1,24 @@
-#[cfg(any(target_os = "dragonfly",
-          target_os = "freebsd",
-          target_os = "ios",
-          target_os = "linux",
-          target_os = "macos",
-          target_os = "netbsd"))]
+#[cfg(all(feature = "std",
+          any(target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "linux",
+              target_os = "macos",
+              target_os = "netbsd")))]
 pub mod aio;
 
+#[cfg(target_os = "freebsd")]
+pub mod capsicum;
+
+#[cfg(all(feature = "std", target_os = "freebsd"))]
+pub mod jail;
+
 #[cfg(any(target_os = "android", target_os = "linux"))]
 pub mod epoll;
 
+#[cfg(any(target_os = "freebsd", target_os = "netbsd"))]
+pub mod extattr;
+
 #[cfg(any(target_os = "dragonfly",
           target_os = "freebsd",
           target_os = "ios",
@@ -20,12 +30,39 @@ pub mod event;
 #[cfg(target_os = "linux")]
 pub mod eventfd;
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub mod futex;
+
+#[cfg(target_os = "linux")]
+pub mod io_uring;
+
 #[cfg(target_os = "linux")]
 pub mod memfd;
 
 #[macro_use]
 pub mod ioctl;
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub mod bpf;
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub mod keyctl;
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub mod klog;
+
+#[cfg(target_os = "linux")]
+pub mod landlock;
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub mod blk;
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub mod net;
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub mod perf_event;
+
 // TODO: Add support for dragonfly, freebsd, and ios/macos.
 #[cfg(any(target_os = "android", target_os = "linux"))]
 pub mod sendfile;
@@ -44,16 +81,43 @@ pub mod reboot;
 
 pub mod termios;
 
+#[cfg(target_os = "linux")]
+pub mod vt;
+
 pub mod utsname;
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub mod sysinfo;
+
 pub mod wait;
 
 pub mod mman;
 
+#[cfg(any(target_os = "freebsd", target_os = "linux"))]
+pub mod ipc;
+
+#[cfg(all(feature = "std", any(target_os = "freebsd", target_os = "linux")))]
+pub mod msg;
+
+#[cfg(target_os = "linux")]
+pub mod sem;
+
+#[cfg(any(target_os = "freebsd", target_os = "linux"))]
+pub mod shm;
+
 pub mod uio;
 
 pub mod time;
 
+#[cfg(any(target_os = "android", target_os = "freebsd", target_os = "linux"))]
+pub mod timer;
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub mod timerfd;
+
+#[cfg(target_os = "linux")]
+pub mod timex;
+
 #[cfg(any(target_os = "android", target_os = "linux"))]
 pub mod ptrace;
 
@@ -62,6 +126,24 @@ pub mod select;
 #[cfg(target_os = "linux")]
 pub mod quota;
 
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+pub mod random;
+
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+pub mod sysctl;
+
 
 #[cfg(all(target_os = "linux",
           any(target_arch = "x86",
@@ -0,0 +1,197 @@
+//! System V message queues (see
+//! [sysvipc(7)](http://man7.org/linux/man-pages/man7/sysvipc.7.html)).
+//!
+//! Every message carries a positive type besides its data, which receivers can
+//! use to select the messages they're interested in.  New code should prefer
+//! the POSIX message queues of [`mqueue`](../../mqueue/index.html), but these
+//! are still needed to talk to programs that use them.
+//!
+//! # Examples
+//!
+//! ```
+//! # use nix::sys::ipc::{IpcFlags, IPC_PRIVATE};
+//! # use nix::sys::msg::*;
+//! # use nix::sys::stat::Mode;
+//! let msqid = msgget(IPC_PRIVATE, IpcFlags::IPC_CREAT, Mode::S_IRUSR | Mode::S_IWUSR).unwrap();
+//! msgsnd(msqid, 1, b"hello", MsgFlags::empty()).unwrap();
+//!
+//! let mut buf = [0u8; 16];
+//! let (mtype, len) = msgrcv(msqid, &mut buf, 0, MsgFlags::empty()).unwrap();
+//! assert_eq!((mtype, &buf[..len]), (1, &b"hello"[..]));
+//! msgctl_rmid(msqid).unwrap();
+//! ```
+use libc::{self, c_int, c_long, c_void, time_t};
+use std::{cmp, mem, ptr};
+use errno::Errno;
+use sys::ipc::{self, IpcFlags, IpcPerm, Key};
+use sys::stat::Mode;
+use unistd::Pid;
+use Result;
+
+libc_bitflags!{
+    /// Flags for [`msgsnd`](fn.msgsnd.html) and [`msgrcv`](fn.msgrcv.html)
+    pub struct MsgFlags: c_int {
+        /// Fail with `EAGAIN` if the queue is full, or with `ENOMSG` if there's
+        /// no matching message, instead of blocking
+        IPC_NOWAIT;
+        /// Truncate messages longer than the buffer instead of failing with
+        /// `E2BIG`
+        MSG_NOERROR;
+        /// With a positive `msgtyp`, receive the first message of any other
+        /// type
+        #[cfg(target_os = "linux")]
+        MSG_EXCEPT;
+    }
+}
+
+/// Get the identifier of the message queue with the given key, creating it
+/// according to `flags` (see
+/// [msgget(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/msgget.html)).
+///
+/// A new queue gets the permissions in `mode`.
+pub fn msgget(key: Key, flags: IpcFlags, mode: Mode) -> Result<c_int> {
+    let res = unsafe { libc::msgget(key.into(), ipc::get_flags(flags.bits(), mode)) };
+
+    Errno::result(res)
+}
+
+// A message as passed to the kernel: the type, directly followed by the data.
+// It's made of `c_long`s, so that the type is aligned.
+fn message_buffer(len: usize) -> Vec<c_long> {
+    let size = mem::size_of::<c_long>();
+    vec![0; 1 + (len + size - 1) / size]
+}
+
+fn message_data(buffer: &mut [c_long]) -> *mut u8 {
+    unsafe { buffer.as_mut_ptr().offset(1) as *mut u8 }
+}
+
+/// Send a message of type `mtype`, which must be positive, to a queue (see
+/// [msgsnd(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/msgsnd.html)).
+///
+/// Blocks while the queue is full, unless `IPC_NOWAIT` is given.
+pub fn msgsnd(msqid: c_int, mtype: c_long, data: &[u8], flags: MsgFlags) -> Result<()> {
+    let mut buffer = message_buffer(data.len());
+    buffer[0] = mtype;
+    let res = unsafe {
+        ptr::copy_nonoverlapping(data.as_ptr(), message_data(&mut buffer), data.len());
+        libc::msgsnd(msqid, buffer.as_ptr() as *const c_void, data.len(), flags.bits())
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Receive a message from a queue (see
+/// [msgrcv(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/msgrcv.html)).
+///
+/// A `msgtyp` of 0 receives the first message in the queue, a positive one the
+/// first message of that type, and a negative one the first message with the
+/// lowest type that's at most its absolute value.  Blocks while there's no such
+/// message, unless `IPC_NOWAIT` is given.
+///
+/// Returns the type of the message and the length of its data, which is copied
+/// into `data`.
+pub fn msgrcv(msqid: c_int, data: &mut [u8], msgtyp: c_long, flags: MsgFlags)
+              -> Result<(c_long, usize)> {
+    let mut buffer = message_buffer(data.len());
+    let res = unsafe {
+        libc::msgrcv(msqid, buffer.as_mut_ptr() as *mut c_void, data.len(), msgtyp,
+                     flags.bits())
+    };
+
+    let len = try!(Errno::result(res)) as usize;
+    let len = cmp::min(len, data.len());
+    unsafe { ptr::copy_nonoverlapping(message_data(&mut buffer), data.as_mut_ptr(), len) };
+    Ok((buffer[0], len))
+}
+
+/// The state of a message queue, as returned by [`msgctl_stat`](fn.msgctl_stat.html)
+// FIXME: Change to repr(transparent)
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct MsqidDs(libc::msqid_ds);
+
+impl MsqidDs {
+    /// The owner and permissions of the queue
+    pub fn perm(&self) -> IpcPerm {
+        IpcPerm::from(self.0.msg_perm)
+    }
+
+    /// Change the owner and permissions, which takes effect with
+    /// [`msgctl_set`](fn.msgctl_set.html)
+    pub fn set_perm(&mut self, perm: IpcPerm) {
+        self.0.msg_perm = *perm.as_ref();
+    }
+
+    /// The number of messages in the queue
+    pub fn qnum(&self) -> u64 {
+        self.0.msg_qnum as u64
+    }
+
+    /// The maximum number of bytes the queue can hold
+    pub fn qbytes(&self) -> u64 {
+        self.0.msg_qbytes as u64
+    }
+
+    /// Change the maximum number of bytes, which takes effect with
+    /// [`msgctl_set`](fn.msgctl_set.html).  Raising it above the system limit
+    /// requires privileges.
+    pub fn set_qbytes(&mut self, qbytes: u64) {
+        self.0.msg_qbytes = qbytes as _;
+    }
+
+    /// The process that sent the last message
+    pub fn lspid(&self) -> Pid {
+        Pid::from_raw(self.0.msg_lspid)
+    }
+
+    /// The process that received the last message
+    pub fn lrpid(&self) -> Pid {
+        Pid::from_raw(self.0.msg_lrpid)
+    }
+
+    /// When the last message was sent
+    pub fn stime(&self) -> time_t {
+        self.0.msg_stime
+    }
+
+    /// When the last message was received
+    pub fn rtime(&self) -> time_t {
+        self.0.msg_rtime
+    }
+
+    /// When the queue was last changed
+    pub fn ctime(&self) -> time_t {
+        self.0.msg_ctime
+    }
+}
+
+/// Get the state of a queue (`IPC_STAT`, see
+/// [msgctl(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/msgctl.html)).
+pub fn msgctl_stat(msqid: c_int) -> Result<MsqidDs> {
+    let mut ds: libc::msqid_ds = unsafe { mem::zeroed() };
+    let res = unsafe { libc::msgctl(msqid, libc::IPC_STAT, &mut ds) };
+
+    Errno::result(res).map(|_| MsqidDs(ds))
+}
+
+/// Change the owner, permissions and size limit of a queue (`IPC_SET`, see
+/// [msgctl(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/msgctl.html)).
+///
+/// `ds` is usually obtained from [`msgctl_stat`](fn.msgctl_stat.html) and then
+/// modified.
+pub fn msgctl_set(msqid: c_int, ds: &MsqidDs) -> Result<()> {
+    let mut ds = ds.0;
+    let res = unsafe { libc::msgctl(msqid, libc::IPC_SET, &mut ds) };
+
+    Errno::result(res).map(drop)
+}
+
+/// Remove a queue immediately, waking up all blocked senders and receivers
+/// with `EIDRM` (`IPC_RMID`, see
+/// [msgctl(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/msgctl.html)).
+pub fn msgctl_rmid(msqid: c_int) -> Result<()> {
+    let res = unsafe { libc::msgctl(msqid, libc::IPC_RMID, ptr::null_mut()) };
+
+    Errno::result(res).map(drop)
+}
@@ -0,0 +1,91 @@
+//! Manage the ARP cache (see
+//! [arp(7)](http://man7.org/linux/man-pages/man7/arp.7.html)).
+//!
+//! The entries map the IPv4 addresses of the neighbours reachable through an
+//! interface to their hardware addresses.  Like the other functions of
+//! [`sys::net`](../index.html), these take the file descriptor of any socket.
+//! Only [`get`](fn.get.html) is unprivileged.
+use libc::{self, c_int};
+use std::mem;
+use std::os::unix::io::RawFd;
+use sys::socket::Ipv4Addr;
+use {NixPath, Result};
+use super::MacAddress;
+
+mod ioctls {
+    use libc::{self, arpreq};
+
+    ioctl_write_ptr_bad!(siocsarp, libc::SIOCSARP, arpreq);
+    ioctl_write_ptr_bad!(siocdarp, libc::SIOCDARP, arpreq);
+    ioctl_readwrite_bad!(siocgarp, libc::SIOCGARP, arpreq);
+}
+
+libc_bitflags!{
+    /// Flags of an [`ArpEntry`](struct.ArpEntry.html)
+    pub struct ArpFlags: c_int {
+        /// The hardware address is known.  Always set by the kernel on the
+        /// entries it reports.
+        ATF_COM;
+        /// The entry never expires
+        ATF_PERM;
+        /// Answer ARP requests for the address on behalf of the neighbour
+        ATF_PUBL;
+        /// The entry is published for a whole network, whose netmask is given
+        /// separately (obsolete)
+        ATF_NETMASK;
+        /// Don't answer requests for a published entry
+        ATF_DONTPUB;
+    }
+}
+
+/// An entry of the ARP cache
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ArpEntry {
+    /// The IPv4 address of the neighbour
+    pub addr: Ipv4Addr,
+    /// The hardware address of the neighbour
+    pub hwaddr: MacAddress,
+    /// The state of the entry
+    pub flags: ArpFlags,
+}
+
+// A request for `addr` on the interface `dev`, with the rest zeroed
+fn arpreq<P: ?Sized + NixPath>(dev: &P, addr: Ipv4Addr) -> Result<libc::arpreq> {
+    let mut req: libc::arpreq = unsafe { mem::zeroed() };
+    try!(super::copy_name(dev, &mut req.arp_dev));
+    req.arp_pa = super::sockaddr_in(addr);
+    Ok(req)
+}
+
+/// Add or replace the entry for `entry.addr` on the interface `dev`
+/// (`SIOCSARP`).
+///
+/// Fails with `EINVAL` if the interface doesn't use ARP, like the loopback
+/// interface.
+pub fn set<P: ?Sized + NixPath>(fd: RawFd, dev: &P, entry: &ArpEntry) -> Result<()> {
+    let mut req = try!(arpreq(dev, entry.addr));
+    req.arp_ha = super::sockaddr_ether(entry.hwaddr);
+    req.arp_flags = entry.flags.bits();
+    unsafe { ioctls::siocsarp(fd, &req) }.map(drop)
+}
+
+/// Get the entry for `addr` on the interface `dev` (`SIOCGARP`).
+///
+/// Fails with `ENXIO` if there's none.
+pub fn get<P: ?Sized + NixPath>(fd: RawFd, dev: &P, addr: Ipv4Addr) -> Result<ArpEntry> {
+    let mut req = try!(arpreq(dev, addr));
+    try!(unsafe { ioctls::siocgarp(fd, &mut req) });
+    Ok(ArpEntry {
+        addr: addr,
+        hwaddr: super::mac_address(&req.arp_ha),
+        flags: ArpFlags::from_bits_truncate(req.arp_flags),
+    })
+}
+
+/// Delete the entry for `addr` on the interface `dev` (`SIOCDARP`).
+///
+/// Fails with `ENXIO` if there's none.
+pub fn delete<P: ?Sized + NixPath>(fd: RawFd, dev: &P, addr: Ipv4Addr) -> Result<()> {
+    let req = try!(arpreq(dev, addr));
+    unsafe { ioctls::siocdarp(fd, &req) }.map(drop)
+}
@@ -0,0 +1,74 @@
+//! Create Ethernet bridges (see
+//! [brctl(8)](http://man7.org/linux/man-pages/man8/brctl.8.html)).
+//!
+//! A bridge is a network interface that forwards the Ethernet frames between
+//! the interfaces attached to it, like a switch.  Like the other functions of
+//! [`sys::net`](../index.html), these take the file descriptor of any socket,
+//! and they require `CAP_NET_ADMIN`.  They fail with `ENOPKG` if the kernel has
+//! no bridge support.
+use libc::{self, c_char};
+use std::os::unix::io::RawFd;
+use net::if_::if_nametoindex;
+use {NixPath, Result};
+
+// Not in libc, from linux/sockios.h
+const SIOCBRADDBR: libc::c_ulong = 0x89a0;
+const SIOCBRDELBR: libc::c_ulong = 0x89a1;
+const SIOCBRADDIF: libc::c_ulong = 0x89a2;
+const SIOCBRDELIF: libc::c_ulong = 0x89a3;
+
+mod ioctls {
+    use libc::{c_char, ifreq};
+
+    ioctl_write_ptr_bad!(siocbraddbr, super::SIOCBRADDBR, c_char);
+    ioctl_write_ptr_bad!(siocbrdelbr, super::SIOCBRDELBR, c_char);
+    ioctl_write_ptr_bad!(siocbraddif, super::SIOCBRADDIF, ifreq);
+    ioctl_write_ptr_bad!(siocbrdelif, super::SIOCBRDELIF, ifreq);
+}
+
+// The kernel reads a whole interface name, whatever the length of the string
+fn name_buf<P: ?Sized + NixPath>(name: &P) -> Result<[c_char; libc::IFNAMSIZ]> {
+    let mut buf = [0; libc::IFNAMSIZ];
+    try!(super::copy_name(name, &mut buf));
+    Ok(buf)
+}
+
+fn ifreq_index<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(bridge: &P1, dev: &P2)
+                                                            -> Result<libc::ifreq> {
+    let mut req = try!(super::ifreq(bridge));
+    req.ifr_ifru.ifru_ifindex = try!(if_nametoindex(dev)) as libc::c_int;
+    Ok(req)
+}
+
+/// Create the bridge `name`, which starts without interfaces and down
+/// (`SIOCBRADDBR`).
+///
+/// Fails with `EEXIST` if an interface with that name exists.
+pub fn add<P: ?Sized + NixPath>(fd: RawFd, name: &P) -> Result<()> {
+    let buf = try!(name_buf(name));
+    unsafe { ioctls::siocbraddbr(fd, buf.as_ptr()) }.map(drop)
+}
+
+/// Delete the bridge `name` (`SIOCBRDELBR`).
+///
+/// Fails with `EBUSY` while the bridge is up.
+pub fn delete<P: ?Sized + NixPath>(fd: RawFd, name: &P) -> Result<()> {
+    let buf = try!(name_buf(name));
+    unsafe { ioctls::siocbrdelbr(fd, buf.as_ptr()) }.map(drop)
+}
+
+/// Attach the interface `dev` to `bridge` (`SIOCBRADDIF`).
+///
+/// Fails with `EBUSY` if `dev` is already attached to a bridge.
+pub fn add_interface<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(fd: RawFd, bridge: &P1,
+                                                                 dev: &P2) -> Result<()> {
+    let req = try!(ifreq_index(bridge, dev));
+    unsafe { ioctls::siocbraddif(fd, &req) }.map(drop)
+}
+
+/// Detach the interface `dev` from `bridge` (`SIOCBRDELIF`).
+pub fn delete_interface<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(fd: RawFd, bridge: &P1,
+                                                                    dev: &P2) -> Result<()> {
+    let req = try!(ifreq_index(bridge, dev));
+    unsafe { ioctls::siocbrdelif(fd, &req) }.map(drop)
+}
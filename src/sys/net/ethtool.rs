@@ -0,0 +1,168 @@
+//! Query the link of network interfaces (see
+//! [ethtool(8)](http://man7.org/linux/man-pages/man8/ethtool.8.html)).
+//!
+//! Like the other functions of [`sys::net`](../index.html), these take the file
+//! descriptor of any socket and the name of the interface.  They fail with
+//! `EOPNOTSUPP` if the driver doesn't report the information.
+use libc::c_char;
+use std::mem;
+use std::os::unix::io::RawFd;
+use errno::Errno;
+use {Error, NixPath, Result};
+
+mod ioctls {
+    use libc::{self, ifreq};
+
+    ioctl_write_ptr_bad!(siocethtool, libc::SIOCETHTOOL, ifreq);
+}
+
+// Not in libc, from linux/ethtool.h
+const ETHTOOL_GSET: u32 = 0x1;
+const ETHTOOL_GLINK: u32 = 0xa;
+const ETHTOOL_GLINKSETTINGS: u32 = 0x4c;
+const SPEED_UNKNOWN: u32 = 0xffff_ffff;
+const DUPLEX_HALF: u8 = 0x0;
+const DUPLEX_FULL: u8 = 0x1;
+const AUTONEG_ENABLE: u8 = 0x1;
+// link_mode_masks_nwords is a signed byte
+const LINK_MODE_MASKS_MAX_NWORDS: usize = 127;
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct ethtool_value {
+    cmd: u32,
+    data: u32,
+}
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct ethtool_cmd {
+    cmd: u32,
+    supported: u32,
+    advertising: u32,
+    speed: u16,
+    duplex: u8,
+    port: u8,
+    phy_address: u8,
+    transceiver: u8,
+    autoneg: u8,
+    mdio_support: u8,
+    maxtxpkt: u32,
+    maxrxpkt: u32,
+    speed_hi: u16,
+    eth_tp_mdix: u8,
+    eth_tp_mdix_ctrl: u8,
+    lp_advertising: u32,
+    reserved: [u32; 2],
+}
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct ethtool_link_settings {
+    cmd: u32,
+    speed: u32,
+    duplex: u8,
+    port: u8,
+    phy_address: u8,
+    autoneg: u8,
+    mdio_support: u8,
+    eth_tp_mdix: u8,
+    eth_tp_mdix_ctrl: u8,
+    link_mode_masks_nwords: i8,
+    transceiver: u8,
+    master_slave_cfg: u8,
+    master_slave_state: u8,
+    rate_matching: u8,
+    reserved: [u32; 7],
+    // The supported, advertised and link partner masks follow, of
+    // link_mode_masks_nwords each.  This makes room for the largest ones.
+    link_mode_masks: [u32; 3 * LINK_MODE_MASKS_MAX_NWORDS],
+}
+
+// The kernel reads the command from `data`, and writes the answer back to it
+fn ethtool<P: ?Sized + NixPath, T>(fd: RawFd, name: &P, data: &mut T) -> Result<()> {
+    let mut req = try!(super::ifreq(name));
+    req.ifr_ifru.ifru_data = data as *mut T as *mut c_char;
+    unsafe { ioctls::siocethtool(fd, &req) }.map(drop)
+}
+
+/// The duplex mode of a link
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Duplex {
+    /// Only one side transmits at a time
+    Half,
+    /// Both sides transmit at the same time
+    Full,
+    /// The driver doesn't know, usually because there's no carrier
+    Unknown,
+}
+
+impl Duplex {
+    fn from_raw(duplex: u8) -> Duplex {
+        match duplex {
+            DUPLEX_HALF => Duplex::Half,
+            DUPLEX_FULL => Duplex::Full,
+            _ => Duplex::Unknown,
+        }
+    }
+}
+
+/// The settings of a link, as returned by [`link_settings`](fn.link_settings.html)
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct LinkSettings {
+    /// The speed in Mb/s, if known
+    pub speed: Option<u32>,
+    /// The duplex mode
+    pub duplex: Duplex,
+    /// Whether the speed and duplex mode are autonegotiated
+    pub autoneg: bool,
+}
+
+impl LinkSettings {
+    fn new(speed: u32, duplex: u8, autoneg: u8) -> LinkSettings {
+        LinkSettings {
+            speed: if speed == 0 || speed == SPEED_UNKNOWN { None } else { Some(speed) },
+            duplex: Duplex::from_raw(duplex),
+            autoneg: autoneg == AUTONEG_ENABLE,
+        }
+    }
+}
+
+/// Get the speed, duplex mode and autonegotiation of the link of an interface
+/// (`ETHTOOL_GLINKSETTINGS`).
+///
+/// Falls back to the deprecated `ETHTOOL_GSET` on kernels older than 4.6.
+pub fn link_settings<P: ?Sized + NixPath>(fd: RawFd, name: &P) -> Result<LinkSettings> {
+    // The kernel answers a request without masks with the number of words it
+    // needs as a negative number, and only fills the settings when asked with
+    // that number
+    let mut settings: ethtool_link_settings = unsafe { mem::zeroed() };
+    settings.cmd = ETHTOOL_GLINKSETTINGS;
+    match ethtool(fd, name, &mut settings) {
+        Ok(()) if settings.link_mode_masks_nwords < 0 => {
+            let nwords = -settings.link_mode_masks_nwords;
+            settings = unsafe { mem::zeroed() };
+            settings.cmd = ETHTOOL_GLINKSETTINGS;
+            settings.link_mode_masks_nwords = nwords;
+            try!(ethtool(fd, name, &mut settings));
+            Ok(LinkSettings::new(settings.speed, settings.duplex, settings.autoneg))
+        },
+        Ok(()) | Err(Error::Sys(Errno::EOPNOTSUPP)) => {
+            let mut cmd: ethtool_cmd = unsafe { mem::zeroed() };
+            cmd.cmd = ETHTOOL_GSET;
+            try!(ethtool(fd, name, &mut cmd));
+            let speed = (cmd.speed_hi as u32) << 16 | cmd.speed as u32;
+            Ok(LinkSettings::new(speed, cmd.duplex, cmd.autoneg))
+        },
+        Err(e) => Err(e),
+    }
+}
+
+/// Get whether the link of an interface is up (`ETHTOOL_GLINK`).
+///
+/// For most drivers, this is whether there's a carrier.
+pub fn link<P: ?Sized + NixPath>(fd: RawFd, name: &P) -> Result<bool> {
+    let mut value = ethtool_value { cmd: ETHTOOL_GLINK, data: 0 };
+    try!(ethtool(fd, name, &mut value));
+    Ok(value.data != 0)
+}
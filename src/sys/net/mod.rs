@@ -0,0 +1,225 @@
+//! Configure network interfaces (see
+//! [netdevice(7)](http://man7.org/linux/man-pages/man7/netdevice.7.html)).
+//!
+//! These wrappers take the file descriptor of any socket, such as a UDP one,
+//! and the name of the interface.  Reading the configuration is unprivileged,
+//! while changing it requires `CAP_NET_ADMIN`.
+//!
+//! # Examples
+//!
+//! ```
+//! # use nix::net::if_::InterfaceFlags;
+//! # use nix::sys::net;
+//! # use nix::sys::socket::*;
+//! let fd = socket(AddressFamily::Inet, SockType::Datagram, SockFlag::empty(), None).unwrap();
+//! assert!(net::flags(fd, "lo").unwrap().contains(InterfaceFlags::IFF_LOOPBACK));
+//! assert_eq!(net::addr(fd, "lo").unwrap().octets(), [127, 0, 0, 1]);
+//! ```
+use libc::{self, c_char, c_int, c_short};
+use std::{fmt, mem, ptr};
+use std::os::unix::io::RawFd;
+use errno::Errno;
+use net::if_::InterfaceFlags;
+use sys::socket::Ipv4Addr;
+use {Error, NixPath, Result};
+
+pub mod arp;
+pub mod bridge;
+pub mod ethtool;
+#[cfg(feature = "std")]
+pub mod netns;
+#[cfg(feature = "std")]
+pub mod tun;
+pub mod vlan;
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub mod wireless;
+
+mod ioctls {
+    use libc::{self, ifreq};
+
+    ioctl_readwrite_bad!(siocgifflags, libc::SIOCGIFFLAGS, ifreq);
+    ioctl_write_ptr_bad!(siocsifflags, libc::SIOCSIFFLAGS, ifreq);
+    ioctl_readwrite_bad!(siocgifmtu, libc::SIOCGIFMTU, ifreq);
+    ioctl_write_ptr_bad!(siocsifmtu, libc::SIOCSIFMTU, ifreq);
+    ioctl_readwrite_bad!(siocgifaddr, libc::SIOCGIFADDR, ifreq);
+    ioctl_write_ptr_bad!(siocsifaddr, libc::SIOCSIFADDR, ifreq);
+    ioctl_readwrite_bad!(siocgifnetmask, libc::SIOCGIFNETMASK, ifreq);
+    ioctl_write_ptr_bad!(siocsifnetmask, libc::SIOCSIFNETMASK, ifreq);
+    ioctl_readwrite_bad!(siocgifhwaddr, libc::SIOCGIFHWADDR, ifreq);
+    ioctl_write_ptr_bad!(siocsifhwaddr, libc::SIOCSIFHWADDR, ifreq);
+}
+
+// Copy the name of an interface into `buf`, which must be zeroed.  Fails with
+// `ENAMETOOLONG` if the name doesn't fit with its terminating nul.
+fn copy_name<P: ?Sized + NixPath>(name: &P, buf: &mut [c_char]) -> Result<()> {
+    if name.len() >= buf.len() {
+        return Err(Error::Sys(Errno::ENAMETOOLONG));
+    }
+    name.with_nix_path(|name| unsafe {
+        ptr::copy_nonoverlapping(name.as_ptr(), buf.as_mut_ptr(), name.to_bytes().len())
+    })
+}
+
+// A request for the named interface, with the rest zeroed
+fn ifreq<P: ?Sized + NixPath>(name: &P) -> Result<libc::ifreq> {
+    let mut req: libc::ifreq = unsafe { mem::zeroed() };
+    try!(copy_name(name, &mut req.ifr_name));
+    Ok(req)
+}
+
+fn sockaddr_in(addr: Ipv4Addr) -> libc::sockaddr {
+    let mut sin: libc::sockaddr_in = unsafe { mem::zeroed() };
+    sin.sin_family = libc::AF_INET as libc::sa_family_t;
+    sin.sin_addr = addr.0;
+    unsafe { mem::transmute(sin) }
+}
+
+// The kernel only returns AF_INET addresses from these requests
+fn in_addr(addr: &libc::sockaddr) -> Ipv4Addr {
+    let sin = unsafe { &*(addr as *const libc::sockaddr as *const libc::sockaddr_in) };
+    Ipv4Addr(sin.sin_addr)
+}
+
+/// Get the flags of an interface (`SIOCGIFFLAGS`).
+///
+/// These only include the flags below `IFF_LOWER_UP`; the others are only
+/// reported by [`getifaddrs`](../../ifaddrs/fn.getifaddrs.html).
+pub fn flags<P: ?Sized + NixPath>(fd: RawFd, name: &P) -> Result<InterfaceFlags> {
+    let mut req = try!(ifreq(name));
+    try!(unsafe { ioctls::siocgifflags(fd, &mut req) });
+    // The flags are an unsigned short in the kernel
+    let flags = unsafe { req.ifr_ifru.ifru_flags } as u16 as c_int;
+    Ok(InterfaceFlags::from_bits_truncate(flags))
+}
+
+/// Set the flags of an interface (`SIOCSIFFLAGS`).
+///
+/// Adding or removing `IFF_UP` brings the interface up or down.  Only the
+/// flags below `IFF_LOWER_UP` can be set, and some of them are only reported
+/// by the kernel, so this is usually called with the result of
+/// [`flags`](fn.flags.html), modified.
+pub fn set_flags<P: ?Sized + NixPath>(fd: RawFd, name: &P, flags: InterfaceFlags)
+                                      -> Result<()> {
+    let mut req = try!(ifreq(name));
+    req.ifr_ifru.ifru_flags = flags.bits() as c_short;
+    unsafe { ioctls::siocsifflags(fd, &req) }.map(drop)
+}
+
+/// Get the maximum transmission unit of an interface, in bytes (`SIOCGIFMTU`).
+pub fn mtu<P: ?Sized + NixPath>(fd: RawFd, name: &P) -> Result<c_int> {
+    let mut req = try!(ifreq(name));
+    try!(unsafe { ioctls::siocgifmtu(fd, &mut req) });
+    Ok(unsafe { req.ifr_ifru.ifru_mtu })
+}
+
+/// Set the maximum transmission unit of an interface, in bytes (`SIOCSIFMTU`).
+///
+/// Fails with `EINVAL` if the driver doesn't support it.
+pub fn set_mtu<P: ?Sized + NixPath>(fd: RawFd, name: &P, mtu: c_int) -> Result<()> {
+    let mut req = try!(ifreq(name));
+    req.ifr_ifru.ifru_mtu = mtu;
+    unsafe { ioctls::siocsifmtu(fd, &req) }.map(drop)
+}
+
+/// Get the primary IPv4 address of an interface (`SIOCGIFADDR`).
+///
+/// Fails with `EADDRNOTAVAIL` if it has none.  The other addresses are only
+/// reported by [`getifaddrs`](../../ifaddrs/fn.getifaddrs.html).
+pub fn addr<P: ?Sized + NixPath>(fd: RawFd, name: &P) -> Result<Ipv4Addr> {
+    let mut req = try!(ifreq(name));
+    try!(unsafe { ioctls::siocgifaddr(fd, &mut req) });
+    Ok(in_addr(unsafe { &req.ifr_ifru.ifru_addr }))
+}
+
+/// Set the primary IPv4 address of an interface (`SIOCSIFADDR`).
+///
+/// This also resets the netmask to the one of the address class, so
+/// [`set_netmask`](fn.set_netmask.html) should be called afterwards.
+pub fn set_addr<P: ?Sized + NixPath>(fd: RawFd, name: &P, addr: Ipv4Addr) -> Result<()> {
+    let mut req = try!(ifreq(name));
+    req.ifr_ifru.ifru_addr = sockaddr_in(addr);
+    unsafe { ioctls::siocsifaddr(fd, &req) }.map(drop)
+}
+
+/// Get the netmask of the primary IPv4 address of an interface
+/// (`SIOCGIFNETMASK`).
+pub fn netmask<P: ?Sized + NixPath>(fd: RawFd, name: &P) -> Result<Ipv4Addr> {
+    let mut req = try!(ifreq(name));
+    try!(unsafe { ioctls::siocgifnetmask(fd, &mut req) });
+    Ok(in_addr(unsafe { &req.ifr_ifru.ifru_netmask }))
+}
+
+/// Set the netmask of the primary IPv4 address of an interface
+/// (`SIOCSIFNETMASK`).
+pub fn set_netmask<P: ?Sized + NixPath>(fd: RawFd, name: &P, netmask: Ipv4Addr) -> Result<()> {
+    let mut req = try!(ifreq(name));
+    req.ifr_ifru.ifru_netmask = sockaddr_in(netmask);
+    unsafe { ioctls::siocsifnetmask(fd, &req) }.map(drop)
+}
+
+/// A 48-bit Ethernet hardware address, displayed as `aa:bb:cc:dd:ee:ff`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct MacAddress([u8; 6]);
+
+impl MacAddress {
+    /// The address made of the given bytes, in transmission order
+    pub fn new(a: u8, b: u8, c: u8, d: u8, e: u8, f: u8) -> MacAddress {
+        MacAddress([a, b, c, d, e, f])
+    }
+
+    /// The bytes of the address, in transmission order
+    pub fn octets(&self) -> [u8; 6] {
+        self.0
+    }
+}
+
+impl From<[u8; 6]> for MacAddress {
+    fn from(octets: [u8; 6]) -> MacAddress {
+        MacAddress(octets)
+    }
+}
+
+impl fmt::Display for MacAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let a = self.0;
+        write!(f, "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}", a[0], a[1], a[2], a[3], a[4], a[5])
+    }
+}
+
+fn sockaddr_ether(addr: MacAddress) -> libc::sockaddr {
+    let mut sa: libc::sockaddr = unsafe { mem::zeroed() };
+    sa.sa_family = libc::ARPHRD_ETHER as libc::sa_family_t;
+    for (byte, &octet) in sa.sa_data.iter_mut().zip(addr.0.iter()) {
+        *byte = octet as libc::c_char;
+    }
+    sa
+}
+
+// The first bytes of the address, whatever its type
+fn mac_address(addr: &libc::sockaddr) -> MacAddress {
+    let mut octets = [0; 6];
+    for (octet, &byte) in octets.iter_mut().zip(addr.sa_data.iter()) {
+        *octet = byte as u8;
+    }
+    MacAddress(octets)
+}
+
+/// Get the hardware address of an interface (`SIOCGIFHWADDR`).
+///
+/// Interfaces without one, like the loopback interface or TUN devices, report
+/// all zeros.
+pub fn hwaddr<P: ?Sized + NixPath>(fd: RawFd, name: &P) -> Result<MacAddress> {
+    let mut req = try!(ifreq(name));
+    try!(unsafe { ioctls::siocgifhwaddr(fd, &mut req) });
+    Ok(mac_address(unsafe { &req.ifr_ifru.ifru_hwaddr }))
+}
+
+/// Set the Ethernet hardware address of an interface (`SIOCSIFHWADDR`).
+///
+/// Most drivers fail with `EBUSY` while the interface is up, and with
+/// `EADDRNOTAVAIL` if the address is a multicast one.
+pub fn set_hwaddr<P: ?Sized + NixPath>(fd: RawFd, name: &P, hwaddr: MacAddress) -> Result<()> {
+    let mut req = try!(ifreq(name));
+    req.ifr_ifru.ifru_hwaddr = sockaddr_ether(hwaddr);
+    unsafe { ioctls::siocsifhwaddr(fd, &req) }.map(drop)
+}
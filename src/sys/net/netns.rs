@@ -0,0 +1,90 @@
+//! Enter network namespaces (see
+//! [network_namespaces(7)](http://man7.org/linux/man-pages/man7/network_namespaces.7.html)).
+//!
+//! A socket belongs to the network namespace of the thread that created it,
+//! for its whole life, so programs managing several namespaces switch to one,
+//! create their sockets there, and switch back.  The namespace belongs to the
+//! calling thread only, and entering one requires `CAP_SYS_ADMIN`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use nix::sys::net::netns;
+//! # use nix::sys::socket::*;
+//! let ns = netns::open("blue").unwrap();
+//! let fd = {
+//!     let _guard = netns::enter(ns).unwrap();
+//!     socket(AddressFamily::Inet, SockType::Datagram, SockFlag::empty(), None).unwrap()
+//! };
+//! // fd still sends and receives in the "blue" namespace
+//! ```
+use std::marker::PhantomData;
+use std::mem;
+use std::os::unix::io::RawFd;
+use std::path::Path;
+use fcntl::{self, OFlag};
+use sched::{self, CloneFlags};
+use sys::stat::Mode;
+use unistd::{self, Pid};
+use Result;
+
+/// Open the namespace named `name`, as created by `ip netns add`, which is
+/// bound to `/var/run/netns/<name>`.
+///
+/// Returns a close-on-exec file descriptor.
+pub fn open<P: ?Sized + AsRef<Path>>(name: &P) -> Result<RawFd> {
+    fcntl::open(&Path::new("/var/run/netns").join(name), OFlag::O_RDONLY | OFlag::O_CLOEXEC,
+                Mode::empty())
+}
+
+/// Open the namespace of the process `pid`, from `/proc/<pid>/ns/net`.
+///
+/// Returns a close-on-exec file descriptor.
+pub fn open_pid(pid: Pid) -> Result<RawFd> {
+    fcntl::open(format!("/proc/{}/ns/net", pid).as_str(), OFlag::O_RDONLY | OFlag::O_CLOEXEC,
+                Mode::empty())
+}
+
+/// Switch the calling thread to the namespace `fd`, until the returned guard
+/// is dropped.
+///
+/// `fd` is usually opened with [`open`](fn.open.html) or
+/// [`open_pid`](fn.open_pid.html), and can be closed once this returns.
+pub fn enter(fd: RawFd) -> Result<NetnsGuard> {
+    // The namespace of the thread, rather than the one of the main thread
+    let path = format!("/proc/self/task/{}/ns/net", unistd::gettid());
+    let orig = try!(fcntl::open(path.as_str(), OFlag::O_RDONLY | OFlag::O_CLOEXEC, Mode::empty()));
+    if let Err(e) = sched::setns(fd, CloneFlags::CLONE_NEWNET) {
+        let _ = unistd::close(orig);
+        return Err(e);
+    }
+    Ok(NetnsGuard { orig: orig, _marker: PhantomData })
+}
+
+/// Switches the thread that created it back to its original network namespace
+/// when dropped, as returned by [`enter`](fn.enter.html).
+///
+/// It can't be sent to other threads, since the namespace belongs to a thread.
+#[derive(Debug)]
+pub struct NetnsGuard {
+    orig: RawFd,
+    _marker: PhantomData<*const ()>,
+}
+
+impl NetnsGuard {
+    /// Switch back to the original namespace now, reporting errors which
+    /// dropping the guard ignores.
+    pub fn leave(self) -> Result<()> {
+        let res = sched::setns(self.orig, CloneFlags::CLONE_NEWNET);
+        let _ = unistd::close(self.orig);
+        mem::forget(self);
+        res
+    }
+}
+
+impl Drop for NetnsGuard {
+    fn drop(&mut self) {
+        let _ = sched::setns(self.orig, CloneFlags::CLONE_NEWNET);
+        let _ = unistd::close(self.orig);
+    }
+}
@@ -0,0 +1,84 @@
+//! Create TUN and TAP devices (see
+//! [tuntap.txt](https://www.kernel.org/doc/Documentation/networking/tuntap.txt)).
+//!
+//! A TUN device is a network interface whose IP packets are read from and
+//! written to a file descriptor instead of a network, and a TAP device does
+//! the same with Ethernet frames.  Both need `CAP_NET_ADMIN` to be created.
+use libc::{self, c_short};
+use std::ffi::{CStr, CString};
+use std::os::unix::io::RawFd;
+use fcntl::{self, OFlag};
+use sys::stat::Mode;
+use unistd::{self, Gid, Uid};
+use {NixPath, Result};
+
+mod ioctls {
+    use libc::{self, ifreq};
+
+    ioctl_write_ptr_bad!(tunsetiff, libc::TUNSETIFF, ifreq);
+    ioctl_write_int_bad!(tunsetpersist, libc::TUNSETPERSIST);
+    ioctl_write_int_bad!(tunsetowner, libc::TUNSETOWNER);
+    ioctl_write_int_bad!(tunsetgroup, libc::TUNSETGROUP);
+}
+
+libc_bitflags!{
+    /// Flags for [`open`](fn.open.html)
+    pub struct TunFlags: c_short {
+        /// Create a TUN device, which carries IP packets
+        IFF_TUN as c_short;
+        /// Create a TAP device, which carries Ethernet frames
+        IFF_TAP as c_short;
+        /// Don't prepend the 4-byte protocol information to each packet
+        IFF_NO_PI as c_short;
+        /// Allow several file descriptors to attach to the device, each
+        /// handling its own queue of packets
+        IFF_MULTI_QUEUE as c_short;
+        /// Prepend a `virtio_net_hdr` to each packet, for offloads
+        IFF_VNET_HDR as c_short;
+        /// Fail with `EBUSY` if the device already exists
+        IFF_TUN_EXCL as c_short;
+    }
+}
+
+/// Open `/dev/net/tun` and attach it to the TUN or TAP device `name`,
+/// creating it, as with `ioctl(TUNSETIFF)`.
+///
+/// `flags` must contain one of `IFF_TUN` and `IFF_TAP`.  An empty `name`, or
+/// one with a `%d`, such as `tap%d`, lets the kernel pick the first free
+/// name.  Returns the file descriptor, which is close-on-exec, and the name of
+/// the device.  A device is destroyed once no descriptor is attached to it,
+/// unless it's made persistent with [`set_persist`](fn.set_persist.html).
+pub fn open<P: ?Sized + NixPath>(name: &P, flags: TunFlags) -> Result<(RawFd, CString)> {
+    let mut req = try!(super::ifreq(name));
+    req.ifr_ifru.ifru_flags = flags.bits();
+
+    let fd = try!(fcntl::open("/dev/net/tun", OFlag::O_RDWR | OFlag::O_CLOEXEC, Mode::empty()));
+    if let Err(e) = unsafe { ioctls::tunsetiff(fd, &req) } {
+        let _ = unistd::close(fd);
+        return Err(e);
+    }
+    // The kernel writes back the name it picked
+    let name = unsafe { CStr::from_ptr(req.ifr_name.as_ptr()) };
+    Ok((fd, name.to_owned()))
+}
+
+/// Make the device attached to `fd` persist after it's closed, or not, as
+/// with `ioctl(TUNSETPERSIST)`.
+///
+/// A persistent device can be reattached with [`open`](fn.open.html), by its
+/// owner or group if any.
+pub fn set_persist(fd: RawFd, persist: bool) -> Result<()> {
+    unsafe { ioctls::tunsetpersist(fd, persist as libc::c_int) }.map(drop)
+}
+
+/// Allow `owner` to attach to the persistent device attached to `fd` without
+/// `CAP_NET_ADMIN`, as with `ioctl(TUNSETOWNER)`.
+pub fn set_owner(fd: RawFd, owner: Uid) -> Result<()> {
+    unsafe { ioctls::tunsetowner(fd, libc::uid_t::from(owner) as libc::c_int) }.map(drop)
+}
+
+/// Allow the members of `group` to attach to the persistent device attached
+/// to `fd` without `CAP_NET_ADMIN`, as with `ioctl(TUNSETGROUP)`.
+pub fn set_group(fd: RawFd, group: Gid) -> Result<()> {
+    unsafe { ioctls::tunsetgroup(fd, libc::gid_t::from(group) as libc::c_int) }.map(drop)
+}
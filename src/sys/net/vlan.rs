@@ -0,0 +1,63 @@
+//! Create 802.1Q VLAN interfaces (see
+//! [vconfig(8)](https://linux.die.net/man/8/vconfig)).
+//!
+//! A VLAN interface sends and receives the frames of an Ethernet interface
+//! which are tagged with its VLAN ID.  Like the other functions of
+//! [`sys::net`](../index.html), these take the file descriptor of any socket,
+//! and they require `CAP_NET_ADMIN`.  They fail with `ENOPKG` if the kernel has
+//! no VLAN support.
+use libc::{self, c_char, c_int, c_short};
+use std::mem;
+use std::os::unix::io::RawFd;
+use {NixPath, Result};
+
+// Not in libc, from linux/sockios.h and linux/if_vlan.h
+const SIOCSIFVLAN: libc::c_ulong = 0x8983;
+const ADD_VLAN_CMD: c_int = 0;
+const DEL_VLAN_CMD: c_int = 1;
+
+#[repr(C)]
+#[allow(dead_code)]
+union vlan_ioctl_args_u {
+    device2: [c_char; 24],
+    vid: c_int,
+}
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct vlan_ioctl_args {
+    cmd: c_int,
+    device1: [c_char; 24],
+    u: vlan_ioctl_args_u,
+    vlan_qos: c_short,
+}
+
+mod ioctls {
+    ioctl_write_ptr_bad!(siocsifvlan, super::SIOCSIFVLAN, super::vlan_ioctl_args);
+}
+
+fn vlan_ioctl_args<P: ?Sized + NixPath>(cmd: c_int, name: &P) -> Result<vlan_ioctl_args> {
+    let mut args: vlan_ioctl_args = unsafe { mem::zeroed() };
+    args.cmd = cmd;
+    try!(super::copy_name(name, &mut args.device1));
+    Ok(args)
+}
+
+/// Create a VLAN interface for the frames of `dev` tagged with `vid`
+/// (`ADD_VLAN_CMD`).
+///
+/// The new interface is named after both, like `eth0.5`.  Fails with `EEXIST`
+/// if `dev` already has an interface for `vid`.
+pub fn add<P: ?Sized + NixPath>(fd: RawFd, dev: &P, vid: u16) -> Result<()> {
+    let mut args = try!(vlan_ioctl_args(ADD_VLAN_CMD, dev));
+    args.u.vid = vid as c_int;
+    unsafe { ioctls::siocsifvlan(fd, &args) }.map(drop)
+}
+
+/// Delete the VLAN interface `name` (`DEL_VLAN_CMD`).
+///
+/// Fails with `EPERM` if `name` isn't a VLAN interface.
+pub fn delete<P: ?Sized + NixPath>(fd: RawFd, name: &P) -> Result<()> {
+    let args = try!(vlan_ioctl_args(DEL_VLAN_CMD, name));
+    unsafe { ioctls::siocsifvlan(fd, &args) }.map(drop)
+}
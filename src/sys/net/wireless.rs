@@ -0,0 +1,134 @@
+//! Query wireless interfaces through the wireless extensions (see
+//! [iwconfig(8)](https://linux.die.net/man/8/iwconfig)).
+//!
+//! Like the other functions of [`sys::net`](../index.html), these take the file
+//! descriptor of any socket and the name of the interface.  They're
+//! unprivileged, and fail with `EOPNOTSUPP` on interfaces that aren't
+//! wireless, or with `ENOTTY` if the kernel was built without the wireless
+//! extensions.  Drivers for the newer nl80211 interface usually still answer
+//! them, through a compatibility layer.
+use libc::{self, c_void};
+use std::ffi::{CStr, CString};
+use std::{cmp, mem};
+use std::os::unix::io::RawFd;
+use {NixPath, Result};
+
+mod ioctls {
+    use libc::{self, iwreq};
+
+    ioctl_readwrite_bad!(siocgiwname, libc::SIOCGIWNAME, iwreq);
+    ioctl_readwrite_bad!(siocgiwessid, libc::SIOCGIWESSID, iwreq);
+    ioctl_readwrite_bad!(siocgiwrate, libc::SIOCGIWRATE, iwreq);
+    ioctl_readwrite_bad!(siocgiwstats, libc::SIOCGIWSTATS, iwreq);
+}
+
+// A request for the named interface, with the rest zeroed
+fn iwreq<P: ?Sized + NixPath>(name: &P) -> Result<libc::iwreq> {
+    let mut req: libc::iwreq = unsafe { mem::zeroed() };
+    try!(super::copy_name(name, unsafe { &mut req.ifr_ifrn.ifrn_name }));
+    Ok(req)
+}
+
+/// Get the wireless protocol of an interface, such as `IEEE 802.11`
+/// (`SIOCGIWNAME`).
+///
+/// This is the usual way to tell whether an interface is wireless.
+pub fn name<P: ?Sized + NixPath>(fd: RawFd, dev: &P) -> Result<CString> {
+    let mut req = try!(iwreq(dev));
+    try!(unsafe { ioctls::siocgiwname(fd, &mut req) });
+    // The kernel writes a nul-terminated string
+    Ok(unsafe { CStr::from_ptr(req.u.name.as_ptr()) }.to_owned())
+}
+
+/// Get the ESSID of the network an interface is associated with
+/// (`SIOCGIWESSID`).
+///
+/// The ESSID is empty if it isn't associated.  It's made of at most 32
+/// arbitrary bytes, which are usually, but not always, UTF-8.
+pub fn essid<P: ?Sized + NixPath>(fd: RawFd, dev: &P) -> Result<Vec<u8>> {
+    let mut essid = [0u8; libc::IW_ESSID_MAX_SIZE + 1];
+    let mut req = try!(iwreq(dev));
+    req.u.essid = libc::iw_point {
+        pointer: essid.as_mut_ptr() as *mut c_void,
+        length: essid.len() as u16,
+        flags: 0,
+    };
+    try!(unsafe { ioctls::siocgiwessid(fd, &mut req) });
+    let len = unsafe { req.u.essid.length } as usize;
+    Ok(essid[..cmp::min(len, libc::IW_ESSID_MAX_SIZE)].to_vec())
+}
+
+/// Get the current bitrate of an interface, in b/s (`SIOCGIWRATE`).
+pub fn bitrate<P: ?Sized + NixPath>(fd: RawFd, dev: &P) -> Result<i32> {
+    let mut req = try!(iwreq(dev));
+    try!(unsafe { ioctls::siocgiwrate(fd, &mut req) });
+    Ok(unsafe { req.u.bitrate.value })
+}
+
+libc_bitflags!{
+    /// Flags of a [`Quality`](struct.Quality.html)
+    pub struct QualityFlags: u8 {
+        /// `qual` was updated since the last query
+        IW_QUAL_QUAL_UPDATED as u8;
+        /// `level` was updated since the last query
+        IW_QUAL_LEVEL_UPDATED as u8;
+        /// `noise` was updated since the last query
+        IW_QUAL_NOISE_UPDATED as u8;
+        /// `level` and `noise` are in dBm
+        IW_QUAL_DBM as u8;
+        /// `qual` is meaningless
+        IW_QUAL_QUAL_INVALID as u8;
+        /// `level` is meaningless
+        IW_QUAL_LEVEL_INVALID as u8;
+        /// `noise` is meaningless
+        IW_QUAL_NOISE_INVALID as u8;
+        /// `level` and `noise` are RCPI values, as defined by 802.11k
+        IW_QUAL_RCPI as u8;
+    }
+}
+
+/// The quality of the link of a wireless interface, as returned by
+/// [`quality`](fn.quality.html)
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Quality {
+    /// The overall quality of the link, on a scale specific to the driver
+    pub qual: u8,
+    /// The signal level, in dBm with `IW_QUAL_DBM`
+    pub level: u8,
+    /// The noise level, in dBm with `IW_QUAL_DBM`
+    pub noise: u8,
+    /// Which of the values are valid or were updated
+    pub flags: QualityFlags,
+}
+
+impl Quality {
+    /// The signal level in dBm, if the driver reports it in dBm
+    pub fn level_dbm(&self) -> Option<i32> {
+        if self.flags.contains(QualityFlags::IW_QUAL_DBM) &&
+           !self.flags.contains(QualityFlags::IW_QUAL_LEVEL_INVALID) {
+            // Negative levels are stored with 256 added, like iwconfig assumes
+            Some(if self.level >= 64 { self.level as i32 - 0x100 } else { self.level as i32 })
+        } else {
+            None
+        }
+    }
+}
+
+/// Get the quality of the link of an interface (`SIOCGIWSTATS`).
+pub fn quality<P: ?Sized + NixPath>(fd: RawFd, dev: &P) -> Result<Quality> {
+    let mut stats: libc::iw_statistics = unsafe { mem::zeroed() };
+    let mut req = try!(iwreq(dev));
+    req.u.data = libc::iw_point {
+        pointer: &mut stats as *mut libc::iw_statistics as *mut c_void,
+        length: mem::size_of::<libc::iw_statistics>() as u16,
+        // Leave the update flags set for other queries
+        flags: 0,
+    };
+    try!(unsafe { ioctls::siocgiwstats(fd, &mut req) });
+    Ok(Quality {
+        qual: stats.qual.qual,
+        level: stats.qual.level,
+        noise: stats.qual.noise,
+        flags: QualityFlags::from_bits_truncate(stats.qual.updated),
+    })
+}
@@ -0,0 +1,427 @@
+//! Count and sample hardware and software events with the kernel's performance
+//! monitoring (see
+//! [perf_event_open(2)](http://man7.org/linux/man-pages/man2/perf_event_open.2.html)).
+//!
+//! [`perf_event_open`](fn.perf_event_open.html) creates a counter for the event
+//! described by a [`PerfEventAttr`](struct.PerfEventAttr.html), on a thread, a
+//! CPU or both.  Reading the returned file descriptor gives the count.  Sampling
+//! events also write records to a ring buffer, which is mapped from the file
+//! descriptor with [`mmap`](../mman/fn.mmap.html).
+//!
+//! Unless `/proc/sys/kernel/perf_event_paranoid` is -1, unprivileged processes
+//! may only count events of threads they could trace, and at levels above 1
+//! only in user space.  Other events fail with `EACCES`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use nix::sys::perf_event::*;
+//! # use nix::unistd::gettid;
+//! let attr = PerfEventAttr::hardware(HardwareEvent::PERF_COUNT_HW_INSTRUCTIONS)
+//!     .disabled(true)
+//!     .exclude_kernel(true);
+//! let fd = perf_event_open(&attr, Some(gettid()), None, None, PerfFlags::PERF_FLAG_FD_CLOEXEC)
+//!     .unwrap();
+//! enable(fd, false).unwrap();
+//! // The code to measure
+//! disable(fd, false).unwrap();
+//! println!("{} instructions", read_count(fd).unwrap());
+//! ```
+use libc::{self, c_int, c_ulong};
+use std::os::unix::io::RawFd;
+use std::{mem, slice};
+use errno::Errno;
+use unistd::{self, Pid, SysconfVar};
+use {Error, Result};
+
+// libc doesn't export perf_event, so these values are taken from linux/perf_event.h
+
+/// Generalized hardware events, see
+/// [`PerfEventAttr::hardware`](struct.PerfEventAttr.html#method.hardware)
+///
+/// Not every CPU supports all of them, and opening unsupported ones fails with
+/// `ENOENT`.
+#[repr(u64)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum HardwareEvent {
+    /// CPU cycles, which vary with the CPU frequency
+    PERF_COUNT_HW_CPU_CYCLES = 0,
+    /// Retired instructions
+    PERF_COUNT_HW_INSTRUCTIONS = 1,
+    /// Accesses to the last level cache
+    PERF_COUNT_HW_CACHE_REFERENCES = 2,
+    /// Misses of the last level cache
+    PERF_COUNT_HW_CACHE_MISSES = 3,
+    /// Retired branch instructions
+    PERF_COUNT_HW_BRANCH_INSTRUCTIONS = 4,
+    /// Mispredicted branch instructions
+    PERF_COUNT_HW_BRANCH_MISSES = 5,
+    /// Bus cycles
+    PERF_COUNT_HW_BUS_CYCLES = 6,
+    /// Cycles where the front end of the pipeline stalled
+    PERF_COUNT_HW_STALLED_CYCLES_FRONTEND = 7,
+    /// Cycles where the back end of the pipeline stalled
+    PERF_COUNT_HW_STALLED_CYCLES_BACKEND = 8,
+    /// CPU cycles at a constant rate, independent of the CPU frequency
+    PERF_COUNT_HW_REF_CPU_CYCLES = 9,
+}
+
+/// Events counted by the kernel, see
+/// [`PerfEventAttr::software`](struct.PerfEventAttr.html#method.software)
+#[repr(u64)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SoftwareEvent {
+    /// Nanoseconds of the CPU clock
+    PERF_COUNT_SW_CPU_CLOCK = 0,
+    /// Nanoseconds the measured task ran
+    PERF_COUNT_SW_TASK_CLOCK = 1,
+    /// Page faults
+    PERF_COUNT_SW_PAGE_FAULTS = 2,
+    /// Context switches
+    PERF_COUNT_SW_CONTEXT_SWITCHES = 3,
+    /// Migrations of the task to another CPU
+    PERF_COUNT_SW_CPU_MIGRATIONS = 4,
+    /// Page faults that didn't require I/O
+    PERF_COUNT_SW_PAGE_FAULTS_MIN = 5,
+    /// Page faults that required I/O
+    PERF_COUNT_SW_PAGE_FAULTS_MAJ = 6,
+    /// Unaligned accesses fixed up by the kernel
+    PERF_COUNT_SW_ALIGNMENT_FAULTS = 7,
+    /// Unimplemented instructions emulated by the kernel
+    PERF_COUNT_SW_EMULATION_FAULTS = 8,
+    /// Counts nothing, for collecting the side-band records of the ring buffer
+    PERF_COUNT_SW_DUMMY = 9,
+}
+
+// Values of perf_event_attr.type
+const PERF_TYPE_HARDWARE: u32 = 0;
+const PERF_TYPE_SOFTWARE: u32 = 1;
+
+// Bits of the perf_event_attr bitfield
+const ATTR_DISABLED: u64 = 1 << 0;
+const ATTR_INHERIT: u64 = 1 << 1;
+const ATTR_PINNED: u64 = 1 << 2;
+const ATTR_EXCLUSIVE: u64 = 1 << 3;
+const ATTR_EXCLUDE_USER: u64 = 1 << 4;
+const ATTR_EXCLUDE_KERNEL: u64 = 1 << 5;
+const ATTR_EXCLUDE_HV: u64 = 1 << 6;
+const ATTR_EXCLUDE_IDLE: u64 = 1 << 7;
+const ATTR_FREQ: u64 = 1 << 10;
+const ATTR_ENABLE_ON_EXEC: u64 = 1 << 12;
+
+bitflags! {
+    /// What the records of samples contain, see
+    /// [`PerfEventAttr::sample_type`](struct.PerfEventAttr.html#method.sample_type)
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct SampleFormat: u64 {
+        /// The instruction pointer
+        const PERF_SAMPLE_IP = 1 << 0;
+        /// The process and thread IDs
+        const PERF_SAMPLE_TID = 1 << 1;
+        /// A timestamp
+        const PERF_SAMPLE_TIME = 1 << 2;
+        /// The address of the access, for events that have one
+        const PERF_SAMPLE_ADDR = 1 << 3;
+        /// The counts, in the layout of the read format
+        const PERF_SAMPLE_READ = 1 << 4;
+        /// The call chain
+        const PERF_SAMPLE_CALLCHAIN = 1 << 5;
+        /// The ID of the event
+        const PERF_SAMPLE_ID = 1 << 6;
+        /// The CPU
+        const PERF_SAMPLE_CPU = 1 << 7;
+        /// The current sampling period
+        const PERF_SAMPLE_PERIOD = 1 << 8;
+        /// The ID of the event that was inherited from
+        const PERF_SAMPLE_STREAM_ID = 1 << 9;
+        /// Raw data specific to the event
+        const PERF_SAMPLE_RAW = 1 << 10;
+    }
+}
+
+bitflags! {
+    /// What reading the file descriptor of an event returns besides the count,
+    /// see [`PerfEventAttr::read_format`](struct.PerfEventAttr.html#method.read_format)
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct ReadFormat: u64 {
+        /// The nanoseconds the event was enabled
+        const PERF_FORMAT_TOTAL_TIME_ENABLED = 1 << 0;
+        /// The nanoseconds the event was counting, which is less than the time
+        /// it was enabled if it shared the counters with other events
+        const PERF_FORMAT_TOTAL_TIME_RUNNING = 1 << 1;
+        /// The ID of the event
+        const PERF_FORMAT_ID = 1 << 2;
+        /// The counts of all the events of the group
+        const PERF_FORMAT_GROUP = 1 << 3;
+    }
+}
+
+bitflags! {
+    /// Flags for [`perf_event_open`](fn.perf_event_open.html)
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct PerfFlags: c_ulong {
+        /// Create the event outside of the group of `group_fd`, only sending
+        /// its samples to the ring buffer of that event
+        const PERF_FLAG_FD_NO_GROUP = 1 << 0;
+        /// Send the samples to the ring buffer of `group_fd`
+        const PERF_FLAG_FD_OUTPUT = 1 << 1;
+        /// `pid` is the file descriptor of a cgroup directory, whose tasks are
+        /// measured
+        const PERF_FLAG_PID_CGROUP = 1 << 2;
+        /// Set the close-on-exec flag on the new file descriptor
+        const PERF_FLAG_FD_CLOEXEC = 1 << 3;
+    }
+}
+
+/// The description of an event, for [`perf_event_open`](fn.perf_event_open.html)
+///
+/// This is the kernel's `perf_event_attr`.  Attributes are created for an event
+/// with one of the constructors, and modified with the other methods.  The
+/// event counts in kernel and user space, isn't inherited by child threads and
+/// starts counting at once, unless changed.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PerfEventAttr {
+    type_: u32,
+    size: u32,
+    config: u64,
+    sample_period: u64,
+    sample_type: u64,
+    read_format: u64,
+    flags: u64,
+    wakeup_events: u32,
+    bp_type: u32,
+    config1: u64,
+    config2: u64,
+    branch_sample_type: u64,
+    sample_regs_user: u64,
+    sample_stack_user: u32,
+    clockid: i32,
+    sample_regs_intr: u64,
+    aux_watermark: u32,
+    sample_max_stack: u16,
+    reserved_2: u16,
+    aux_sample_size: u32,
+    reserved_3: u32,
+    sig_data: u64,
+    config3: u64,
+}
+
+impl PerfEventAttr {
+    /// Attributes of the event `config` of the PMU `type_`.
+    ///
+    /// The types of PMUs other than the generic ones, like the ones for
+    /// uncore or tracepoint events, are listed in
+    /// `/sys/bus/event_source/devices/*/type`.
+    pub fn new(type_: u32, config: u64) -> PerfEventAttr {
+        let mut attr: PerfEventAttr = unsafe { mem::zeroed() };
+        attr.type_ = type_;
+        attr.size = mem::size_of::<PerfEventAttr>() as u32;
+        attr.config = config;
+        attr
+    }
+
+    /// Attributes of a generalized hardware event (`PERF_TYPE_HARDWARE`)
+    pub fn hardware(event: HardwareEvent) -> PerfEventAttr {
+        PerfEventAttr::new(PERF_TYPE_HARDWARE, event as u64)
+    }
+
+    /// Attributes of a software event (`PERF_TYPE_SOFTWARE`)
+    pub fn software(event: SoftwareEvent) -> PerfEventAttr {
+        PerfEventAttr::new(PERF_TYPE_SOFTWARE, event as u64)
+    }
+
+    fn flag(mut self, flag: u64, on: bool) -> PerfEventAttr {
+        if on {
+            self.flags |= flag;
+        } else {
+            self.flags &= !flag;
+        }
+        self
+    }
+
+    /// Turn the event into a sampling event, which records a sample every
+    /// `period` events
+    pub fn sample_period(mut self, period: u64) -> PerfEventAttr {
+        self.sample_period = period;
+        self.flag(ATTR_FREQ, false)
+    }
+
+    /// Turn the event into a sampling event, which adjusts the period to
+    /// record about `freq` samples per second
+    pub fn sample_freq(mut self, freq: u64) -> PerfEventAttr {
+        self.sample_period = freq;
+        self.flag(ATTR_FREQ, true)
+    }
+
+    /// Set what the records of samples contain
+    pub fn sample_type(mut self, sample_type: SampleFormat) -> PerfEventAttr {
+        self.sample_type = sample_type.bits();
+        self
+    }
+
+    /// Set what reading the file descriptor returns besides the count
+    pub fn read_format(mut self, read_format: ReadFormat) -> PerfEventAttr {
+        self.read_format = read_format.bits();
+        self
+    }
+
+    /// Wake up waiters on the file descriptor every `events` samples, instead
+    /// of only when the ring buffer fills
+    pub fn wakeup_events(mut self, events: u32) -> PerfEventAttr {
+        self.wakeup_events = events;
+        self
+    }
+
+    /// Start the event disabled, until it's enabled with
+    /// [`enable`](fn.enable.html) or an `exec`
+    pub fn disabled(self, disabled: bool) -> PerfEventAttr {
+        self.flag(ATTR_DISABLED, disabled)
+    }
+
+    /// Also count the events of the threads created by the measured threads,
+    /// after the event was opened
+    pub fn inherit(self, inherit: bool) -> PerfEventAttr {
+        self.flag(ATTR_INHERIT, inherit)
+    }
+
+    /// Always keep the event on the CPU, instead of sharing the counters with
+    /// other events.  If it can't be, reading it returns end of file.
+    pub fn pinned(self, pinned: bool) -> PerfEventAttr {
+        self.flag(ATTR_PINNED, pinned)
+    }
+
+    /// Only count when no other groups of events use the counters of the CPU
+    pub fn exclusive(self, exclusive: bool) -> PerfEventAttr {
+        self.flag(ATTR_EXCLUSIVE, exclusive)
+    }
+
+    /// Don't count events in user space
+    pub fn exclude_user(self, exclude: bool) -> PerfEventAttr {
+        self.flag(ATTR_EXCLUDE_USER, exclude)
+    }
+
+    /// Don't count events in the kernel
+    pub fn exclude_kernel(self, exclude: bool) -> PerfEventAttr {
+        self.flag(ATTR_EXCLUDE_KERNEL, exclude)
+    }
+
+    /// Don't count events in the hypervisor
+    pub fn exclude_hv(self, exclude: bool) -> PerfEventAttr {
+        self.flag(ATTR_EXCLUDE_HV, exclude)
+    }
+
+    /// Don't count events while the CPU is idle
+    pub fn exclude_idle(self, exclude: bool) -> PerfEventAttr {
+        self.flag(ATTR_EXCLUDE_IDLE, exclude)
+    }
+
+    /// Enable the event when the measured thread calls `exec`
+    pub fn enable_on_exec(self, enable: bool) -> PerfEventAttr {
+        self.flag(ATTR_ENABLE_ON_EXEC, enable)
+    }
+}
+
+/// Create a counter for an event (see
+/// [perf_event_open(2)](http://man7.org/linux/man-pages/man2/perf_event_open.2.html)).
+///
+/// The event is counted for the thread `pid` on the CPU `cpu`.  A `pid` of 0
+/// is the calling thread, and `None` all threads on `cpu`, which requires
+/// `CAP_PERFMON` or `CAP_SYS_ADMIN`.  A `cpu` of `None` counts the thread on
+/// every CPU.  They can't both be `None`.
+///
+/// The counter is put in the group of the counter `group_fd`, or starts a new
+/// one.  The counters of a group are only ever scheduled together, so their
+/// counts can be compared.
+pub fn perf_event_open(attr: &PerfEventAttr, pid: Option<Pid>, cpu: Option<usize>,
+                       group_fd: Option<RawFd>, flags: PerfFlags) -> Result<RawFd> {
+    let pid = pid.map(libc::pid_t::from).unwrap_or(-1);
+    let cpu = cpu.map(|cpu| cpu as c_int).unwrap_or(-1);
+    let res = unsafe {
+        libc::syscall(libc::SYS_perf_event_open, attr as *const PerfEventAttr, pid, cpu,
+                      group_fd.unwrap_or(-1), flags.bits())
+    };
+
+    Errno::result(res).map(|fd| fd as RawFd)
+}
+
+/// Read the count of the event open as `fd`.
+///
+/// This only works if the read format is empty, the default.  Fails with
+/// `ENODATA` if the event is pinned, but couldn't be kept on the CPU.
+pub fn read_count(fd: RawFd) -> Result<u64> {
+    let mut count = 0u64;
+    let len = try!(unistd::read(fd, unsafe {
+        slice::from_raw_parts_mut(&mut count as *mut u64 as *mut u8, mem::size_of::<u64>())
+    }));
+    // Which reads as end of file
+    if len != mem::size_of::<u64>() {
+        return Err(Error::Sys(Errno::ENODATA));
+    }
+    Ok(count)
+}
+
+// Apply the ioctl to the whole group of the event
+const PERF_IOC_FLAG_GROUP: c_int = 1;
+
+mod ioctls {
+    use std::mem;
+
+    ioctl_write_int_bad!(perf_event_ioc_enable, request_code_none!(b'$', 0));
+    ioctl_write_int_bad!(perf_event_ioc_disable, request_code_none!(b'$', 1));
+    ioctl_write_int_bad!(perf_event_ioc_reset, request_code_none!(b'$', 3));
+    ioctl_write_ptr!(perf_event_ioc_period, b'$', 4, u64);
+    // The size in the request is the one of a pointer to the ID
+    ioctl_read_bad!(perf_event_ioc_id, request_code_read!(b'$', 7, mem::size_of::<*mut u64>()), u64);
+}
+
+fn group_flag(group: bool) -> c_int {
+    if group { PERF_IOC_FLAG_GROUP } else { 0 }
+}
+
+/// Start counting the event open as `fd`, or with `group`, every event of its
+/// group (`PERF_EVENT_IOC_ENABLE`).
+pub fn enable(fd: RawFd, group: bool) -> Result<()> {
+    unsafe { ioctls::perf_event_ioc_enable(fd, group_flag(group)) }.map(drop)
+}
+
+/// Stop counting the event open as `fd`, or with `group`, every event of its
+/// group (`PERF_EVENT_IOC_DISABLE`).
+pub fn disable(fd: RawFd, group: bool) -> Result<()> {
+    unsafe { ioctls::perf_event_ioc_disable(fd, group_flag(group)) }.map(drop)
+}
+
+/// Set the count of the event open as `fd`, or with `group`, of every event of
+/// its group, to 0 (`PERF_EVENT_IOC_RESET`).
+pub fn reset(fd: RawFd, group: bool) -> Result<()> {
+    unsafe { ioctls::perf_event_ioc_reset(fd, group_flag(group)) }.map(drop)
+}
+
+/// Change the sampling period, or frequency, of the event open as `fd`
+/// (`PERF_EVENT_IOC_PERIOD`).
+pub fn set_period(fd: RawFd, period: u64) -> Result<()> {
+    unsafe { ioctls::perf_event_ioc_period(fd, &period) }.map(drop)
+}
+
+/// Get the ID of the event open as `fd`, which identifies it in records of the
+/// group's ring buffer (`PERF_EVENT_IOC_ID`).
+pub fn id(fd: RawFd) -> Result<u64> {
+    let mut id = 0;
+    try!(unsafe { ioctls::perf_event_ioc_id(fd, &mut id) });
+    Ok(id)
+}
+
+/// The length to `mmap` the ring buffer of a sampling event with, for
+/// `data_pages` pages of samples.
+///
+/// The kernel requires a power of two of data pages, following one page of
+/// metadata.  With no data pages, only the metadata is mapped, which lets
+/// some CPUs read the count without a system call.  Fails with `EINVAL` if
+/// `data_pages` isn't 0 or a power of two.
+pub fn ring_buffer_len(data_pages: usize) -> Result<usize> {
+    if data_pages != 0 && !data_pages.is_power_of_two() {
+        return Err(Error::invalid_argument());
+    }
+    let page_size = try!(unistd::sysconf(SysconfVar::PAGE_SIZE)).unwrap_or(4096) as usize;
+    Ok((data_pages + 1) * page_size)
+}
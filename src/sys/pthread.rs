@@ -1,4 +1,11 @@
 use libc::{self, pthread_t};
+use errno::Errno;
+
+#[cfg(any(target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos"))]
+pub use self::mutex::PthreadMutex;
 
 pub type Pthread = pthread_t;
 
@@ -11,3 +18,308 @@ pub type Pthread = pthread_t;
 pub fn pthread_self() -> Pthread {
     unsafe { libc::pthread_self() }
 }
+
+/// Get the clock that measures the CPU time consumed by the given thread (see
+/// [`pthread_getcpuclockid(3)`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/pthread_getcpuclockid.html)).
+///
+/// The returned clock can be passed to
+/// [`clock_gettime`](../time/fn.clock_gettime.html) to read the thread's CPU
+/// time from any thread of the process.
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "linux"))]
+pub fn pthread_getcpuclockid(thread: Pthread) -> ::Result<::sys::time::ClockId> {
+    use std::mem;
+    use sys::time::ClockId;
+
+    let mut clk_id: libc::clockid_t = unsafe { mem::zeroed() };
+    let res = unsafe { libc::pthread_getcpuclockid(thread, &mut clk_id) };
+
+    Errno::result_from_code(res).map(|_| ClockId::from_raw(clk_id))
+}
+
+/// Set the name of the calling thread (see
+/// [`pthread_setname_np(3)`](http://man7.org/linux/man-pages/man3/pthread_setname_np.3.html)).
+///
+/// The name is shown by debuggers and by tools like `top` and `ps`.  macOS
+/// and iOS can only name the calling thread, so that is all this does on
+/// every platform.  Names are limited to 15 bytes on Linux and Android, and to
+/// 63 on macOS and iOS; longer names fail with `ERANGE` on Linux.
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos"))]
+pub fn pthread_setname_np<P: ?Sized + ::NixPath>(name: &P) -> ::Result<()> {
+    let res = try!(name.with_nix_path(|cstr| unsafe {
+        cfg_if! {
+            if #[cfg(any(target_os = "ios", target_os = "macos"))] {
+                libc::pthread_setname_np(cstr.as_ptr())
+            } else {
+                libc::pthread_setname_np(libc::pthread_self(), cstr.as_ptr())
+            }
+        }
+    }));
+
+    Errno::result_from_code(res)
+}
+
+/// Get the name of the given thread and store it in the provided buffer,
+/// returning the `CStr` in that buffer on success (see
+/// [`pthread_getname_np(3)`](http://man7.org/linux/man-pages/man3/pthread_getname_np.3.html)).
+///
+/// The buffer should be at least 16 bytes long on Linux, and 64 bytes long
+/// on macOS and iOS, or the call may fail with `ERANGE`.
+///
+/// ```no_run
+/// use nix::sys::pthread::{pthread_getname_np, pthread_self};
+///
+/// let mut buf = [0u8; 64];
+/// let name = pthread_getname_np(pthread_self(), &mut buf).expect("pthread_getname_np failed");
+/// println!("Thread name: {}", name.to_string_lossy());
+/// ```
+#[cfg(any(target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos"))]
+pub fn pthread_getname_np(thread: Pthread, buffer: &mut [u8]) -> ::Result<&::std::ffi::CStr> {
+    use libc::c_char;
+    use std::ffi::CStr;
+
+    if buffer.is_empty() {
+        return Err(::Error::Sys(Errno::ERANGE));
+    }
+    let res = unsafe {
+        libc::pthread_getname_np(thread,
+                                 buffer.as_mut_ptr() as *mut c_char,
+                                 buffer.len() as libc::size_t)
+    };
+
+    try!(Errno::result_from_code(res));
+
+    let len = buffer.len();
+    buffer[len - 1] = 0; // ensure always null-terminated
+    Ok(unsafe { CStr::from_ptr(buffer.as_ptr() as *const c_char) })
+}
+
+/// Register handlers to be run around calls to `fork` (see
+/// [`pthread_atfork(3)`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/pthread_atfork.html)).
+///
+/// `prepare` runs in the parent before the fork, then `parent` runs in the
+/// parent and `child` in the child after it.  Libraries holding locks or
+/// descriptors use them to take the locks before the fork, so that no other
+/// thread holds them while the child is created, and to release them or
+/// reinitialize their state afterwards.
+///
+/// Handlers can't be unregistered.  When several are registered, the
+/// `prepare` handlers run in the reverse order of registration, and the
+/// `parent` and `child` ones in the order of registration.  A `child` handler
+/// is subject to the same restrictions as the child of
+/// [`fork`](../../unistd/fn.fork.html): in a multithreaded program, it may
+/// only call async-signal-safe functions.
+pub fn pthread_atfork(prepare: Option<extern fn()>,
+                      parent: Option<extern fn()>,
+                      child: Option<extern fn()>) -> ::Result<()> {
+    let res = unsafe {
+        libc::pthread_atfork(prepare.map(|f| f as unsafe extern fn()),
+                             parent.map(|f| f as unsafe extern fn()),
+                             child.map(|f| f as unsafe extern fn()))
+    };
+
+    Errno::result_from_code(res)
+}
+
+/// Set the CPU affinity mask of the given thread (see
+/// [`pthread_setaffinity_np(3)`](http://man7.org/linux/man-pages/man3/pthread_setaffinity_np.3.html)).
+///
+/// The thread will only run on the CPUs in `cpuset`.  Unlike
+/// [`sched_setaffinity`](../../sched/fn.sched_setaffinity.html), this takes a
+/// `Pthread`, so it works for any thread of the process without knowing its
+/// kernel thread ID.
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub fn pthread_setaffinity_np(thread: Pthread, cpuset: &::sched::DynCpuSet) -> ::Result<()> {
+    let res = unsafe { libc::pthread_setaffinity_np(thread, cpuset.size(), cpuset.as_ptr()) };
+
+    Errno::result_from_code(res)
+}
+
+/// Get the CPU affinity mask of the given thread (see
+/// [`pthread_getaffinity_np(3)`](http://man7.org/linux/man-pages/man3/pthread_getaffinity_np.3.html)).
+///
+/// The set has room for every CPU of the host, which may be more than 1024.
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub fn pthread_getaffinity_np(thread: Pthread) -> ::Result<::sched::DynCpuSet> {
+    use std::mem;
+    use sched::DynCpuSet;
+
+    let mut cpus = 8 * mem::size_of::<libc::cpu_set_t>();
+    loop {
+        let mut cpuset = DynCpuSet::new(cpus);
+        let res = unsafe {
+            libc::pthread_getaffinity_np(thread, cpuset.size(), cpuset.as_mut_ptr())
+        };
+        match Errno::result_from_code(res) {
+            // The set is smaller than the kernel's, so retry with twice the room
+            Err(::Error::Sys(Errno::EINVAL)) => cpus *= 2,
+            res => return res.map(|_| cpuset),
+        }
+    }
+}
+
+#[cfg(any(target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos"))]
+mod mutex {
+    use libc;
+    use std::cell::UnsafeCell;
+    use std::mem;
+    use errno::Errno;
+    use {Error, Result};
+
+    /// A mutex, which lives in memory provided by the caller (see
+    /// [`pthread_mutex_init(3)`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/pthread_mutex_init.html)).
+    ///
+    /// Placed in memory shared between processes, such as a `MAP_SHARED` mapping
+    /// or a System V shared memory segment, and initialized with `pshared`, it
+    /// synchronizes those processes, like a
+    /// [`UnnamedSemaphore`](../../semaphore/struct.UnnamedSemaphore.html)
+    /// initialized to 1.  Unlike the semaphore, only the thread that locked the
+    /// mutex may unlock it, and a process that exits while holding it leaves it
+    /// locked.
+    ///
+    /// It must not be moved after it's initialized, so it's only ever accessed by
+    /// reference.
+    ///
+    /// # Examples
+    ///
+    /// Share a mutex with a child process:
+    ///
+    /// ```
+    /// # extern crate libc;
+    /// # extern crate nix;
+    /// # use std::ptr;
+    /// # use nix::sys::mman::*;
+    /// # use nix::sys::pthread::PthreadMutex;
+    /// # use nix::sys::wait::waitpid;
+    /// # use nix::unistd::{fork, ForkResult};
+    /// # fn main() {
+    /// let region = unsafe {
+    ///     mmap(ptr::null_mut(), PthreadMutex::size(), ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+    ///          MapFlags::MAP_SHARED | MapFlags::MAP_ANONYMOUS, -1, 0).unwrap()
+    /// };
+    /// let mutex = unsafe { PthreadMutex::init(region, true) }.unwrap();
+    ///
+    /// match fork().unwrap() {
+    ///     ForkResult::Child => {
+    ///         mutex.lock().unwrap();
+    ///         // ... critical section ...
+    ///         mutex.unlock().unwrap();
+    ///         unsafe { libc::_exit(0) };
+    ///     }
+    ///     ForkResult::Parent { child } => {
+    ///         mutex.lock().unwrap();
+    ///         // ... critical section ...
+    ///         mutex.unlock().unwrap();
+    ///         waitpid(child, None).unwrap();
+    ///     }
+    /// }
+    ///
+    /// unsafe {
+    ///     mutex.destroy().unwrap();
+    ///     munmap(region, PthreadMutex::size()).unwrap();
+    /// }
+    /// # }
+    /// ```
+    #[repr(C)]
+    pub struct PthreadMutex(UnsafeCell<libc::pthread_mutex_t>);
+
+    unsafe impl Send for PthreadMutex {}
+
+    unsafe impl Sync for PthreadMutex {}
+
+    impl PthreadMutex {
+        /// The number of bytes a mutex occupies in memory
+        pub fn size() -> usize {
+            mem::size_of::<PthreadMutex>()
+        }
+
+        /// The alignment a mutex requires in memory
+        pub fn align() -> usize {
+            mem::align_of::<PthreadMutex>()
+        }
+
+        /// Initialize an unlocked mutex at `addr`.
+        ///
+        /// With `pshared`, the mutex is initialized with the
+        /// `PTHREAD_PROCESS_SHARED` attribute, so it can be used by every process
+        /// that can access the memory; otherwise only by the threads of this
+        /// process.  Fails with `EINVAL` if `addr` isn't suitably aligned.
+        ///
+        /// # Safety
+        ///
+        /// `addr` must point to [`size`](#method.size) writable bytes that stay
+        /// valid for `'a`, and no mutex must already be in use there.
+        pub unsafe fn init<'a>(addr: *mut libc::c_void, pshared: bool)
+                               -> Result<&'a PthreadMutex> {
+            if addr as usize % PthreadMutex::align() != 0 {
+                return Err(Error::invalid_argument());
+            }
+            let mutex = addr as *mut PthreadMutex;
+
+            let mut attr: libc::pthread_mutexattr_t = mem::zeroed();
+            try!(Errno::result_from_code(libc::pthread_mutexattr_init(&mut attr)));
+            let mut res = 0;
+            if pshared {
+                res = libc::pthread_mutexattr_setpshared(&mut attr, libc::PTHREAD_PROCESS_SHARED);
+            }
+            if res == 0 {
+                res = libc::pthread_mutex_init((*mutex).0.get(), &attr);
+            }
+            libc::pthread_mutexattr_destroy(&mut attr);
+
+            Errno::result_from_code(res).map(|_| &*mutex)
+        }
+
+        /// Get a reference to a mutex that was already initialized at `addr`,
+        /// for instance by another process.
+        ///
+        /// # Safety
+        ///
+        /// `addr` must point to a mutex initialized with [`init`](#method.init),
+        /// which stays valid for `'a`.
+        pub unsafe fn from_ptr<'a>(addr: *mut libc::c_void) -> &'a PthreadMutex {
+            &*(addr as *const PthreadMutex)
+        }
+
+        /// Destroy the mutex, which must be unlocked (see
+        /// [`pthread_mutex_destroy(3)`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/pthread_mutex_destroy.html)).
+        ///
+        /// # Safety
+        ///
+        /// No thread or process may use the mutex afterwards, unless it's
+        /// initialized again.
+        pub unsafe fn destroy(&self) -> Result<()> {
+            Errno::result_from_code(libc::pthread_mutex_destroy(self.0.get()))
+        }
+
+        /// Lock the mutex, blocking while another thread holds it (see
+        /// [`pthread_mutex_lock(3)`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/pthread_mutex_lock.html)).
+        pub fn lock(&self) -> Result<()> {
+            Errno::result_from_code(unsafe { libc::pthread_mutex_lock(self.0.get()) })
+        }
+
+        /// Lock the mutex, or fail with `EBUSY` if another thread holds it (see
+        /// [`pthread_mutex_trylock(3)`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/pthread_mutex_trylock.html)).
+        pub fn try_lock(&self) -> Result<()> {
+            Errno::result_from_code(unsafe { libc::pthread_mutex_trylock(self.0.get()) })
+        }
+
+        /// Unlock the mutex, which the calling thread must hold (see
+        /// [`pthread_mutex_unlock(3)`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/pthread_mutex_unlock.html)).
+        pub fn unlock(&self) -> Result<()> {
+            Errno::result_from_code(unsafe { libc::pthread_mutex_unlock(self.0.get()) })
+        }
+    }
+}
@@ -1,11 +1,11 @@
 //! For detailed description of the ptrace requests, consult `man ptrace`.
 
-use std::{mem, ptr};
+use std::{cmp, mem, ptr};
 use {Error, Result};
 use errno::Errno;
 use libc::{self, c_void, c_long, siginfo_t};
 use ::unistd::Pid;
-use sys::signal::Signal;
+use sys::signal::{SigInfo, Signal};
 
 
 cfg_if! {
@@ -34,28 +34,28 @@ libc_enum!{
         PTRACE_CONT,
         PTRACE_KILL,
         PTRACE_SINGLESTEP,
-        #[cfg(all(any(target_env = "musl", target_arch ="x86_64", target_arch = "s390x"), not(target_os = "android")))]
+        #[cfg(all(any(target_env = "musl", target_arch = "x86", target_arch ="x86_64", target_arch = "s390x"), not(target_os = "android")))]
         PTRACE_GETREGS,
-        #[cfg(all(any(target_env = "musl", target_arch ="x86_64", target_arch = "s390x"), not(target_os = "android")))]
+        #[cfg(all(any(target_env = "musl", target_arch = "x86", target_arch ="x86_64", target_arch = "s390x"), not(target_os = "android")))]
         PTRACE_SETREGS,
-        #[cfg(all(any(target_env = "musl", target_arch ="x86_64", target_arch = "s390x"), not(target_os = "android")))]
+        #[cfg(all(any(target_env = "musl", target_arch = "x86", target_arch ="x86_64", target_arch = "s390x"), not(target_os = "android")))]
         PTRACE_GETFPREGS,
-        #[cfg(all(any(target_env = "musl", target_arch ="x86_64", target_arch = "s390x"), not(target_os = "android")))]
+        #[cfg(all(any(target_env = "musl", target_arch = "x86", target_arch ="x86_64", target_arch = "s390x"), not(target_os = "android")))]
         PTRACE_SETFPREGS,
         PTRACE_ATTACH,
         PTRACE_DETACH,
-        #[cfg(all(any(target_env = "musl", target_arch ="x86_64"), not(target_os = "android")))]
+        #[cfg(all(any(target_env = "musl", target_arch = "x86", target_arch ="x86_64"), not(target_os = "android")))]
         PTRACE_GETFPXREGS,
-        #[cfg(all(any(target_env = "musl", target_arch ="x86_64"), not(target_os = "android")))]
+        #[cfg(all(any(target_env = "musl", target_arch = "x86", target_arch ="x86_64"), not(target_os = "android")))]
         PTRACE_SETFPXREGS,
         PTRACE_SYSCALL,
         PTRACE_SETOPTIONS,
         PTRACE_GETEVENTMSG,
         PTRACE_GETSIGINFO,
         PTRACE_SETSIGINFO,
-        #[cfg(all(any(target_env = "musl", target_arch ="x86_64", target_arch = "s390x"), not(target_os = "android")))]
+        #[cfg(all(any(target_env = "musl", target_env = "gnu"), not(target_os = "android")))]
         PTRACE_GETREGSET,
-        #[cfg(all(any(target_env = "musl", target_arch ="x86_64", target_arch = "s390x"), not(target_os = "android")))]
+        #[cfg(all(any(target_env = "musl", target_env = "gnu"), not(target_os = "android")))]
         PTRACE_SETREGSET,
         #[cfg(not(any(target_os = "android", target_arch = "mips", target_arch = "mips64")))]
         PTRACE_SEIZE,
@@ -89,7 +89,9 @@ libc_enum!{
         PTRACE_EVENT_EXIT,
         /// STop triggered by a seccomp rule on a tracee.
         PTRACE_EVENT_SECCOMP,
-        // PTRACE_EVENT_STOP not provided by libc because it's defined in glibc 2.26
+        /// Stop of a tracee attached with `seize`, on `interrupt` or when it
+        /// enters a group-stop.
+        PTRACE_EVENT_STOP,
     }
 }
 
@@ -171,6 +173,510 @@ unsafe fn ptrace_other(request: Request, pid: Pid, addr: *mut c_void, data: *mut
     Errno::result(libc::ptrace(request as RequestType, libc::pid_t::from(pid), addr, data)).map(|_| 0)
 }
 
+/// The general-purpose registers of a tracee, as laid out by the kernel for the
+/// architecture.
+#[cfg(all(target_os = "linux",
+          any(all(target_arch = "x86", target_env = "gnu"),
+              all(target_arch = "x86_64", any(target_env = "gnu", target_env = "musl")),
+              all(target_arch = "aarch64", any(target_env = "gnu", target_env = "musl")))))]
+pub use libc::user_regs_struct;
+
+/// Get the general-purpose registers of a stopped tracee, as with
+/// `ptrace(PTRACE_GETREGS, ...)`.
+#[cfg(all(target_os = "linux",
+          any(all(target_arch = "x86", target_env = "gnu"),
+              all(target_arch = "x86_64", any(target_env = "gnu", target_env = "musl")))))]
+pub fn getregs(pid: Pid) -> Result<user_regs_struct> {
+    ptrace_get_data::<user_regs_struct>(Request::PTRACE_GETREGS, pid)
+}
+
+/// Set the general-purpose registers of a stopped tracee, as with
+/// `ptrace(PTRACE_SETREGS, ...)`.
+#[cfg(all(target_os = "linux",
+          any(all(target_arch = "x86", target_env = "gnu"),
+              all(target_arch = "x86_64", any(target_env = "gnu", target_env = "musl")))))]
+pub fn setregs(pid: Pid, regs: user_regs_struct) -> Result<()> {
+    let res = unsafe {
+        libc::ptrace(Request::PTRACE_SETREGS as RequestType,
+                     libc::pid_t::from(pid),
+                     ptr::null_mut::<c_void>(),
+                     &regs as *const _ as *const c_void)
+    };
+    Errno::result(res).map(drop)
+}
+
+/// Get the general-purpose registers of a stopped tracee.
+///
+/// This architecture lacks `PTRACE_GETREGS`, so they're read as the
+/// `NT_PRSTATUS` register set with [`getregset`](fn.getregset.html).
+#[cfg(all(target_os = "linux", target_arch = "aarch64",
+          any(target_env = "gnu", target_env = "musl")))]
+pub fn getregs(pid: Pid) -> Result<user_regs_struct> {
+    let mut regs: user_regs_struct = unsafe { mem::uninitialized() };
+    unsafe {
+        ptrace_regset(Request::PTRACE_GETREGSET, pid, RegisterSet::NT_PRSTATUS,
+                      &mut regs as *mut _ as *mut c_void, mem::size_of::<user_regs_struct>())
+    }.map(|_| regs)
+}
+
+/// Set the general-purpose registers of a stopped tracee.
+///
+/// This architecture lacks `PTRACE_SETREGS`, so they're written as the
+/// `NT_PRSTATUS` register set with [`setregset`](fn.setregset.html).
+#[cfg(all(target_os = "linux", target_arch = "aarch64",
+          any(target_env = "gnu", target_env = "musl")))]
+pub fn setregs(pid: Pid, mut regs: user_regs_struct) -> Result<()> {
+    unsafe {
+        ptrace_regset(Request::PTRACE_SETREGSET, pid, RegisterSet::NT_PRSTATUS,
+                      &mut regs as *mut _ as *mut c_void, mem::size_of::<user_regs_struct>())
+    }.map(drop)
+}
+
+/// Identifies a register set for [`getregset`](fn.getregset.html) and
+/// [`setregset`](fn.setregset.html), by the type of the ELF core dump note that
+/// holds it
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RegisterSet(libc::c_int);
+
+// Only the generic note types are in libc, the others are from linux/elf.h
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+impl RegisterSet {
+    /// The general-purpose registers, as a `user_regs_struct`
+    pub const NT_PRSTATUS: RegisterSet = RegisterSet(libc::NT_PRSTATUS);
+    /// The floating-point registers
+    pub const NT_PRFPREG: RegisterSet = RegisterSet(libc::NT_PRFPREG);
+    /// The thread-local storage descriptors
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub const NT_386_TLS: RegisterSet = RegisterSet(0x200);
+    /// The extended processor state saved by `XSAVE`, including the AVX registers
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub const NT_X86_XSTATE: RegisterSet = RegisterSet(0x202);
+    /// The VFP registers
+    #[cfg(target_arch = "arm")]
+    pub const NT_ARM_VFP: RegisterSet = RegisterSet(0x400);
+    /// The thread pointer
+    #[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
+    pub const NT_ARM_TLS: RegisterSet = RegisterSet(0x401);
+    /// The hardware breakpoint registers
+    #[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
+    pub const NT_ARM_HW_BREAK: RegisterSet = RegisterSet(0x402);
+    /// The hardware watchpoint registers
+    #[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
+    pub const NT_ARM_HW_WATCH: RegisterSet = RegisterSet(0x403);
+    /// The number of the system call the tracee is stopped in
+    #[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
+    pub const NT_ARM_SYSTEM_CALL: RegisterSet = RegisterSet(0x404);
+    /// The Scalable Vector Extension registers
+    #[cfg(target_arch = "aarch64")]
+    pub const NT_ARM_SVE: RegisterSet = RegisterSet(0x405);
+    /// The pointer authentication code masks
+    #[cfg(target_arch = "aarch64")]
+    pub const NT_ARM_PAC_MASK: RegisterSet = RegisterSet(0x406);
+
+    /// Creates `RegisterSet` from a raw note type.
+    pub fn from_raw(regset: libc::c_int) -> RegisterSet {
+        RegisterSet(regset)
+    }
+
+    /// Get the raw note type wrapped by `self`.
+    pub fn as_raw(&self) -> libc::c_int {
+        self.0
+    }
+}
+
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+impl From<RegisterSet> for libc::c_int {
+    fn from(regset: RegisterSet) -> Self {
+        regset.0
+    }
+}
+
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+unsafe fn ptrace_regset(request: Request, pid: Pid, regset: RegisterSet, buf: *mut c_void,
+                        len: usize) -> Result<usize> {
+    let mut iov = libc::iovec { iov_base: buf, iov_len: len };
+    let res = libc::ptrace(request as RequestType,
+                           libc::pid_t::from(pid),
+                           regset.0 as usize as *mut c_void,
+                           &mut iov as *mut _ as *mut c_void);
+    // The kernel shrinks the length to the size of the register set
+    Errno::result(res).map(|_| iov.iov_len)
+}
+
+/// Read a register set of a stopped tracee into `buf`, as with
+/// `ptrace(PTRACE_GETREGSET, ...)`.
+///
+/// Returns the size of the register set, which is written to the start of
+/// `buf`; a larger set is truncated to the length of `buf`.  The layout of each
+/// set is specific to the architecture.
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+pub fn getregset(pid: Pid, regset: RegisterSet, buf: &mut [u8]) -> Result<usize> {
+    unsafe {
+        ptrace_regset(Request::PTRACE_GETREGSET, pid, regset, buf.as_mut_ptr() as *mut c_void,
+                      buf.len())
+    }
+}
+
+/// Write a register set of a stopped tracee from `buf`, as with
+/// `ptrace(PTRACE_SETREGSET, ...)`.
+///
+/// Returns the number of bytes used, which is less than the length of `buf` if
+/// the register set is smaller.  Some sets can be partially written.
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+pub fn setregset(pid: Pid, regset: RegisterSet, buf: &[u8]) -> Result<usize> {
+    // The kernel doesn't modify the buffer
+    unsafe {
+        ptrace_regset(Request::PTRACE_SETREGSET, pid, regset, buf.as_ptr() as *mut c_void,
+                      buf.len())
+    }
+}
+
+/// The floating-point registers of a tracee, as laid out by the kernel for
+/// the architecture.
+#[cfg(all(target_os = "linux",
+          any(all(target_arch = "x86", target_env = "gnu"),
+              all(target_arch = "x86_64", any(target_env = "gnu", target_env = "musl")))))]
+pub use libc::user_fpregs_struct;
+
+/// The floating-point and SIMD registers of a tracee, as laid out by the kernel.
+#[cfg(all(target_os = "linux", target_arch = "aarch64",
+          any(target_env = "gnu", target_env = "musl")))]
+pub use libc::user_fpsimd_struct;
+
+/// The x87, MMX and SSE registers of a 32-bit tracee, as laid out by `FXSAVE`.
+#[cfg(all(target_os = "linux", target_arch = "x86", target_env = "gnu"))]
+pub use libc::user_fpxregs_struct;
+
+/// Get the floating-point registers of a stopped tracee, as with
+/// `ptrace(PTRACE_GETFPREGS, ...)`.
+///
+/// On x86_64, these include the SSE registers; on x86, only the x87 ones, and
+/// [`getfpxregs`](fn.getfpxregs.html) gets the SSE ones.  The AVX registers
+/// are only part of [`getxstate`](fn.getxstate.html).
+#[cfg(all(target_os = "linux",
+          any(all(target_arch = "x86", target_env = "gnu"),
+              all(target_arch = "x86_64", any(target_env = "gnu", target_env = "musl")))))]
+pub fn getfpregs(pid: Pid) -> Result<user_fpregs_struct> {
+    ptrace_get_data::<user_fpregs_struct>(Request::PTRACE_GETFPREGS, pid)
+}
+
+/// Set the floating-point registers of a stopped tracee, as with
+/// `ptrace(PTRACE_SETFPREGS, ...)`.
+#[cfg(all(target_os = "linux",
+          any(all(target_arch = "x86", target_env = "gnu"),
+              all(target_arch = "x86_64", any(target_env = "gnu", target_env = "musl")))))]
+pub fn setfpregs(pid: Pid, regs: user_fpregs_struct) -> Result<()> {
+    let res = unsafe {
+        libc::ptrace(Request::PTRACE_SETFPREGS as RequestType,
+                     libc::pid_t::from(pid),
+                     ptr::null_mut::<c_void>(),
+                     &regs as *const _ as *const c_void)
+    };
+    Errno::result(res).map(drop)
+}
+
+/// Get the floating-point and SIMD registers of a stopped tracee.
+///
+/// This architecture lacks `PTRACE_GETFPREGS`, so they're read as the
+/// `NT_PRFPREG` register set with [`getregset`](fn.getregset.html).
+#[cfg(all(target_os = "linux", target_arch = "aarch64",
+          any(target_env = "gnu", target_env = "musl")))]
+pub fn getfpregs(pid: Pid) -> Result<user_fpsimd_struct> {
+    let mut regs: user_fpsimd_struct = unsafe { mem::uninitialized() };
+    unsafe {
+        ptrace_regset(Request::PTRACE_GETREGSET, pid, RegisterSet::NT_PRFPREG,
+                      &mut regs as *mut _ as *mut c_void, mem::size_of::<user_fpsimd_struct>())
+    }.map(|_| regs)
+}
+
+/// Set the floating-point and SIMD registers of a stopped tracee.
+///
+/// This architecture lacks `PTRACE_SETFPREGS`, so they're written as the
+/// `NT_PRFPREG` register set with [`setregset`](fn.setregset.html).
+#[cfg(all(target_os = "linux", target_arch = "aarch64",
+          any(target_env = "gnu", target_env = "musl")))]
+pub fn setfpregs(pid: Pid, mut regs: user_fpsimd_struct) -> Result<()> {
+    unsafe {
+        ptrace_regset(Request::PTRACE_SETREGSET, pid, RegisterSet::NT_PRFPREG,
+                      &mut regs as *mut _ as *mut c_void, mem::size_of::<user_fpsimd_struct>())
+    }.map(drop)
+}
+
+/// Get the x87, MMX and SSE registers of a stopped 32-bit tracee, as with
+/// `ptrace(PTRACE_GETFPXREGS, ...)`.
+#[cfg(all(target_os = "linux", target_arch = "x86", target_env = "gnu"))]
+pub fn getfpxregs(pid: Pid) -> Result<user_fpxregs_struct> {
+    ptrace_get_data::<user_fpxregs_struct>(Request::PTRACE_GETFPXREGS, pid)
+}
+
+/// Set the x87, MMX and SSE registers of a stopped 32-bit tracee, as with
+/// `ptrace(PTRACE_SETFPXREGS, ...)`.
+#[cfg(all(target_os = "linux", target_arch = "x86", target_env = "gnu"))]
+pub fn setfpxregs(pid: Pid, regs: user_fpxregs_struct) -> Result<()> {
+    let res = unsafe {
+        libc::ptrace(Request::PTRACE_SETFPXREGS as RequestType,
+                     libc::pid_t::from(pid),
+                     ptr::null_mut::<c_void>(),
+                     &regs as *const _ as *const c_void)
+    };
+    Errno::result(res).map(drop)
+}
+
+/// Get the full extended processor state of a stopped tracee, as saved by
+/// `XSAVE`, from the `NT_X86_XSTATE` register set.
+///
+/// Its size depends on the features of the CPU.  It starts with the legacy
+/// `FXSAVE` area of 512 bytes, followed by the `XSAVE` header, whose first
+/// 8 bytes tell which components are present, and by the components, such as
+/// the upper halves of the AVX registers at offset 576.  Bytes 464 to 471 hold
+/// the `XCR0` value the kernel uses, which tells which components the CPU
+/// supports.
+#[cfg(all(feature = "std",
+          target_os = "linux",
+          any(all(target_arch = "x86", target_env = "gnu"),
+              all(target_arch = "x86_64", any(target_env = "gnu", target_env = "musl")))))]
+pub fn getxstate(pid: Pid) -> Result<Vec<u8>> {
+    // The kernel truncates the state to the buffer, so grow it until it fits
+    let mut buf = vec![0u8; 4096];
+    loop {
+        let len = try!(getregset(pid, RegisterSet::NT_X86_XSTATE, &mut buf));
+        if len < buf.len() {
+            buf.truncate(len);
+            return Ok(buf);
+        }
+        let len = buf.len() * 2;
+        buf.resize(len, 0);
+    }
+}
+
+/// Set the extended processor state of a stopped tracee, as obtained from
+/// [`getxstate`](fn.getxstate.html), in the `NT_X86_XSTATE` register set.
+///
+/// Fails with `EINVAL` if the `XSAVE` header is invalid, for instance with
+/// components the kernel doesn't support.
+#[cfg(all(target_os = "linux",
+          any(all(target_arch = "x86", target_env = "gnu"),
+              all(target_arch = "x86_64", any(target_env = "gnu", target_env = "musl")))))]
+pub fn setxstate(pid: Pid, xstate: &[u8]) -> Result<()> {
+    setregset(pid, RegisterSet::NT_X86_XSTATE, xstate).map(drop)
+}
+
+// Not in libc, from linux/ptrace.h
+#[cfg(target_os = "linux")]
+const PTRACE_GET_SYSCALL_INFO: RequestType = 0x420e;
+#[cfg(target_os = "linux")]
+const PTRACE_SYSCALL_INFO_NONE: u8 = 0;
+#[cfg(target_os = "linux")]
+const PTRACE_SYSCALL_INFO_ENTRY: u8 = 1;
+#[cfg(target_os = "linux")]
+const PTRACE_SYSCALL_INFO_EXIT: u8 = 2;
+#[cfg(target_os = "linux")]
+const PTRACE_SYSCALL_INFO_SECCOMP: u8 = 3;
+
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct SyscallInfoEntry {
+    nr: u64,
+    args: [u64; 6],
+}
+
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct SyscallInfoExit {
+    rval: i64,
+    is_error: u8,
+}
+
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct SyscallInfoSeccomp {
+    nr: u64,
+    args: [u64; 6],
+    ret_data: u32,
+}
+
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+union SyscallInfoData {
+    entry: SyscallInfoEntry,
+    exit: SyscallInfoExit,
+    seccomp: SyscallInfoSeccomp,
+}
+
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct RawSyscallInfo {
+    op: u8,
+    pad: [u8; 3],
+    arch: u32,
+    instruction_pointer: u64,
+    stack_pointer: u64,
+    data: SyscallInfoData,
+}
+
+/// The system call a tracee is stopped at, as returned by
+/// [`get_syscall_info`](fn.get_syscall_info.html)
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SyscallInfo {
+    /// The `AUDIT_ARCH_*` value identifying the system call convention, which
+    /// differs for instance for 32-bit tracees on a 64-bit kernel
+    pub arch: u32,
+    /// The instruction pointer of the tracee
+    pub instruction_pointer: u64,
+    /// The stack pointer of the tracee
+    pub stack_pointer: u64,
+    /// Why the tracee is stopped
+    pub stop: SyscallStop,
+}
+
+/// The kind of stop described by a [`SyscallInfo`](struct.SyscallInfo.html)
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SyscallStop {
+    /// The tracee isn't stopped at a system call
+    None,
+    /// The tracee is about to execute a system call
+    Entry {
+        /// The number of the system call
+        nr: u64,
+        /// Its arguments
+        args: [u64; 6],
+    },
+    /// The tracee has executed a system call
+    Exit {
+        /// The return value, or the negated error number
+        rval: i64,
+        /// Whether the system call failed
+        is_error: bool,
+    },
+    /// A seccomp filter has returned `SECCOMP_RET_TRACE` for a system call
+    Seccomp {
+        /// The number of the system call
+        nr: u64,
+        /// Its arguments
+        args: [u64; 6],
+        /// The data part of the value returned by the filter
+        ret_data: u32,
+    },
+}
+
+/// Get the system call a stopped tracee has entered or left, as with
+/// `ptrace(PTRACE_GET_SYSCALL_INFO, ...)`
+///
+/// This works on every architecture, unlike decoding the registers.  Entry and
+/// exit stops are only identified with `PTRACE_O_TRACESYSGOOD`, and seccomp
+/// stops with `PTRACE_O_TRACESECCOMP`.  Requires Linux 5.3, and fails with `EIO`
+/// on older kernels.
+#[cfg(target_os = "linux")]
+pub fn get_syscall_info(pid: Pid) -> Result<SyscallInfo> {
+    let mut info: RawSyscallInfo = unsafe { mem::zeroed() };
+    let res = unsafe {
+        libc::ptrace(PTRACE_GET_SYSCALL_INFO,
+                     libc::pid_t::from(pid),
+                     mem::size_of::<RawSyscallInfo>() as *mut c_void,
+                     &mut info as *mut _ as *mut c_void)
+    };
+    try!(Errno::result(res));
+
+    let stop = unsafe {
+        match info.op {
+            PTRACE_SYSCALL_INFO_NONE => SyscallStop::None,
+            PTRACE_SYSCALL_INFO_ENTRY => SyscallStop::Entry {
+                nr: info.data.entry.nr,
+                args: info.data.entry.args,
+            },
+            PTRACE_SYSCALL_INFO_EXIT => SyscallStop::Exit {
+                rval: info.data.exit.rval,
+                is_error: info.data.exit.is_error != 0,
+            },
+            PTRACE_SYSCALL_INFO_SECCOMP => SyscallStop::Seccomp {
+                nr: info.data.seccomp.nr,
+                args: info.data.seccomp.args,
+                ret_data: info.data.seccomp.ret_data,
+            },
+            _ => return Err(Error::UnsupportedOperation),
+        }
+    };
+    Ok(SyscallInfo {
+        arch: info.arch,
+        instruction_pointer: info.instruction_pointer,
+        stack_pointer: info.stack_pointer,
+        stop: stop,
+    })
+}
+
+// Not in libc, from linux/ptrace.h
+const PTRACE_SECCOMP_GET_FILTER: RequestType = 0x420c;
+const PTRACE_SECCOMP_GET_METADATA: RequestType = 0x420d;
+
+/// A classic BPF instruction of a seccomp filter
+pub use libc::sock_filter;
+
+/// Get the instructions of a seccomp filter installed in a stopped tracee, as
+/// with `ptrace(PTRACE_SECCOMP_GET_FILTER, ...)`
+///
+/// Filters are numbered from 0, the most recently installed one, which the
+/// kernel runs first; an `index` beyond the oldest one fails with `ENOENT`.
+/// Filters installed before the tracee forked are shared with its parent.
+/// Fails with `EINVAL` if the tracee has no filter.  Requires `CAP_SYS_ADMIN`,
+/// and fails with `EIO` unless the kernel is built with
+/// `CONFIG_CHECKPOINT_RESTORE`.
+#[cfg(feature = "std")]
+pub fn seccomp_get_filter(pid: Pid, index: usize) -> Result<Vec<sock_filter>> {
+    // Passing no buffer returns the number of instructions.  Installed filters
+    // can't change, so the second call copies all of them.
+    let len = try!(unsafe { ptrace_seccomp_get_filter(pid, index, ptr::null_mut()) });
+    let mut filter = Vec::with_capacity(len);
+    let len = try!(unsafe { ptrace_seccomp_get_filter(pid, index, filter.as_mut_ptr()) });
+    unsafe { filter.set_len(len) };
+    Ok(filter)
+}
+
+unsafe fn ptrace_seccomp_get_filter(pid: Pid, index: usize, buf: *mut sock_filter)
+                                    -> Result<usize> {
+    let res = libc::ptrace(PTRACE_SECCOMP_GET_FILTER,
+                           libc::pid_t::from(pid),
+                           index as *mut c_void,
+                           buf as *mut c_void);
+    Errno::result(res).map(|r| r as usize)
+}
+
+/// The properties of a seccomp filter, as returned by
+/// [`seccomp_get_metadata`](fn.seccomp_get_metadata.html)
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SeccompMetadata {
+    /// The index of the filter, as passed to
+    /// [`seccomp_get_filter`](fn.seccomp_get_filter.html)
+    pub filter_off: u64,
+    /// The `SECCOMP_FILTER_FLAG_*` flags the filter was installed with that
+    /// change its behavior, currently only `SECCOMP_FILTER_FLAG_LOG`
+    pub flags: u64,
+}
+
+/// Get the properties of a seccomp filter installed in a stopped tracee, as
+/// with `ptrace(PTRACE_SECCOMP_GET_METADATA, ...)`
+///
+/// Filters are numbered as for [`seccomp_get_filter`](fn.seccomp_get_filter.html),
+/// with the same requirements.  Requires Linux 4.16.
+pub fn seccomp_get_metadata(pid: Pid, index: usize) -> Result<SeccompMetadata> {
+    let mut metadata = SeccompMetadata { filter_off: index as u64, flags: 0 };
+    let res = unsafe {
+        libc::ptrace(PTRACE_SECCOMP_GET_METADATA,
+                     libc::pid_t::from(pid),
+                     mem::size_of::<SeccompMetadata>() as *mut c_void,
+                     &mut metadata as *mut _ as *mut c_void)
+    };
+    Errno::result(res).map(|_| metadata)
+}
+
 /// Set options, as with `ptrace(PTRACE_SETOPTIONS,...)`.
 pub fn setoptions(pid: Pid, options: Options) -> Result<()> {
     use std::ptr;
@@ -189,19 +695,28 @@ pub fn getevent(pid: Pid) -> Result<c_long> {
     ptrace_get_data::<c_long>(Request::PTRACE_GETEVENTMSG, pid)
 }
 
-/// Get siginfo as with `ptrace(PTRACE_GETSIGINFO,...)`
-pub fn getsiginfo(pid: Pid) -> Result<siginfo_t> {
-    ptrace_get_data::<siginfo_t>(Request::PTRACE_GETSIGINFO, pid)
+/// Get the information about the signal that stopped the tracee, as with
+/// `ptrace(PTRACE_GETSIGINFO,...)`
+///
+/// It's only available in a signal-delivery-stop, where it tells e.g. the
+/// faulting address of a `SIGSEGV`.
+pub fn getsiginfo(pid: Pid) -> Result<SigInfo> {
+    ptrace_get_data::<siginfo_t>(Request::PTRACE_GETSIGINFO, pid).map(SigInfo::from)
 }
 
-/// Set siginfo as with `ptrace(PTRACE_SETSIGINFO,...)`
-pub fn setsiginfo(pid: Pid, sig: &siginfo_t) -> Result<()> {
+/// Change the information about the signal that stopped the tracee, as with
+/// `ptrace(PTRACE_SETSIGINFO,...)`
+///
+/// The signal actually delivered is the one passed to the restarting request,
+/// such as [`cont`](fn.cont.html), but the tracee's handler sees the
+/// information given here.
+pub fn setsiginfo(pid: Pid, sig: &SigInfo) -> Result<()> {
     let ret = unsafe{
         Errno::clear();
         libc::ptrace(Request::PTRACE_SETSIGINFO as RequestType,
                      libc::pid_t::from(pid),
                      ptr::null_mut::<libc::c_void>(),
-                     sig as *const _ as *const c_void)
+                     sig.as_ref() as *const _ as *const c_void)
     };
     match Errno::result(ret) {
         Ok(_) => Ok(()),
@@ -280,3 +795,431 @@ pub fn cont<T: Into<Option<Signal>>>(pid: Pid, sig: T) -> Result<()> {
     }
 }
 
+
+/// Attach to a running process without stopping it, as with
+/// `ptrace(PTRACE_SEIZE, ...)`
+///
+/// Unlike with [`attach`](fn.attach.html), the `options` are set at once, the
+/// tracee can be stopped with [`interrupt`](fn.interrupt.html), and group-stops
+/// are reported as `PTRACE_EVENT_STOP` events.
+#[cfg(not(any(target_os = "android", target_arch = "mips", target_arch = "mips64")))]
+pub fn seize(pid: Pid, options: Options) -> Result<()> {
+    unsafe {
+        ptrace_other(
+            Request::PTRACE_SEIZE,
+            pid,
+            ptr::null_mut(),
+            options.bits() as *mut c_void,
+        ).map(|_| ()) // ignore the useless return value
+    }
+}
+
+/// Stop a tracee attached with [`seize`](fn.seize.html), as with
+/// `ptrace(PTRACE_INTERRUPT, ...)`
+///
+/// The stop is reported as a `PTRACE_EVENT_STOP` event, unless the tracee
+/// stops for another reason first.
+#[cfg(not(any(target_os = "android", target_arch = "mips", target_arch = "mips64")))]
+pub fn interrupt(pid: Pid) -> Result<()> {
+    unsafe {
+        ptrace_other(
+            Request::PTRACE_INTERRUPT,
+            pid,
+            ptr::null_mut(),
+            ptr::null_mut(),
+        ).map(|_| ()) // ignore the useless return value
+    }
+}
+
+/// Let a tracee attached with [`seize`](fn.seize.html) that is in a
+/// group-stop stay stopped, while the tracer is notified when it's resumed by
+/// `SIGCONT`, as with `ptrace(PTRACE_LISTEN, ...)`
+#[cfg(not(any(target_os = "android", target_arch = "mips", target_arch = "mips64")))]
+pub fn listen(pid: Pid) -> Result<()> {
+    unsafe {
+        ptrace_other(
+            Request::PTRACE_LISTEN,
+            pid,
+            ptr::null_mut(),
+            ptr::null_mut(),
+        ).map(|_| ()) // ignore the useless return value
+    }
+}
+
+fn peek_data(pid: Pid, addr: usize) -> Result<c_long> {
+    ptrace_peek(Request::PTRACE_PEEKDATA, pid, addr as *mut c_void, ptr::null_mut())
+}
+
+fn poke_data(pid: Pid, addr: usize, word: c_long) -> Result<()> {
+    unsafe {
+        ptrace_other(Request::PTRACE_POKEDATA, pid, addr as *mut c_void, word as *mut c_void)
+    }.map(drop)
+}
+
+// Split the range starting at `addr` into the word-aligned words that cover it,
+// calling `f` with the address of each word and the offsets of the range that
+// falls into it.
+fn for_each_word<F>(addr: usize, len: usize, mut f: F) -> Result<()>
+    where F: FnMut(usize, usize, usize, usize) -> Result<()>
+{
+    let word_size = mem::size_of::<c_long>();
+    let mut pos = 0;
+    while pos < len {
+        let word = (addr + pos) & !(word_size - 1);
+        let offset = addr + pos - word;
+        let n = cmp::min(word_size - offset, len - pos);
+        try!(f(word, offset, pos, n));
+        pos += n;
+    }
+    Ok(())
+}
+
+// Copy as much as possible with a single system call.  It's unavailable before
+// Linux 3.2, and process_vm_writev can't write to read-only mappings, where
+// breakpoints usually go, so whatever fails is left to the word-wise fallback.
+#[cfg(target_os = "linux")]
+fn vm_read(pid: Pid, addr: usize, buf: &mut [u8]) -> usize {
+    use sys::uio::{process_vm_readv, IoVec, RemoteIoVec};
+
+    let remote = [RemoteIoVec { base: addr, len: buf.len() }];
+    process_vm_readv(pid, &[IoVec::from_mut_slice(buf)], &remote).unwrap_or(0)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn vm_read(_pid: Pid, _addr: usize, _buf: &mut [u8]) -> usize {
+    0
+}
+
+#[cfg(target_os = "linux")]
+fn vm_write(pid: Pid, addr: usize, buf: &[u8]) -> usize {
+    use sys::uio::{process_vm_writev, IoVec, RemoteIoVec};
+
+    let remote = [RemoteIoVec { base: addr, len: buf.len() }];
+    process_vm_writev(pid, &[IoVec::from_slice(buf)], &remote).unwrap_or(0)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn vm_write(_pid: Pid, _addr: usize, _buf: &[u8]) -> usize {
+    0
+}
+
+/// Read the memory of a stopped tracee at `addr` into `buf`.
+///
+/// This uses `process_vm_readv` where possible, and `ptrace(PTRACE_PEEKDATA,
+/// ...)` on the words that cover the rest, so `addr` and the length of `buf`
+/// needn't be aligned.  Fails, typically with `EIO` or `EFAULT`, if part of the
+/// range isn't mapped in the tracee.
+pub fn read_memory(pid: Pid, addr: usize, buf: &mut [u8]) -> Result<()> {
+    let done = vm_read(pid, addr, buf);
+    let rest = &mut buf[done..];
+    for_each_word(addr + done, rest.len(), |word, offset, pos, n| {
+        let value = try!(peek_data(pid, word));
+        unsafe {
+            let src = (&value as *const c_long as *const u8).offset(offset as isize);
+            ptr::copy_nonoverlapping(src, rest[pos..].as_mut_ptr(), n);
+        }
+        Ok(())
+    })
+}
+
+/// Write `buf` to the memory of a stopped tracee at `addr`.
+///
+/// This uses `process_vm_writev` where possible, and `ptrace(PTRACE_POKEDATA,
+/// ...)` on the words that cover the rest, which also works on read-only
+/// mappings such as code.  The bytes surrounding an unaligned range are
+/// preserved.  If it fails, part of the range may have been written already.
+pub fn write_memory(pid: Pid, addr: usize, buf: &[u8]) -> Result<()> {
+    let done = vm_write(pid, addr, buf);
+    let rest = &buf[done..];
+    for_each_word(addr + done, rest.len(), |word, offset, pos, n| {
+        let mut value = if n == mem::size_of::<c_long>() {
+            0
+        } else {
+            try!(peek_data(pid, word))
+        };
+        unsafe {
+            let dst = (&mut value as *mut c_long as *mut u8).offset(offset as isize);
+            ptr::copy_nonoverlapping(rest[pos..].as_ptr(), dst, n);
+        }
+        poke_data(pid, word, value)
+    })
+}
+
+#[cfg(all(target_os = "linux",
+          any(all(target_arch = "x86", target_env = "gnu"),
+              all(target_arch = "x86_64", any(target_env = "gnu", target_env = "musl")),
+              all(target_arch = "aarch64", any(target_env = "gnu", target_env = "musl")))))]
+pub use self::hw_breakpoint::*;
+
+#[cfg(all(target_os = "linux",
+          any(all(target_arch = "x86", target_env = "gnu"),
+              all(target_arch = "x86_64", any(target_env = "gnu", target_env = "musl")),
+              all(target_arch = "aarch64", any(target_env = "gnu", target_env = "musl")))))]
+mod hw_breakpoint {
+    use super::*;
+
+    /// The accesses that trigger a hardware breakpoint
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub enum HwBreakpointCondition {
+        /// Executing the instruction at the address
+        Execute,
+        /// Reading the watched bytes
+        #[cfg(target_arch = "aarch64")]
+        Read,
+        /// Writing the watched bytes
+        Write,
+        /// Reading or writing the watched bytes
+        ReadWrite,
+    }
+
+    /// A hardware breakpoint or watchpoint, for
+    /// [`set_hw_breakpoint`](fn.set_hw_breakpoint.html)
+    ///
+    /// The tracee stops with a `SIGTRAP` when it accesses any of the `len` bytes
+    /// at `addr` as given by `condition`: before executing the instruction for
+    /// `Execute`, and after the access for the other conditions.
+    ///
+    /// On x86, `len` is 1, 2, 4 or (on x86_64) 8, `addr` must be aligned to it,
+    /// and it must be 1 for `Execute`.  On aarch64, the watched bytes must lie
+    /// in one aligned 8-byte word, and `Execute` takes an aligned instruction
+    /// with a `len` of 4.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub struct HwBreakpoint {
+        /// The address of the first byte
+        pub addr: usize,
+        /// The number of bytes
+        pub len: usize,
+        /// The accesses that trigger the breakpoint
+        pub condition: HwBreakpointCondition,
+    }
+
+    // The debug registers, accessed with PTRACE_PEEKUSER and PTRACE_POKEUSER
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    const X86_DEBUG_SLOTS: usize = 4;
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    const DR_STATUS: usize = 6;
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    const DR_CONTROL: usize = 7;
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    fn debugreg_offset(n: usize) -> *mut c_void {
+        let user: libc::user = unsafe { mem::zeroed() };
+        let offset = &user.u_debugreg[n] as *const _ as usize - &user as *const _ as usize;
+        offset as *mut c_void
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    fn peek_debugreg(pid: Pid, n: usize) -> Result<c_long> {
+        ptrace_peek(Request::PTRACE_PEEKUSER, pid, debugreg_offset(n), ptr::null_mut())
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    fn poke_debugreg(pid: Pid, n: usize, value: c_long) -> Result<()> {
+        unsafe {
+            ptrace_other(Request::PTRACE_POKEUSER, pid, debugreg_offset(n), value as *mut c_void)
+        }.map(drop)
+    }
+
+    // The enable bit of a slot in DR7, followed by its condition and length
+    // fields
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    fn dr7_slot_mask(slot: usize) -> c_long {
+        (1 << (2 * slot)) | (0xf << (16 + 4 * slot))
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    fn dr7_slot_bits(bp: &HwBreakpoint, slot: usize) -> Result<c_long> {
+        let rw = match bp.condition {
+            HwBreakpointCondition::Execute if bp.len == 1 => 0b00,
+            HwBreakpointCondition::Execute => return Err(::Error::invalid_argument()),
+            HwBreakpointCondition::Write => 0b01,
+            HwBreakpointCondition::ReadWrite => 0b11,
+        };
+        let len = match bp.len {
+            1 => 0b00,
+            2 => 0b01,
+            #[cfg(target_arch = "x86_64")]
+            8 => 0b10,
+            4 => 0b11,
+            _ => return Err(::Error::invalid_argument()),
+        };
+        if bp.addr % bp.len != 0 {
+            return Err(::Error::invalid_argument());
+        }
+        Ok((1 << (2 * slot)) | (rw << (16 + 4 * slot)) | (len << (18 + 4 * slot)))
+    }
+
+    /// The number of hardware breakpoints that can be set with `condition` in
+    /// a stopped tracee.
+    ///
+    /// On x86, the 4 slots are shared by all conditions.  On aarch64, there
+    /// are separate slots for `Execute` and for the others, and their number
+    /// depends on the CPU.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub fn hw_breakpoint_slots(_pid: Pid, _condition: HwBreakpointCondition) -> Result<usize> {
+        Ok(X86_DEBUG_SLOTS)
+    }
+
+    /// Set hardware breakpoint number `slot` of a stopped tracee, replacing
+    /// the one that was there.
+    ///
+    /// Fails with `EINVAL` if `slot` is out of range, or if the breakpoint
+    /// isn't supported, and with `ENOSPC` if the CPU runs out of debug
+    /// registers.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub fn set_hw_breakpoint(pid: Pid, slot: usize, bp: &HwBreakpoint) -> Result<()> {
+        if slot >= X86_DEBUG_SLOTS {
+            return Err(::Error::invalid_argument());
+        }
+        let bits = try!(dr7_slot_bits(bp, slot));
+        // Disable the slot while it has a stale address
+        let control = try!(peek_debugreg(pid, DR_CONTROL)) & !dr7_slot_mask(slot);
+        try!(poke_debugreg(pid, DR_CONTROL, control));
+        try!(poke_debugreg(pid, slot, bp.addr as c_long));
+        poke_debugreg(pid, DR_CONTROL, control | bits)
+    }
+
+    /// Clear hardware breakpoint number `slot` of a stopped tracee, among the
+    /// slots used for `condition`.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub fn clear_hw_breakpoint(pid: Pid, slot: usize, _condition: HwBreakpointCondition)
+                               -> Result<()> {
+        if slot >= X86_DEBUG_SLOTS {
+            return Err(::Error::invalid_argument());
+        }
+        let control = try!(peek_debugreg(pid, DR_CONTROL));
+        poke_debugreg(pid, DR_CONTROL, control & !dr7_slot_mask(slot))
+    }
+
+    /// Get the slot of the hardware breakpoint that stopped the tracee with a
+    /// `SIGTRAP`, if any, and reset the debug status for the next stop.
+    ///
+    /// Only available on x86.  On aarch64, the address the tracee accessed is
+    /// given by [`getsiginfo`](../fn.getsiginfo.html) instead.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub fn hw_breakpoint_hit(pid: Pid) -> Result<Option<usize>> {
+        let status = try!(peek_debugreg(pid, DR_STATUS));
+        try!(poke_debugreg(pid, DR_STATUS, 0));
+        Ok((0..X86_DEBUG_SLOTS).find(|&slot| status & (1 << slot) != 0))
+    }
+
+    // Not in libc, from asm/ptrace.h
+    #[cfg(target_arch = "aarch64")]
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct HwDebugReg {
+        addr: u64,
+        ctrl: u32,
+        pad: u32,
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct HwDebugState {
+        dbg_info: u32,
+        pad: u32,
+        dbg_regs: [HwDebugReg; 16],
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    fn debug_regset(condition: HwBreakpointCondition) -> RegisterSet {
+        match condition {
+            HwBreakpointCondition::Execute => RegisterSet::NT_ARM_HW_BREAK,
+            _ => RegisterSet::NT_ARM_HW_WATCH,
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    fn get_debug_state(pid: Pid, regset: RegisterSet) -> Result<HwDebugState> {
+        let mut state: HwDebugState = unsafe { mem::zeroed() };
+        try!(unsafe {
+            ptrace_regset(Request::PTRACE_GETREGSET, pid, regset,
+                          &mut state as *mut _ as *mut c_void, mem::size_of::<HwDebugState>())
+        });
+        Ok(state)
+    }
+
+    // The kernel rejects slots the CPU doesn't have, so only those are written
+    #[cfg(target_arch = "aarch64")]
+    fn set_debug_state(pid: Pid, regset: RegisterSet, state: &mut HwDebugState) -> Result<()> {
+        let slots = (state.dbg_info & 0xff) as usize;
+        let len = mem::size_of::<HwDebugState>() -
+                  (state.dbg_regs.len() - slots) * mem::size_of::<HwDebugReg>();
+        unsafe {
+            ptrace_regset(Request::PTRACE_SETREGSET, pid, regset,
+                          state as *mut _ as *mut c_void, len)
+        }.map(drop)
+    }
+
+    // The control register of a slot: the byte address select mask, the type,
+    // the EL0 privilege level and the enable bit
+    #[cfg(target_arch = "aarch64")]
+    fn debug_ctrl(bp: &HwBreakpoint) -> Result<(u64, u32)> {
+        let (addr, mask, kind) = match bp.condition {
+            HwBreakpointCondition::Execute => {
+                if bp.len != 4 || bp.addr % 4 != 0 {
+                    return Err(::Error::invalid_argument());
+                }
+                (bp.addr, 0xf, 0b00)
+            },
+            condition => {
+                let offset = bp.addr % 8;
+                if bp.len == 0 || offset + bp.len > 8 {
+                    return Err(::Error::invalid_argument());
+                }
+                let kind = match condition {
+                    HwBreakpointCondition::Read => 0b01,
+                    HwBreakpointCondition::Write => 0b10,
+                    _ => 0b11,
+                };
+                (bp.addr - offset, ((1u32 << bp.len) - 1) << offset, kind)
+            },
+        };
+        Ok((addr as u64, (mask << 5) | (kind << 3) | (0b10 << 1) | 1))
+    }
+
+    /// The number of hardware breakpoints that can be set with `condition` in
+    /// a stopped tracee.
+    ///
+    /// On x86, the 4 slots are shared by all conditions.  On aarch64, there
+    /// are separate slots for `Execute` and for the others, and their number
+    /// depends on the CPU.
+    #[cfg(target_arch = "aarch64")]
+    pub fn hw_breakpoint_slots(pid: Pid, condition: HwBreakpointCondition) -> Result<usize> {
+        get_debug_state(pid, debug_regset(condition)).map(|state| (state.dbg_info & 0xff) as usize)
+    }
+
+    /// Set hardware breakpoint number `slot` of a stopped tracee, replacing
+    /// the one that was there.
+    ///
+    /// Fails with `EINVAL` if `slot` is out of range, or if the breakpoint
+    /// isn't supported, and with `ENOSPC` if the CPU runs out of debug
+    /// registers.
+    #[cfg(target_arch = "aarch64")]
+    pub fn set_hw_breakpoint(pid: Pid, slot: usize, bp: &HwBreakpoint) -> Result<()> {
+        let (addr, ctrl) = try!(debug_ctrl(bp));
+        let regset = debug_regset(bp.condition);
+        let mut state = try!(get_debug_state(pid, regset));
+        if slot >= (state.dbg_info & 0xff) as usize {
+            return Err(::Error::invalid_argument());
+        }
+        state.dbg_regs[slot].addr = addr;
+        state.dbg_regs[slot].ctrl = ctrl;
+        set_debug_state(pid, regset, &mut state)
+    }
+
+    /// Clear hardware breakpoint number `slot` of a stopped tracee, among the
+    /// slots used for `condition`.
+    #[cfg(target_arch = "aarch64")]
+    pub fn clear_hw_breakpoint(pid: Pid, slot: usize, condition: HwBreakpointCondition)
+                               -> Result<()> {
+        let regset = debug_regset(condition);
+        let mut state = try!(get_debug_state(pid, regset));
+        if slot >= (state.dbg_info & 0xff) as usize {
+            return Err(::Error::invalid_argument());
+        }
+        state.dbg_regs[slot].ctrl = 0;
+        set_debug_state(pid, regset, &mut state)
+    }
+}
@@ -245,6 +245,7 @@ fn quotactl<P: ?Sized + NixPath>(cmd: QuotaCmd, special: Option<&P>, id: c_int,
 }
 
 /// Turn on disk quotas for a block device.
+#[cfg(feature = "std")]
 pub fn quotactl_on<P: ?Sized + NixPath>(which: QuotaType, special: &P, format: QuotaFmt, quota_file: &P) -> Result<()> {
     try!(quota_file.with_nix_path(|path| {
         let mut path_copy = path.to_bytes_with_nul().to_owned();
@@ -0,0 +1,71 @@
+//! Get random bytes from the kernel, without opening `/dev/urandom`.
+//!
+//! This works in chroots and sandboxes without the device, and can't fail
+//! because the process ran out of file descriptors.  The bytes are suitable
+//! for cryptographic keys.
+use libc::{self, c_void};
+use errno::Errno;
+use Result;
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+libc_bitflags!{
+    /// Flags for [`getrandom`](fn.getrandom.html)
+    pub struct GetRandomFlags: libc::c_uint {
+        /// Fail with `EAGAIN` instead of blocking until the kernel has gathered
+        /// enough entropy, early during boot
+        GRND_NONBLOCK;
+        /// Draw from the blocking pool of `/dev/random` instead of the one of
+        /// `/dev/urandom`.  This is only useful on kernels older than 5.6,
+        /// where it blocks much more often, for no security benefit.
+        GRND_RANDOM;
+        /// Never block, returning bytes that aren't suitable for cryptography
+        /// if there isn't enough entropy yet (since Linux 5.6)
+        GRND_INSECURE;
+    }
+}
+
+/// Fill `buf` with random bytes (see
+/// [getrandom(2)](http://man7.org/linux/man-pages/man2/getrandom.2.html)).
+///
+/// Unless `GRND_NONBLOCK` or `GRND_INSECURE` is given, this only blocks early
+/// during boot, until the kernel has gathered enough entropy.  The kernel may
+/// fill the buffer in several parts, and this retries until it's full, also
+/// after interruptions by signal handlers.  Fails with `ENOSYS` on kernels
+/// older than 3.17.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn getrandom(buf: &mut [u8], flags: GetRandomFlags) -> Result<()> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        let rest = &mut buf[filled..];
+        // Call the kernel directly, since older C libraries lack a wrapper
+        let res = unsafe {
+            libc::syscall(libc::SYS_getrandom, rest.as_mut_ptr() as *mut c_void, rest.len(),
+                          flags.bits())
+        };
+        match Errno::result(res) {
+            Ok(len) => filled += len as usize,
+            Err(::Error::Sys(Errno::EINTR)) => (),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// Fill `buf` with random bytes (see
+/// [getentropy(2)](https://man.openbsd.org/getentropy.2)).
+///
+/// The C function only accepts 256 bytes at a time, so larger buffers take
+/// several calls.
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+pub fn getentropy(buf: &mut [u8]) -> Result<()> {
+    for chunk in buf.chunks_mut(256) {
+        let res = unsafe { libc::getentropy(chunk.as_mut_ptr() as *mut c_void, chunk.len()) };
+        try!(Errno::result(res));
+    }
+    Ok(())
+}
@@ -1,9 +1,10 @@
 //! Reboot/shutdown or enable/disable Ctrl-Alt-Delete.
 
-use {Error, Result};
+use {Error, NixPath, Result};
 use errno::Errno;
-use libc;
+use libc::{self, c_ulong, c_void, size_t};
 use void::Void;
+use std::marker::PhantomData;
 use std::mem::drop;
 
 libc_enum! {
@@ -17,11 +18,17 @@ libc_enum! {
         RB_KEXEC,
         RB_POWER_OFF,
         RB_AUTOBOOT,
-        // we do not support Restart2,
+        // Restart2 takes an argument, see restart2()
         RB_SW_SUSPEND,
     }
 }
 
+/// Reboot or halt the system, or suspend it to disk (see
+/// [reboot(2)](http://man7.org/linux/man-pages/man2/reboot.2.html)).
+///
+/// The file systems aren't synced first, so this is usually preceded by a call
+/// to `sync(2)`.  Apart from `RB_SW_SUSPEND`, which returns once the system
+/// resumes, this only returns on error.  Requires `CAP_SYS_BOOT`.
 pub fn reboot(how: RebootMode) -> Result<Void> {
     unsafe {
         libc::reboot(how as libc::c_int)
@@ -43,3 +50,80 @@ pub fn set_cad_enabled(enable: bool) -> Result<()> {
     };
     Errno::result(res).map(drop)
 }
+
+/// Restart the system with a command for the firmware or the boot loader,
+/// such as `bootloader` or `recovery` on some platforms
+/// (`LINUX_REBOOT_CMD_RESTART2`).
+///
+/// The meaning of `arg` is specific to the platform.  Like
+/// [`reboot`](fn.reboot.html), this only returns on error.
+pub fn restart2<P: ?Sized + NixPath>(arg: &P) -> Result<Void> {
+    // The C library wrapper doesn't pass the argument
+    try!(arg.with_nix_path(|arg| unsafe {
+        libc::syscall(libc::SYS_reboot, libc::LINUX_REBOOT_MAGIC1, libc::LINUX_REBOOT_MAGIC2,
+                      libc::LINUX_REBOOT_CMD_RESTART2, arg.as_ptr())
+    }));
+    Err(Error::Sys(Errno::last()))
+}
+
+libc_bitflags!{
+    /// Flags for [`kexec_load`](fn.kexec_load.html)
+    pub struct KexecFlags: c_ulong {
+        /// Load a kernel to run when the current one panics, instead of on
+        /// `reboot(RB_KEXEC)`
+        KEXEC_ON_CRASH as c_ulong;
+        /// Save the state of the system, so that the loaded kernel can return
+        /// to it
+        KEXEC_PRESERVE_CONTEXT as c_ulong;
+    }
+}
+
+/// A part of the kernel image for [`kexec_load`](fn.kexec_load.html): some
+/// bytes to copy to physical memory
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct KexecSegment<'a> {
+    buf: *const c_void,
+    bufsz: size_t,
+    mem: *const c_void,
+    memsz: size_t,
+    _marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> KexecSegment<'a> {
+    /// Copy `buf` to the physical address `mem`, followed by zeros up to
+    /// `memsz` bytes.
+    ///
+    /// `mem` and `memsz` must be multiples of the page size, and `memsz` at
+    /// least the length of `buf`.
+    pub fn new(buf: &'a [u8], mem: usize, memsz: usize) -> KexecSegment<'a> {
+        KexecSegment {
+            buf: buf.as_ptr() as *const c_void,
+            bufsz: buf.len(),
+            mem: mem as *const c_void,
+            memsz: memsz,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Load a new kernel, to be run by `reboot(RB_KEXEC)` (see
+/// [kexec_load(2)](http://man7.org/linux/man-pages/man2/kexec_load.2.html)).
+///
+/// The kernel starts at the physical address `entry`, after its segments
+/// have been copied.  Passing no segments unloads the current one.  Requires
+/// `CAP_SYS_BOOT`.
+///
+/// # Safety
+///
+/// The segments must make up a kernel image that can run from `entry`, since
+/// rebooting into anything else crashes the system or corrupts its memory.
+pub unsafe fn kexec_load(entry: usize, segments: &[KexecSegment], flags: KexecFlags)
+                         -> Result<()> {
+    // The architecture is in the upper bits of the flags, and 0 means the
+    // current one
+    let res = libc::syscall(libc::SYS_kexec_load, entry as c_ulong, segments.len() as c_ulong,
+                            segments.as_ptr(), flags.bits());
+
+    Errno::result(res).map(drop)
+}
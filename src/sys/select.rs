@@ -1,36 +1,44 @@
 use std::mem;
+use std::ops::Range;
 use std::os::unix::io::RawFd;
-use std::ptr::null_mut;
+use std::ptr::{null, null_mut};
 use libc::{self, c_int};
 use Result;
 use errno::Errno;
-use sys::time::TimeVal;
+use sys::signal::SigSet;
+use sys::time::{TimeSpec, TimeVal};
 
 pub use libc::FD_SETSIZE;
 
 // FIXME: Change to repr(transparent) once it's stable
+/// Contains a set of file descriptors used by [`select`](fn.select.html)
 #[repr(C)]
 pub struct FdSet(libc::fd_set);
 
 impl FdSet {
+    /// Create an empty `FdSet`
     pub fn new() -> FdSet {
         let mut fdset = unsafe { mem::uninitialized() };
         unsafe { libc::FD_ZERO(&mut fdset) };
         FdSet(fdset)
     }
 
+    /// Add a file descriptor to the set
     pub fn insert(&mut self, fd: RawFd) {
         unsafe { libc::FD_SET(fd, &mut self.0) };
     }
 
+    /// Remove a file descriptor from the set
     pub fn remove(&mut self, fd: RawFd) {
         unsafe { libc::FD_CLR(fd, &mut self.0) };
     }
 
-    pub fn contains(&mut self, fd: RawFd) -> bool {
-        unsafe { libc::FD_ISSET(fd, &mut self.0) }
+    /// Test whether a file descriptor is in the set
+    pub fn contains(&self, fd: RawFd) -> bool {
+        unsafe { libc::FD_ISSET(fd, &self.0) }
     }
 
+    /// Remove all file descriptors from the set
     pub fn clear(&mut self) {
         unsafe { libc::FD_ZERO(&mut self.0) };
     }
@@ -55,14 +63,82 @@ impl FdSet {
     /// ```
     ///
     /// [`select`]: fn.select.html
-    pub fn highest(&mut self) -> Option<RawFd> {
-        for i in (0..libc::FD_SETSIZE).rev() {
-            let i = i as RawFd;
-            if unsafe { libc::FD_ISSET(i, self as *mut _ as *mut libc::fd_set) } {
-                return Some(i)
+    pub fn highest(&self) -> Option<RawFd> {
+        self.fds(None).next_back()
+    }
+
+    /// Returns an iterator over the file descriptors in the set, in ascending order.
+    ///
+    /// For efficiency, the search can be limited to the file descriptors up to and including
+    /// `highest`, such as the `nfds - 1` passed to `select`.  Otherwise, all file descriptors up to
+    /// `FD_SETSIZE` are checked.  A negative `highest`, like the `nfds - 1` of an empty set, makes
+    /// the iterator empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate nix;
+    /// # use nix::sys::select::FdSet;
+    /// # use std::os::unix::io::RawFd;
+    /// # fn main() {
+    /// let mut set = FdSet::new();
+    /// set.insert(4);
+    /// set.insert(9);
+    /// let fds: Vec<RawFd> = set.fds(None).collect();
+    /// assert_eq!(fds, vec![4, 9]);
+    /// # }
+    /// ```
+    pub fn fds<'a>(&'a self, highest: Option<RawFd>) -> Fds<'a> {
+        let end = match highest {
+            // No file descriptor is negative, so there's nothing to search
+            Some(highest) if highest < 0 => 0,
+            Some(highest) if (highest as usize) < FD_SETSIZE => highest as usize + 1,
+            _ => FD_SETSIZE,
+        };
+        Fds {
+            set: self,
+            range: 0..end,
+        }
+    }
+}
+
+impl Default for FdSet {
+    fn default() -> FdSet {
+        FdSet::new()
+    }
+}
+
+/// Iterator over the file descriptors in an [`FdSet`](struct.FdSet.html), as returned by
+/// [`FdSet::fds`](struct.FdSet.html#method.fds)
+pub struct Fds<'a> {
+    set: &'a FdSet,
+    range: Range<usize>,
+}
+
+impl<'a> Iterator for Fds<'a> {
+    type Item = RawFd;
+
+    fn next(&mut self) -> Option<RawFd> {
+        while let Some(i) = self.range.next() {
+            if self.set.contains(i as RawFd) {
+                return Some(i as RawFd);
             }
         }
+        None
+    }
 
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.range.size_hint().1)
+    }
+}
+
+impl<'a> DoubleEndedIterator for Fds<'a> {
+    fn next_back(&mut self) -> Option<RawFd> {
+        while let Some(i) = self.range.next_back() {
+            if self.set.contains(i as RawFd) {
+                return Some(i as RawFd);
+            }
+        }
         None
     }
 }
@@ -129,6 +205,77 @@ where
     Errno::result(res)
 }
 
+/// Monitors file descriptors for readiness with a signal mask
+///
+/// Returns the total number of ready file descriptors in all sets. The sets are changed so that all
+/// file descriptors that are ready for the given operation are set.
+///
+/// When this function returns, the original signal mask is restored.
+///
+/// Unlike [`select`](fn.select.html), `pselect` does not mutate the `timeout` value.
+///
+/// # Parameters
+///
+/// * `nfds`: The highest file descriptor set in any of the passed `FdSet`s, plus 1. If `None`, this
+///   is calculated automatically by calling [`FdSet::highest`] on all descriptor sets and adding 1
+///   to the maximum of that.
+/// * `readfds`: File descriptors to check for read readiness
+/// * `writefds`: File descriptors to check for write readiness
+/// * `errorfds`: File descriptors to check for pending error conditions.
+/// * `timeout`: Maximum time to wait for descriptors to become ready (`None` to block
+///   indefinitely).
+/// * `sigmask`: Signal mask to activate while waiting for file descriptors to turn
+///    ready (`None` to set no alternative signal mask).
+///
+/// # References
+///
+/// [pselect(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/pselect.html)
+///
+/// [The new pselect() system call](https://lwn.net/Articles/176911/)
+///
+/// [`FdSet::highest`]: struct.FdSet.html#method.highest
+pub fn pselect<'a, N, R, W, E, T, S>(nfds: N,
+                                     readfds: R,
+                                     writefds: W,
+                                     errorfds: E,
+                                     timeout: T,
+                                     sigmask: S) -> Result<c_int>
+where
+    N: Into<Option<c_int>>,
+    R: Into<Option<&'a mut FdSet>>,
+    W: Into<Option<&'a mut FdSet>>,
+    E: Into<Option<&'a mut FdSet>>,
+    T: Into<Option<&'a TimeSpec>>,
+    S: Into<Option<&'a SigSet>>,
+{
+    let mut readfds = readfds.into();
+    let mut writefds = writefds.into();
+    let mut errorfds = errorfds.into();
+    let sigmask = sigmask.into();
+    let timeout = timeout.into();
+
+    let nfds = nfds.into().unwrap_or_else(|| {
+        readfds.iter_mut()
+            .chain(writefds.iter_mut())
+            .chain(errorfds.iter_mut())
+            .map(|set| set.highest().unwrap_or(-1))
+            .max()
+            .unwrap_or(-1) + 1
+    });
+
+    let readfds = readfds.map(|set| set as *mut _ as *mut libc::fd_set).unwrap_or(null_mut());
+    let writefds = writefds.map(|set| set as *mut _ as *mut libc::fd_set).unwrap_or(null_mut());
+    let errorfds = errorfds.map(|set| set as *mut _ as *mut libc::fd_set).unwrap_or(null_mut());
+    let timeout = timeout.map(|ts| ts.as_ref() as *const libc::timespec).unwrap_or(null());
+    let sigmask = sigmask.map(|sm| sm.as_ref() as *const libc::sigset_t).unwrap_or(null());
+
+    let res = unsafe {
+        libc::pselect(nfds, readfds, writefds, errorfds, timeout, sigmask)
+    };
+
+    Errno::result(res)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -198,6 +345,24 @@ mod tests {
         assert_eq!(set.highest(), Some(7));
     }
 
+    #[test]
+    fn fdset_fds() {
+        let mut set = FdSet::new();
+        assert_eq!(set.fds(None).collect::<Vec<_>>(), vec![]);
+        set.insert(0);
+        assert_eq!(set.fds(None).collect::<Vec<_>>(), vec![0]);
+        set.insert(90);
+        assert_eq!(set.fds(None).collect::<Vec<_>>(), vec![0, 90]);
+        assert_eq!(set.fds(None).rev().collect::<Vec<_>>(), vec![90, 0]);
+
+        // Only the file descriptors up to `highest` are searched
+        assert_eq!(set.fds(Some(89)).collect::<Vec<_>>(), vec![0]);
+        assert_eq!(set.fds(Some(90)).collect::<Vec<_>>(), vec![0, 90]);
+        assert_eq!(set.fds(Some(-1)).collect::<Vec<_>>(), vec![]);
+        set.insert((FD_SETSIZE - 1) as RawFd);
+        assert_eq!(set.fds(Some(FD_SETSIZE as RawFd)).last(), Some((FD_SETSIZE - 1) as RawFd));
+    }
+
     // powerpc-unknown-linux-gnu currently fails on the first `assert_eq` because
     // `select()` returns a 0 instead of a 1. Since this test has only been run on
     // qemu, it's unclear if this is a OS or qemu bug. Just disable it on that arch
@@ -266,4 +431,44 @@ mod tests {
         assert!(fd_set.contains(r1));
         assert!(!fd_set.contains(r2));
     }
+
+    #[test]
+    #[cfg_attr(any(target_arch = "powerpc", target_arch = "mips"), ignore)]
+    fn test_pselect() {
+        let (r1, w1) = pipe().unwrap();
+        write(w1, b"hi!").unwrap();
+        let (r2, _w2) = pipe().unwrap();
+
+        let mut fd_set = FdSet::new();
+        fd_set.insert(r1);
+        fd_set.insert(r2);
+
+        let timeout = TimeSpec::seconds(10);
+        let sigmask = SigSet::empty();
+        assert_eq!(1, pselect(None,
+                              &mut fd_set,
+                              None,
+                              None,
+                              &timeout,
+                              &sigmask).unwrap());
+        assert!(fd_set.contains(r1));
+        assert!(!fd_set.contains(r2));
+    }
+
+    #[test]
+    fn test_pselect_timeout() {
+        let (r, _w) = pipe().unwrap();
+
+        let mut fd_set = FdSet::new();
+        fd_set.insert(r);
+
+        let timeout = TimeSpec::milliseconds(1);
+        assert_eq!(0, pselect(None,
+                              &mut fd_set,
+                              None,
+                              None,
+                              &timeout,
+                              None).unwrap());
+        assert!(!fd_set.contains(r));
+    }
 }
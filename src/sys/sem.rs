@@ -0,0 +1,250 @@
+//! System V semaphore sets (see
+//! [sysvipc(7)](http://man7.org/linux/man-pages/man7/sysvipc.7.html)).
+//!
+//! A set holds any number of counting semaphores, and [`semop`](fn.semop.html)
+//! applies several operations to them atomically: either all of them are
+//! performed, or the call blocks until they can be.  With `SEM_UNDO`, the kernel
+//! reverts an operation when the process exits, so a lock held by a crashed
+//! process is released.
+//!
+//! # Examples
+//!
+//! Use a semaphore as a lock:
+//!
+//! ```
+//! # use nix::sys::ipc::{IpcFlags, IPC_PRIVATE};
+//! # use nix::sys::sem::*;
+//! # use nix::sys::stat::Mode;
+//! let semid = semget(IPC_PRIVATE, 1, IpcFlags::IPC_CREAT, Mode::S_IRUSR | Mode::S_IWUSR).unwrap();
+//! semctl_setval(semid, 0, 1).unwrap();
+//!
+//! semop(semid, &[SemBuf::new(0, -1, SemFlags::SEM_UNDO)]).unwrap();
+//! // ... critical section ...
+//! semop(semid, &[SemBuf::new(0, 1, SemFlags::SEM_UNDO)]).unwrap();
+//! semctl_rmid(semid).unwrap();
+//! ```
+use libc::{self, c_int, c_short, c_ushort, c_void};
+use std::ptr;
+use errno::Errno;
+use sys::ipc::{self, IpcFlags, Key};
+#[cfg(target_env = "gnu")]
+use sys::ipc::IpcPerm;
+use sys::stat::Mode;
+use unistd::Pid;
+use Result;
+
+libc_bitflags!{
+    /// Flags of a [`SemBuf`](struct.SemBuf.html)
+    pub struct SemFlags: c_short {
+        /// Fail with `EAGAIN` instead of blocking
+        IPC_NOWAIT as c_short;
+        /// Revert the operation when the process exits
+        SEM_UNDO as c_short;
+    }
+}
+
+/// An operation on one semaphore of a set, for [`semop`](fn.semop.html)
+// FIXME: Change to repr(transparent)
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct SemBuf(libc::sembuf);
+
+impl SemBuf {
+    /// An operation on semaphore number `sem_num`.
+    ///
+    /// A positive `sem_op` adds to the value of the semaphore.  A negative one
+    /// subtracts from it, blocking while the value is smaller than its absolute
+    /// value.  Zero blocks until the value is zero.
+    pub fn new(sem_num: c_ushort, sem_op: c_short, flags: SemFlags) -> SemBuf {
+        SemBuf(libc::sembuf {
+            sem_num: sem_num,
+            sem_op: sem_op,
+            sem_flg: flags.bits(),
+        })
+    }
+
+    /// The number of the semaphore
+    pub fn sem_num(&self) -> c_ushort {
+        self.0.sem_num
+    }
+
+    /// The operation
+    pub fn sem_op(&self) -> c_short {
+        self.0.sem_op
+    }
+
+    /// The flags of the operation
+    pub fn flags(&self) -> SemFlags {
+        SemFlags::from_bits_truncate(self.0.sem_flg)
+    }
+}
+
+/// Get the identifier of the semaphore set with the given key, creating it
+/// with `nsems` semaphores according to `flags` (see
+/// [semget(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/semget.html)).
+///
+/// A new set gets the permissions in `mode`, and its semaphores are
+/// initialized to zero.
+pub fn semget(key: Key, nsems: c_int, flags: IpcFlags, mode: Mode) -> Result<c_int> {
+    let res = unsafe { libc::semget(key.into(), nsems, ipc::get_flags(flags.bits(), mode)) };
+
+    Errno::result(res)
+}
+
+/// Perform all operations of `ops` atomically (see
+/// [semop(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/semop.html)).
+pub fn semop(semid: c_int, ops: &[SemBuf]) -> Result<()> {
+    // The kernel doesn't modify the operations
+    let res = unsafe { libc::semop(semid, ops.as_ptr() as *mut libc::sembuf, ops.len()) };
+
+    Errno::result(res).map(drop)
+}
+
+// The fourth argument of semctl.  libc doesn't define it, since it's up to the
+// caller to.
+#[repr(C)]
+#[allow(dead_code)]
+union semun {
+    val: c_int,
+    buf: *mut c_void,
+    array: *mut c_ushort,
+}
+
+fn semctl(semid: c_int, semnum: c_int, cmd: c_int, arg: semun) -> Result<c_int> {
+    let res = unsafe { libc::semctl(semid, semnum, cmd, arg) };
+
+    Errno::result(res)
+}
+
+/// Get the value of a semaphore (`GETVAL`, see
+/// [semctl(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/semctl.html)).
+pub fn semctl_getval(semid: c_int, semnum: c_int) -> Result<c_int> {
+    semctl(semid, semnum, libc::GETVAL, semun { val: 0 })
+}
+
+/// Set the value of a semaphore (`SETVAL`, see
+/// [semctl(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/semctl.html)).
+///
+/// This clears the undo records of the semaphore in all processes.
+pub fn semctl_setval(semid: c_int, semnum: c_int, val: c_int) -> Result<()> {
+    semctl(semid, semnum, libc::SETVAL, semun { val: val }).map(drop)
+}
+
+/// Get the values of all semaphores of a set (`GETALL`, see
+/// [semctl(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/semctl.html)).
+///
+/// `values` must have an element for every semaphore of the set, or the call
+/// fails with `EINVAL`.
+#[cfg(target_env = "gnu")]
+pub fn semctl_getall(semid: c_int, values: &mut [c_ushort]) -> Result<()> {
+    try!(check_nsems(semid, values.len()));
+    semctl(semid, 0, libc::GETALL, semun { array: values.as_mut_ptr() }).map(drop)
+}
+
+/// Set the values of all semaphores of a set (`SETALL`, see
+/// [semctl(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/semctl.html)).
+///
+/// `values` must have an element for every semaphore of the set, or the call
+/// fails with `EINVAL`.
+#[cfg(target_env = "gnu")]
+pub fn semctl_setall(semid: c_int, values: &[c_ushort]) -> Result<()> {
+    try!(check_nsems(semid, values.len()));
+    // The kernel doesn't modify the values
+    let array = values.as_ptr() as *mut c_ushort;
+    semctl(semid, 0, libc::SETALL, semun { array: array }).map(drop)
+}
+
+// The kernel reads or writes as many values as there are semaphores, whatever
+// the size of the buffer
+#[cfg(target_env = "gnu")]
+fn check_nsems(semid: c_int, len: usize) -> Result<()> {
+    let mut ds: libc::semid_ds = unsafe { ::std::mem::zeroed() };
+    try!(semctl(semid, 0, libc::IPC_STAT, semun { buf: &mut ds as *mut _ as *mut c_void }));
+    if ds.sem_nsems as usize == len {
+        Ok(())
+    } else {
+        Err(::Error::invalid_argument())
+    }
+}
+
+/// Get the process that last operated on a semaphore (`GETPID`, see
+/// [semctl(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/semctl.html)).
+pub fn semctl_getpid(semid: c_int, semnum: c_int) -> Result<Pid> {
+    semctl(semid, semnum, libc::GETPID, semun { val: 0 }).map(Pid::from_raw)
+}
+
+/// Get the number of processes waiting for a semaphore to increase (`GETNCNT`,
+/// see [semctl(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/semctl.html)).
+pub fn semctl_getncnt(semid: c_int, semnum: c_int) -> Result<c_int> {
+    semctl(semid, semnum, libc::GETNCNT, semun { val: 0 })
+}
+
+/// Get the number of processes waiting for a semaphore to become zero
+/// (`GETZCNT`, see
+/// [semctl(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/semctl.html)).
+pub fn semctl_getzcnt(semid: c_int, semnum: c_int) -> Result<c_int> {
+    semctl(semid, semnum, libc::GETZCNT, semun { val: 0 })
+}
+
+/// The state of a semaphore set, as returned by [`semctl_stat`](fn.semctl_stat.html)
+// FIXME: Change to repr(transparent)
+#[cfg(target_env = "gnu")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct SemidDs(libc::semid_ds);
+
+#[cfg(target_env = "gnu")]
+impl SemidDs {
+    /// The owner and permissions of the set
+    pub fn perm(&self) -> IpcPerm {
+        IpcPerm::from(self.0.sem_perm)
+    }
+
+    /// Change the owner and permissions, which takes effect with
+    /// [`semctl_set`](fn.semctl_set.html)
+    pub fn set_perm(&mut self, perm: IpcPerm) {
+        self.0.sem_perm = *perm.as_ref();
+    }
+
+    /// The number of semaphores in the set
+    pub fn nsems(&self) -> usize {
+        self.0.sem_nsems as usize
+    }
+
+    /// When `semop` was last called
+    pub fn otime(&self) -> libc::time_t {
+        self.0.sem_otime
+    }
+
+    /// When the set was last changed
+    pub fn ctime(&self) -> libc::time_t {
+        self.0.sem_ctime
+    }
+}
+
+/// Get the state of a semaphore set (`IPC_STAT`, see
+/// [semctl(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/semctl.html)).
+#[cfg(target_env = "gnu")]
+pub fn semctl_stat(semid: c_int) -> Result<SemidDs> {
+    let mut ds: libc::semid_ds = unsafe { ::std::mem::zeroed() };
+    try!(semctl(semid, 0, libc::IPC_STAT, semun { buf: &mut ds as *mut _ as *mut c_void }));
+    Ok(SemidDs(ds))
+}
+
+/// Change the owner and permissions of a semaphore set (`IPC_SET`, see
+/// [semctl(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/semctl.html)).
+///
+/// `ds` is usually obtained from [`semctl_stat`](fn.semctl_stat.html) and then
+/// modified.
+#[cfg(target_env = "gnu")]
+pub fn semctl_set(semid: c_int, ds: &SemidDs) -> Result<()> {
+    let mut ds = ds.0;
+    semctl(semid, 0, libc::IPC_SET, semun { buf: &mut ds as *mut _ as *mut c_void }).map(drop)
+}
+
+/// Remove a semaphore set immediately, waking up all blocked processes with
+/// `EIDRM` (`IPC_RMID`, see
+/// [semctl(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/semctl.html)).
+pub fn semctl_rmid(semid: c_int) -> Result<()> {
+    semctl(semid, 0, libc::IPC_RMID, semun { buf: ptr::null_mut() }).map(drop)
+}
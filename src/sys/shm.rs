@@ -0,0 +1,211 @@
+//! System V shared memory (see
+//! [sysvipc(7)](http://man7.org/linux/man-pages/man7/sysvipc.7.html)).
+//!
+//! A segment is created with [`shmget`](fn.shmget.html) and mapped into the
+//! address space of every process that attaches it with [`shmat`](fn.shmat.html).
+//! It persists until it's removed with [`shmctl_rmid`](fn.shmctl_rmid.html)
+//! and the last process has detached it.
+//!
+//! # Examples
+//!
+//! ```
+//! # use nix::sys::ipc::IPC_PRIVATE;
+//! # use nix::sys::shm::*;
+//! # use nix::sys::stat::Mode;
+//! let shmid = shmget(IPC_PRIVATE, 4096, ShmgetFlags::IPC_CREAT, Mode::S_IRUSR | Mode::S_IWUSR)
+//!     .unwrap();
+//! let mut shm = shmat(shmid, ShmatFlags::empty()).unwrap();
+//! // No other process has attached the segment yet
+//! unsafe { shm.as_mut_slice()[0] = 42 };
+//! assert_eq!(unsafe { shm.as_slice()[0] }, 42);
+//! shmctl_rmid(shmid).unwrap();
+//! ```
+use libc::{self, c_int, c_void, size_t, time_t};
+use std::{mem, ptr, slice};
+use errno::Errno;
+use sys::ipc::{self, IpcPerm, Key};
+use sys::stat::Mode;
+use unistd::Pid;
+use {Error, Result};
+
+libc_bitflags!{
+    /// Flags for [`shmget`](fn.shmget.html)
+    pub struct ShmgetFlags: c_int {
+        /// Create the segment if it doesn't exist
+        IPC_CREAT;
+        /// Together with `IPC_CREAT`, fail with `EEXIST` if the segment exists
+        IPC_EXCL;
+        /// Allocate the segment from huge pages
+        #[cfg(target_os = "linux")]
+        SHM_HUGETLB;
+        /// Don't reserve swap space for the segment
+        #[cfg(target_os = "linux")]
+        SHM_NORESERVE;
+    }
+}
+
+libc_bitflags!{
+    /// Flags for [`shmat`](fn.shmat.html)
+    pub struct ShmatFlags: c_int {
+        /// Attach the segment read-only
+        SHM_RDONLY;
+    }
+}
+
+/// Get the identifier of the shared memory segment with the given key,
+/// creating it according to `flags` (see
+/// [shmget(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/shmget.html)).
+///
+/// A new segment has `size` bytes, rounded up to a multiple of the page size,
+/// which are initialized to zero, and gets the permissions in `mode`.
+pub fn shmget(key: Key, size: size_t, flags: ShmgetFlags, mode: Mode) -> Result<c_int> {
+    let res = unsafe { libc::shmget(key.into(), size, ipc::get_flags(flags.bits(), mode)) };
+
+    Errno::result(res)
+}
+
+/// A shared memory segment attached to the address space of the process, which
+/// is detached when dropped.
+///
+/// Other processes can access the memory at any time, so reading or writing
+/// it through a slice is unsafe; they need to agree on some synchronization.
+#[derive(Debug)]
+pub struct ShmAttachment {
+    addr: *mut c_void,
+    len: usize,
+}
+
+impl ShmAttachment {
+    /// The address the segment is attached at
+    pub fn as_ptr(&self) -> *mut c_void {
+        self.addr
+    }
+
+    /// The size of the segment, in bytes
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// View the segment as a slice.
+    ///
+    /// # Safety
+    ///
+    /// No other process or thread may write to the segment while the slice is
+    /// in use.
+    pub unsafe fn as_slice(&self) -> &[u8] {
+        slice::from_raw_parts(self.addr as *const u8, self.len)
+    }
+
+    /// View the segment as a mutable slice.
+    ///
+    /// # Safety
+    ///
+    /// No other process or thread may access the segment while the slice is in
+    /// use, and the segment must not have been attached with `SHM_RDONLY`.
+    pub unsafe fn as_mut_slice(&mut self) -> &mut [u8] {
+        slice::from_raw_parts_mut(self.addr as *mut u8, self.len)
+    }
+}
+
+impl Drop for ShmAttachment {
+    fn drop(&mut self) {
+        unsafe { libc::shmdt(self.addr) };
+    }
+}
+
+/// Attach a shared memory segment at an address chosen by the kernel (see
+/// [shmat(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/shmat.html)).
+pub fn shmat(shmid: c_int, flags: ShmatFlags) -> Result<ShmAttachment> {
+    let len = try!(shmctl_stat(shmid)).segsz();
+    let addr = unsafe { libc::shmat(shmid, ptr::null(), flags.bits()) };
+
+    if addr as isize == -1 {
+        Err(Error::Sys(Errno::last()))
+    } else {
+        Ok(ShmAttachment { addr: addr, len: len })
+    }
+}
+
+/// The state of a shared memory segment, as returned by [`shmctl_stat`](fn.shmctl_stat.html)
+// FIXME: Change to repr(transparent)
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct ShmidDs(libc::shmid_ds);
+
+impl ShmidDs {
+    /// The owner and permissions of the segment
+    pub fn perm(&self) -> IpcPerm {
+        IpcPerm::from(self.0.shm_perm)
+    }
+
+    /// Change the owner and permissions, which takes effect with
+    /// [`shmctl_set`](fn.shmctl_set.html)
+    pub fn set_perm(&mut self, perm: IpcPerm) {
+        self.0.shm_perm = *perm.as_ref();
+    }
+
+    /// The size of the segment, in bytes
+    pub fn segsz(&self) -> usize {
+        self.0.shm_segsz as usize
+    }
+
+    /// The number of current attachments
+    pub fn nattch(&self) -> u64 {
+        self.0.shm_nattch as u64
+    }
+
+    /// The process that created the segment
+    pub fn cpid(&self) -> Pid {
+        Pid::from_raw(self.0.shm_cpid)
+    }
+
+    /// The process that last attached or detached the segment
+    pub fn lpid(&self) -> Pid {
+        Pid::from_raw(self.0.shm_lpid)
+    }
+
+    /// When the segment was last attached
+    pub fn atime(&self) -> time_t {
+        self.0.shm_atime
+    }
+
+    /// When the segment was last detached
+    pub fn dtime(&self) -> time_t {
+        self.0.shm_dtime
+    }
+
+    /// When the segment was last changed
+    pub fn ctime(&self) -> time_t {
+        self.0.shm_ctime
+    }
+}
+
+/// Get the state of a segment (`IPC_STAT`, see
+/// [shmctl(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/shmctl.html)).
+pub fn shmctl_stat(shmid: c_int) -> Result<ShmidDs> {
+    let mut ds: libc::shmid_ds = unsafe { mem::zeroed() };
+    let res = unsafe { libc::shmctl(shmid, libc::IPC_STAT, &mut ds) };
+
+    Errno::result(res).map(|_| ShmidDs(ds))
+}
+
+/// Change the owner and permissions of a segment (`IPC_SET`, see
+/// [shmctl(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/shmctl.html)).
+///
+/// `ds` is usually obtained from [`shmctl_stat`](fn.shmctl_stat.html) and then
+/// modified.
+pub fn shmctl_set(shmid: c_int, ds: &ShmidDs) -> Result<()> {
+    let mut ds = ds.0;
+    let res = unsafe { libc::shmctl(shmid, libc::IPC_SET, &mut ds) };
+
+    Errno::result(res).map(drop)
+}
+
+/// Mark a segment to be destroyed once the last process detaches it
+/// (`IPC_RMID`, see
+/// [shmctl(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/shmctl.html)).
+pub fn shmctl_rmid(shmid: c_int) -> Result<()> {
+    let res = unsafe { libc::shmctl(shmid, libc::IPC_RMID, ptr::null_mut()) };
+
+    Errno::result(res).map(drop)
+}
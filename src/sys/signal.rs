@@ -18,6 +18,7 @@ libc_enum!{
     // We would prefer to use the libc::c_int alias in the repr attribute. Unfortunately
     // this is not (yet) possible.
     #[repr(i32)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub enum Signal {
         SIGHUP,
         SIGINT,
@@ -307,7 +308,7 @@ impl SigSet {
 
     /// Gets the currently blocked (masked) set of signals for the calling thread.
     pub fn thread_get_mask() -> Result<SigSet> {
-        let mut oldmask: SigSet = unsafe { mem::uninitialized() };
+        let mut oldmask = SigSet::empty();
         try!(pthread_sigmask(SigmaskHow::SIG_SETMASK, None, Some(&mut oldmask)));
         Ok(oldmask)
     }
@@ -329,7 +330,7 @@ impl SigSet {
 
     /// Sets the set of signals as the signal mask, and returns the old mask.
     pub fn thread_swap_mask(&self, how: SigmaskHow) -> Result<SigSet> {
-        let mut oldmask: SigSet = unsafe { mem::uninitialized() };
+        let mut oldmask = SigSet::empty();
         try!(pthread_sigmask(how, Some(self), Some(&mut oldmask)));
         Ok(oldmask)
     }
@@ -350,6 +351,15 @@ impl AsRef<libc::sigset_t> for SigSet {
     }
 }
 
+// The size of the kernel's sigset, which the raw system calls taking a sigset expect instead of
+// the size of `libc::sigset_t`
+#[cfg(all(any(target_os = "android", target_os = "linux"),
+          any(target_arch = "mips", target_arch = "mips64")))]
+pub(crate) const KERNEL_SIGSET_SIZE: usize = 16;
+#[cfg(all(any(target_os = "android", target_os = "linux"),
+          not(any(target_arch = "mips", target_arch = "mips64"))))]
+pub(crate) const KERNEL_SIGSET_SIZE: usize = 8;
+
 #[allow(unknown_lints)]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SigHandler {
@@ -424,6 +434,10 @@ pub unsafe fn sigaction(signal: Signal, sigaction: &SigAction) -> Result<SigActi
 ///
 /// If both `set` and `oldset` is None, this function is a no-op.
 ///
+/// Passing only `oldset` queries the signal mask without changing it, which is
+/// also what [`SigSet::thread_get_mask`](struct.SigSet.html#method.thread_get_mask)
+/// does.
+///
 /// For more information, visit the [`pthread_sigmask`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/pthread_sigmask.html),
 /// or [`sigprocmask`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/sigprocmask.html) man pages.
 pub fn pthread_sigmask(how: SigmaskHow,
@@ -442,7 +456,7 @@ pub fn pthread_sigmask(how: SigmaskHow,
                                                 |os| &mut os.sigset as *mut libc::sigset_t))
     };
 
-    Errno::result(res).map(drop)
+    Errno::result_from_code(res)
 }
 
 /// Examine and change blocked signals.
@@ -476,6 +490,23 @@ pub fn kill<T: Into<Option<Signal>>>(pid: ::unistd::Pid, signal: T) -> Result<()
     Errno::result(res).map(drop)
 }
 
+/// Send a signal to a thread of the calling process (see
+/// [`pthread_kill(3)`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/pthread_kill.html)).
+///
+/// The signal is delivered to the given thread, unlike with
+/// [`kill`](fn.kill.html), where any thread not blocking it may receive it.
+/// If `signal` is `None`, only the error checking is done, which tells whether
+/// the thread still exists.
+pub fn pthread_kill<T: Into<Option<Signal>>>(thread: ::sys::pthread::Pthread, signal: T) -> Result<()> {
+    let sig = match signal.into() {
+        Some(s) => s as libc::c_int,
+        None => 0,
+    };
+    let res = unsafe { libc::pthread_kill(thread, sig) };
+
+    Errno::result_from_code(res)
+}
+
 pub fn raise(signal: Signal) -> Result<()> {
     let res = unsafe { libc::raise(signal as libc::c_int) };
 
@@ -483,6 +514,134 @@ pub fn raise(signal: Signal) -> Result<()> {
 }
 
 
+/// Information about a signal, as passed to a `SigAction` handler or obtained
+/// from a tracee with [`ptrace::getsiginfo`](../ptrace/fn.getsiginfo.html)
+///
+/// Which fields are valid depends on the signal and its code, so the
+/// accessors return `None` for the fields that aren't.
+// FIXME: Change to repr(transparent)
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct SigInfo(libc::siginfo_t);
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl SigInfo {
+    /// Information about `signal` with the given code, and all other fields
+    /// zeroed.
+    ///
+    /// A code of `SI_USER` (0) makes the signal look like it was sent by
+    /// `kill` from the process given by [`pid`](#method.pid).
+    pub fn new(signal: Signal, code: libc::c_int) -> SigInfo {
+        let mut info: libc::siginfo_t = unsafe { mem::zeroed() };
+        info.si_signo = signal as libc::c_int;
+        info.si_code = code;
+        SigInfo(info)
+    }
+
+    /// The signal
+    pub fn signal(&self) -> Result<Signal> {
+        Signal::from_c_int(self.0.si_signo)
+    }
+
+    /// Change the signal
+    pub fn set_signal(&mut self, signal: Signal) {
+        self.0.si_signo = signal as libc::c_int;
+    }
+
+    /// The errno value associated with the signal, usually 0
+    pub fn errno(&self) -> libc::c_int {
+        self.0.si_errno
+    }
+
+    /// Why the signal was sent: positive values are specific to the signal
+    /// (e.g. `SEGV_MAPERR` for `SIGSEGV`), others tell how a process sent it
+    /// (e.g. `SI_USER` for `kill`)
+    pub fn code(&self) -> libc::c_int {
+        self.0.si_code
+    }
+
+    /// Change the code
+    pub fn set_code(&mut self, code: libc::c_int) {
+        self.0.si_code = code;
+    }
+
+    // The kernel fills in the fault fields for these signals, when it sends
+    // them itself
+    fn is_fault(&self) -> bool {
+        match Signal::from_c_int(self.0.si_signo) {
+            Ok(SIGILL) | Ok(SIGFPE) | Ok(SIGSEGV) | Ok(SIGBUS) | Ok(SIGTRAP) => self.0.si_code > 0,
+            _ => false,
+        }
+    }
+
+    // The sender fields are valid for signals sent by processes, and for
+    // SIGCHLD, where they describe the child
+    fn has_sender(&self) -> bool {
+        self.0.si_code <= 0 || self.0.si_signo == libc::SIGCHLD
+    }
+
+    /// The faulting address, for `SIGILL`, `SIGFPE`, `SIGSEGV`, `SIGBUS` and
+    /// `SIGTRAP` sent by the kernel
+    pub fn addr(&self) -> Option<*mut libc::c_void> {
+        if self.is_fault() {
+            Some(unsafe { self.0.si_addr() })
+        } else {
+            None
+        }
+    }
+
+    /// The process that sent the signal, or the child that changed state for
+    /// `SIGCHLD`
+    pub fn pid(&self) -> Option<::unistd::Pid> {
+        if self.has_sender() {
+            Some(::unistd::Pid::from_raw(unsafe { self.0.si_pid() }))
+        } else {
+            None
+        }
+    }
+
+    /// The real user ID of the process that sent the signal, or of the child
+    /// for `SIGCHLD`
+    pub fn uid(&self) -> Option<::unistd::Uid> {
+        if self.has_sender() {
+            Some(::unistd::Uid::from_raw(unsafe { self.0.si_uid() }))
+        } else {
+            None
+        }
+    }
+
+    /// The exit status or signal of the child, for `SIGCHLD`
+    pub fn status(&self) -> Option<libc::c_int> {
+        if self.0.si_signo == libc::SIGCHLD {
+            Some(unsafe { self.0.si_status() })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl From<libc::siginfo_t> for SigInfo {
+    fn from(info: libc::siginfo_t) -> SigInfo {
+        SigInfo(info)
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl AsRef<libc::siginfo_t> for SigInfo {
+    fn as_ref(&self) -> &libc::siginfo_t {
+        &self.0
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl AsMut<libc::siginfo_t> for SigInfo {
+    fn as_mut(&mut self) -> &mut libc::siginfo_t {
+        &mut self.0
+    }
+}
+
 #[cfg(target_os = "freebsd")]
 pub type type_of_thread_id = libc::lwpid_t;
 #[cfg(target_os = "linux")]
@@ -493,7 +652,7 @@ pub type type_of_thread_id = libc::pid_t;
 // sigval is actually a union of a int and a void*.  But it's never really used
 // as a pointer, because neither libc nor the kernel ever dereference it.  nix
 // therefore presents it as an intptr_t, which is how kevent uses it.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug)]
 pub enum SigevNotify {
     /// No notification will be delivered
     SigevNone,
@@ -501,8 +660,14 @@ pub enum SigevNotify {
     /// value in `si_value` will be present in the `si_value` field of the
     /// `siginfo_t` structure of the queued signal.
     SigevSignal { signal: Signal, si_value: libc::intptr_t },
-    // Note: SIGEV_THREAD is not implemented because libc::sigevent does not
-    // expose a way to set the union members needed by SIGEV_THREAD.
+    /// A new thread is created, which calls `function` with the value in
+    /// `si_value` as its argument.  The thread is created with default
+    /// attributes.
+    ///
+    /// Only `si_value` is compared, since the addresses of functions aren't
+    /// unique.
+    #[cfg(any(target_os = "freebsd", target_os = "linux"))]
+    SigevThread { function: extern fn(libc::sigval), si_value: libc::intptr_t },
     /// A new `kevent` is posted to the kqueue `kq`.  The `kevent`'s `udata`
     /// field will contain the value in `udata`.
     #[cfg(any(target_os = "dragonfly", target_os = "freebsd"))]
@@ -515,6 +680,30 @@ pub enum SigevNotify {
                     si_value: libc::intptr_t },
 }
 
+impl PartialEq for SigevNotify {
+    fn eq(&self, other: &SigevNotify) -> bool {
+        use self::SigevNotify::*;
+
+        match (*self, *other) {
+            (SigevNone, SigevNone) => true,
+            (SigevSignal { signal: s1, si_value: v1 },
+             SigevSignal { signal: s2, si_value: v2 }) => s1 == s2 && v1 == v2,
+            #[cfg(any(target_os = "freebsd", target_os = "linux"))]
+            (SigevThread { si_value: v1, .. }, SigevThread { si_value: v2, .. }) => v1 == v2,
+            #[cfg(any(target_os = "dragonfly", target_os = "freebsd"))]
+            (SigevKevent { kq: k1, udata: u1 }, SigevKevent { kq: k2, udata: u2 }) => {
+                k1 == k2 && u1 == u2
+            },
+            #[cfg(any(target_os = "freebsd", target_os = "linux"))]
+            (SigevThreadId { signal: s1, thread_id: t1, si_value: v1 },
+             SigevThreadId { signal: s2, thread_id: t2, si_value: v2 }) => {
+                s1 == s2 && t1 == t2 && v1 == v2
+            },
+            _ => false,
+        }
+    }
+}
+
 #[cfg(not(target_os = "openbsd"))]
 mod sigevent {
     use libc;
@@ -538,19 +727,19 @@ mod sigevent {
         /// at least those flags don't do anything useful.  That field is part of a
         /// union that shares space with the more genuinely useful fields.
         ///
-        /// **Note:** This constructor also doesn't allow the caller to set the
-        /// `sigev_notify_function` or `sigev_notify_attributes` fields, which are
-        /// required for `SIGEV_THREAD`.  That's considered ok because on no operating
+        /// **Note:** `SigevThread` always leaves `sigev_notify_attributes` null, so
+        /// the notification thread gets the default attributes.  On no operating
         /// system is `SIGEV_THREAD` the most efficient way to deliver AIO
-        /// notification.  FreeBSD and DragonFly BSD programs should prefer `SIGEV_KEVENT`.
-        /// Linux, Solaris, and portable programs should prefer `SIGEV_THREAD_ID` or
-        /// `SIGEV_SIGNAL`.  That field is part of a union that shares space with the
-        /// more genuinely useful `sigev_notify_thread_id`
+        /// notification, since every notification costs a new thread.  FreeBSD and
+        /// DragonFly BSD programs should prefer `SIGEV_KEVENT`.  Linux, Solaris, and
+        /// portable programs should prefer `SIGEV_THREAD_ID` or `SIGEV_SIGNAL`.
         pub fn new(sigev_notify: SigevNotify) -> SigEvent {
             let mut sev = unsafe { mem::zeroed::<libc::sigevent>()};
             sev.sigev_notify = match sigev_notify {
                 SigevNotify::SigevNone => libc::SIGEV_NONE,
                 SigevNotify::SigevSignal{..} => libc::SIGEV_SIGNAL,
+                #[cfg(any(target_os = "freebsd", target_os = "linux"))]
+                SigevNotify::SigevThread{..} => libc::SIGEV_THREAD,
                 #[cfg(any(target_os = "dragonfly", target_os = "freebsd"))]
                 SigevNotify::SigevKevent{..} => libc::SIGEV_KEVENT,
                 #[cfg(target_os = "freebsd")]
@@ -571,6 +760,8 @@ mod sigevent {
             sev.sigev_value.sival_ptr = match sigev_notify {
                 SigevNotify::SigevNone => ptr::null_mut::<libc::c_void>(),
                 SigevNotify::SigevSignal{ si_value, .. } => si_value as *mut libc::c_void,
+                #[cfg(any(target_os = "freebsd", target_os = "linux"))]
+                SigevNotify::SigevThread{ si_value, .. } => si_value as *mut libc::c_void,
                 #[cfg(any(target_os = "dragonfly", target_os = "freebsd"))]
                 SigevNotify::SigevKevent{ udata, .. } => udata as *mut libc::c_void,
                 #[cfg(any(target_os = "freebsd", target_os = "linux"))]
@@ -586,6 +777,15 @@ mod sigevent {
                 SigevNotify::SigevThreadId { thread_id, .. } => thread_id,
                 _ => 0 as type_of_thread_id
             };
+            if let SigevNotify::SigevThread { function, .. } = *sigev_notify {
+                // libc only exposes sigev_notify_thread_id, the first member of
+                // the union that also holds sigev_notify_function and, right
+                // after it, sigev_notify_attributes, which stays null.
+                unsafe {
+                    let p = &mut sev.sigev_notify_thread_id as *mut type_of_thread_id;
+                    ptr::write(p as *mut extern fn(libc::sigval), function);
+                }
+            }
         }
 
         #[cfg(not(any(target_os = "freebsd", target_os = "linux")))]
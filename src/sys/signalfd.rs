@@ -16,13 +16,13 @@
 //! Please note that signal discarding is not specific to `signalfd`, but also happens with regular
 //! signal handlers.
 use libc;
-use unistd;
+use unistd::{self, Fd};
 use {Error, Result};
 use errno::Errno;
 pub use sys::signal::{self, SigSet};
 pub use libc::signalfd_siginfo as siginfo;
 
-use std::os::unix::io::{RawFd, AsRawFd};
+use std::os::unix::io::{RawFd, AsRawFd, FromRawFd, IntoRawFd};
 use std::mem;
 
 
@@ -53,6 +53,12 @@ pub fn signalfd(fd: RawFd, mask: &SigSet, flags: SfdFlags) -> Result<RawFd> {
     }
 }
 
+/// Create a new file descriptor for reading the signals of `mask`, like
+/// [`signalfd`](fn.signalfd.html) with `SIGNALFD_NEW`, and return it owned.
+pub fn signalfd_owned(mask: &SigSet, flags: SfdFlags) -> Result<Fd> {
+    signalfd(SIGNALFD_NEW, mask, flags).map(|fd| unsafe { Fd::from_raw_fd(fd) })
+}
+
 /// A helper struct for creating, reading and closing a `signalfd` instance.
 ///
 /// **Important:** please read the module level documentation about signal discarding before using
@@ -79,7 +85,7 @@ pub fn signalfd(fd: RawFd, mask: &SigSet, flags: SfdFlags) -> Result<RawFd> {
 ///     Err(err) => (), // some error happend
 /// }
 /// ```
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Debug, Eq, Hash, PartialEq)]
 pub struct SignalFd(RawFd);
 
 impl SignalFd {
@@ -121,6 +127,22 @@ impl AsRawFd for SignalFd {
     }
 }
 
+impl IntoRawFd for SignalFd {
+    fn into_raw_fd(self) -> RawFd {
+        let fd = self.0;
+        mem::forget(self);
+        fd
+    }
+}
+
+impl FromRawFd for SignalFd {
+    /// Take ownership of `fd`, which must be an open signalfd not owned by anything
+    /// else.
+    unsafe fn from_raw_fd(fd: RawFd) -> SignalFd {
+        SignalFd(fd)
+    }
+}
+
 impl Iterator for SignalFd {
     type Item = siginfo;
 
@@ -3,8 +3,11 @@ use {Error, Result, NixPath};
 use errno::Errno;
 use libc;
 use std::{fmt, hash, mem, net, ptr, slice};
+#[cfg(feature = "std")]
 use std::ffi::OsStr;
+#[cfg(feature = "std")]
 use std::path::Path;
+#[cfg(feature = "std")]
 use std::os::unix::ffi::OsStrExt;
 #[cfg(any(target_os = "android", target_os = "linux"))]
 use ::sys::socket::addr::netlink::NetlinkAddr;
@@ -321,6 +324,7 @@ impl InetAddr {
         }
     }
 
+    #[cfg(feature = "std")]
     pub fn to_str(&self) -> String {
         format!("{}", self)
     }
@@ -651,8 +655,7 @@ impl UnixAddr {
         unsafe { slice::from_raw_parts(self.0.sun_path.as_ptr() as *const u8, self.1) }
     }
 
-    /// If this address represents a filesystem path, return that path.
-    pub fn path(&self) -> Option<&Path> {
+    fn path_bytes(&self) -> Option<&[u8]> {
         if self.1 == 0 || self.0.sun_path[0] == 0 {
             // unnamed or abstract
             None
@@ -664,10 +667,16 @@ impl UnixAddr {
             // null-terminated length or the full length.
             let ptr = &self.0.sun_path as *const libc::c_char;
             let reallen = unsafe { libc::strnlen(ptr, p.len()) };
-            Some(Path::new(<OsStr as OsStrExt>::from_bytes(&p[..reallen])))
+            Some(&p[..reallen])
         }
     }
 
+    /// If this address represents a filesystem path, return that path.
+    #[cfg(feature = "std")]
+    pub fn path(&self) -> Option<&Path> {
+        self.path_bytes().map(|p| Path::new(<OsStr as OsStrExt>::from_bytes(p)))
+    }
+
     /// If this address represents an abstract socket, return its name.
     ///
     /// For abstract sockets only the bare name is returned, without the
@@ -708,11 +717,11 @@ impl fmt::Display for UnixAddr {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if self.1 == 0 {
             f.write_str("<unbound UNIX socket>")
-        } else if let Some(path) = self.path() {
-            path.display().fmt(f)
+        } else if let Some(path) = self.path_bytes() {
+            ::fmt_lossy(f, path)
         } else {
-            let display = String::from_utf8_lossy(&self.sun_path()[1..]);
-            write!(f, "@{}", display)
+            try!(f.write_str("@"));
+            ::fmt_lossy(f, &self.sun_path()[1..])
         }
     }
 }
@@ -731,6 +740,7 @@ impl fmt::Debug for UnixAddr {
 
 /// Represents a socket address
 #[derive(Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SockAddr {
     Inet(InetAddr),
     Unix(UnixAddr),
@@ -790,6 +800,7 @@ impl SockAddr {
         }
     }
 
+    #[cfg(feature = "std")]
     pub fn to_str(&self) -> String {
         format!("{}", self)
     }
@@ -1029,7 +1040,7 @@ pub mod sys_control {
     const CTL_IOC_INFO: u8 = 3;
     const MAX_KCTL_NAME: usize = 96;
 
-    ioctl!(readwrite ctl_info with CTL_IOC_MAGIC, CTL_IOC_INFO; ctl_ioc_info);
+    ioctl_readwrite!(ctl_info, CTL_IOC_MAGIC, CTL_IOC_INFO, ctl_ioc_info);
 
     #[repr(C)]
     #[derive(Copy, Clone)]
@@ -1356,6 +1367,276 @@ mod datalink {
     }
 }
 
+// Internet addresses serialize like their equivalents in std::net, and the others as the fields
+// that tell them apart
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use libc;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde::de::Error as DeError;
+    use std::{cmp, mem, net, result};
+    use std::os::unix::ffi::OsStrExt;
+    use super::*;
+
+    impl Serialize for InetAddr {
+        fn serialize<S: Serializer>(&self, serializer: S) -> result::Result<S::Ok, S::Error> {
+            self.to_std().serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for InetAddr {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> result::Result<InetAddr, D::Error> {
+            net::SocketAddr::deserialize(deserializer).map(|addr| InetAddr::from_std(&addr))
+        }
+    }
+
+    impl Serialize for IpAddr {
+        fn serialize<S: Serializer>(&self, serializer: S) -> result::Result<S::Ok, S::Error> {
+            let addr = match *self {
+                IpAddr::V4(ref ip) => net::IpAddr::V4(ip.to_std()),
+                IpAddr::V6(ref ip) => net::IpAddr::V6(ip.to_std()),
+            };
+            addr.serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for IpAddr {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> result::Result<IpAddr, D::Error> {
+            Ok(match try!(net::IpAddr::deserialize(deserializer)) {
+                net::IpAddr::V4(ref ip) => IpAddr::V4(Ipv4Addr::from_std(ip)),
+                net::IpAddr::V6(ref ip) => IpAddr::V6(Ipv6Addr::from_std(ip)),
+            })
+        }
+    }
+
+    impl Serialize for Ipv4Addr {
+        fn serialize<S: Serializer>(&self, serializer: S) -> result::Result<S::Ok, S::Error> {
+            self.to_std().serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Ipv4Addr {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> result::Result<Ipv4Addr, D::Error> {
+            net::Ipv4Addr::deserialize(deserializer).map(|ip| Ipv4Addr::from_std(&ip))
+        }
+    }
+
+    impl Serialize for Ipv6Addr {
+        fn serialize<S: Serializer>(&self, serializer: S) -> result::Result<S::Ok, S::Error> {
+            self.to_std().serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Ipv6Addr {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> result::Result<Ipv6Addr, D::Error> {
+            net::Ipv6Addr::deserialize(deserializer).map(|ip| Ipv6Addr::from_std(&ip))
+        }
+    }
+
+    // Paths are kept as bytes, since they needn't be UTF-8
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "UnixAddr")]
+    enum UnixAddrKind {
+        Unnamed,
+        Path(Vec<u8>),
+        Abstract(Vec<u8>),
+    }
+
+    impl Serialize for UnixAddr {
+        fn serialize<S: Serializer>(&self, serializer: S) -> result::Result<S::Ok, S::Error> {
+            let kind = if self.1 == 0 {
+                UnixAddrKind::Unnamed
+            } else if let Some(path) = self.path() {
+                UnixAddrKind::Path(path.as_os_str().as_bytes().to_vec())
+            } else {
+                UnixAddrKind::Abstract(self.sun_path()[1..].to_vec())
+            };
+            kind.serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for UnixAddr {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> result::Result<UnixAddr, D::Error> {
+            let addr = match try!(UnixAddrKind::deserialize(deserializer)) {
+                UnixAddrKind::Unnamed => {
+                    let addr = libc::sockaddr_un {
+                        sun_family: AddressFamily::Unix as sa_family_t,
+                        .. unsafe { mem::zeroed() }
+                    };
+                    Ok(UnixAddr(addr, 0))
+                },
+                UnixAddrKind::Path(path) => UnixAddr::new(&path[..]),
+                #[cfg(any(target_os = "android", target_os = "linux"))]
+                UnixAddrKind::Abstract(name) => UnixAddr::new_abstract(&name),
+                #[cfg(not(any(target_os = "android", target_os = "linux")))]
+                UnixAddrKind::Abstract(_) => Err(Error::UnsupportedOperation),
+            };
+            addr.map_err(D::Error::custom)
+        }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "NetlinkAddr")]
+    struct NetlinkAddrFields {
+        pid: u32,
+        groups: u32,
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    impl Serialize for NetlinkAddr {
+        fn serialize<S: Serializer>(&self, serializer: S) -> result::Result<S::Ok, S::Error> {
+            NetlinkAddrFields {
+                pid: self.pid(),
+                groups: self.groups(),
+            }.serialize(serializer)
+        }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    impl<'de> Deserialize<'de> for NetlinkAddr {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> result::Result<NetlinkAddr, D::Error> {
+            let fields = try!(NetlinkAddrFields::deserialize(deserializer));
+            Ok(NetlinkAddr::new(fields.pid, fields.groups))
+        }
+    }
+
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "SysControlAddr")]
+    struct SysControlAddrFields {
+        id: u32,
+        unit: u32,
+    }
+
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    impl Serialize for SysControlAddr {
+        fn serialize<S: Serializer>(&self, serializer: S) -> result::Result<S::Ok, S::Error> {
+            SysControlAddrFields {
+                id: self.id(),
+                unit: self.unit(),
+            }.serialize(serializer)
+        }
+    }
+
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    impl<'de> Deserialize<'de> for SysControlAddr {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D)
+                                             -> result::Result<SysControlAddr, D::Error> {
+            let fields = try!(SysControlAddrFields::deserialize(deserializer));
+            Ok(SysControlAddr::new(fields.id, fields.unit))
+        }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "LinkAddr")]
+    struct LinkAddrFields {
+        protocol: u16,
+        ifindex: i32,
+        hatype: u16,
+        pkttype: u8,
+        addr: Vec<u8>,
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    impl Serialize for LinkAddr {
+        fn serialize<S: Serializer>(&self, serializer: S) -> result::Result<S::Ok, S::Error> {
+            let len = cmp::min(self.halen(), self.0.sll_addr.len());
+            LinkAddrFields {
+                protocol: self.0.sll_protocol,
+                ifindex: self.0.sll_ifindex as i32,
+                hatype: self.0.sll_hatype,
+                pkttype: self.0.sll_pkttype,
+                addr: self.0.sll_addr[..len].to_vec(),
+            }.serialize(serializer)
+        }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    impl<'de> Deserialize<'de> for LinkAddr {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> result::Result<LinkAddr, D::Error> {
+            let fields = try!(LinkAddrFields::deserialize(deserializer));
+            let mut addr: libc::sockaddr_ll = unsafe { mem::zeroed() };
+            if fields.addr.len() > addr.sll_addr.len() {
+                return Err(D::Error::custom("hardware address too long"));
+            }
+            addr.sll_family = libc::AF_PACKET as _;
+            addr.sll_protocol = fields.protocol;
+            addr.sll_ifindex = fields.ifindex as _;
+            addr.sll_hatype = fields.hatype;
+            addr.sll_pkttype = fields.pkttype;
+            addr.sll_halen = fields.addr.len() as _;
+            addr.sll_addr[..fields.addr.len()].copy_from_slice(&fields.addr);
+            Ok(LinkAddr(addr))
+        }
+    }
+
+    #[cfg(any(target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "macos",
+              target_os = "netbsd",
+              target_os = "openbsd"))]
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "LinkAddr")]
+    struct LinkAddrFields {
+        ifindex: u16,
+        datalink_type: u8,
+        nlen: u8,
+        alen: u8,
+        slen: u8,
+        data: Vec<u8>,
+    }
+
+    #[cfg(any(target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "macos",
+              target_os = "netbsd",
+              target_os = "openbsd"))]
+    impl Serialize for LinkAddr {
+        fn serialize<S: Serializer>(&self, serializer: S) -> result::Result<S::Ok, S::Error> {
+            let len = cmp::min(self.nlen() + self.alen() + self.slen(), self.0.sdl_data.len());
+            LinkAddrFields {
+                ifindex: self.0.sdl_index as u16,
+                datalink_type: self.0.sdl_type,
+                nlen: self.0.sdl_nlen,
+                alen: self.0.sdl_alen,
+                slen: self.0.sdl_slen,
+                data: self.0.sdl_data[..len].iter().map(|&b| b as u8).collect(),
+            }.serialize(serializer)
+        }
+    }
+
+    #[cfg(any(target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "macos",
+              target_os = "netbsd",
+              target_os = "openbsd"))]
+    impl<'de> Deserialize<'de> for LinkAddr {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> result::Result<LinkAddr, D::Error> {
+            let fields = try!(LinkAddrFields::deserialize(deserializer));
+            let mut addr: libc::sockaddr_dl = unsafe { mem::zeroed() };
+            if fields.data.len() > addr.sdl_data.len() {
+                return Err(D::Error::custom("link address too long"));
+            }
+            addr.sdl_len = mem::size_of::<libc::sockaddr_dl>() as _;
+            addr.sdl_family = libc::AF_LINK as _;
+            addr.sdl_index = fields.ifindex as _;
+            addr.sdl_type = fields.datalink_type;
+            addr.sdl_nlen = fields.nlen;
+            addr.sdl_alen = fields.alen;
+            addr.sdl_slen = fields.slen;
+            for (dst, &src) in addr.sdl_data.iter_mut().zip(&fields.data) {
+                *dst = src as _;
+            }
+            Ok(LinkAddr(addr))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(any(target_os = "dragonfly",
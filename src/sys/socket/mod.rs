@@ -6,9 +6,10 @@ use errno::Errno;
 use features;
 use libc::{self, c_void, c_int, socklen_t, size_t};
 use std::{fmt, mem, ptr, slice};
-use std::os::unix::io::RawFd;
+use std::os::unix::io::{FromRawFd, RawFd};
 use sys::time::TimeVal;
 use sys::uio::IoVec;
+use unistd::Fd;
 
 mod addr;
 pub mod sockopt;
@@ -590,6 +591,7 @@ impl<'a> ControlMessage<'a> {
 /// as with sendto.
 ///
 /// Allocates if cmsgs is nonempty.
+#[cfg(feature = "std")]
 pub fn sendmsg<'a>(fd: RawFd, iov: &[IoVec<&'a [u8]>], cmsgs: &[ControlMessage<'a>], flags: MsgFlags, addr: Option<&'a SockAddr>) -> Result<usize> {
     let mut len = 0;
     let mut capacity = 0;
@@ -720,6 +722,11 @@ pub fn socket<T: Into<Option<SockProtocol>>>(domain: AddressFamily, ty: SockType
     Ok(res)
 }
 
+/// Like [`socket`](fn.socket.html), but returns an owned file descriptor.
+pub fn socket_owned<T: Into<Option<SockProtocol>>>(domain: AddressFamily, ty: SockType, flags: SockFlag, protocol: T) -> Result<Fd> {
+    socket(domain, ty, flags, protocol).map(|fd| unsafe { Fd::from_raw_fd(fd) })
+}
+
 /// Create a pair of connected sockets
 ///
 /// [Further reading](http://pubs.opengroup.org/onlinepubs/9699919799/functions/socketpair.html)
@@ -812,13 +819,44 @@ pub fn accept(sockfd: RawFd) -> Result<RawFd> {
     Errno::result(res)
 }
 
+/// Like [`accept`](fn.accept.html), but returns an owned file descriptor.
+pub fn accept_owned(sockfd: RawFd) -> Result<Fd> {
+    accept(sockfd).map(|fd| unsafe { Fd::from_raw_fd(fd) })
+}
+
 /// Accept a connection on a socket
 ///
+/// Where the system doesn't have `accept4` (see
+/// [`features::accept4_available`](../../features/fn.accept4_available.html)),
+/// this calls `accept`.  On Linux kernels without `accept4(2)` it then sets
+/// the flags with `fcntl`, so another thread may fork before `SOCK_CLOEXEC` is
+/// set.  On iOS and macOS `SockFlag` has no flags, so this is just `accept`.
+///
 /// [Further reading](http://man7.org/linux/man-pages/man2/accept.2.html)
 pub fn accept4(sockfd: RawFd, flags: SockFlag) -> Result<RawFd> {
+    #[cfg(any(target_os = "android",
+              target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "linux",
+              target_os = "netbsd",
+              target_os = "openbsd"))]
+    {
+        if features::accept4_available() {
+            let res = unsafe {
+                libc::accept4(sockfd, ptr::null_mut(), ptr::null_mut(), flags.bits())
+            };
+            return Errno::result(res);
+        }
+    }
+
     accept4_polyfill(sockfd, flags)
 }
 
+/// Like [`accept4`](fn.accept4.html), but returns an owned file descriptor.
+pub fn accept4_owned(sockfd: RawFd, flags: SockFlag) -> Result<Fd> {
+    accept4(sockfd, flags).map(|fd| unsafe { Fd::from_raw_fd(fd) })
+}
+
 #[inline]
 fn accept4_polyfill(sockfd: RawFd, flags: SockFlag) -> Result<RawFd> {
     let res = try!(Errno::result(unsafe { libc::accept(sockfd, ptr::null_mut(), ptr::null_mut()) }));
@@ -7,6 +7,7 @@ use fcntl::AtFlags;
 use libc::{self, mode_t};
 use std::mem;
 use std::os::unix::io::RawFd;
+use sys::time::TimeVal;
 
 libc_bitflags!(
     pub struct SFlag: mode_t {
@@ -122,3 +123,282 @@ pub fn fstatat<P: ?Sized + NixPath>(dirfd: RawFd, pathname: &P, f: AtFlags) -> R
     Ok(dst)
 }
 
+
+/// Change the access and modification times of a file (see
+/// [utimes(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/utimes.html)).
+///
+/// Follows symbolic links. Times only have microsecond resolution, but unlike `utimensat(2)` this
+/// is available on every supported platform.
+pub fn utimes<P: ?Sized + NixPath>(path: &P, atime: &TimeVal, mtime: &TimeVal) -> Result<()> {
+    let times: [libc::timeval; 2] = [*atime.as_ref(), *mtime.as_ref()];
+    let res = try!(path.with_nix_path(|cstr| unsafe {
+        libc::utimes(cstr.as_ptr(), &times[0])
+    }));
+
+    Errno::result(res).map(drop)
+}
+
+/// Change the access and modification times of a file without following symbolic links (see
+/// [lutimes(3)](https://www.freebsd.org/cgi/man.cgi?query=lutimes&sektion=3)).
+///
+/// If `path` is a symbolic link, the times of the link itself are changed.
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd"))]
+pub fn lutimes<P: ?Sized + NixPath>(path: &P, atime: &TimeVal, mtime: &TimeVal) -> Result<()> {
+    let times: [libc::timeval; 2] = [*atime.as_ref(), *mtime.as_ref()];
+    let res = try!(path.with_nix_path(|cstr| unsafe {
+        libc::lutimes(cstr.as_ptr(), &times[0])
+    }));
+
+    Errno::result(res).map(drop)
+}
+
+/// Change the access and modification times of an open file (see
+/// [futimes(3)](https://www.freebsd.org/cgi/man.cgi?query=futimes&sektion=3)).
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+pub fn futimes(fd: RawFd, atime: &TimeVal, mtime: &TimeVal) -> Result<()> {
+    let times: [libc::timeval; 2] = [*atime.as_ref(), *mtime.as_ref()];
+    let res = unsafe { libc::futimes(fd, &times[0]) };
+
+    Errno::result(res).map(drop)
+}
+
+/// The integer type used for file flags on this platform.
+#[cfg(any(target_os = "ios", target_os = "macos", target_os = "openbsd"))]
+type FileFlagsT = libc::c_uint;
+/// The integer type used for file flags on this platform.
+#[cfg(any(target_os = "dragonfly", target_os = "freebsd", target_os = "netbsd"))]
+type FileFlagsT = libc::c_ulong;
+
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+libc_bitflags! {
+    /// File flags as used by `chflags(2)` and reported in `FileStat::st_flags`.
+    ///
+    /// The `UF_*` flags may be changed by the owner of the file, while the `SF_*` flags may only
+    /// be changed by the superuser.
+    pub struct FileFlag: FileFlagsT {
+        /// Mask of the flags which may be changed by the file owner.
+        UF_SETTABLE;
+        /// Do not dump the file.
+        UF_NODUMP;
+        /// The file may not be changed.
+        UF_IMMUTABLE;
+        /// The file may only be appended to.
+        UF_APPEND;
+        /// The directory is opaque when viewed through a union mount.
+        UF_OPAQUE;
+        /// The file may not be renamed or deleted.
+        #[cfg(any(target_os = "dragonfly", target_os = "freebsd"))]
+        UF_NOUNLINK;
+        /// The file is compressed.
+        #[cfg(any(target_os = "ios", target_os = "macos"))]
+        UF_COMPRESSED;
+        /// Renames and deletes of the file are tracked.
+        #[cfg(any(target_os = "ios", target_os = "macos"))]
+        UF_TRACKED;
+        /// The file should be hidden from GUI file browsers.
+        #[cfg(any(target_os = "freebsd", target_os = "ios", target_os = "macos"))]
+        UF_HIDDEN;
+        /// Windows system file bit.
+        #[cfg(target_os = "freebsd")]
+        UF_SYSTEM;
+        /// The file is sparse.
+        #[cfg(target_os = "freebsd")]
+        UF_SPARSE;
+        /// The file is offline, or has the Windows offline attribute.
+        #[cfg(target_os = "freebsd")]
+        UF_OFFLINE;
+        /// The file is a Windows reparse point.
+        #[cfg(target_os = "freebsd")]
+        UF_REPARSE;
+        /// The file needs to be archived, like the Windows archive attribute.
+        #[cfg(target_os = "freebsd")]
+        UF_ARCHIVE;
+        /// Windows read only bit.
+        #[cfg(target_os = "freebsd")]
+        UF_READONLY;
+        /// Do not retain history for the file.
+        #[cfg(target_os = "dragonfly")]
+        UF_NOHISTORY;
+        /// Set the file to be cached by swapcache.
+        #[cfg(target_os = "dragonfly")]
+        UF_CACHE;
+        /// Cross-link the file with other files with identical content.
+        #[cfg(target_os = "dragonfly")]
+        UF_XLINK;
+        /// Mask of the flags which may only be changed by the superuser.
+        SF_SETTABLE;
+        /// The file has been archived.
+        SF_ARCHIVED;
+        /// The file may not be changed, even by the superuser.
+        SF_IMMUTABLE;
+        /// The file may only be appended to, even by the superuser.
+        SF_APPEND;
+        /// The file may not be renamed or deleted, even by the superuser.
+        #[cfg(any(target_os = "dragonfly", target_os = "freebsd"))]
+        SF_NOUNLINK;
+        /// The file is a snapshot file.
+        #[cfg(any(target_os = "freebsd", target_os = "netbsd"))]
+        SF_SNAPSHOT;
+        /// The file is a log file.
+        #[cfg(target_os = "netbsd")]
+        SF_LOG;
+        /// The snapshot is invalid.
+        #[cfg(target_os = "netbsd")]
+        SF_SNAPINVAL;
+        /// Do not retain history for the file, even for the superuser.
+        #[cfg(target_os = "dragonfly")]
+        SF_NOHISTORY;
+        /// Set the file to be cached by swapcache, set by the superuser.
+        #[cfg(target_os = "dragonfly")]
+        SF_CACHE;
+        /// Cross-link the file with other files with identical content, set by the superuser.
+        #[cfg(target_os = "dragonfly")]
+        SF_XLINK;
+    }
+}
+
+/// Change the flags of a file (see
+/// [chflags(2)](https://www.freebsd.org/cgi/man.cgi?query=chflags&sektion=2)).
+///
+/// Follows symbolic links. The current flags of a file can be obtained from
+/// `FileStat::st_flags`, e.g. with `FileFlag::from_bits_truncate(stat(path)?.st_flags)`.
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+pub fn chflags<P: ?Sized + NixPath>(path: &P, flags: FileFlag) -> Result<()> {
+    let res = try!(path.with_nix_path(|cstr| unsafe {
+        libc::chflags(cstr.as_ptr(), flags.bits())
+    }));
+
+    Errno::result(res).map(drop)
+}
+
+/// Change the flags of an open file (see
+/// [fchflags(2)](https://www.freebsd.org/cgi/man.cgi?query=chflags&sektion=2)).
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+pub fn fchflags(fd: RawFd, flags: FileFlag) -> Result<()> {
+    let res = unsafe { libc::fchflags(fd, flags.bits()) };
+
+    Errno::result(res).map(drop)
+}
+
+/// Change the flags of a file without following symbolic links (see
+/// [lchflags(2)](https://www.freebsd.org/cgi/man.cgi?query=chflags&sektion=2)).
+///
+/// If `path` is a symbolic link, the flags of the link itself are changed.
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "macos",
+          target_os = "netbsd"))]
+pub fn lchflags<P: ?Sized + NixPath>(path: &P, flags: FileFlag) -> Result<()> {
+    let res = try!(path.with_nix_path(|cstr| unsafe {
+        libc::lchflags(cstr.as_ptr(), flags.bits())
+    }));
+
+    Errno::result(res).map(drop)
+}
+
+/// Serialize and deserialize a `FileStat`, which is defined by libc, as a
+/// field of your own type with `#[serde(with = "nix::sys::stat::serde_file_stat")]`.
+///
+/// Only the fields every platform has are kept.  The others are zero once
+/// deserialized.
+#[cfg(feature = "serde")]
+pub mod serde_file_stat {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::{mem, result};
+    use super::FileStat;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "FileStat")]
+    struct Fields {
+        st_dev: u64,
+        st_ino: u64,
+        st_mode: u32,
+        st_nlink: u64,
+        st_uid: u32,
+        st_gid: u32,
+        st_rdev: u64,
+        st_size: i64,
+        st_blksize: i64,
+        st_blocks: i64,
+        st_atime: i64,
+        st_atime_nsec: i64,
+        st_mtime: i64,
+        st_mtime_nsec: i64,
+        st_ctime: i64,
+        st_ctime_nsec: i64,
+    }
+
+    /// Serialize a `FileStat`
+    pub fn serialize<S: Serializer>(stat: &FileStat, serializer: S)
+                                    -> result::Result<S::Ok, S::Error> {
+        Fields {
+            st_dev: stat.st_dev as u64,
+            st_ino: stat.st_ino as u64,
+            st_mode: stat.st_mode as u32,
+            st_nlink: stat.st_nlink as u64,
+            st_uid: stat.st_uid,
+            st_gid: stat.st_gid,
+            st_rdev: stat.st_rdev as u64,
+            st_size: stat.st_size as i64,
+            st_blksize: stat.st_blksize as i64,
+            st_blocks: stat.st_blocks as i64,
+            st_atime: stat.st_atime as i64,
+            st_atime_nsec: stat.st_atime_nsec as i64,
+            st_mtime: stat.st_mtime as i64,
+            st_mtime_nsec: stat.st_mtime_nsec as i64,
+            st_ctime: stat.st_ctime as i64,
+            st_ctime_nsec: stat.st_ctime_nsec as i64,
+        }.serialize(serializer)
+    }
+
+    /// Deserialize a `FileStat`
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D)
+                                                 -> result::Result<FileStat, D::Error> {
+        let fields = try!(Fields::deserialize(deserializer));
+        let mut stat: FileStat = unsafe { mem::zeroed() };
+        stat.st_dev = fields.st_dev as _;
+        stat.st_ino = fields.st_ino as _;
+        stat.st_mode = fields.st_mode as _;
+        stat.st_nlink = fields.st_nlink as _;
+        stat.st_uid = fields.st_uid;
+        stat.st_gid = fields.st_gid;
+        stat.st_rdev = fields.st_rdev as _;
+        stat.st_size = fields.st_size as _;
+        stat.st_blksize = fields.st_blksize as _;
+        stat.st_blocks = fields.st_blocks as _;
+        stat.st_atime = fields.st_atime as _;
+        stat.st_atime_nsec = fields.st_atime_nsec as _;
+        stat.st_mtime = fields.st_mtime as _;
+        stat.st_mtime_nsec = fields.st_mtime_nsec as _;
+        stat.st_ctime = fields.st_ctime as _;
+        stat.st_ctime_nsec = fields.st_ctime_nsec as _;
+        Ok(stat)
+    }
+}
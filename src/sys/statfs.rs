@@ -20,3 +20,61 @@ pub fn fstatfs<T: AsRawFd>(fd: &T, stat: &mut libc::statfs) -> Result<()> {
         Errno::result(libc::fstatfs(fd.as_raw_fd(), stat)).map(drop)
     }
 }
+
+/// Serialize and deserialize a `libc::statfs` as a field of your own type with
+/// `#[serde(with = "nix::sys::statfs::serde_statfs")]`.
+///
+/// The file system ID isn't kept, and is zero once deserialized.
+#[cfg(feature = "serde")]
+pub mod serde_statfs {
+    use libc;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::{mem, result};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "Statfs")]
+    struct Fields {
+        f_type: i64,
+        f_bsize: i64,
+        f_blocks: u64,
+        f_bfree: u64,
+        f_bavail: u64,
+        f_files: u64,
+        f_ffree: u64,
+        f_namelen: i64,
+        f_frsize: i64,
+    }
+
+    /// Serialize a `libc::statfs`
+    pub fn serialize<S: Serializer>(stat: &libc::statfs, serializer: S)
+                                    -> result::Result<S::Ok, S::Error> {
+        Fields {
+            f_type: stat.f_type as i64,
+            f_bsize: stat.f_bsize as i64,
+            f_blocks: stat.f_blocks as u64,
+            f_bfree: stat.f_bfree as u64,
+            f_bavail: stat.f_bavail as u64,
+            f_files: stat.f_files as u64,
+            f_ffree: stat.f_ffree as u64,
+            f_namelen: stat.f_namelen as i64,
+            f_frsize: stat.f_frsize as i64,
+        }.serialize(serializer)
+    }
+
+    /// Deserialize a `libc::statfs`
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D)
+                                                 -> result::Result<libc::statfs, D::Error> {
+        let fields = try!(Fields::deserialize(deserializer));
+        let mut stat: libc::statfs = unsafe { mem::zeroed() };
+        stat.f_type = fields.f_type as _;
+        stat.f_bsize = fields.f_bsize as _;
+        stat.f_blocks = fields.f_blocks as _;
+        stat.f_bfree = fields.f_bfree as _;
+        stat.f_bavail = fields.f_bavail as _;
+        stat.f_files = fields.f_files as _;
+        stat.f_ffree = fields.f_ffree as _;
+        stat.f_namelen = fields.f_namelen as _;
+        stat.f_frsize = fields.f_frsize as _;
+        Ok(stat)
+    }
+}
@@ -141,6 +141,66 @@ pub fn fstatvfs<T: AsRawFd>(fd: &T) -> Result<Statvfs> {
     }
 }
 
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::{mem, result};
+    use super::Statvfs;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "Statvfs")]
+    struct Fields {
+        f_bsize: u64,
+        f_frsize: u64,
+        f_blocks: u64,
+        f_bfree: u64,
+        f_bavail: u64,
+        f_files: u64,
+        f_ffree: u64,
+        f_favail: u64,
+        f_fsid: u64,
+        f_flag: u64,
+        f_namemax: u64,
+    }
+
+    impl Serialize for Statvfs {
+        fn serialize<S: Serializer>(&self, serializer: S) -> result::Result<S::Ok, S::Error> {
+            Fields {
+                f_bsize: self.0.f_bsize as u64,
+                f_frsize: self.0.f_frsize as u64,
+                f_blocks: self.0.f_blocks as u64,
+                f_bfree: self.0.f_bfree as u64,
+                f_bavail: self.0.f_bavail as u64,
+                f_files: self.0.f_files as u64,
+                f_ffree: self.0.f_ffree as u64,
+                f_favail: self.0.f_favail as u64,
+                f_fsid: self.0.f_fsid as u64,
+                f_flag: self.0.f_flag as u64,
+                f_namemax: self.0.f_namemax as u64,
+            }.serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Statvfs {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> result::Result<Statvfs, D::Error> {
+            let fields = try!(Fields::deserialize(deserializer));
+            let mut stat: Statvfs = unsafe { mem::zeroed() };
+            stat.0.f_bsize = fields.f_bsize as _;
+            stat.0.f_frsize = fields.f_frsize as _;
+            stat.0.f_blocks = fields.f_blocks as _;
+            stat.0.f_bfree = fields.f_bfree as _;
+            stat.0.f_bavail = fields.f_bavail as _;
+            stat.0.f_files = fields.f_files as _;
+            stat.0.f_ffree = fields.f_ffree as _;
+            stat.0.f_favail = fields.f_favail as _;
+            stat.0.f_fsid = fields.f_fsid as _;
+            stat.0.f_flag = fields.f_flag as _;
+            stat.0.f_namemax = fields.f_namemax as _;
+            Ok(stat)
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::fs::File;
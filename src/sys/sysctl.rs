@@ -0,0 +1,149 @@
+//! Query and change kernel state through the sysctl tree (see
+//! [sysctl(3)](https://www.freebsd.org/cgi/man.cgi?query=sysctl&sektion=3)).
+//!
+//! Every value has a numeric name, its Management Information Base (MIB), like
+//! `[CTL_KERN, KERN_OSTYPE]`, and except on OpenBSD also a string name, like
+//! `kern.ostype`.  Looking values up by MIB is faster, but most of them only
+//! have a fixed MIB on some systems.
+use libc::{self, c_int, c_uint, c_void, size_t};
+use std::ffi::CStr;
+use std::{mem, ptr, slice};
+use errno::Errno;
+use {Error, Result};
+#[cfg(not(target_os = "openbsd"))]
+use NixPath;
+
+/// The raw value of a sysctl, as returned by [`sysctl`](fn.sysctl.html)
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SysctlValue(Vec<u8>);
+
+impl SysctlValue {
+    /// The value as bytes, in the layout of its C type
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Convert into the bytes of the value
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+
+    /// The value as a string, up to its nul terminator.
+    ///
+    /// Fails with `EINVAL` if the value isn't nul-terminated.
+    pub fn as_c_str(&self) -> Result<&CStr> {
+        match self.0.iter().position(|&b| b == 0) {
+            Some(nul) => Ok(CStr::from_bytes_with_nul(&self.0[..nul + 1]).unwrap()),
+            None => Err(Error::invalid_argument()),
+        }
+    }
+
+    /// The value as a signed `int`, `long` or `int64_t`.
+    ///
+    /// Fails with `EINVAL` if the value isn't 4 or 8 bytes long.
+    pub fn as_int(&self) -> Result<i64> {
+        // The bytes of a Vec<u8> may not be aligned for the integer
+        match self.0.len() {
+            4 => Ok(unsafe { ptr::read_unaligned(self.0.as_ptr() as *const i32) } as i64),
+            8 => Ok(unsafe { ptr::read_unaligned(self.0.as_ptr() as *const i64) }),
+            _ => Err(Error::invalid_argument()),
+        }
+    }
+
+    /// The value as an unsigned `int`, `long` or `uint64_t`.
+    ///
+    /// Fails with `EINVAL` if the value isn't 4 or 8 bytes long.
+    pub fn as_uint(&self) -> Result<u64> {
+        match self.0.len() {
+            4 => Ok(unsafe { ptr::read_unaligned(self.0.as_ptr() as *const u32) } as u64),
+            8 => Ok(unsafe { ptr::read_unaligned(self.0.as_ptr() as *const u64) }),
+            _ => Err(Error::invalid_argument()),
+        }
+    }
+}
+
+// Read a value with `f(oldp, oldlenp)`, first asking for its size
+fn get<F>(mut f: F) -> Result<SysctlValue>
+    where F: FnMut(*mut c_void, *mut size_t) -> c_int
+{
+    loop {
+        let mut len: size_t = 0;
+        try!(Errno::result(f(ptr::null_mut(), &mut len)));
+
+        let mut buf = Vec::with_capacity(len);
+        match Errno::result(f(buf.as_mut_ptr() as *mut c_void, &mut len)) {
+            Ok(_) => {
+                unsafe { buf.set_len(len) };
+                return Ok(SysctlValue(buf));
+            },
+            // The value grew between the calls, like the process table can
+            Err(Error::Sys(Errno::ENOMEM)) => (),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn int_bytes(value: &c_int) -> &[u8] {
+    unsafe { slice::from_raw_parts(value as *const c_int as *const u8, mem::size_of::<c_int>()) }
+}
+
+/// Get the value with the MIB `mib`.
+///
+/// Fails with `ENOENT` if there's no such value, and with `EISDIR` if it's a
+/// node of the tree, not a leaf.
+pub fn sysctl(mib: &[c_int]) -> Result<SysctlValue> {
+    get(|oldp, oldlenp| unsafe {
+        // Some systems take the MIB and the new value as mutable pointers
+        libc::sysctl(mib.as_ptr() as *mut c_int, mib.len() as c_uint, oldp, oldlenp,
+                     ptr::null_mut(), 0)
+    })
+}
+
+/// Set the value with the MIB `mib` to `value`, which must be in the layout of
+/// its C type.
+///
+/// Requires root for most values.  Fails with `EPERM` if the value is
+/// read-only.
+pub fn sysctl_set(mib: &[c_int], value: &[u8]) -> Result<()> {
+    let res = unsafe {
+        libc::sysctl(mib.as_ptr() as *mut c_int, mib.len() as c_uint, ptr::null_mut(),
+                     ptr::null_mut(), value.as_ptr() as *mut c_void, value.len())
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Set the value with the MIB `mib` to the C `int` `value`.
+pub fn sysctl_set_int(mib: &[c_int], value: c_int) -> Result<()> {
+    sysctl_set(mib, int_bytes(&value))
+}
+
+/// Get the value named `name`, like `kern.ostype`.
+///
+/// Fails with `ENOENT` if there's no such value.
+#[cfg(not(target_os = "openbsd"))]
+pub fn sysctlbyname<P: ?Sized + NixPath>(name: &P) -> Result<SysctlValue> {
+    try!(name.with_nix_path(|name| {
+        get(|oldp, oldlenp| unsafe {
+            libc::sysctlbyname(name.as_ptr(), oldp, oldlenp, ptr::null_mut(), 0)
+        })
+    }))
+}
+
+/// Set the value named `name` to `value`, which must be in the layout of its C
+/// type.
+#[cfg(not(target_os = "openbsd"))]
+pub fn sysctlbyname_set<P: ?Sized + NixPath>(name: &P, value: &[u8]) -> Result<()> {
+    let res = try!(name.with_nix_path(|name| unsafe {
+        libc::sysctlbyname(name.as_ptr(), ptr::null_mut(), ptr::null_mut(),
+                           value.as_ptr() as *mut c_void, value.len())
+    }));
+
+    Errno::result(res).map(drop)
+}
+
+/// Set the value named `name` to the C `int` `value`.
+#[cfg(not(target_os = "openbsd"))]
+pub fn sysctlbyname_set_int<P: ?Sized + NixPath>(name: &P, value: c_int) -> Result<()> {
+    sysctlbyname_set(name, int_bytes(&value))
+}
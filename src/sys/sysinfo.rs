@@ -0,0 +1,78 @@
+//! Overall statistics of the system (see
+//! [sysinfo(2)](http://man7.org/linux/man-pages/man2/sysinfo.2.html)).
+use libc::{self, SI_LOAD_SHIFT};
+use std::{cmp, mem};
+use std::time::Duration;
+use errno::Errno;
+use Result;
+
+/// The statistics of the system, as returned by [`sysinfo`](fn.sysinfo.html)
+// FIXME: Change to repr(transparent)
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct SysInfo(libc::sysinfo);
+
+impl SysInfo {
+    /// The time since the system booted, including the time it was suspended
+    pub fn uptime(&self) -> Duration {
+        // A negative uptime would be a kernel bug
+        Duration::from_secs(cmp::max(self.0.uptime, 0) as u64)
+    }
+
+    /// The average number of runnable processes over the last 1, 5 and 15
+    /// minutes
+    pub fn load_average(&self) -> (f64, f64, f64) {
+        let scale = (1 << SI_LOAD_SHIFT) as f64;
+        (self.0.loads[0] as f64 / scale,
+         self.0.loads[1] as f64 / scale,
+         self.0.loads[2] as f64 / scale)
+    }
+
+    /// The number of processes, that is threads
+    pub fn process_count(&self) -> u16 {
+        self.0.procs
+    }
+
+    /// The usable RAM, in bytes
+    pub fn ram_total(&self) -> u64 {
+        self.scale_mem(self.0.totalram)
+    }
+
+    /// The RAM that's neither used nor in the caches, in bytes
+    pub fn ram_unused(&self) -> u64 {
+        self.scale_mem(self.0.freeram)
+    }
+
+    /// The RAM used by shared memory, in bytes
+    pub fn ram_shared(&self) -> u64 {
+        self.scale_mem(self.0.sharedram)
+    }
+
+    /// The RAM used by the buffers of block devices, in bytes
+    pub fn ram_buffered(&self) -> u64 {
+        self.scale_mem(self.0.bufferram)
+    }
+
+    /// The swap space, in bytes
+    pub fn swap_total(&self) -> u64 {
+        self.scale_mem(self.0.totalswap)
+    }
+
+    /// The swap space that's unused, in bytes
+    pub fn swap_free(&self) -> u64 {
+        self.scale_mem(self.0.freeswap)
+    }
+
+    // The sizes are in units of mem_unit bytes, and c_ulongs on 32-bit targets
+    fn scale_mem<T: Into<u64>>(&self, units: T) -> u64 {
+        units.into() * self.0.mem_unit as u64
+    }
+}
+
+/// Get the statistics of the system.
+pub fn sysinfo() -> Result<SysInfo> {
+    let mut info: libc::sysinfo = unsafe { mem::zeroed() };
+    let res = unsafe { libc::sysinfo(&mut info) };
+
+    Errno::result(res).map(|_| SysInfo(info))
+}
@@ -43,7 +43,7 @@
 //! termios.control_flags |= ControlFlags::CS5;
 //! ```
 
-use Result;
+use {Error, Result};
 use errno::Errno;
 use libc::{self, c_int, tcflag_t};
 use std::cell::{Ref, RefCell};
@@ -53,6 +53,8 @@ use std::os::unix::io::RawFd;
 
 use ::unistd::Pid;
 
+pub use libc::winsize as Winsize;
+
 /// Stores settings for the termios API
 ///
 /// This is a wrapper around the `libc::termios` struct that provides a safe interface for the
@@ -71,6 +73,10 @@ pub struct Termios {
     pub local_flags: LocalFlags,
     /// Control characters (see `termios.c_cc` documentation)
     pub control_chars: [libc::cc_t; libc::NCCS],
+    /// The baud rate used when the `CBAUD` bits of `control_flags` are set to `BOTHER`
+    #[cfg(all(target_os = "linux",
+              not(any(target_arch = "powerpc", target_arch = "powerpc64"))))]
+    custom_speed: libc::speed_t,
 }
 
 impl Termios {
@@ -138,6 +144,9 @@ impl Termios {
             control_flags: ControlFlags::empty(),
             local_flags: LocalFlags::empty(),
             control_chars: [0 as libc::cc_t; NCCS],
+            #[cfg(all(target_os = "linux",
+                      not(any(target_arch = "powerpc", target_arch = "powerpc64"))))]
+            custom_speed: 0,
         }
     }
 
@@ -151,6 +160,78 @@ impl Termios {
         self.local_flags = LocalFlags::from_bits_truncate(termios.c_lflag);
         self.control_chars = termios.c_cc;
     }
+
+    /// Set both the input and output baud rates to an arbitrary value.
+    ///
+    /// Unlike [`cfsetspeed()`](fn.cfsetspeed.html) this isn't restricted to the speeds listed in
+    /// `BaudRate`, which allows using the non-standard rates many serial devices require, such as
+    /// 250000 baud. Whether a given rate is actually supported depends on the driver, and an
+    /// unsupported rate may either produce an error or be rounded when calling `tcsetattr()`.
+    ///
+    /// On Linux this uses the `BOTHER` mechanism, so `tcsetattr()` configures the port with
+    /// `TCSETS2` instead of the classic `termios` interface. On the BSDs and macOS the baud rate
+    /// constants are simply the speed itself.
+    #[cfg(any(target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              all(target_os = "linux",
+                  not(any(target_arch = "powerpc", target_arch = "powerpc64"))),
+              target_os = "macos",
+              target_os = "netbsd",
+              target_os = "openbsd"))]
+    pub fn set_custom_speed(&mut self, speed: u32) -> Result<()> {
+        self.set_custom_speed_inner(speed)
+    }
+
+    #[cfg(all(target_os = "linux",
+              not(any(target_arch = "powerpc", target_arch = "powerpc64"))))]
+    fn set_custom_speed_inner(&mut self, speed: u32) -> Result<()> {
+        let bother = libc::BOTHER as tcflag_t | ((libc::BOTHER as tcflag_t) << libc::IBSHIFT);
+        self.control_flags.remove(ControlFlags::CBAUD | ControlFlags::CIBAUD);
+        self.control_flags.insert(ControlFlags::from_bits_truncate(bother));
+        self.custom_speed = speed as libc::speed_t;
+        Ok(())
+    }
+
+    #[cfg(any(target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "macos",
+              target_os = "netbsd",
+              target_os = "openbsd"))]
+    fn set_custom_speed_inner(&mut self, speed: u32) -> Result<()> {
+        let inner_termios = unsafe { self.get_libc_termios_mut() };
+        let res = unsafe { libc::cfsetspeed(inner_termios, speed as libc::speed_t) };
+        self.update_wrapper();
+        Errno::result(res).map(drop)
+    }
+
+    /// Whether the `CBAUD` bits select the custom speed set with `set_custom_speed()`.
+    #[cfg(all(target_os = "linux",
+              not(any(target_arch = "powerpc", target_arch = "powerpc64"))))]
+    fn uses_custom_speed(&self) -> bool {
+        (self.control_flags & ControlFlags::CBAUD).bits() == libc::BOTHER as tcflag_t
+    }
+
+    /// Converts to the `termios2` structure used by the `TCSETS2` family of ioctls.
+    #[cfg(all(target_os = "linux",
+              not(any(target_arch = "powerpc", target_arch = "powerpc64"))))]
+    fn to_termios2(&self) -> libc::termios2 {
+        let inner_termios = self.get_libc_termios();
+        let mut termios2: libc::termios2 = unsafe { mem::zeroed() };
+        termios2.c_iflag = inner_termios.c_iflag;
+        termios2.c_oflag = inner_termios.c_oflag;
+        termios2.c_cflag = inner_termios.c_cflag;
+        termios2.c_lflag = inner_termios.c_lflag;
+        termios2.c_line = inner_termios.c_line;
+        // The kernel only knows about the leading control characters; the C library pads the
+        // rest of the array.
+        let nccs = termios2.c_cc.len();
+        termios2.c_cc.copy_from_slice(&inner_termios.c_cc[..nccs]);
+        termios2.c_ispeed = self.custom_speed;
+        termios2.c_ospeed = self.custom_speed;
+        termios2
+    }
 }
 
 impl From<libc::termios> for Termios {
@@ -162,6 +243,9 @@ impl From<libc::termios> for Termios {
             control_flags: ControlFlags::from_bits_truncate(termios.c_cflag),
             local_flags: LocalFlags::from_bits_truncate(termios.c_lflag),
             control_chars: termios.c_cc,
+            #[cfg(all(target_os = "linux",
+                      not(any(target_arch = "powerpc", target_arch = "powerpc64"))))]
+            custom_speed: 0,
         }
     }
 }
@@ -255,9 +339,13 @@ libc_enum!{
     }
 }
 
-impl From<libc::speed_t> for BaudRate {
-    fn from(s: libc::speed_t) -> BaudRate {
-
+impl BaudRate {
+    /// Converts a `speed_t` to the matching baud rate.
+    ///
+    /// Fails with `EINVAL` for speeds that have no `BaudRate`, like the ones set with
+    /// [`Termios::set_custom_speed`](struct.Termios.html#method.set_custom_speed).
+    // Not the From trait, since this is fallible
+    pub fn from_speed_t(s: libc::speed_t) -> Result<BaudRate> {
         use libc::{B0, B50, B75, B110, B134, B150, B200, B300, B600, B1200, B1800, B2400, B4800,
                    B9600, B19200, B38400, B57600, B115200, B230400};
         #[cfg(any(target_os = "android", target_os = "linux"))]
@@ -275,7 +363,7 @@ impl From<libc::speed_t> for BaudRate {
                   target_os = "netbsd"))]
         use libc::{B460800, B921600};
 
-        match s {
+        Ok(match s {
             B0 => BaudRate::B0,
             B50 => BaudRate::B50,
             B75 => BaudRate::B75,
@@ -349,8 +437,8 @@ impl From<libc::speed_t> for BaudRate {
             B3500000 => BaudRate::B3500000,
             #[cfg(any(target_os = "android", target_os = "linux"))]
             B4000000 => BaudRate::B4000000,
-            b => unreachable!("Invalid baud constant: {}", b),
-        }
+            _ => return Err(Error::invalid_argument()),
+        })
     }
 }
 
@@ -764,19 +852,23 @@ libc_bitflags! {
 /// Get input baud rate (see
 /// [cfgetispeed(3p)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/cfgetispeed.html)).
 ///
-/// `cfgetispeed()` extracts the input baud rate from the given Termios structure.
-pub fn cfgetispeed(termios: &Termios) -> BaudRate {
+/// `cfgetispeed()` extracts the input baud rate from the given Termios structure. Fails with
+/// `EINVAL` if the port uses a speed that has no `BaudRate`, like one set with
+/// [`Termios::set_custom_speed`](struct.Termios.html#method.set_custom_speed).
+pub fn cfgetispeed(termios: &Termios) -> Result<BaudRate> {
     let inner_termios = termios.get_libc_termios();
-    unsafe { libc::cfgetispeed(&*inner_termios) }.into()
+    BaudRate::from_speed_t(unsafe { libc::cfgetispeed(&*inner_termios) })
 }
 
 /// Get output baud rate (see
 /// [cfgetospeed(3p)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/cfgetospeed.html)).
 ///
-/// `cfgetospeed()` extracts the output baud rate from the given Termios structure.
-pub fn cfgetospeed(termios: &Termios) -> BaudRate {
+/// `cfgetospeed()` extracts the output baud rate from the given Termios structure. Fails with
+/// `EINVAL` if the port uses a speed that has no `BaudRate`, like one set with
+/// [`Termios::set_custom_speed`](struct.Termios.html#method.set_custom_speed).
+pub fn cfgetospeed(termios: &Termios) -> Result<BaudRate> {
     let inner_termios = termios.get_libc_termios();
-    unsafe { libc::cfgetospeed(&*inner_termios) }.into()
+    BaudRate::from_speed_t(unsafe { libc::cfgetospeed(&*inner_termios) })
 }
 
 /// Configures the port to something like the "raw" mode of the old Version 7 terminal driver (see
@@ -840,7 +932,21 @@ pub fn tcgetattr(fd: RawFd) -> Result<Termios> {
 
     try!(Errno::result(res));
 
-    Ok(termios.into())
+    #[allow(unused_mut)]
+    let mut termios: Termios = termios.into();
+
+    // The classic interface can't report custom speeds, so fetch them separately.
+    #[cfg(all(target_os = "linux",
+              not(any(target_arch = "powerpc", target_arch = "powerpc64"))))]
+    {
+        if termios.uses_custom_speed() {
+            let mut termios2: libc::termios2 = unsafe { mem::zeroed() };
+            try!(unsafe { ioctls::tcgets2(fd, &mut termios2) });
+            termios.custom_speed = termios2.c_ospeed;
+        }
+    }
+
+    Ok(termios)
 }
 
 /// Set the configuration for a terminal (see
@@ -850,6 +956,22 @@ pub fn tcgetattr(fd: RawFd) -> Result<Termios> {
 /// takes affect at a time specified by `actions`. Note that this function may return success if
 /// *any* of the parameters were successfully set, not only if all were set successfully.
 pub fn tcsetattr(fd: RawFd, actions: SetArg, termios: &Termios) -> Result<()> {
+    #[cfg(all(target_os = "linux",
+              not(any(target_arch = "powerpc", target_arch = "powerpc64"))))]
+    {
+        if termios.uses_custom_speed() {
+            let termios2 = termios.to_termios2();
+            let res = unsafe {
+                match actions {
+                    SetArg::TCSANOW => ioctls::tcsets2(fd, &termios2),
+                    SetArg::TCSADRAIN => ioctls::tcsetsw2(fd, &termios2),
+                    SetArg::TCSAFLUSH => ioctls::tcsetsf2(fd, &termios2),
+                }
+            };
+            return res.map(drop);
+        }
+    }
+
     let inner_termios = termios.get_libc_termios();
     Errno::result(unsafe { libc::tcsetattr(fd, actions as c_int, &*inner_termios) }).map(drop)
 }
@@ -894,3 +1016,352 @@ pub fn tcgetsid(fd: RawFd) -> Result<Pid> {
 
     Errno::result(res).map(Pid::from_raw)
 }
+
+/// Puts a terminal into raw mode for as long as it is alive.
+///
+/// Creating a `RawModeGuard` saves the current settings of the terminal, applies
+/// [`cfmakeraw()`](fn.cfmakeraw.html) to a copy of them and configures the terminal accordingly.
+/// When the guard is dropped, including while unwinding from a panic, the original settings are
+/// restored.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use nix::sys::termios::{LocalFlags, RawModeGuard};
+/// # fn run() -> nix::Result<()> {
+/// // Raw mode, but keep Ctrl-C working
+/// let guard = RawModeGuard::with(0, |termios| termios.local_flags.insert(LocalFlags::ISIG))?;
+/// // ... read keystrokes from stdin ...
+/// drop(guard);
+/// # Ok(())
+/// # }
+/// ```
+pub struct RawModeGuard {
+    fd: RawFd,
+    original: Termios,
+}
+
+impl RawModeGuard {
+    /// Put the terminal referred to by `fd` into raw mode.
+    pub fn new(fd: RawFd) -> Result<RawModeGuard> {
+        RawModeGuard::with(fd, |_| ())
+    }
+
+    /// Put the terminal referred to by `fd` into raw mode, letting `tweak` adjust the raw
+    /// settings before they are applied.
+    pub fn with<F>(fd: RawFd, tweak: F) -> Result<RawModeGuard>
+        where F: FnOnce(&mut Termios)
+    {
+        let original = try!(tcgetattr(fd));
+        let mut raw = original.clone();
+        cfmakeraw(&mut raw);
+        tweak(&mut raw);
+        try!(tcsetattr(fd, SetArg::TCSAFLUSH, &raw));
+        Ok(RawModeGuard { fd: fd, original: original })
+    }
+
+    /// The settings the terminal had before entering raw mode, which will be restored on drop.
+    pub fn original(&self) -> &Termios {
+        &self.original
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        // There is nothing useful to do with an error here, and panicking could abort the process
+        // if we are already unwinding.
+        let _ = tcsetattr(self.fd, SetArg::TCSAFLUSH, &self.original);
+    }
+}
+
+mod ioctls {
+    use libc;
+
+    ioctl_read_bad!(tiocgwinsz, libc::TIOCGWINSZ, libc::winsize);
+    ioctl_write_ptr_bad!(tiocswinsz, libc::TIOCSWINSZ, libc::winsize);
+    ioctl_write_int_bad!(tiocsctty, libc::TIOCSCTTY);
+    ioctl_read_bad!(fionread, libc::FIONREAD, libc::c_int);
+    #[cfg(any(target_os = "android",
+              target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "linux",
+              target_os = "macos"))]
+    ioctl_read_bad!(tiocoutq, libc::TIOCOUTQ, libc::c_int);
+    ioctl_read_bad!(tiocmget, libc::TIOCMGET, libc::c_int);
+    ioctl_write_ptr_bad!(tiocmset, libc::TIOCMSET, libc::c_int);
+    ioctl_write_ptr_bad!(tiocmbis, libc::TIOCMBIS, libc::c_int);
+    ioctl_write_ptr_bad!(tiocmbic, libc::TIOCMBIC, libc::c_int);
+    #[cfg(target_os = "linux")]
+    ioctl_read_bad!(tiocgetd, libc::TIOCGETD, libc::c_int);
+    #[cfg(target_os = "linux")]
+    ioctl_write_ptr_bad!(tiocsetd, libc::TIOCSETD, libc::c_int);
+    #[cfg(any(target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "linux",
+              target_os = "macos"))]
+    ioctl_none_bad!(tiocnotty, libc::TIOCNOTTY);
+
+    #[cfg(all(target_os = "linux",
+              not(any(target_arch = "powerpc", target_arch = "powerpc64"))))]
+    ioctl_read_bad!(tcgets2, libc::TCGETS2, libc::termios2);
+    #[cfg(all(target_os = "linux",
+              not(any(target_arch = "powerpc", target_arch = "powerpc64"))))]
+    ioctl_write_ptr_bad!(tcsets2, libc::TCSETS2, libc::termios2);
+    #[cfg(all(target_os = "linux",
+              not(any(target_arch = "powerpc", target_arch = "powerpc64"))))]
+    ioctl_write_ptr_bad!(tcsetsw2, libc::TCSETSW2, libc::termios2);
+    #[cfg(all(target_os = "linux",
+              not(any(target_arch = "powerpc", target_arch = "powerpc64"))))]
+    ioctl_write_ptr_bad!(tcsetsf2, libc::TCSETSF2, libc::termios2);
+}
+
+/// Get the window size of a terminal (`TIOCGWINSZ`, see
+/// [tty_ioctl(4)](http://man7.org/linux/man-pages/man4/tty_ioctl.4.html)).
+///
+/// Returns the number of rows and columns, and on some platforms the size in pixels, of the
+/// terminal referred to by `fd`.
+pub fn tcgetwinsize(fd: RawFd) -> Result<Winsize> {
+    let mut winsize: Winsize = unsafe { mem::zeroed() };
+    unsafe { ioctls::tiocgwinsz(fd, &mut winsize) }?;
+    Ok(winsize)
+}
+
+/// Set the window size of a terminal (`TIOCSWINSZ`, see
+/// [tty_ioctl(4)](http://man7.org/linux/man-pages/man4/tty_ioctl.4.html)).
+///
+/// If the size changes, `SIGWINCH` is sent to the foreground process group of the terminal. This
+/// is typically done on the master side of a pseudoterminal when the window of a terminal
+/// emulator is resized.
+pub fn tcsetwinsize(fd: RawFd, winsize: &Winsize) -> Result<()> {
+    unsafe { ioctls::tiocswinsz(fd, winsize) }.map(drop)
+}
+
+/// Make the given terminal the controlling terminal of the calling process (`TIOCSCTTY`, see
+/// [tty_ioctl(4)](http://man7.org/linux/man-pages/man4/tty_ioctl.4.html)).
+///
+/// The calling process must be a session leader without a controlling terminal, e.g. a child that
+/// has just called [`setsid()`](../../unistd/fn.setsid.html). If the terminal is already the
+/// controlling terminal of another session, this fails with `EPERM` unless `steal` is `true` and
+/// the caller has `CAP_SYS_ADMIN`, in which case the terminal is taken away from that session.
+/// `steal` is ignored on platforms other than Linux and Android.
+pub fn tcsetctty(fd: RawFd, steal: bool) -> Result<()> {
+    unsafe { ioctls::tiocsctty(fd, steal as c_int) }.map(drop)
+}
+
+/// Give up the given terminal as the controlling terminal of the calling process (`TIOCNOTTY`,
+/// see [tty_ioctl(4)](http://man7.org/linux/man-pages/man4/tty_ioctl.4.html)).
+///
+/// `fd` must refer to the current controlling terminal. If the caller is the session leader,
+/// `SIGHUP` and `SIGCONT` are sent to the foreground process group and the terminal is detached
+/// from the whole session.
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos"))]
+pub fn tcnotty(fd: RawFd) -> Result<()> {
+    unsafe { ioctls::tiocnotty(fd) }.map(drop)
+}
+
+/// Line disciplines that can be attached to a terminal (see
+/// [tty_ldisc(7)](https://www.kernel.org/doc/html/latest/driver-api/tty/tty_ldisc.html)).
+///
+/// A line discipline sits between the serial driver and the terminal device and interprets the
+/// traffic on the line. `N_TTY` provides the usual terminal behavior, while the others turn the
+/// port into a network interface or a multiplexer for the protocol they implement. Most of them
+/// are only available if the corresponding kernel module is loaded.
+// libc doesn't export these, so their values are taken from linux/tty.h
+#[cfg(target_os = "linux")]
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum LineDiscipline {
+    /// The default terminal line discipline
+    N_TTY = 0,
+    /// Serial Line IP
+    N_SLIP = 1,
+    /// Serial mice
+    N_MOUSE = 2,
+    /// Point-to-Point Protocol, used by `pppd`
+    N_PPP = 3,
+    /// Metricom Starmode radio IP
+    N_STRIP = 4,
+    /// AX.25 packet radio
+    N_AX25 = 5,
+    /// X.25 async
+    N_X25 = 6,
+    /// 6PACK packet radio
+    N_6PACK = 7,
+    /// Mobitex module
+    N_MASC = 8,
+    /// Simatic R3964 module
+    N_R3964 = 9,
+    /// Profibus
+    N_PROFIBUS_FDL = 10,
+    /// Linux IrDA
+    N_IRDA = 11,
+    /// SMS block mode for mobile phones
+    N_SMSBLOCK = 12,
+    /// Asynchronous HDLC
+    N_HDLC = 13,
+    /// Synchronous PPP
+    N_SYNC_PPP = 14,
+    /// Bluetooth HCI UART
+    N_HCI = 15,
+    /// Siemens Gigaset M101 serial DECT adapter
+    N_GIGASET_M101 = 16,
+    /// Serial line CAN interface
+    N_SLCAN = 17,
+    /// Pulse per second, used by NTP servers with GPS receivers
+    N_PPS = 18,
+    /// ITU-T V.253 voice modems
+    N_V253 = 19,
+    /// CAIF protocol for talking to modems
+    N_CAIF = 20,
+    /// GSM 07.10 multiplexing, used by cellular modems
+    N_GSM0710 = 21,
+    /// TI's WL BT, FM and GPS combo chips
+    N_TI_WL = 22,
+    /// Trace data routing for MIPI P1149.7
+    N_TRACESINK = 23,
+    /// Trace data routing for MIPI P1149.7
+    N_TRACEROUTER = 24,
+    /// NFC NCI UART
+    N_NCI = 25,
+    /// Speakup communication with synthesizers
+    N_SPEAKUP = 26,
+    /// Null line discipline, which discards all data
+    N_NULL = 27,
+    /// MCTP over serial
+    N_MCTP = 28,
+    /// Reserved for the development of out-of-tree line disciplines
+    N_DEVELOPMENT = 29,
+    /// ELM327-based OBD-II interfaces
+    N_CAN327 = 30,
+}
+
+#[cfg(target_os = "linux")]
+impl LineDiscipline {
+    fn from_c_int(ldisc: c_int) -> Result<LineDiscipline> {
+        use self::LineDiscipline::*;
+
+        const ALL: [LineDiscipline; 31] = [
+            N_TTY, N_SLIP, N_MOUSE, N_PPP, N_STRIP, N_AX25, N_X25, N_6PACK, N_MASC, N_R3964,
+            N_PROFIBUS_FDL, N_IRDA, N_SMSBLOCK, N_HDLC, N_SYNC_PPP, N_HCI, N_GIGASET_M101, N_SLCAN,
+            N_PPS, N_V253, N_CAIF, N_GSM0710, N_TI_WL, N_TRACESINK, N_TRACEROUTER, N_NCI, N_SPEAKUP,
+            N_NULL, N_MCTP, N_DEVELOPMENT, N_CAN327];
+        ALL.iter().cloned().find(|&l| l as c_int == ldisc).ok_or(::Error::invalid_argument())
+    }
+}
+
+/// Get the line discipline of a terminal (`TIOCGETD`, see
+/// [tty_ioctl(4)](http://man7.org/linux/man-pages/man4/tty_ioctl.4.html)).
+///
+/// Fails with `EINVAL` if the kernel reports a line discipline this version of nix doesn't know
+/// about.
+#[cfg(target_os = "linux")]
+pub fn tcgetldisc(fd: RawFd) -> Result<LineDiscipline> {
+    let mut ldisc: c_int = 0;
+    unsafe { ioctls::tiocgetd(fd, &mut ldisc) }?;
+    LineDiscipline::from_c_int(ldisc)
+}
+
+/// Attach a line discipline to a terminal (`TIOCSETD`, see
+/// [tty_ioctl(4)](http://man7.org/linux/man-pages/man4/tty_ioctl.4.html)).
+///
+/// Anything other than `N_TTY` requires `CAP_NET_ADMIN` on recent kernels, and fails with
+/// `EINVAL` if the line discipline isn't available.
+#[cfg(target_os = "linux")]
+pub fn tcsetldisc(fd: RawFd, ldisc: LineDiscipline) -> Result<()> {
+    let ldisc = ldisc as c_int;
+    unsafe { ioctls::tiocsetd(fd, &ldisc) }.map(drop)
+}
+
+libc_bitflags! {
+    /// State of the modem control lines of a serial port
+    pub struct ModemBits: c_int {
+        /// Line enable
+        TIOCM_LE;
+        /// Data Terminal Ready
+        TIOCM_DTR;
+        /// Request To Send
+        TIOCM_RTS;
+        /// Secondary transmit
+        TIOCM_ST;
+        /// Secondary receive
+        TIOCM_SR;
+        /// Clear To Send
+        TIOCM_CTS;
+        /// Data Carrier Detect
+        TIOCM_CAR;
+        /// Data Carrier Detect, an alias for `TIOCM_CAR`
+        TIOCM_CD;
+        /// Ring Indicator
+        TIOCM_RNG;
+        /// Ring Indicator, an alias for `TIOCM_RNG`
+        TIOCM_RI;
+        /// Data Set Ready
+        TIOCM_DSR;
+    }
+}
+
+/// Get the state of the modem control lines of a serial port (`TIOCMGET`, see
+/// [tty_ioctl(4)](http://man7.org/linux/man-pages/man4/tty_ioctl.4.html)).
+pub fn tcgetmodembits(fd: RawFd) -> Result<ModemBits> {
+    let mut bits: c_int = 0;
+    unsafe { ioctls::tiocmget(fd, &mut bits) }?;
+    Ok(ModemBits::from_bits_truncate(bits))
+}
+
+/// Set the state of the modem control lines of a serial port (`TIOCMSET`, see
+/// [tty_ioctl(4)](http://man7.org/linux/man-pages/man4/tty_ioctl.4.html)).
+///
+/// Only the output lines, such as `TIOCM_DTR` and `TIOCM_RTS`, can be changed. Lines not in
+/// `bits` are cleared.
+pub fn tcsetmodembits(fd: RawFd, bits: ModemBits) -> Result<()> {
+    let bits = bits.bits();
+    unsafe { ioctls::tiocmset(fd, &bits) }.map(drop)
+}
+
+/// Raise the given modem control lines of a serial port, leaving the others alone (`TIOCMBIS`,
+/// see [tty_ioctl(4)](http://man7.org/linux/man-pages/man4/tty_ioctl.4.html)).
+pub fn tcaddmodembits(fd: RawFd, bits: ModemBits) -> Result<()> {
+    let bits = bits.bits();
+    unsafe { ioctls::tiocmbis(fd, &bits) }.map(drop)
+}
+
+/// Lower the given modem control lines of a serial port, leaving the others alone (`TIOCMBIC`,
+/// see [tty_ioctl(4)](http://man7.org/linux/man-pages/man4/tty_ioctl.4.html)).
+///
+/// Briefly lowering `TIOCM_DTR` is a common way of resetting devices attached to a serial port.
+pub fn tcclearmodembits(fd: RawFd, bits: ModemBits) -> Result<()> {
+    let bits = bits.bits();
+    unsafe { ioctls::tiocmbic(fd, &bits) }.map(drop)
+}
+
+/// Get the number of bytes that have been received but not read yet (`FIONREAD`, see
+/// [tty_ioctl(4)](http://man7.org/linux/man-pages/man4/tty_ioctl.4.html)).
+///
+/// Besides terminals, this also works on pipes and sockets.
+pub fn tcinq(fd: RawFd) -> Result<usize> {
+    let mut len: c_int = 0;
+    unsafe { ioctls::fionread(fd, &mut len) }?;
+    Ok(len as usize)
+}
+
+/// Get the number of bytes that have been written but not sent yet (`TIOCOUTQ`, see
+/// [tty_ioctl(4)](http://man7.org/linux/man-pages/man4/tty_ioctl.4.html)).
+///
+/// On Linux this also works on sockets, where it's known as `SIOCOUTQ`.
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos"))]
+pub fn tcoutq(fd: RawFd) -> Result<usize> {
+    let mut len: c_int = 0;
+    unsafe { ioctls::tiocoutq(fd, &mut len) }?;
+    Ok(len as usize)
+}
@@ -1,5 +1,8 @@
-use std::{cmp, fmt, ops};
-use libc::{c_long, time_t, suseconds_t, timespec, timeval};
+use std::{cmp, fmt, ops, ptr};
+use std::time::Duration;
+use libc::{self, c_long, clockid_t, time_t, suseconds_t, timespec, timeval};
+use errno::Errno;
+use Result;
 
 pub trait TimeValLike: Sized {
     #[inline]
@@ -59,6 +62,25 @@ const TS_MAX_SECONDS: i64 = ::std::isize::MAX as i64;
 const TS_MIN_SECONDS: i64 = -TS_MAX_SECONDS;
 
 
+impl From<timespec> for TimeSpec {
+    /// Normalizes `tv_nsec` into `[0, 1_000_000_000)`, so that comparisons are
+    /// correct even for values that didn't come from the kernel.
+    fn from(ts: timespec) -> TimeSpec {
+        let (secs, nanos) = div_mod_floor_64(ts.tv_nsec as i64, NANOS_PER_SEC);
+        TimeSpec(timespec {tv_sec: ts.tv_sec + secs as time_t,
+                           tv_nsec: nanos as c_long })
+    }
+}
+
+impl From<Duration> for TimeSpec {
+    fn from(duration: Duration) -> TimeSpec {
+        assert!(duration.as_secs() <= TS_MAX_SECONDS as u64,
+                "TimeSpec out of bounds; seconds={}", duration.as_secs());
+        TimeSpec(timespec {tv_sec: duration.as_secs() as time_t,
+                           tv_nsec: duration.subsec_nanos() as c_long })
+    }
+}
+
 impl AsRef<timespec> for TimeSpec {
     fn as_ref(&self) -> &timespec {
         &self.0
@@ -150,7 +172,7 @@ impl TimeValLike for TimeSpec {
     }
 
     fn num_microseconds(&self) -> i64 {
-        self.num_nanoseconds() / 1_000_000_000
+        self.num_nanoseconds() / 1_000
     }
 
     fn num_nanoseconds(&self) -> i64 {
@@ -176,6 +198,15 @@ impl TimeSpec {
     pub fn tv_nsec(&self) -> c_long {
         self.0.tv_nsec
     }
+
+    /// Convert to a `Duration`, or return `None` if `self` is negative.
+    pub fn to_duration(&self) -> Option<Duration> {
+        if self.tv_sec() < 0 {
+            None
+        } else {
+            Some(Duration::new(self.tv_sec() as u64, self.tv_nsec() as u32))
+        }
+    }
 }
 
 impl ops::Neg for TimeSpec {
@@ -204,6 +235,18 @@ impl ops::Sub for TimeSpec {
     }
 }
 
+impl ops::AddAssign for TimeSpec {
+    fn add_assign(&mut self, rhs: TimeSpec) {
+        *self = *self + rhs;
+    }
+}
+
+impl ops::SubAssign for TimeSpec {
+    fn sub_assign(&mut self, rhs: TimeSpec) {
+        *self = *self - rhs;
+    }
+}
+
 impl ops::Mul<i32> for TimeSpec {
     type Output = TimeSpec;
 
@@ -270,6 +313,26 @@ const TV_MAX_SECONDS: i64 = ::std::isize::MAX as i64;
 
 const TV_MIN_SECONDS: i64 = -TV_MAX_SECONDS;
 
+impl From<timeval> for TimeVal {
+    /// Normalizes `tv_usec` into `[0, 1_000_000)`, so that comparisons are
+    /// correct even for values that didn't come from the kernel.
+    fn from(tv: timeval) -> TimeVal {
+        let (secs, micros) = div_mod_floor_64(tv.tv_usec as i64, MICROS_PER_SEC);
+        TimeVal(timeval {tv_sec: tv.tv_sec + secs as time_t,
+                         tv_usec: micros as suseconds_t })
+    }
+}
+
+impl From<Duration> for TimeVal {
+    /// Sub-microsecond precision is truncated.
+    fn from(duration: Duration) -> TimeVal {
+        assert!(duration.as_secs() <= TV_MAX_SECONDS as u64,
+                "TimeVal out of bounds; seconds={}", duration.as_secs());
+        TimeVal(timeval {tv_sec: duration.as_secs() as time_t,
+                         tv_usec: (duration.subsec_nanos() / 1_000) as suseconds_t })
+    }
+}
+
 impl AsRef<timeval> for TimeVal {
     fn as_ref(&self) -> &timeval {
         &self.0
@@ -390,6 +453,15 @@ impl TimeVal {
     pub fn tv_usec(&self) -> suseconds_t {
         self.0.tv_usec
     }
+
+    /// Convert to a `Duration`, or return `None` if `self` is negative.
+    pub fn to_duration(&self) -> Option<Duration> {
+        if self.tv_sec() < 0 {
+            None
+        } else {
+            Some(Duration::new(self.tv_sec() as u64, self.tv_usec() as u32 * 1_000))
+        }
+    }
 }
 
 impl ops::Neg for TimeVal {
@@ -418,6 +490,18 @@ impl ops::Sub for TimeVal {
     }
 }
 
+impl ops::AddAssign for TimeVal {
+    fn add_assign(&mut self, rhs: TimeVal) {
+        *self = *self + rhs;
+    }
+}
+
+impl ops::SubAssign for TimeVal {
+    fn sub_assign(&mut self, rhs: TimeVal) {
+        *self = *self - rhs;
+    }
+}
+
 impl ops::Mul<i32> for TimeVal {
     type Output = TimeVal;
 
@@ -466,6 +550,298 @@ impl fmt::Display for TimeVal {
     }
 }
 
+/// Identifies a clock for use with [`clock_gettime`](fn.clock_gettime.html) and friends
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ClockId(clockid_t);
+
+impl ClockId {
+    /// System-wide wall clock time, which can jump when it's set
+    pub const CLOCK_REALTIME: ClockId = ClockId(libc::CLOCK_REALTIME);
+    /// Time since an unspecified starting point, which can't be set and never jumps
+    pub const CLOCK_MONOTONIC: ClockId = ClockId(libc::CLOCK_MONOTONIC);
+    /// Like `CLOCK_MONOTONIC`, but not subject to frequency adjustments by NTP
+    #[cfg(any(target_os = "android",
+              target_os = "ios",
+              target_os = "linux",
+              target_os = "macos"))]
+    pub const CLOCK_MONOTONIC_RAW: ClockId = ClockId(libc::CLOCK_MONOTONIC_RAW);
+    /// Like `CLOCK_MONOTONIC`, but also counting the time the system was suspended
+    #[cfg(any(target_os = "android",
+              target_os = "freebsd",
+              target_os = "linux",
+              target_os = "openbsd"))]
+    pub const CLOCK_BOOTTIME: ClockId = ClockId(libc::CLOCK_BOOTTIME);
+    /// CPU time consumed by all threads of the calling process
+    #[cfg(any(target_os = "android",
+              target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "linux",
+              target_os = "macos",
+              target_os = "openbsd"))]
+    pub const CLOCK_PROCESS_CPUTIME_ID: ClockId = ClockId(libc::CLOCK_PROCESS_CPUTIME_ID);
+    /// CPU time consumed by the calling thread
+    #[cfg(any(target_os = "android",
+              target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "linux",
+              target_os = "macos",
+              target_os = "openbsd"))]
+    pub const CLOCK_THREAD_CPUTIME_ID: ClockId = ClockId(libc::CLOCK_THREAD_CPUTIME_ID);
+
+    /// Creates `ClockId` from raw `clockid_t`.
+    pub fn from_raw(clk_id: clockid_t) -> ClockId {
+        ClockId(clk_id)
+    }
+
+    /// Get the raw `clockid_t` wrapped by `self`.
+    pub fn as_raw(&self) -> clockid_t {
+        self.0
+    }
+}
+
+impl From<ClockId> for clockid_t {
+    fn from(clock_id: ClockId) -> Self {
+        clock_id.0
+    }
+}
+
+/// Get the current time of the given clock (see
+/// [clock_gettime(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/clock_gettime.html)).
+pub fn clock_gettime(clock_id: ClockId) -> Result<TimeSpec> {
+    let mut ts = TimeSpec::zero();
+    let res = unsafe { libc::clock_gettime(clock_id.0, &mut ts.0) };
+
+    Errno::result(res).map(|_| ts)
+}
+
+/// Get the resolution of the given clock (see
+/// [clock_getres(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/clock_getres.html)).
+pub fn clock_getres(clock_id: ClockId) -> Result<TimeSpec> {
+    let mut ts = TimeSpec::zero();
+    let res = unsafe { libc::clock_getres(clock_id.0, &mut ts.0) };
+
+    Errno::result(res).map(|_| ts)
+}
+
+/// Set the time of the given clock (see
+/// [clock_settime(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/clock_settime.html)).
+///
+/// Only `CLOCK_REALTIME` can be set, and setting it requires `CAP_SYS_TIME` or root privileges.
+pub fn clock_settime(clock_id: ClockId, timespec: &TimeSpec) -> Result<()> {
+    let res = unsafe { libc::clock_settime(clock_id.0, timespec.as_ref()) };
+
+    Errno::result(res).map(drop)
+}
+
+/// Get the clock that measures the CPU time consumed by the given process (see
+/// [clock_getcpuclockid(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/clock_getcpuclockid.html)).
+///
+/// The returned clock can be passed to [`clock_gettime`](fn.clock_gettime.html) to read the
+/// process's CPU time.  Reading the clock of another process may require privileges.
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "linux"))]
+pub fn clock_getcpuclockid(pid: ::unistd::Pid) -> Result<ClockId> {
+    let mut clk_id: clockid_t = unsafe { ::std::mem::zeroed() };
+    let res = unsafe { libc::clock_getcpuclockid(pid.into(), &mut clk_id) };
+
+    Errno::result_from_code(res).map(|_| ClockId(clk_id))
+}
+
+/// The obsolete timezone argument of [`gettimeofday`](fn.gettimeofday.html) and
+/// [`settimeofday`](fn.settimeofday.html)
+///
+/// Only the kernel's notion of the timezone is stored here; it isn't used for converting times to
+/// local time, which is what the `TZ` environment variable and the timezone database are for.  On
+/// Linux, the first `settimeofday` call that sets a timezone also tells the kernel that the
+/// hardware clock keeps local time rather than UTC.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TimeZone {
+    /// Minutes west of Greenwich
+    pub tz_minuteswest: libc::c_int,
+    /// The type of daylight saving time correction
+    pub tz_dsttime: libc::c_int,
+}
+
+/// Get the current wall clock time and the kernel's timezone (see
+/// [gettimeofday(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/gettimeofday.html)).
+///
+/// New code should prefer [`clock_gettime`](fn.clock_gettime.html) with `CLOCK_REALTIME`, which
+/// has nanosecond resolution.
+pub fn gettimeofday() -> Result<(TimeVal, TimeZone)> {
+    let mut tv = TimeVal::zero();
+    let mut tz = TimeZone::default();
+    let res = unsafe {
+        libc::gettimeofday(&mut tv.0, &mut tz as *mut TimeZone as *mut _)
+    };
+
+    Errno::result(res).map(|_| (tv, tz))
+}
+
+/// Set the wall clock time and optionally the kernel's timezone (see
+/// [settimeofday(2)](http://man7.org/linux/man-pages/man2/settimeofday.2.html)).
+///
+/// Setting the time requires `CAP_SYS_TIME` or root privileges.  Passing `None` for `tz` leaves
+/// the kernel's timezone unchanged.
+pub fn settimeofday(tv: &TimeVal, tz: Option<&TimeZone>) -> Result<()> {
+    let tz_ptr = match tz {
+        Some(tz) => tz as *const TimeZone as *const _,
+        None => ptr::null(),
+    };
+    let res = unsafe { libc::settimeofday(tv.as_ref(), tz_ptr) };
+
+    Errno::result(res).map(drop)
+}
+
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+libc_enum!{
+    /// The interval timers of a process, for use with [`setitimer`](fn.setitimer.html)
+    #[repr(i32)]
+    pub enum IntervalTimer {
+        /// Counts down in real time, and delivers `SIGALRM` on expiration
+        ITIMER_REAL,
+        /// Counts down while the process is running in user mode, and delivers `SIGVTALRM` on
+        /// expiration
+        ITIMER_VIRTUAL,
+        /// Counts down while the process is running in user or kernel mode, and delivers `SIGPROF`
+        /// on expiration.  Profilers use it to sample where a process spends its time.
+        ITIMER_PROF,
+    }
+}
+
+/// The setting of an interval timer
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ItimerSpec {
+    /// The interval with which the timer is restarted after it expires, or zero for a one-shot
+    /// timer
+    pub interval: TimeVal,
+    /// The time until the next expiration, or zero for a disarmed timer
+    pub value: TimeVal,
+}
+
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+impl ItimerSpec {
+    fn to_itimerval(&self) -> libc::itimerval {
+        libc::itimerval {
+            it_interval: *self.interval.as_ref(),
+            it_value: *self.value.as_ref(),
+        }
+    }
+
+    fn from_itimerval(itv: &libc::itimerval) -> ItimerSpec {
+        ItimerSpec {
+            interval: TimeVal::from(itv.it_interval),
+            value: TimeVal::from(itv.it_value),
+        }
+    }
+}
+
+/// Arm or disarm an interval timer of the calling process, returning its previous setting (see
+/// [setitimer(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/setitimer.html)).
+///
+/// A zero `value` disarms the timer.
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+pub fn setitimer(which: IntervalTimer, spec: &ItimerSpec) -> Result<ItimerSpec> {
+    let new = spec.to_itimerval();
+    let mut old: libc::itimerval = unsafe { ::std::mem::zeroed() };
+    let res = unsafe { libc::setitimer(which as libc::c_int, &new, &mut old) };
+
+    Errno::result(res).map(|_| ItimerSpec::from_itimerval(&old))
+}
+
+/// Get the current setting of an interval timer of the calling process (see
+/// [getitimer(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/getitimer.html)).
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+pub fn getitimer(which: IntervalTimer) -> Result<ItimerSpec> {
+    let mut itv: libc::itimerval = unsafe { ::std::mem::zeroed() };
+    let res = unsafe { libc::getitimer(which as libc::c_int, &mut itv) };
+
+    Errno::result(res).map(|_| ItimerSpec::from_itimerval(&itv))
+}
+
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd"))]
+libc_bitflags! {
+    /// Flags for [`clock_nanosleep`](fn.clock_nanosleep.html)
+    pub struct ClockNanosleepFlags: libc::c_int {
+        /// Interpret the requested time as an absolute value of the clock rather than as an
+        /// interval
+        TIMER_ABSTIME;
+    }
+}
+
+/// Suspend the calling thread until the requested time has elapsed on the given clock (see
+/// [clock_nanosleep(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/clock_nanosleep.html)).
+///
+/// With `TIMER_ABSTIME`, the thread sleeps until the clock reaches `request`. Periodic loops
+/// should sleep until absolute deadlines on `CLOCK_MONOTONIC`, so that the time spent between
+/// sleeps doesn't make them drift.
+///
+/// Returns `Ok(None)` once the requested time has elapsed. If the sleep is interrupted by a signal
+/// handler, returns `Ok(Some(t))`, where passing `t` along with the same clock and flags resumes
+/// the sleep: for relative sleeps `t` is the remaining time, and for absolute sleeps it's the
+/// original deadline.
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd"))]
+pub fn clock_nanosleep(clock_id: ClockId, flags: ClockNanosleepFlags, request: &TimeSpec)
+    -> Result<Option<TimeSpec>>
+{
+    let mut remain = TimeSpec::zero();
+    let res = unsafe {
+        libc::clock_nanosleep(clock_id.0, flags.bits(), request.as_ref(), &mut remain.0)
+    };
+
+    match Errno::result_from_code(res) {
+        Ok(()) => Ok(None),
+        Err(::Error::Sys(Errno::EINTR)) if flags.contains(ClockNanosleepFlags::TIMER_ABSTIME) => {
+            Ok(Some(*request))
+        },
+        Err(::Error::Sys(Errno::EINTR)) => Ok(Some(remain)),
+        Err(e) => Err(e),
+    }
+}
+
 #[inline]
 fn div_mod_floor_64(this: i64, other: i64) -> (i64, i64) {
     (div_floor_64(this, other), mod_floor_64(this, other))
@@ -494,9 +870,72 @@ fn div_rem_64(this: i64, other: i64) -> (i64, i64) {
     (this / other, this % other)
 }
 
+// TimeSpec and TimeVal serialize as their two fields, normalized again when deserialized
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use libc::{c_long, time_t, suseconds_t, timespec, timeval};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::result;
+    use super::{TimeSpec, TimeVal};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "TimeSpec")]
+    struct TimeSpecFields {
+        tv_sec: i64,
+        tv_nsec: i64,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "TimeVal")]
+    struct TimeValFields {
+        tv_sec: i64,
+        tv_usec: i64,
+    }
+
+    impl Serialize for TimeSpec {
+        fn serialize<S: Serializer>(&self, serializer: S) -> result::Result<S::Ok, S::Error> {
+            TimeSpecFields {
+                tv_sec: self.tv_sec() as i64,
+                tv_nsec: self.tv_nsec() as i64,
+            }.serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for TimeSpec {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> result::Result<TimeSpec, D::Error> {
+            let fields = try!(TimeSpecFields::deserialize(deserializer));
+            Ok(TimeSpec::from(timespec {
+                tv_sec: fields.tv_sec as time_t,
+                tv_nsec: fields.tv_nsec as c_long,
+            }))
+        }
+    }
+
+    impl Serialize for TimeVal {
+        fn serialize<S: Serializer>(&self, serializer: S) -> result::Result<S::Ok, S::Error> {
+            TimeValFields {
+                tv_sec: self.tv_sec() as i64,
+                tv_usec: self.tv_usec() as i64,
+            }.serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for TimeVal {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> result::Result<TimeVal, D::Error> {
+            let fields = try!(TimeValFields::deserialize(deserializer));
+            Ok(TimeVal::from(timeval {
+                tv_sec: fields.tv_sec as time_t,
+                tv_usec: fields.tv_usec as suseconds_t,
+            }))
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::{TimeSpec, TimeVal, TimeValLike};
+    use libc::{timespec, timeval};
+    use std::time::Duration;
 
     #[test]
     pub fn test_timespec() {
@@ -534,6 +973,43 @@ mod test {
         assert_eq!(TimeSpec::seconds(-86401).to_string(), "-86401 seconds");
     }
 
+    #[test]
+    pub fn test_timespec_num() {
+        let ts = TimeSpec::seconds(2) + TimeSpec::nanoseconds(3_004_005);
+        assert_eq!(ts.num_seconds(), 2);
+        assert_eq!(ts.num_milliseconds(), 2_003);
+        assert_eq!(ts.num_microseconds(), 2_003_004);
+        assert_eq!(ts.num_nanoseconds(), 2_003_004_005);
+    }
+
+    #[test]
+    pub fn test_timespec_assign() {
+        let mut ts = TimeSpec::seconds(1);
+        ts += TimeSpec::milliseconds(1_500);
+        assert_eq!(ts, TimeSpec::milliseconds(2_500));
+        ts -= TimeSpec::seconds(3);
+        assert_eq!(ts, TimeSpec::milliseconds(-500));
+    }
+
+    #[test]
+    pub fn test_timespec_from_timespec() {
+        // Out of range tv_nsec values are normalized
+        let ts = TimeSpec::from(timespec { tv_sec: 1, tv_nsec: 1_500_000_000 });
+        assert_eq!(ts, TimeSpec::milliseconds(2_500));
+        let ts = TimeSpec::from(timespec { tv_sec: 1, tv_nsec: -1 });
+        assert_eq!(ts, TimeSpec::nanoseconds(999_999_999));
+    }
+
+    #[test]
+    pub fn test_timespec_duration() {
+        let duration = Duration::new(3, 700);
+        let ts = TimeSpec::from(duration);
+        assert_eq!(ts, TimeSpec::seconds(3) + TimeSpec::nanoseconds(700));
+        assert_eq!(ts.to_duration(), Some(duration));
+        assert_eq!(TimeSpec::zero().to_duration(), Some(Duration::new(0, 0)));
+        assert_eq!(TimeSpec::nanoseconds(-1).to_duration(), None);
+    }
+
     #[test]
     pub fn test_timeval() {
         assert!(TimeVal::seconds(1) != TimeVal::zero());
@@ -560,6 +1036,32 @@ mod test {
         assert_eq!(a, -b);
     }
 
+    #[test]
+    pub fn test_timeval_assign() {
+        let mut tv = TimeVal::seconds(1);
+        tv += TimeVal::milliseconds(1_500);
+        assert_eq!(tv, TimeVal::milliseconds(2_500));
+        tv -= TimeVal::seconds(3);
+        assert_eq!(tv, TimeVal::milliseconds(-500));
+    }
+
+    #[test]
+    pub fn test_timeval_from_timeval() {
+        let tv = TimeVal::from(timeval { tv_sec: 1, tv_usec: 1_500_000 });
+        assert_eq!(tv, TimeVal::milliseconds(2_500));
+        let tv = TimeVal::from(timeval { tv_sec: 1, tv_usec: -1 });
+        assert_eq!(tv, TimeVal::microseconds(999_999));
+    }
+
+    #[test]
+    pub fn test_timeval_duration() {
+        // Sub-microsecond precision is lost
+        let tv = TimeVal::from(Duration::new(3, 4_700));
+        assert_eq!(tv, TimeVal::seconds(3) + TimeVal::microseconds(4));
+        assert_eq!(tv.to_duration(), Some(Duration::new(3, 4_000)));
+        assert_eq!(TimeVal::microseconds(-1).to_duration(), None);
+    }
+
     #[test]
     pub fn test_timeval_fmt() {
         assert_eq!(TimeVal::zero().to_string(), "0 seconds");
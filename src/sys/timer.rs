@@ -0,0 +1,159 @@
+//! POSIX per-process timers (see
+//! [timer_create(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/timer_create.html)).
+//!
+//! Unlike the interval timers of `setitimer`, a process can create any number
+//! of these timers, on any clock, and choose how each one notifies it when it
+//! expires.
+//!
+//! # Examples
+//!
+//! Deliver `SIGALRM` every 500 microseconds:
+//!
+//! ```no_run
+//! # use nix::sys::signal::{SigevNotify, Signal};
+//! # use nix::sys::time::{ClockId, TimeSpec, TimeValLike};
+//! # use nix::sys::timer::{Expiration, Timer, TimerSetTimeFlags};
+//! # fn run() -> nix::Result<()> {
+//! let notify = SigevNotify::SigevSignal { signal: Signal::SIGALRM, si_value: 0 };
+//! let mut timer = Timer::new(ClockId::CLOCK_MONOTONIC, notify)?;
+//! timer.set(Expiration::Interval(TimeSpec::microseconds(500)), TimerSetTimeFlags::empty())?;
+//! # Ok(())
+//! # }
+//! ```
+use libc;
+use std::mem;
+use std::ptr;
+use errno::Errno;
+use sys::signal::{SigevNotify, SigEvent};
+use sys::time::{ClockId, TimeSpec, TimeValLike};
+use Result;
+
+/// When a timer expires
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Expiration {
+    /// Expire once, after the given time
+    OneShot(TimeSpec),
+    /// Expire for the first time after the first value, and then periodically
+    /// with the second value as the interval
+    IntervalDelayed(TimeSpec, TimeSpec),
+    /// Expire periodically with the given interval, starting one interval from
+    /// now
+    Interval(TimeSpec),
+}
+
+impl Expiration {
+    // FIXME: Switch these over to use pub(crate)
+    #[doc(hidden)]
+    pub fn to_itimerspec(&self) -> libc::itimerspec {
+        let (interval, value) = match *self {
+            Expiration::OneShot(value) => (TimeSpec::zero(), value),
+            Expiration::IntervalDelayed(value, interval) => (interval, value),
+            Expiration::Interval(interval) => (interval, interval),
+        };
+        libc::itimerspec {
+            it_interval: *interval.as_ref(),
+            it_value: *value.as_ref(),
+        }
+    }
+
+    /// Returns `None` for a disarmed timer.
+    #[doc(hidden)]
+    pub fn from_itimerspec(spec: &libc::itimerspec) -> Option<Expiration> {
+        let interval = TimeSpec::from(spec.it_interval);
+        let value = TimeSpec::from(spec.it_value);
+        if value == TimeSpec::zero() {
+            None
+        } else if interval == TimeSpec::zero() {
+            Some(Expiration::OneShot(value))
+        } else if interval == value {
+            Some(Expiration::Interval(interval))
+        } else {
+            Some(Expiration::IntervalDelayed(value, interval))
+        }
+    }
+}
+
+libc_bitflags! {
+    /// Flags for [`Timer::set`](struct.Timer.html#method.set)
+    pub struct TimerSetTimeFlags: libc::c_int {
+        /// Interpret the initial expiration as an absolute value of the
+        /// timer's clock rather than as an interval from now
+        TIMER_ABSTIME;
+    }
+}
+
+/// An owned POSIX timer, which is deleted when dropped.
+#[derive(Debug)]
+pub struct Timer(libc::timer_t);
+
+impl Timer {
+    /// Create a new timer on the given clock (see
+    /// [timer_create(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/timer_create.html)).
+    ///
+    /// The timer is created disarmed. `sigev_notify` selects how the process
+    /// is notified of expirations, and `SigevNone` can be used to poll the
+    /// timer with [`get`](#method.get) instead.
+    pub fn new(clock_id: ClockId, sigev_notify: SigevNotify) -> Result<Timer> {
+        let mut sigevent = SigEvent::new(sigev_notify).sigevent();
+        let mut timer: libc::timer_t = unsafe { mem::zeroed() };
+        let res = unsafe {
+            libc::timer_create(clock_id.as_raw(), &mut sigevent, &mut timer)
+        };
+
+        Errno::result(res).map(|_| Timer(timer))
+    }
+
+    /// Arm the timer, replacing any previous setting (see
+    /// [timer_settime(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/timer_settime.html)).
+    pub fn set(&mut self, expiration: Expiration, flags: TimerSetTimeFlags) -> Result<()> {
+        let spec = expiration.to_itimerspec();
+        let res = unsafe {
+            libc::timer_settime(self.0, flags.bits(), &spec, ptr::null_mut())
+        };
+
+        Errno::result(res).map(drop)
+    }
+
+    /// Disarm the timer.
+    pub fn unset(&mut self) -> Result<()> {
+        let spec: libc::itimerspec = unsafe { mem::zeroed() };
+        let res = unsafe {
+            libc::timer_settime(self.0, 0, &spec, ptr::null_mut())
+        };
+
+        Errno::result(res).map(drop)
+    }
+
+    /// Get the time until the next expiration of the timer, and its interval
+    /// (see
+    /// [timer_gettime(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/timer_gettime.html)).
+    ///
+    /// The initial expiration is always returned as a relative value, and
+    /// `None` is returned if the timer is disarmed.
+    pub fn get(&self) -> Result<Option<Expiration>> {
+        let mut spec: libc::itimerspec = unsafe { mem::zeroed() };
+        let res = unsafe { libc::timer_gettime(self.0, &mut spec) };
+
+        Errno::result(res).map(|_| Expiration::from_itimerspec(&spec))
+    }
+
+    /// Get the number of expirations that were missed since the last
+    /// notification was delivered (see
+    /// [timer_getoverrun(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/timer_getoverrun.html)).
+    ///
+    /// A signal is only queued once however often the timer expires while the
+    /// signal is pending, so this tells how many expirations it stands for.
+    pub fn overruns(&self) -> Result<libc::c_int> {
+        let res = unsafe { libc::timer_getoverrun(self.0) };
+
+        Errno::result(res)
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        // Deleting a timer also disarms it.  The only possible error is EINVAL
+        // for an invalid timer, which can't happen for a timer we own.
+        unsafe { libc::timer_delete(self.0) };
+    }
+}
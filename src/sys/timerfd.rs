@@ -0,0 +1,151 @@
+//! Timers that notify through a file descriptor (see
+//! [timerfd_create(2)](http://man7.org/linux/man-pages/man2/timerfd_create.2.html)).
+//!
+//! A `TimerFd` becomes readable when it expires, so it can be waited on with
+//! `poll`, `select` or `epoll` together with other file descriptors.  Reading
+//! it returns the number of expirations since the last read.
+//!
+//! # Examples
+//!
+//! Wait for a periodic timer to expire three times:
+//!
+//! ```
+//! # use nix::sys::time::{ClockId, TimeSpec, TimeValLike};
+//! # use nix::sys::timer::Expiration;
+//! # use nix::sys::timerfd::{TimerFd, TimerFlags, TimerSetTimeFlags};
+//! let mut timer = TimerFd::new(ClockId::CLOCK_MONOTONIC, TimerFlags::empty()).unwrap();
+//! timer.set(Expiration::Interval(TimeSpec::milliseconds(1)), TimerSetTimeFlags::empty())
+//!     .unwrap();
+//! let mut expirations = 0;
+//! while expirations < 3 {
+//!     expirations += timer.read_expirations().unwrap().unwrap();
+//! }
+//! ```
+use libc;
+use unistd;
+use {Error, Result};
+use errno::Errno;
+use sys::time::ClockId;
+pub use sys::timer::Expiration;
+
+use std::os::unix::io::{RawFd, AsRawFd, FromRawFd, IntoRawFd};
+use std::{mem, ptr, slice};
+
+libc_bitflags!{
+    /// Flags for [`TimerFd::new`](struct.TimerFd.html#method.new)
+    pub struct TimerFlags: libc::c_int {
+        TFD_NONBLOCK;
+        TFD_CLOEXEC;
+    }
+}
+
+libc_bitflags!{
+    /// Flags for [`TimerFd::set`](struct.TimerFd.html#method.set)
+    pub struct TimerSetTimeFlags: libc::c_int {
+        /// Interpret the initial expiration as an absolute value of the
+        /// timer's clock rather than as an interval from now
+        TFD_TIMER_ABSTIME;
+        /// Together with `TFD_TIMER_ABSTIME` on a `CLOCK_REALTIME` timer, make
+        /// reads fail with `ECANCELED` when the clock is set discontinuously
+        TFD_TIMER_CANCEL_ON_SET;
+    }
+}
+
+/// An owned `timerfd` instance, which is closed when dropped.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct TimerFd(RawFd);
+
+impl TimerFd {
+    /// Create a new, disarmed timer on the given clock.
+    ///
+    /// Only `CLOCK_REALTIME`, `CLOCK_MONOTONIC` and `CLOCK_BOOTTIME` (and
+    /// their `_ALARM` variants) are supported.
+    pub fn new(clock_id: ClockId, flags: TimerFlags) -> Result<TimerFd> {
+        let fd = unsafe { libc::timerfd_create(clock_id.as_raw(), flags.bits()) };
+
+        Errno::result(fd).map(TimerFd)
+    }
+
+    /// Arm the timer, replacing any previous setting.
+    pub fn set(&mut self, expiration: Expiration, flags: TimerSetTimeFlags) -> Result<()> {
+        let spec = expiration.to_itimerspec();
+        let res = unsafe {
+            libc::timerfd_settime(self.0, flags.bits(), &spec, ptr::null_mut())
+        };
+
+        Errno::result(res).map(drop)
+    }
+
+    /// Disarm the timer.
+    pub fn unset(&mut self) -> Result<()> {
+        let spec: libc::itimerspec = unsafe { mem::zeroed() };
+        let res = unsafe {
+            libc::timerfd_settime(self.0, 0, &spec, ptr::null_mut())
+        };
+
+        Errno::result(res).map(drop)
+    }
+
+    /// Get the time until the next expiration of the timer, and its interval.
+    ///
+    /// The initial expiration is always returned as a relative value, and
+    /// `None` is returned if the timer is disarmed.
+    pub fn get(&self) -> Result<Option<Expiration>> {
+        let mut spec: libc::itimerspec = unsafe { mem::zeroed() };
+        let res = unsafe { libc::timerfd_gettime(self.0, &mut spec) };
+
+        Errno::result(res).map(|_| Expiration::from_itimerspec(&spec))
+    }
+
+    /// Read the number of times the timer has expired since it was armed or
+    /// last read.
+    ///
+    /// Blocks until the timer expires, unless the `TimerFd` was created with
+    /// `TFD_NONBLOCK`, in which case `None` is returned if it hasn't expired
+    /// yet.
+    pub fn read_expirations(&mut self) -> Result<Option<u64>> {
+        let mut count: u64 = 0;
+        let res = {
+            let buffer = unsafe {
+                slice::from_raw_parts_mut(&mut count as *mut u64 as *mut u8,
+                                          mem::size_of::<u64>())
+            };
+            unistd::read(self.0, buffer)
+        };
+
+        match res {
+            Ok(8) => Ok(Some(count)),
+            Ok(_) => unreachable!("partial read on timerfd"),
+            Err(Error::Sys(Errno::EAGAIN)) => Ok(None),
+            Err(error) => Err(error)
+        }
+    }
+}
+
+impl Drop for TimerFd {
+    fn drop(&mut self) {
+        let _ = unistd::close(self.0);
+    }
+}
+
+impl AsRawFd for TimerFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+impl IntoRawFd for TimerFd {
+    fn into_raw_fd(self) -> RawFd {
+        let fd = self.0;
+        mem::forget(self);
+        fd
+    }
+}
+
+impl FromRawFd for TimerFd {
+    /// Take ownership of `fd`, which must be an open timerfd not owned by anything
+    /// else.
+    unsafe fn from_raw_fd(fd: RawFd) -> TimerFd {
+        TimerFd(fd)
+    }
+}
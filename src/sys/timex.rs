@@ -0,0 +1,302 @@
+//! Steer the kernel clock (see
+//! [adjtimex(2)](http://man7.org/linux/man-pages/man2/adjtimex.2.html)).
+//!
+//! This is the interface NTP and PTP daemons use to discipline the system
+//! clock: rather than stepping it, they slew it by adjusting its frequency
+//! and phase.  Reading the current state is unprivileged, while changing it
+//! requires `CAP_SYS_TIME`.
+//!
+//! # Examples
+//!
+//! ```
+//! # use nix::sys::timex::{adjtimex, Timex, TimexStatus};
+//! let mut timex = Timex::default();
+//! adjtimex(&mut timex).unwrap();
+//! if timex.status().contains(TimexStatus::STA_UNSYNC) {
+//!     println!("the clock isn't synchronized");
+//! }
+//! ```
+use libc::{self, c_int, c_uint};
+use std::mem;
+use errno::Errno;
+use sys::time::{ClockId, TimeSpec, TimeValLike};
+use Result;
+
+libc_bitflags!(
+    /// Which fields of a [`Timex`](struct.Timex.html) are applied to the clock
+    pub struct AdjFlags: c_uint {
+        /// Set the time offset from `offset`
+        ADJ_OFFSET;
+        /// Set the frequency offset from `freq`
+        ADJ_FREQUENCY;
+        /// Set the maximum time error from `maxerror`
+        ADJ_MAXERROR;
+        /// Set the estimated time error from `esterror`
+        ADJ_ESTERROR;
+        /// Set the clock status bits from `status`
+        ADJ_STATUS;
+        /// Set the PLL time constant from `constant`
+        ADJ_TIMECONST;
+        /// Set the TAI offset from `constant`
+        ADJ_TAI;
+        /// Step the clock by `time`
+        ADJ_SETOFFSET;
+        /// Use microseconds for `offset` and `time`
+        ADJ_MICRO;
+        /// Use nanoseconds for `offset` and `time`
+        ADJ_NANO;
+        /// Set the tick value from `tick`
+        ADJ_TICK;
+        /// Slew the clock by `offset`, like `adjtime(3)`
+        ADJ_OFFSET_SINGLESHOT;
+        /// Return the remaining time of a slew started with
+        /// `ADJ_OFFSET_SINGLESHOT` in `offset`
+        ADJ_OFFSET_SS_READ;
+    }
+);
+
+libc_bitflags!(
+    /// Clock status bits, as found in the `status` field of a
+    /// [`Timex`](struct.Timex.html)
+    pub struct TimexStatus: c_int {
+        /// Enable phase-locked loop updates
+        STA_PLL;
+        /// Enable PPS frequency discipline
+        STA_PPSFREQ;
+        /// Enable PPS time discipline
+        STA_PPSTIME;
+        /// Select frequency-locked loop mode
+        STA_FLL;
+        /// Insert a leap second after the last second of the UTC day
+        STA_INS;
+        /// Delete the last second of the UTC day
+        STA_DEL;
+        /// The clock isn't synchronized
+        STA_UNSYNC;
+        /// Hold the frequency
+        STA_FREQHOLD;
+        /// A valid PPS signal is present (read-only)
+        STA_PPSSIGNAL;
+        /// PPS signal jitter exceeded (read-only)
+        STA_PPSJITTER;
+        /// PPS signal wander exceeded (read-only)
+        STA_PPSWANDER;
+        /// PPS signal calibration error (read-only)
+        STA_PPSERROR;
+        /// Clock hardware fault (read-only)
+        STA_CLOCKERR;
+        /// Offsets are in nanoseconds rather than microseconds (read-only)
+        STA_NANO;
+        /// Frequency-locked loop mode is selected (read-only)
+        STA_MODE;
+        /// Clock source is B rather than A (read-only)
+        STA_CLK;
+    }
+);
+
+/// The state of the clock, as returned by [`adjtimex`](fn.adjtimex.html)
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ClockState {
+    /// The clock is synchronized, and no leap second is pending
+    TIME_OK,
+    /// A leap second will be inserted at the end of the UTC day
+    TIME_INS,
+    /// A leap second will be deleted at the end of the UTC day
+    TIME_DEL,
+    /// A leap second is being inserted
+    TIME_OOP,
+    /// A leap second has just occurred
+    TIME_WAIT,
+    /// The clock isn't synchronized
+    TIME_ERROR,
+}
+
+impl ClockState {
+    fn from_c_int(state: c_int) -> Result<ClockState> {
+        match state {
+            libc::TIME_OK => Ok(ClockState::TIME_OK),
+            libc::TIME_INS => Ok(ClockState::TIME_INS),
+            libc::TIME_DEL => Ok(ClockState::TIME_DEL),
+            libc::TIME_OOP => Ok(ClockState::TIME_OOP),
+            libc::TIME_WAIT => Ok(ClockState::TIME_WAIT),
+            libc::TIME_ERROR => Ok(ClockState::TIME_ERROR),
+            _ => Err(::Error::invalid_argument()),
+        }
+    }
+}
+
+/// Wrapper type for `timex`, the argument of [`adjtimex`](fn.adjtimex.html)
+///
+/// A default `Timex` has no modes set, so passing it to `adjtimex` only reads
+/// the state of the clock.  Each setter also sets the mode that makes the
+/// kernel apply the field.
+///
+/// Unless `ADJ_NANO` is set, offsets are in microseconds, while frequencies
+/// are in units of 2<sup>-16</sup> ppm.
+// FIXME: Change to repr(transparent)
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Timex(libc::timex);
+
+impl Default for Timex {
+    fn default() -> Timex {
+        Timex(unsafe { mem::zeroed() })
+    }
+}
+
+impl AsRef<libc::timex> for Timex {
+    fn as_ref(&self) -> &libc::timex {
+        &self.0
+    }
+}
+
+impl AsMut<libc::timex> for Timex {
+    fn as_mut(&mut self) -> &mut libc::timex {
+        &mut self.0
+    }
+}
+
+impl Timex {
+    /// Which fields will be applied to the clock
+    pub fn modes(&self) -> AdjFlags {
+        AdjFlags::from_bits_truncate(self.0.modes)
+    }
+
+    /// Select which fields will be applied to the clock
+    pub fn set_modes(&mut self, modes: AdjFlags) {
+        self.0.modes = modes.bits();
+    }
+
+    /// The time offset
+    pub fn offset(&self) -> i64 {
+        self.0.offset as i64
+    }
+
+    /// Set the time offset for the phase-locked loop to correct (`ADJ_OFFSET`)
+    pub fn set_offset(&mut self, offset: i64) {
+        self.0.offset = offset as _;
+        self.0.modes |= libc::ADJ_OFFSET;
+    }
+
+    /// The frequency offset
+    pub fn freq(&self) -> i64 {
+        self.0.freq as i64
+    }
+
+    /// Set the frequency offset (`ADJ_FREQUENCY`)
+    pub fn set_freq(&mut self, freq: i64) {
+        self.0.freq = freq as _;
+        self.0.modes |= libc::ADJ_FREQUENCY;
+    }
+
+    /// The maximum error, in microseconds
+    pub fn maxerror(&self) -> i64 {
+        self.0.maxerror as i64
+    }
+
+    /// Set the maximum error (`ADJ_MAXERROR`)
+    pub fn set_maxerror(&mut self, maxerror: i64) {
+        self.0.maxerror = maxerror as _;
+        self.0.modes |= libc::ADJ_MAXERROR;
+    }
+
+    /// The estimated error, in microseconds
+    pub fn esterror(&self) -> i64 {
+        self.0.esterror as i64
+    }
+
+    /// Set the estimated error (`ADJ_ESTERROR`)
+    pub fn set_esterror(&mut self, esterror: i64) {
+        self.0.esterror = esterror as _;
+        self.0.modes |= libc::ADJ_ESTERROR;
+    }
+
+    /// The clock status bits
+    pub fn status(&self) -> TimexStatus {
+        TimexStatus::from_bits_truncate(self.0.status)
+    }
+
+    /// Set the clock status bits (`ADJ_STATUS`).  Read-only bits are ignored.
+    pub fn set_status(&mut self, status: TimexStatus) {
+        self.0.status = status.bits();
+        self.0.modes |= libc::ADJ_STATUS;
+    }
+
+    /// The time constant of the phase-locked loop
+    pub fn constant(&self) -> i64 {
+        self.0.constant as i64
+    }
+
+    /// Set the time constant of the phase-locked loop (`ADJ_TIMECONST`)
+    pub fn set_constant(&mut self, constant: i64) {
+        self.0.constant = constant as _;
+        self.0.modes |= libc::ADJ_TIMECONST;
+    }
+
+    /// The precision of the clock, in microseconds (read-only)
+    pub fn precision(&self) -> i64 {
+        self.0.precision as i64
+    }
+
+    /// The maximum frequency offset (read-only)
+    pub fn tolerance(&self) -> i64 {
+        self.0.tolerance as i64
+    }
+
+    /// The current time of the clock (read-only, except with `ADJ_SETOFFSET`)
+    ///
+    /// The kernel stores nanoseconds rather than microseconds in the `tv_usec`
+    /// field of the time when the status has `STA_NANO`.
+    pub fn time(&self) -> TimeSpec {
+        let subsec = if self.status().contains(TimexStatus::STA_NANO) {
+            TimeSpec::nanoseconds(self.0.time.tv_usec as i64)
+        } else {
+            TimeSpec::microseconds(self.0.time.tv_usec as i64)
+        };
+        TimeSpec::seconds(self.0.time.tv_sec as i64) + subsec
+    }
+
+    /// The number of microseconds between clock ticks
+    pub fn tick(&self) -> i64 {
+        self.0.tick as i64
+    }
+
+    /// Set the number of microseconds between clock ticks (`ADJ_TICK`)
+    pub fn set_tick(&mut self, tick: i64) {
+        self.0.tick = tick as _;
+        self.0.modes |= libc::ADJ_TICK;
+    }
+
+    /// The offset between TAI and UTC, in seconds
+    pub fn tai(&self) -> c_int {
+        self.0.tai
+    }
+
+    /// Set the offset between TAI and UTC (`ADJ_TAI`).  The kernel takes the
+    /// new value from the `constant` field.
+    pub fn set_tai(&mut self, tai: c_int) {
+        self.0.constant = tai as _;
+        self.0.modes |= libc::ADJ_TAI;
+    }
+}
+
+/// Read and optionally adjust the system clock (see
+/// [adjtimex(2)](http://man7.org/linux/man-pages/man2/adjtimex.2.html)).
+///
+/// The fields selected by `timex.modes()` are applied, and then `timex` is
+/// filled in with the current state of the clock.
+pub fn adjtimex(timex: &mut Timex) -> Result<ClockState> {
+    let res = unsafe { libc::adjtimex(&mut timex.0) };
+
+    Errno::result(res).and_then(ClockState::from_c_int)
+}
+
+/// Like [`adjtimex`](fn.adjtimex.html), but for any adjustable clock (see
+/// [clock_adjtime(2)](http://man7.org/linux/man-pages/man2/clock_adjtime.2.html)).
+///
+/// Besides `CLOCK_REALTIME`, this can adjust the clocks of PTP hardware.
+pub fn clock_adjtime(clock_id: ClockId, timex: &mut Timex) -> Result<ClockState> {
+    let res = unsafe { libc::clock_adjtime(clock_id.as_raw(), &mut timex.0) };
+
+    Errno::result(res).and_then(ClockState::from_c_int)
+}
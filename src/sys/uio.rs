@@ -5,6 +5,10 @@ use Result;
 use errno::Errno;
 use libc::{self, c_int, c_void, size_t, off_t};
 use std::marker::PhantomData;
+#[cfg(all(io_slice, feature = "std"))]
+use std::io::{IoSlice, IoSliceMut};
+#[cfg(all(io_slice, feature = "std"))]
+use std::{mem, slice};
 use std::os::unix::io::RawFd;
 
 pub fn writev(fd: RawFd, iov: &[IoVec<&[u8]>]) -> Result<usize> {
@@ -159,6 +163,22 @@ impl<'a> IoVec<&'a [u8]> {
             iov_len: buf.len() as size_t,
         }, PhantomData)
     }
+
+    /// View `bufs` as `IoVec`s, for [`writev`](fn.writev.html) and the like.
+    ///
+    /// `IoSlice` has the layout of `iovec`, so this doesn't copy anything.
+    /// Requires Rust 1.36 or newer.
+    #[cfg(all(io_slice, feature = "std"))]
+    pub fn from_io_slices<'b>(bufs: &'b [IoSlice<'a>]) -> &'b [IoVec<&'a [u8]>] {
+        unsafe { slice::from_raw_parts(bufs.as_ptr() as *const IoVec<&'a [u8]>, bufs.len()) }
+    }
+}
+
+#[cfg(all(io_slice, feature = "std"))]
+impl<'a> From<IoSlice<'a>> for IoVec<&'a [u8]> {
+    fn from(buf: IoSlice<'a>) -> IoVec<&'a [u8]> {
+        IoVec(unsafe { mem::transmute::<IoSlice<'a>, libc::iovec>(buf) }, PhantomData)
+    }
 }
 
 impl<'a> IoVec<&'a mut [u8]> {
@@ -168,4 +188,22 @@ impl<'a> IoVec<&'a mut [u8]> {
             iov_len: buf.len() as size_t,
         }, PhantomData)
     }
+
+    /// View `bufs` as `IoVec`s, for [`readv`](fn.readv.html) and the like.
+    ///
+    /// `IoSliceMut` has the layout of `iovec`, so this doesn't copy anything.
+    /// Requires Rust 1.36 or newer.
+    #[cfg(all(io_slice, feature = "std"))]
+    pub fn from_io_slices_mut<'b>(bufs: &'b mut [IoSliceMut<'a>]) -> &'b mut [IoVec<&'a mut [u8]>] {
+        unsafe {
+            slice::from_raw_parts_mut(bufs.as_mut_ptr() as *mut IoVec<&'a mut [u8]>, bufs.len())
+        }
+    }
+}
+
+#[cfg(all(io_slice, feature = "std"))]
+impl<'a> From<IoSliceMut<'a>> for IoVec<&'a mut [u8]> {
+    fn from(buf: IoSliceMut<'a>) -> IoVec<&'a mut [u8]> {
+        IoVec(unsafe { mem::transmute::<IoSliceMut<'a>, libc::iovec>(buf) }, PhantomData)
+    }
 }
@@ -0,0 +1,118 @@
+//! Control the Linux virtual consoles (see
+//! [console_ioctl(4)](http://man7.org/linux/man-pages/man4/console_ioctl.4.html)).
+//!
+//! These wrappers operate on a file descriptor referring to a virtual console,
+//! such as `/dev/tty0` or `/dev/tty1`.  Display servers and seat managers use
+//! them to switch between consoles and to take the console over for graphics.
+//! Calling them on any other kind of file fails with `ENOTTY`.
+use libc::{c_int, c_ushort};
+use std::mem;
+use std::os::unix::io::RawFd;
+use Result;
+
+// libc doesn't export the console ioctls, so their numbers are taken from
+// linux/vt.h and linux/kd.h
+mod ioctls {
+    use libc::c_int;
+    use super::VtStat;
+
+    ioctl_read_bad!(vt_getstate, 0x5603, VtStat);
+    ioctl_write_int_bad!(vt_activate, 0x5606);
+    ioctl_write_int_bad!(vt_waitactive, 0x5607);
+    ioctl_write_int_bad!(kdsetmode, 0x4B3A);
+    ioctl_read_bad!(kdgetmode, 0x4B3B, c_int);
+    ioctl_read_bad!(kdgkbmode, 0x4B44, c_int);
+}
+
+/// The state of the virtual consoles, as returned by [`get_state`](fn.get_state.html)
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct VtStat {
+    /// The number of the active console
+    pub v_active: c_ushort,
+    /// The signal to send when switching consoles
+    pub v_signal: c_ushort,
+    /// A bitmask of the consoles in use, where bit `n` stands for console `n`.
+    /// Only the first 16 consoles are reported.
+    pub v_state: c_ushort,
+}
+
+/// Display modes of a virtual console
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ConsoleMode {
+    /// The kernel draws text on the console
+    KD_TEXT = 0,
+    /// The kernel leaves the display alone, so that a display server can draw
+    /// on it
+    KD_GRAPHICS = 1,
+}
+
+/// Keyboard modes of a virtual console
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum KeyboardMode {
+    /// Raw scancodes
+    K_RAW = 0,
+    /// Characters translated with the keymap
+    K_XLATE = 1,
+    /// Keycodes
+    K_MEDIUMRAW = 2,
+    /// Characters translated with the keymap and encoded as UTF-8
+    K_UNICODE = 3,
+    /// No input at all
+    K_OFF = 4,
+}
+
+/// Get the active console and the consoles in use (`VT_GETSTATE`).
+pub fn get_state(fd: RawFd) -> Result<VtStat> {
+    let mut state = VtStat::default();
+    unsafe { ioctls::vt_getstate(fd, &mut state) }.map(|_| state)
+}
+
+/// Switch to the console with the given number (`VT_ACTIVATE`).
+///
+/// The switch happens asynchronously, use [`wait_active`](fn.wait_active.html)
+/// to wait until it's done.
+pub fn activate(fd: RawFd, vt: c_int) -> Result<()> {
+    unsafe { ioctls::vt_activate(fd, vt) }.map(mem::drop)
+}
+
+/// Wait until the console with the given number is active (`VT_WAITACTIVE`).
+pub fn wait_active(fd: RawFd, vt: c_int) -> Result<()> {
+    unsafe { ioctls::vt_waitactive(fd, vt) }.map(mem::drop)
+}
+
+/// Set the display mode of the console (`KDSETMODE`).
+///
+/// Switching to `KD_GRAPHICS` stops the kernel from drawing on the console,
+/// including the cursor. Display servers must switch back to `KD_TEXT` when
+/// they exit, or the console stays blank.
+pub fn set_mode(fd: RawFd, mode: ConsoleMode) -> Result<()> {
+    unsafe { ioctls::kdsetmode(fd, mode as c_int) }.map(mem::drop)
+}
+
+/// Get the display mode of the console (`KDGETMODE`).
+pub fn get_mode(fd: RawFd) -> Result<ConsoleMode> {
+    let mut mode: c_int = 0;
+    try!(unsafe { ioctls::kdgetmode(fd, &mut mode) });
+    match mode {
+        0 => Ok(ConsoleMode::KD_TEXT),
+        1 => Ok(ConsoleMode::KD_GRAPHICS),
+        _ => Err(::Error::invalid_argument()),
+    }
+}
+
+/// Get the keyboard mode of the console (`KDGKBMODE`).
+pub fn get_keyboard_mode(fd: RawFd) -> Result<KeyboardMode> {
+    let mut mode: c_int = 0;
+    try!(unsafe { ioctls::kdgkbmode(fd, &mut mode) });
+    match mode {
+        0 => Ok(KeyboardMode::K_RAW),
+        1 => Ok(KeyboardMode::K_XLATE),
+        2 => Ok(KeyboardMode::K_MEDIUMRAW),
+        3 => Ok(KeyboardMode::K_UNICODE),
+        4 => Ok(KeyboardMode::K_OFF),
+        _ => Err(::Error::invalid_argument()),
+    }
+}
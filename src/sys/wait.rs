@@ -52,10 +52,10 @@ libc_bitflags!(
 /// in a child process `Pid`, such as the process exiting or stopping,
 /// plus additional data about the transition if any.
 ///
-/// Note that there are two Linux-specific enum variants, `PtraceEvent`
-/// and `PtraceSyscall`. Portable code should avoid exhaustively
-/// matching on `WaitStatus`.
+/// Note that the `Ptrace*` variants are Linux-specific. Portable code
+/// should avoid exhaustively matching on `WaitStatus`.
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum WaitStatus {
     /// The process exited normally (as with `exit()` or returning from
     /// `main`) with the given exit code. This case matches the C macro
@@ -72,14 +72,56 @@ pub enum WaitStatus {
     /// is `WSTOPSIG(status)`.
     Stopped(Pid, Signal),
     /// The traced process was stopped by a `PTRACE_EVENT_*` event. See
-    /// [`nix::sys::ptrace`] and [`ptrace`(2)] for more information. All
-    /// currently-defined events use `SIGTRAP` as the signal; the third
-    /// field is the `PTRACE_EVENT_*` value of the event.
+    /// [`nix::sys::ptrace`] and [`ptrace`(2)] for more information. The
+    /// third field is the `PTRACE_EVENT_*` value of the event.
+    ///
+    /// The events below are reported as their own variants, so this one
+    /// is only returned for `PTRACE_EVENT_STOP`, whose signal is the one
+    /// that caused the stop, for unknown events, and when the event
+    /// message can't be read, because the process was killed meanwhile or
+    /// isn't traced by the caller.
     ///
     /// [`nix::sys::ptrace`]: ../ptrace/index.html
     /// [`ptrace`(2)]: http://man7.org/linux/man-pages/man2/ptrace.2.html
     #[cfg(any(target_os = "linux", target_os = "android"))]
     PtraceEvent(Pid, Signal, c_int),
+    /// The traced process called `fork` (`PTRACE_EVENT_FORK`, with
+    /// `PTRACE_O_TRACEFORK`). The second field is the new child, which
+    /// is traced too.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    PtraceFork(Pid, Pid),
+    /// The traced process called `vfork` (`PTRACE_EVENT_VFORK`, with
+    /// `PTRACE_O_TRACEVFORK`). The second field is the new child, which
+    /// is traced too.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    PtraceVfork(Pid, Pid),
+    /// The traced process called `clone` (`PTRACE_EVENT_CLONE`, with
+    /// `PTRACE_O_TRACECLONE`). The second field is the new thread or
+    /// process, which is traced too.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    PtraceClone(Pid, Pid),
+    /// The child of a `vfork` by the traced process exited or called
+    /// `exec`, resuming its parent (`PTRACE_EVENT_VFORK_DONE`, with
+    /// `PTRACE_O_TRACEVFORKDONE`). The second field is the child.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    PtraceVforkDone(Pid, Pid),
+    /// The traced process called `exec` successfully
+    /// (`PTRACE_EVENT_EXEC`, with `PTRACE_O_TRACEEXEC`). The second field
+    /// is the thread that called it, which differs from the first if it
+    /// wasn't the main thread.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    PtraceExec(Pid, Pid),
+    /// The traced process is about to exit (`PTRACE_EVENT_EXIT`, with
+    /// `PTRACE_O_TRACEEXIT`). The second field is the raw status it's
+    /// going to exit with, which can be decoded with `from_raw`.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    PtraceExit(Pid, i32),
+    /// The traced process made a system call for which a seccomp filter
+    /// returned `SECCOMP_RET_TRACE` (`PTRACE_EVENT_SECCOMP`, with
+    /// `PTRACE_O_TRACESECCOMP`). The second field is the `SECCOMP_RET_DATA`
+    /// part of the return value.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    PtraceSeccomp(Pid, u16),
     /// The traced process was stopped by execution of a system call,
     /// and `PTRACE_O_TRACESYSGOOD` is in effect. See [`ptrace`(2)] for
     /// more information.
@@ -108,7 +150,10 @@ impl WaitStatus {
                 Stopped(p, _) | Continued(p) => Some(p),
             StillAlive => None,
             #[cfg(any(target_os = "android", target_os = "linux"))]
-            PtraceEvent(p, _, _) | PtraceSyscall(p) => Some(p),
+            PtraceEvent(p, _, _) | PtraceSyscall(p) |
+                PtraceFork(p, _) | PtraceVfork(p, _) | PtraceClone(p, _) |
+                PtraceVforkDone(p, _) | PtraceExec(p, _) | PtraceExit(p, _) |
+                PtraceSeccomp(p, _) => Some(p),
         }
     }
 }
@@ -160,6 +205,10 @@ fn continued(status: i32) -> bool {
 impl WaitStatus {
     /// Convert a raw `wstatus` as returned by `waitpid`/`wait` into a `WaitStatus`
     ///
+    /// On Linux, this reads the message of the ptrace events that have one
+    /// with `PTRACE_GETEVENTMSG`, to report them as distinct variants, such
+    /// as `PtraceFork` with the new child, like `waitpid` does.
+    ///
     /// # Errors
     ///
     /// Returns an `Error` corresponding to `EINVAL` for invalid status values.
@@ -199,7 +248,7 @@ impl WaitStatus {
                     }
                 }
             }
-            return decode_stopped(pid, status);
+            return decode_stopped(pid, status).map(decode_ptrace_event);
         } else {
             assert!(continued(status));
             WaitStatus::Continued(pid)
@@ -207,6 +256,46 @@ impl WaitStatus {
     }
 }
 
+// Decode the events whose message tells more about them.  If the process
+// was killed meanwhile, the message can't be read, and the event is reported
+// as is.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn decode_ptrace_event(status: WaitStatus) -> WaitStatus {
+    use sys::ptrace;
+
+    let (pid, event) = match status {
+        WaitStatus::PtraceEvent(pid, Signal::SIGTRAP, event) => (pid, event),
+        _ => return status,
+    };
+    let message = match ptrace::getevent(pid) {
+        Ok(message) => message,
+        Err(_) => return status,
+    };
+    match event {
+        libc::PTRACE_EVENT_FORK => WaitStatus::PtraceFork(pid, Pid::from_raw(message as _)),
+        libc::PTRACE_EVENT_VFORK => WaitStatus::PtraceVfork(pid, Pid::from_raw(message as _)),
+        libc::PTRACE_EVENT_CLONE => WaitStatus::PtraceClone(pid, Pid::from_raw(message as _)),
+        libc::PTRACE_EVENT_VFORK_DONE => {
+            WaitStatus::PtraceVforkDone(pid, Pid::from_raw(message as _))
+        },
+        libc::PTRACE_EVENT_EXEC => WaitStatus::PtraceExec(pid, Pid::from_raw(message as _)),
+        libc::PTRACE_EVENT_EXIT => WaitStatus::PtraceExit(pid, message as i32),
+        libc::PTRACE_EVENT_SECCOMP => WaitStatus::PtraceSeccomp(pid, message as u16),
+        _ => status,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn decode_ptrace_event(status: WaitStatus) -> WaitStatus {
+    status
+}
+
+/// Wait for a child process to change state (see
+/// [waitpid(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/waitpid.html)).
+///
+/// The status is decoded with [`WaitStatus::from_raw`](enum.WaitStatus.html#method.from_raw),
+/// so on Linux the ptrace events with a message are reported as distinct
+/// variants, such as `PtraceFork` with the new child.
 pub fn waitpid<P: Into<Option<Pid>>>(pid: P, options: Option<WaitPidFlag>) -> Result<WaitStatus> {
     use self::WaitStatus::*;
 
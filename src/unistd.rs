@@ -6,11 +6,18 @@ use fcntl::{fcntl, FdFlag, OFlag};
 use fcntl::FcntlArg::F_SETFD;
 use libc::{self, c_char, c_void, c_int, c_long, c_uint, size_t, pid_t, off_t,
            uid_t, gid_t, mode_t};
-use std::{fmt, mem, ptr};
-use std::ffi::{CString, CStr, OsString, OsStr};
+use std::{fmt, mem};
+#[cfg(feature = "std")]
+use std::ptr;
+use std::ffi::CStr;
+#[cfg(feature = "std")]
+use std::ffi::{CString, OsString, OsStr};
+#[cfg(feature = "std")]
 use std::os::unix::ffi::{OsStringExt, OsStrExt};
-use std::os::unix::io::RawFd;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+#[cfg(feature = "std")]
 use std::path::{PathBuf};
+#[cfg(feature = "std")]
 use void::Void;
 use sys::stat::Mode;
 
@@ -21,6 +28,20 @@ pub use self::pivot_root::*;
           target_os = "linux", target_os = "openbsd"))]
 pub use self::setres::*;
 
+#[cfg(target_os = "openbsd")]
+pub use self::pledge::*;
+
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "emscripten",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+pub use self::owned_pipe::*;
+
 /// User identifier
 ///
 /// Newtype pattern around `uid_t` (which is just alias). It prevents bugs caused by accidentally
@@ -106,6 +127,7 @@ impl fmt::Display for Gid {
 /// Newtype pattern around `pid_t` (which is just alias). It prevents bugs caused by accidentally
 /// passing wrong value.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Pid(pid_t);
 
 impl Pid {
@@ -264,6 +286,18 @@ pub fn setsid() -> Result<Pid> {
     Errno::result(unsafe { libc::setsid() }).map(Pid)
 }
 
+/// Simulate a hangup on the controlling terminal of the calling process (see
+/// [vhangup(2)](http://man7.org/linux/man-pages/man2/vhangup.2.html)).
+///
+/// Every other open file descriptor referring to the terminal becomes unusable, so that a new
+/// login session can start on it without earlier users still having access. `getty` and `login`
+/// implementations call this before handing the terminal to the user. Requires
+/// `CAP_SYS_TTY_CONFIG`.
+#[cfg(target_os = "linux")]
+pub fn vhangup() -> Result<()> {
+    Errno::result(unsafe { libc::vhangup() }).map(drop)
+}
+
 
 /// Get the terminal foreground process group (see
 /// [tcgetpgrp(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/tcgetpgrp.html)).
@@ -498,6 +532,7 @@ pub fn mkfifo<P: ?Sized + NixPath>(path: &P, mode: Mode) -> Result<()> {
 ///     println!("The current directory is {:?}", dir);
 /// }
 /// ```
+#[cfg(feature = "std")]
 #[inline]
 pub fn getcwd() -> Result<PathBuf> {
     let mut buf = Vec::with_capacity(512);
@@ -552,6 +587,7 @@ pub fn chown<P: ?Sized + NixPath>(path: &P, owner: Option<Uid>, group: Option<Gi
     Errno::result(res).map(drop)
 }
 
+#[cfg(feature = "std")]
 fn to_exec_array(args: &[CString]) -> Vec<*const c_char> {
     let mut args_p: Vec<*const c_char> = args.iter().map(|s| s.as_ptr()).collect();
     args_p.push(ptr::null());
@@ -564,13 +600,14 @@ fn to_exec_array(args: &[CString]) -> Vec<*const c_char> {
 /// See the `::nix::unistd::execve` system call for additional details.  `execv`
 /// performs the same action but does not allow for customization of the
 /// environment for the new process.
+#[cfg(feature = "std")]
 #[inline]
-pub fn execv(path: &CString, argv: &[CString]) -> Result<Void> {
+pub fn execv<P: ?Sized + NixPath>(path: &P, argv: &[CString]) -> Result<Void> {
     let args_p = to_exec_array(argv);
 
-    unsafe {
+    try!(path.with_nix_path(|path| unsafe {
         libc::execv(path.as_ptr(), args_p.as_ptr())
-    };
+    }));
 
     Err(Error::Sys(Errno::last()))
 }
@@ -588,14 +625,15 @@ pub fn execv(path: &CString, argv: &[CString]) -> Result<Void> {
 /// of `::std::ffi::CString`s for `args` and `env` (for `execve`). Each element
 /// in the `args` list is an argument to the new process. Each element in the
 /// `env` list should be a string in the form "key=value".
+#[cfg(feature = "std")]
 #[inline]
-pub fn execve(path: &CString, args: &[CString], env: &[CString]) -> Result<Void> {
+pub fn execve<P: ?Sized + NixPath>(path: &P, args: &[CString], env: &[CString]) -> Result<Void> {
     let args_p = to_exec_array(args);
     let env_p = to_exec_array(env);
 
-    unsafe {
+    try!(path.with_nix_path(|path| unsafe {
         libc::execve(path.as_ptr(), args_p.as_ptr(), env_p.as_ptr())
-    };
+    }));
 
     Err(Error::Sys(Errno::last()))
 }
@@ -609,13 +647,14 @@ pub fn execve(path: &CString, args: &[CString], env: &[CString]) -> Result<Void>
 /// for file names not specified with a leading slash.  For example, `execv`
 /// would not work if "bash" was specified for the path argument, but `execvp`
 /// would assuming that a bash executable was on the system `PATH`.
+#[cfg(feature = "std")]
 #[inline]
-pub fn execvp(filename: &CString, args: &[CString]) -> Result<Void> {
+pub fn execvp<P: ?Sized + NixPath>(filename: &P, args: &[CString]) -> Result<Void> {
     let args_p = to_exec_array(args);
 
-    unsafe {
+    try!(filename.with_nix_path(|filename| unsafe {
         libc::execvp(filename.as_ptr(), args_p.as_ptr())
-    };
+    }));
 
     Err(Error::Sys(Errno::last()))
 }
@@ -630,11 +669,12 @@ pub fn execvp(filename: &CString, args: &[CString]) -> Result<Void> {
 ///
 /// This function is similar to `execve`, except that the program to be executed
 /// is referenced as a file descriptor instead of a path.
-#[cfg(any(target_os = "android",
-          target_os = "freebsd",
-          target_os = "linux",
-          target_os = "netbsd",
-          target_os = "openbsd"))]
+#[cfg(all(feature = "std",
+          any(target_os = "android",
+              target_os = "freebsd",
+              target_os = "linux",
+              target_os = "netbsd",
+              target_os = "openbsd")))]
 #[inline]
 pub fn fexecve(fd: RawFd, args: &[CString], env: &[CString]) -> Result<Void> {
     let args_p = to_exec_array(args);
@@ -657,17 +697,18 @@ pub fn fexecve(fd: RawFd, args: &[CString], env: &[CString]) -> Result<Void> {
 ///
 /// This function is similar to `execve`, except that the program to be executed
 /// is referenced as a file descriptor to the base directory plus a path.
-#[cfg(any(target_os = "android", target_os = "linux"))]
+#[cfg(all(feature = "std", any(target_os = "android", target_os = "linux")))]
 #[inline]
-pub fn execveat(dirfd: RawFd, pathname: &CString, args: &[CString],
-                env: &[CString], flags: super::fcntl::AtFlags) -> Result<Void> {
+pub fn execveat<P: ?Sized + NixPath>(dirfd: RawFd, pathname: &P, args: &[CString],
+                                     env: &[CString], flags: super::fcntl::AtFlags)
+                                     -> Result<Void> {
     let args_p = to_exec_array(args);
     let env_p = to_exec_array(env);
 
-    unsafe {
+    try!(pathname.with_nix_path(|pathname| unsafe {
         libc::syscall(libc::SYS_execveat, dirfd, pathname.as_ptr(),
-                      args_p.as_ptr(), env_p.as_ptr(), flags);
-    };
+                      args_p.as_ptr(), env_p.as_ptr(), flags)
+    }));
 
     Err(Error::Sys(Errno::last()))
 }
@@ -709,6 +750,7 @@ pub fn daemon(nochdir: bool, noclose: bool) -> Result<()> {
 /// On some systems, the host name is limited to as few as 64 bytes.  An error
 /// will be return if the name is not valid or the current process does not have
 /// permissions to update the host name.
+#[cfg(feature = "std")]
 pub fn sethostname<S: AsRef<OsStr>>(name: S) -> Result<()> {
     // Handle some differences in type of the len arg across platforms.
     cfg_if! {
@@ -799,6 +841,60 @@ pub fn close(fd: RawFd) -> Result<()> {
     Errno::result(res).map(drop)
 }
 
+/// An owned file descriptor, closed when dropped.
+///
+/// Unlike a `RawFd`, which can be copied around after it's closed or closed
+/// twice, an `Fd` is the only handle of its file descriptor.  Functions that
+/// create file descriptors have variants returning it, like
+/// [`open_owned`](../fcntl/fn.open_owned.html), and it can be turned into a
+/// `RawFd` with `into_raw_fd` to hand the file descriptor over to something
+/// else, or borrowed with `as_raw_fd` to call the other functions of nix.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct Fd(RawFd);
+
+impl Fd {
+    /// Duplicate the file descriptor into a new one, which shares its offset
+    /// and status flags but not its close-on-exec flag.  The new file
+    /// descriptor has `FD_CLOEXEC` set.
+    pub fn try_clone(&self) -> Result<Fd> {
+        let res = unsafe { libc::fcntl(self.0, libc::F_DUPFD_CLOEXEC, 0) };
+
+        Errno::result(res).map(Fd)
+    }
+}
+
+impl AsRawFd for Fd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+impl IntoRawFd for Fd {
+    fn into_raw_fd(self) -> RawFd {
+        let fd = self.0;
+        mem::forget(self);
+        fd
+    }
+}
+
+impl FromRawFd for Fd {
+    /// Take ownership of `fd`, which must be open and not owned by anything
+    /// else.
+    unsafe fn from_raw_fd(fd: RawFd) -> Fd {
+        Fd(fd)
+    }
+}
+
+impl Drop for Fd {
+    fn drop(&mut self) {
+        // Like for `PtyMaster`, errors other than EBADF leave the file
+        // descriptor closed anyway, and EBADF means something else closed it.
+        if close(self.0) == Err(Error::Sys(Errno::EBADF)) {
+            panic!("Closing an invalid file descriptor!");
+        }
+    }
+}
+
 /// Read from a raw file descriptor.
 ///
 /// See also [read(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/read.html)
@@ -888,6 +984,11 @@ pub fn pipe() -> Result<(RawFd, RawFd)> {
 /// `O_CLOEXEC`:    Set the close-on-exec flag for the new file descriptors.
 /// `O_NONBLOCK`:   Set the non-blocking flag for the ends of the pipe.
 ///
+/// On Linux kernels older than 2.6.27, which don't have `pipe2` (see
+/// [`features::pipe2_available`](../features/fn.pipe2_available.html)), this
+/// calls `pipe` and sets these two flags with `fcntl` afterwards, so they
+/// aren't set atomically, and ignores the others.
+///
 /// See also [pipe(2)](http://man7.org/linux/man-pages/man2/pipe.2.html)
 #[cfg(any(target_os = "android",
           target_os = "dragonfly",
@@ -897,6 +998,16 @@ pub fn pipe() -> Result<(RawFd, RawFd)> {
           target_os = "netbsd",
           target_os = "openbsd"))]
 pub fn pipe2(flags: OFlag) -> Result<(RawFd, RawFd)> {
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    {
+        if !::features::pipe2_available() {
+            let (fd1, fd2) = try!(pipe());
+            try!(pipe2_setflags(fd1, fd2, flags));
+
+            return Ok((fd1, fd2));
+        }
+    }
+
     let mut fds: [c_int; 2] = unsafe { mem::uninitialized() };
 
     let res = unsafe { libc::pipe2(fds.as_mut_ptr(), flags.bits()) };
@@ -930,7 +1041,10 @@ pub fn pipe2(flags: OFlag) -> Result<(RawFd, RawFd)> {
     Ok((fds[0], fds[1]))
 }
 
-#[cfg(any(target_os = "ios", target_os = "macos"))]
+#[cfg(any(target_os = "android",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos"))]
 fn pipe2_setflags(fd1: RawFd, fd2: RawFd, flags: OFlag) -> Result<()> {
     use fcntl::FdFlag;
     use fcntl::FcntlArg::F_SETFL;
@@ -967,6 +1081,10 @@ pub fn ftruncate(fd: RawFd, len: off_t) -> Result<()> {
     Errno::result(unsafe { libc::ftruncate(fd, len) }).map(drop)
 }
 
+/// Check whether a file descriptor refers to a terminal (see
+/// [isatty(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/isatty.html)).
+///
+/// Returns `Ok(false)` if `fd` is valid but refers to something else, such as a file or a pipe.
 pub fn isatty(fd: RawFd) -> Result<bool> {
     use libc;
 
@@ -984,6 +1102,39 @@ pub fn isatty(fd: RawFd) -> Result<bool> {
     }
 }
 
+/// Get the path of the terminal a file descriptor refers to (see
+/// [ttyname(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/ttyname.html)).
+///
+/// Fails with `ENOTTY` if `fd` doesn't refer to a terminal. Unlike `ttyname(3)` this is
+/// thread-safe, as it's implemented with `ttyname_r(3)`.
+#[cfg(feature = "std")]
+pub fn ttyname(fd: RawFd) -> Result<OsString> {
+    ttyname_with_len(fd, 64)
+}
+
+#[cfg(feature = "std")]
+fn ttyname_with_len(fd: RawFd, len: usize) -> Result<OsString> {
+    let mut buf: Vec<u8> = vec![0; len];
+    loop {
+        let res = unsafe {
+            libc::ttyname_r(fd, buf.as_mut_ptr() as *mut c_char, buf.len())
+        };
+        match res {
+            0 => break,
+            // The buffer was too small to store the name, so double it and try again
+            libc::ERANGE => {
+                let len = buf.len();
+                buf.resize(len * 2, 0);
+            },
+            err => return Err(Error::Sys(Errno::from_i32(err))),
+        }
+    }
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    buf.truncate(len);
+    buf.shrink_to_fit();
+    Ok(OsString::from_vec(buf))
+}
+
 /// Remove a directory entry
 ///
 /// See also [unlink(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/unlink.html)
@@ -1098,7 +1249,7 @@ pub fn setgid(gid: Gid) -> Result<()> {
 /// **Note:** This function is not available for Apple platforms. On those
 /// platforms, checking group membership should be achieved via communication
 /// with the `opendirectoryd` service.
-#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+#[cfg(all(feature = "std", not(any(target_os = "ios", target_os = "macos"))))]
 pub fn getgroups() -> Result<Vec<Gid>> {
     // First get the number of groups so we can size our Vec
     let ret = unsafe { libc::getgroups(0, ptr::null_mut()) };
@@ -1210,7 +1361,7 @@ pub fn setgroups(groups: &[Gid]) -> Result<()> {
 /// and `setgroups()`. Additionally, while some implementations will return a
 /// partial list of groups when `NGROUPS_MAX` is exceeded, this implementation
 /// will only ever return the complete list or else an error.
-#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+#[cfg(all(feature = "std", not(any(target_os = "ios", target_os = "macos"))))]
 pub fn getgrouplist(user: &CStr, group: Gid) -> Result<Vec<Gid>> {
     let ngroups_max = match sysconf(SysconfVar::NGROUPS_MAX) {
         Ok(Some(n)) => n as c_int,
@@ -1357,6 +1508,7 @@ pub fn sleep(seconds: libc::c_uint) -> c_uint {
 /// };
 /// // do something with fd
 /// ```
+#[cfg(feature = "std")]
 #[inline]
 pub fn mkstemp<P: ?Sized + NixPath>(template: &P) -> Result<(RawFd, PathBuf)> {
     let mut path = try!(template.with_nix_path(|path| {path.to_bytes_with_nul().to_owned()}));
@@ -2045,3 +2197,322 @@ mod setres {
         Errno::result(res).map(drop)
     }
 }
+
+#[cfg(target_os = "openbsd")]
+mod pledge {
+    use libc::{self, c_char};
+    use std::ptr;
+    use {Result, NixPath};
+    use errno::Errno;
+
+    bitflags! {
+        /// The promises of [`pledge`](fn.pledge.html): the groups of
+        /// system calls the process may still use
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+        pub struct Promises: u64 {
+            /// Basic functionality, like I/O on open descriptors, memory
+            /// allocation and time
+            const STDIO = 1 << 0;
+            /// Read-only file system access
+            const RPATH = 1 << 1;
+            /// Write-only file system access
+            const WPATH = 1 << 2;
+            /// Creating and removing files and directories
+            const CPATH = 1 << 3;
+            /// Creating special files, like FIFOs
+            const DPATH = 1 << 4;
+            /// Creating and accessing files in `/tmp`
+            const TMPPATH = 1 << 5;
+            /// Internet sockets
+            const INET = 1 << 6;
+            /// Multicast socket options
+            const MCAST = 1 << 7;
+            /// Changing file attributes, like the mode and times
+            const FATTR = 1 << 8;
+            /// Changing the owner of files
+            const CHOWN = 1 << 9;
+            /// File locks
+            const FLOCK = 1 << 10;
+            /// Unix domain sockets
+            const UNIX = 1 << 11;
+            /// Name resolution
+            const DNS = 1 << 12;
+            /// Reading the password and group databases
+            const GETPW = 1 << 13;
+            /// Sending file descriptors
+            const SENDFD = 1 << 14;
+            /// Receiving file descriptors
+            const RECVFD = 1 << 15;
+            /// Tape drive ioctls
+            const TAPE = 1 << 16;
+            /// Terminal ioctls
+            const TTY = 1 << 17;
+            /// Process management, like `fork` and `kill`
+            const PROC = 1 << 18;
+            /// `execve`
+            const EXEC = 1 << 19;
+            /// `PROT_EXEC` mappings
+            const PROT_EXEC = 1 << 20;
+            /// Setting the time
+            const SETTIME = 1 << 21;
+            /// Inspecting other processes
+            const PS = 1 << 22;
+            /// Inspecting the system's virtual memory
+            const VMINFO = 1 << 23;
+            /// Changing the credentials of the process
+            const ID = 1 << 24;
+            /// Packet filter ioctls
+            const PF = 1 << 25;
+            /// Reading the routing table
+            const ROUTE = 1 << 26;
+            /// Changing the routing table
+            const WROUTE = 1 << 27;
+            /// Audio device ioctls
+            const AUDIO = 1 << 28;
+            /// Video device ioctls
+            const VIDEO = 1 << 29;
+            /// BPF device ioctls
+            const BPF = 1 << 30;
+            /// `unveil`
+            const UNVEIL = 1 << 31;
+            /// Fail forbidden system calls with `ENOSYS`, rather than killing
+            /// the process
+            const ERROR = 1 << 32;
+        }
+    }
+
+    const PROMISE_NAMES: &[(Promises, &str)] = &[
+        (Promises::STDIO, "stdio"),
+        (Promises::RPATH, "rpath"),
+        (Promises::WPATH, "wpath"),
+        (Promises::CPATH, "cpath"),
+        (Promises::DPATH, "dpath"),
+        (Promises::TMPPATH, "tmppath"),
+        (Promises::INET, "inet"),
+        (Promises::MCAST, "mcast"),
+        (Promises::FATTR, "fattr"),
+        (Promises::CHOWN, "chown"),
+        (Promises::FLOCK, "flock"),
+        (Promises::UNIX, "unix"),
+        (Promises::DNS, "dns"),
+        (Promises::GETPW, "getpw"),
+        (Promises::SENDFD, "sendfd"),
+        (Promises::RECVFD, "recvfd"),
+        (Promises::TAPE, "tape"),
+        (Promises::TTY, "tty"),
+        (Promises::PROC, "proc"),
+        (Promises::EXEC, "exec"),
+        (Promises::PROT_EXEC, "prot_exec"),
+        (Promises::SETTIME, "settime"),
+        (Promises::PS, "ps"),
+        (Promises::VMINFO, "vminfo"),
+        (Promises::ID, "id"),
+        (Promises::PF, "pf"),
+        (Promises::ROUTE, "route"),
+        (Promises::WROUTE, "wroute"),
+        (Promises::AUDIO, "audio"),
+        (Promises::VIDEO, "video"),
+        (Promises::BPF, "bpf"),
+        (Promises::UNVEIL, "unveil"),
+        (Promises::ERROR, "error"),
+    ];
+
+    // Write the names of the promises, separated by spaces, as a C string.
+    // All of them take less than 256 bytes.
+    fn promises_str(promises: Promises, buf: &mut [u8; 256]) -> *const c_char {
+        let mut len = 0;
+        for &(promise, name) in PROMISE_NAMES {
+            if promises.contains(promise) {
+                if len > 0 {
+                    buf[len] = b' ';
+                    len += 1;
+                }
+                buf[len..len + name.len()].copy_from_slice(name.as_bytes());
+                len += name.len();
+            }
+        }
+        buf[len] = 0;
+        buf.as_ptr() as *const c_char
+    }
+
+    /// Restrict the system calls the process may use (see
+    /// [pledge(2)](https://man.openbsd.org/pledge.2)).
+    ///
+    /// `promises` applies to the process, and `execpromises` to the programs
+    /// it executes.  `None` leaves the current promises unchanged.  Promises
+    /// can only be removed by later calls, never added back.  Using a system
+    /// call that no promise allows kills the process with `SIGABRT`, unless
+    /// the promises include `ERROR`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use nix::unistd::{pledge, Promises};
+    ///
+    /// pledge(Some(Promises::STDIO | Promises::RPATH), Some(Promises::empty()))
+    ///     .expect("pledge failed");
+    /// ```
+    pub fn pledge(promises: Option<Promises>, execpromises: Option<Promises>) -> Result<()> {
+        let mut buf = [0u8; 256];
+        let mut execbuf = [0u8; 256];
+        let promises = promises.map_or(ptr::null(), |p| promises_str(p, &mut buf));
+        let execpromises = execpromises.map_or(ptr::null(), |p| promises_str(p, &mut execbuf));
+
+        let res = unsafe { libc::pledge(promises, execpromises) };
+
+        Errno::result(res).map(drop)
+    }
+
+    bitflags! {
+        /// The permissions of [`unveil`](fn.unveil.html)
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+        pub struct UnveilPermissions: u8 {
+            /// Read files (`r`)
+            const READ = 1 << 0;
+            /// Write files (`w`)
+            const WRITE = 1 << 1;
+            /// Execute files (`x`)
+            const EXEC = 1 << 2;
+            /// Create and remove files (`c`)
+            const CREATE = 1 << 3;
+        }
+    }
+
+    /// Make a path and what is beneath it visible to the process, with the
+    /// given permissions (see [unveil(2)](https://man.openbsd.org/unveil.2)).
+    ///
+    /// Once `unveil` is called, the rest of the file system is hidden, and
+    /// accessing it fails with `ENOENT`.  Empty permissions hide the path too.
+    /// Call [`unveil_lock`](fn.unveil_lock.html) afterwards to forbid further
+    /// changes.
+    pub fn unveil<P: ?Sized + NixPath>(path: &P, permissions: UnveilPermissions) -> Result<()> {
+        let mut perms = [0u8; 5];
+        let mut len = 0;
+        for &(perm, c) in &[(UnveilPermissions::READ, b'r'),
+                            (UnveilPermissions::WRITE, b'w'),
+                            (UnveilPermissions::EXEC, b'x'),
+                            (UnveilPermissions::CREATE, b'c')] {
+            if permissions.contains(perm) {
+                perms[len] = c;
+                len += 1;
+            }
+        }
+
+        let res = try!(path.with_nix_path(|cstr| unsafe {
+            libc::unveil(cstr.as_ptr(), perms.as_ptr() as *const c_char)
+        }));
+
+        Errno::result(res).map(drop)
+    }
+
+    /// Forbid further calls to [`unveil`](fn.unveil.html) (see
+    /// [unveil(2)](https://man.openbsd.org/unveil.2)).
+    pub fn unveil_lock() -> Result<()> {
+        let res = unsafe { libc::unveil(ptr::null(), ptr::null()) };
+
+        Errno::result(res).map(drop)
+    }
+}
+
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "emscripten",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+mod owned_pipe {
+    use Result;
+    use fcntl::OFlag;
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    use fcntl::{set_cloexec, set_nonblocking};
+    use std::os::unix::io::{AsRawFd, FromRawFd};
+    use super::{Fd, read, write};
+
+    /// The two ends of a pipe, which are closed when dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::fcntl::OFlag;
+    /// # use nix::unistd::Pipe;
+    /// let pipe = Pipe::new(OFlag::O_CLOEXEC).unwrap();
+    /// pipe.write(b"ping").unwrap();
+    /// let mut buf = [0u8; 4];
+    /// assert_eq!(pipe.read(&mut buf), Ok(4));
+    /// assert_eq!(&buf, b"ping");
+    /// ```
+    #[derive(Debug, Eq, Hash, PartialEq)]
+    pub struct Pipe {
+        /// The end to read from
+        pub read: Fd,
+        /// The end to write to
+        pub write: Fd,
+    }
+
+    impl Pipe {
+        /// Create a pipe, with the flags of [`pipe2`](fn.pipe2.html).
+        ///
+        /// Besides `O_CLOEXEC` and `O_NONBLOCK`, Linux since 3.4 supports
+        /// `O_DIRECT`, which makes each write a packet that reads don't mix with
+        /// the next one.  On iOS and macOS, the flags are set after the pipe is
+        /// created, so another thread calling `exec` may inherit it.
+        pub fn new(flags: OFlag) -> Result<Pipe> {
+            #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+            let (read, write) = try!(super::pipe2(flags));
+            #[cfg(any(target_os = "ios", target_os = "macos"))]
+            let (read, write) = try!(super::pipe());
+
+            // Owned before setting any flags, so both ends are closed on error
+            let pipe = unsafe { Pipe { read: Fd::from_raw_fd(read), write: Fd::from_raw_fd(write) } };
+            #[cfg(any(target_os = "ios", target_os = "macos"))]
+            try!(pipe.set_flags(flags));
+
+            Ok(pipe)
+        }
+
+        #[cfg(any(target_os = "ios", target_os = "macos"))]
+        fn set_flags(&self, flags: OFlag) -> Result<()> {
+            for fd in &[self.read.as_raw_fd(), self.write.as_raw_fd()] {
+                if flags.contains(OFlag::O_CLOEXEC) {
+                    try!(set_cloexec(*fd, true));
+                }
+                if flags.contains(OFlag::O_NONBLOCK) {
+                    try!(set_nonblocking(*fd, true));
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Read from the read end of the pipe.
+        pub fn read(&self, buf: &mut [u8]) -> Result<usize> {
+            read(self.read.as_raw_fd(), buf)
+        }
+
+        /// Write to the write end of the pipe.
+        pub fn write(&self, buf: &[u8]) -> Result<usize> {
+            write(self.write.as_raw_fd(), buf)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ttyname_grows_buffer() {
+        use pty::openpty;
+
+        let pty = openpty(None, None).unwrap();
+        // Start from a buffer too small for any name, so it must be grown
+        let name = ttyname_with_len(pty.slave, 1).unwrap();
+        assert_eq!(name, ttyname(pty.slave).unwrap());
+        assert!(name.to_str().unwrap().starts_with("/dev/"));
+        close(pty.master).unwrap();
+        close(pty.slave).unwrap();
+    }
+}
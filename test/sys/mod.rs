@@ -11,17 +11,85 @@ mod test_signal;
           target_os = "macos",
           target_os = "netbsd"))]
 mod test_aio;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod test_blk;
+#[cfg(target_os = "freebsd")]
+mod test_capsicum;
+#[cfg(any(target_os = "freebsd", target_os = "netbsd"))]
+mod test_extattr;
+#[cfg(target_os = "freebsd")]
+mod test_jail;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod test_bpf;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod test_futex;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod test_keyctl;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod test_klog;
+#[cfg(target_os = "linux")]
+mod test_landlock;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod test_net;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod test_perf_event;
 #[cfg(target_os = "linux")]
 mod test_signalfd;
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+mod test_random;
 mod test_socket;
 mod test_sockopt;
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+mod test_sysctl;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod test_sysinfo;
 mod test_termios;
+mod test_time;
+#[cfg(any(target_os = "android", target_os = "freebsd", target_os = "linux"))]
+mod test_timer;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod test_timerfd;
+#[cfg(target_os = "linux")]
+mod test_timex;
+#[cfg(target_os = "linux")]
+mod test_vt;
 mod test_ioctl;
 mod test_wait;
 mod test_uio;
+#[cfg(any(target_os = "freebsd", target_os = "linux"))]
+mod test_ipc;
+#[cfg(any(target_os = "freebsd", target_os = "linux"))]
+mod test_msg;
+#[cfg(target_os = "linux")]
+mod test_sem;
+#[cfg(any(target_os = "freebsd", target_os = "linux"))]
+mod test_shm;
 
 #[cfg(target_os = "linux")]
 mod test_epoll;
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+mod test_event;
+#[cfg(target_os = "linux")]
+mod test_eventfd;
+#[cfg(target_os = "linux")]
+mod test_io_uring;
 mod test_pthread;
 #[cfg(any(target_os = "android",
           target_os = "linux"))]
@@ -1,5 +1,5 @@
 use bytes::{Bytes, BytesMut};
-use libc::{c_int, c_void};
+use libc::{self, c_int, c_void};
 use nix::{Error, Result};
 use nix::errno::*;
 use nix::sys::aio::*;
@@ -8,7 +8,7 @@ use nix::sys::time::{TimeSpec, TimeValLike};
 use std::io::{Write, Read, Seek, SeekFrom};
 use std::ops::Deref;
 use std::os::unix::io::AsRawFd;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::{thread, time};
 use tempfile::tempfile;
 
@@ -505,6 +505,50 @@ fn test_write_sigev_signal() {
     assert!(rbuf == EXPECT);
 }
 
+lazy_static! {
+    pub static ref THREAD_NOTIFIED: AtomicUsize = AtomicUsize::new(0);
+}
+
+extern fn thread_notify(sv: libc::sigval) {
+    THREAD_NOTIFIED.store(sv.sival_ptr as usize, Ordering::Relaxed);
+}
+
+// Test an aio operation with completion delivered by calling a function in a
+// new thread
+#[test]
+#[cfg(any(target_os = "freebsd", target_os = "linux"))]
+#[cfg_attr(all(target_env = "musl", target_arch = "x86_64"), ignore)]
+fn test_write_sigev_thread() {
+    const INITIAL: &[u8] = b"abcdef123456";
+    const WBUF: &[u8] = b"CDEF";
+    let mut rbuf = Vec::new();
+    const EXPECT: &[u8] = b"abCDEF123456";
+
+    let mut f = tempfile().unwrap();
+    f.write_all(INITIAL).unwrap();
+    THREAD_NOTIFIED.store(0, Ordering::Relaxed);
+    let mut aiocb = AioCb::from_slice( f.as_raw_fd(),
+                           2,   //offset
+                           WBUF,
+                           0,   //priority
+                           SigevNotify::SigevThread {
+                               function: thread_notify,
+                               si_value: 42
+                           },
+                           LioOpcode::LIO_NOP);
+    aiocb.write().unwrap();
+    while THREAD_NOTIFIED.load(Ordering::Relaxed) == 0 {
+        thread::sleep(time::Duration::from_millis(10));
+    }
+    assert_eq!(THREAD_NOTIFIED.load(Ordering::Relaxed), 42);
+
+    assert!(aiocb.aio_return().unwrap() as usize == WBUF.len());
+    f.seek(SeekFrom::Start(0)).unwrap();
+    let len = f.read_to_end(&mut rbuf).unwrap();
+    assert!(len == EXPECT.len());
+    assert!(rbuf == EXPECT);
+}
+
 // Test lio_listio with LIO_WAIT, so all AIO ops should be complete by the time
 // lio_listio returns.
 #[test]
@@ -535,7 +579,7 @@ fn test_lio_listio_wait() {
                                 0,   //priority
                                 SigevNotify::SigevNone,
                                 LioOpcode::LIO_READ);
-        let err = lio_listio(LioMode::LIO_WAIT, &[&mut wcb, &mut rcb], SigevNotify::SigevNone);
+        let err = lio_listio(LioMode::LIO_WAIT, &mut [&mut wcb, &mut rcb], SigevNotify::SigevNone);
         err.expect("lio_listio failed");
 
         assert!(wcb.aio_return().unwrap() as usize == WBUF.len());
@@ -579,7 +623,7 @@ fn test_lio_listio_nowait() {
                                 0,   //priority
                                 SigevNotify::SigevNone,
                                 LioOpcode::LIO_READ);
-        let err = lio_listio(LioMode::LIO_NOWAIT, &[&mut wcb, &mut rcb], SigevNotify::SigevNone);
+        let err = lio_listio(LioMode::LIO_NOWAIT, &mut [&mut wcb, &mut rcb], SigevNotify::SigevNone);
         err.expect("lio_listio failed");
 
         poll_aio(&mut wcb).unwrap();
@@ -635,7 +679,7 @@ fn test_lio_listio_signal() {
                                 LioOpcode::LIO_READ);
         SIGNALED.store(false, Ordering::Relaxed);
         unsafe { sigaction(Signal::SIGUSR2, &sa) }.unwrap();
-        let err = lio_listio(LioMode::LIO_NOWAIT, &[&mut wcb, &mut rcb], sigev_notify);
+        let err = lio_listio(LioMode::LIO_NOWAIT, &mut [&mut wcb, &mut rcb], sigev_notify);
         err.expect("lio_listio failed");
         while !SIGNALED.load(Ordering::Relaxed) {
             thread::sleep(time::Duration::from_millis(10));
@@ -669,5 +713,5 @@ fn test_lio_listio_read_immutable() {
                            0,   //priority
                            SigevNotify::SigevNone,
                            LioOpcode::LIO_READ);
-    let _ = lio_listio(LioMode::LIO_NOWAIT, &[&mut rcb], SigevNotify::SigevNone);
+    let _ = lio_listio(LioMode::LIO_NOWAIT, &mut [&mut rcb], SigevNotify::SigevNone);
 }
@@ -0,0 +1,16 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::sys::blk;
+use std::os::unix::io::AsRawFd;
+use tempfile::tempfile;
+
+// Block device ioctls must be rejected on anything that isn't a block device.
+#[test]
+fn test_blk_regular_file() {
+    let tmp = tempfile().unwrap();
+    let fd = tmp.as_raw_fd();
+
+    assert_eq!(blk::size(fd), Err(Error::Sys(Errno::ENOTTY)));
+    assert_eq!(blk::logical_sector_size(fd), Err(Error::Sys(Errno::ENOTTY)));
+    assert_eq!(blk::discard(fd, 0, 512), Err(Error::Sys(Errno::ENOTTY)));
+}
@@ -0,0 +1,76 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::sys::bpf::*;
+use nix::unistd::close;
+use std::mem;
+
+fn key(k: u32) -> [u8; 4] {
+    unsafe { mem::transmute(k) }
+}
+
+#[test]
+fn test_bpf_hash_map() {
+    let fd = match map_create(MapType::BPF_MAP_TYPE_HASH, 4, 4, 8, MapFlags::empty()) {
+        // Restricted to privileged processes, or the kernel lacks bpf
+        Err(Error::Sys(Errno::EPERM)) | Err(Error::Sys(Errno::ENOSYS)) => return,
+        res => res.unwrap(),
+    };
+    let mut value = [0u8; 4];
+    let mut next = [0u8; 4];
+
+    assert_eq!(unsafe { map_get_next_key(fd, None, &mut next) },
+               Err(Error::Sys(Errno::ENOENT)));
+    unsafe { map_update_elem(fd, &key(1), b"abcd", UpdateFlags::empty()) }.unwrap();
+    assert_eq!(unsafe { map_update_elem(fd, &key(1), b"efgh", UpdateFlags::BPF_NOEXIST) },
+               Err(Error::Sys(Errno::EEXIST)));
+    unsafe { map_lookup_elem(fd, &key(1), &mut value) }.unwrap();
+    assert_eq!(&value, b"abcd");
+
+    unsafe { map_get_next_key(fd, None, &mut next) }.unwrap();
+    assert_eq!(next, key(1));
+    assert_eq!(unsafe { map_get_next_key(fd, Some(&key(1)), &mut next) },
+               Err(Error::Sys(Errno::ENOENT)));
+
+    unsafe { map_delete_elem(fd, &key(1)) }.unwrap();
+    assert_eq!(unsafe { map_lookup_elem(fd, &key(1), &mut value) },
+               Err(Error::Sys(Errno::ENOENT)));
+    close(fd).unwrap();
+}
+
+#[test]
+fn test_bpf_array_map() {
+    let fd = match map_create(MapType::BPF_MAP_TYPE_ARRAY, 4, 4, 2, MapFlags::empty()) {
+        Err(Error::Sys(Errno::EPERM)) | Err(Error::Sys(Errno::ENOSYS)) => return,
+        res => res.unwrap(),
+    };
+    let mut value = [0xffu8; 4];
+
+    // The elements of arrays always exist, and start zeroed
+    unsafe { map_lookup_elem(fd, &key(1), &mut value) }.unwrap();
+    assert_eq!(value, [0; 4]);
+    assert_eq!(unsafe { map_delete_elem(fd, &key(1)) }, Err(Error::Sys(Errno::EINVAL)));
+    assert_eq!(unsafe { map_update_elem(fd, &key(2), b"abcd", UpdateFlags::empty()) },
+               Err(Error::Sys(Errno::E2BIG)));
+    close(fd).unwrap();
+}
+
+#[test]
+fn test_bpf_prog_load() {
+    // r0 = 0; exit
+    let insns = [0xb7, 0, 0, 0, 0, 0, 0, 0,
+                 0x95, 0, 0, 0, 0, 0, 0, 0];
+    let fd = match prog_load(ProgType::BPF_PROG_TYPE_SOCKET_FILTER, &insns, "GPL", None) {
+        Err(Error::Sys(Errno::EPERM)) | Err(Error::Sys(Errno::ENOSYS)) => return,
+        res => res.unwrap(),
+    };
+    close(fd).unwrap();
+
+    // exit, without setting r0
+    let mut log = [0u8; 4096];
+    assert_eq!(prog_load(ProgType::BPF_PROG_TYPE_SOCKET_FILTER, &insns[8..], "GPL",
+                         Some(&mut log)),
+               Err(Error::Sys(Errno::EACCES)));
+    assert!(log[0] != 0);
+    assert_eq!(prog_load(ProgType::BPF_PROG_TYPE_SOCKET_FILTER, &insns[..7], "GPL", None),
+               Err(Error::Sys(Errno::EINVAL)));
+}
@@ -0,0 +1,52 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::fcntl::{fcntl, FcntlArg};
+use nix::sys::capsicum::*;
+use nix::unistd::{read, write};
+use tempfile::tempfile;
+use std::os::unix::io::AsRawFd;
+
+#[test]
+fn test_cap_getmode() {
+    // The test harness never enters capability mode
+    assert_eq!(cap_getmode(), Ok(false));
+}
+
+#[test]
+fn test_cap_rights() {
+    let mut rights = CapRights::new();
+    assert!(!rights.contains(CapRight::CAP_READ));
+    rights.add(CapRight::CAP_READ);
+    rights.add(CapRight::CAP_PWRITE);
+    assert!(rights.contains(CapRight::CAP_READ));
+    // CAP_PWRITE is made of CAP_SEEK and CAP_WRITE
+    assert!(rights.contains(CapRight::CAP_WRITE));
+    rights.remove(CapRight::CAP_READ);
+    assert!(!rights.contains(CapRight::CAP_READ));
+}
+
+#[test]
+fn test_cap_rights_limit() {
+    let f = tempfile().unwrap();
+    let fd = f.as_raw_fd();
+
+    let mut rights = CapRights::new();
+    rights.add(CapRight::CAP_READ);
+    rights.add(CapRight::CAP_FCNTL);
+    cap_rights_limit(fd, &rights).unwrap();
+    cap_fcntls_limit(fd, CapFcntlRights::CAP_FCNTL_GETFL).unwrap();
+
+    let limited = cap_rights_get(fd).unwrap();
+    assert!(limited.contains_all(&rights));
+    assert!(!limited.contains(CapRight::CAP_WRITE));
+    assert_eq!(cap_fcntls_get(fd), Ok(CapFcntlRights::CAP_FCNTL_GETFL));
+
+    let mut buf = [0u8; 1];
+    read(fd, &mut buf).unwrap();
+    assert_eq!(write(fd, b"x"), Err(Error::Sys(Errno::ENOTCAPABLE)));
+    fcntl(fd, FcntlArg::F_GETFL).unwrap();
+
+    // Rights can't be given back
+    rights.add(CapRight::CAP_WRITE);
+    assert_eq!(cap_rights_limit(fd, &rights), Err(Error::Sys(Errno::ENOTCAPABLE)));
+}
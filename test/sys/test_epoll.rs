@@ -1,5 +1,6 @@
 use nix::sys::epoll::{EpollCreateFlags, EpollFlags, EpollOp, EpollEvent};
-use nix::sys::epoll::{epoll_create1, epoll_ctl};
+use nix::sys::epoll::{epoll_create1, epoll_ctl, Epoll};
+use nix::unistd::{close, pipe, write};
 use nix::Error;
 use nix::errno::Errno;
 
@@ -22,3 +23,83 @@ pub fn test_epoll_ctl() {
     epoll_ctl(efd, EpollOp::EpollCtlAdd, 1, &mut event).unwrap();
     epoll_ctl(efd, EpollOp::EpollCtlDel, 1, None).unwrap();
 }
+
+#[test]
+pub fn test_epoll_owned() {
+    let (r, w) = pipe().unwrap();
+    let epoll = Epoll::new().unwrap();
+    let mut events = [EpollEvent::empty(); 4];
+
+    epoll.add(r, EpollFlags::EPOLLIN, 1).unwrap();
+    assert_eq!(epoll.add(r, EpollFlags::EPOLLIN, 1), Err(Error::Sys(Errno::EEXIST)));
+    assert_eq!(epoll.wait(&mut events, 0), Ok(0));
+
+    write(w, b"x").unwrap();
+    assert_eq!(epoll.wait(&mut events, 0), Ok(1));
+    assert_eq!(events[0].data(), 1);
+    assert_eq!(events[0].events(), EpollFlags::EPOLLIN);
+
+    epoll.modify(r, EpollFlags::EPOLLIN, 2).unwrap();
+    assert_eq!(epoll.wait(&mut events, 0), Ok(1));
+    assert_eq!(events[0].data(), 2);
+
+    epoll.delete(r).unwrap();
+    assert_eq!(epoll.wait(&mut events, 0), Ok(0));
+    assert_eq!(epoll.delete(r), Err(Error::Sys(Errno::ENOENT)));
+
+    close(r).unwrap();
+    close(w).unwrap();
+}
+
+#[test]
+pub fn test_epoll_pwait() {
+    use nix::sys::signal::SigSet;
+    use std::os::unix::io::AsRawFd;
+    use nix::sys::epoll::epoll_pwait;
+
+    let (r, w) = pipe().unwrap();
+    let epoll = Epoll::new().unwrap();
+    epoll.add(r, EpollFlags::EPOLLIN, 7).unwrap();
+    write(w, b"x").unwrap();
+
+    let mut events = [EpollEvent::empty(); 4];
+    assert_eq!(epoll.pwait(&mut events, 0, &SigSet::all()), Ok(1));
+    assert_eq!(events[0].data(), 7);
+    assert_eq!(epoll_pwait(epoll.as_raw_fd(), &mut events, 0, None), Ok(1));
+
+    close(r).unwrap();
+    close(w).unwrap();
+}
+
+#[test]
+pub fn test_epoll_wait_timeout() {
+    use nix::sys::epoll::{epoll_pwait2, epoll_pwait2_available};
+    use nix::sys::time::{TimeSpec, TimeValLike};
+    use std::os::unix::io::AsRawFd;
+    use std::time::{Duration, Instant};
+
+    let (r, w) = pipe().unwrap();
+    let epoll = Epoll::new().unwrap();
+    epoll.add(r, EpollFlags::EPOLLIN, 3).unwrap();
+    let mut events = [EpollEvent::empty(); 4];
+
+    let start = Instant::now();
+    assert_eq!(epoll.wait_timeout(&mut events, Some(&TimeSpec::microseconds(500)), None), Ok(0));
+    assert!(start.elapsed() >= Duration::new(0, 500_000));
+
+    write(w, b"x").unwrap();
+    assert_eq!(epoll.wait_timeout(&mut events, None, None), Ok(1));
+    assert_eq!(events[0].data(), 3);
+    assert_eq!(epoll.wait_timeout(&mut events, Some(&TimeSpec::seconds(-1)), None),
+               Err(Error::Sys(Errno::EINVAL)));
+
+    let res = epoll_pwait2(epoll.as_raw_fd(), &mut events, Some(&TimeSpec::zero()), None);
+    if epoll_pwait2_available() {
+        assert_eq!(res, Ok(1));
+    } else {
+        assert_eq!(res, Err(Error::Sys(Errno::ENOSYS)));
+    }
+
+    close(r).unwrap();
+    close(w).unwrap();
+}
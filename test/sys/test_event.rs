@@ -0,0 +1,99 @@
+use nix::sys::event::*;
+use nix::sys::time::{TimeSpec, TimeValLike};
+use nix::unistd::close;
+
+#[test]
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "macos"))]
+fn test_evfilt_user() {
+    let kq = kqueue().unwrap();
+    let register = KEventBuilder::user(1).add().clear().udata(5).build();
+    kevent(kq, &[register], &mut [], 0).unwrap();
+
+    let mut events = [KEventBuilder::new(0, EventFilter::EVFILT_READ).build()];
+    assert_eq!(kevent(kq, &[], &mut events, 0).unwrap(), 0);
+
+    let trigger = KEventBuilder::user(1).trigger().build();
+    kevent(kq, &[trigger], &mut [], 0).unwrap();
+    assert_eq!(kevent(kq, &[], &mut events, 0).unwrap(), 1);
+    assert_eq!(events[0].ident(), 1);
+    assert_eq!(events[0].filter(), EventFilter::EVFILT_USER);
+    assert_eq!(events[0].udata(), 5);
+
+    // EV_CLEAR resets the event once it's returned
+    assert_eq!(kevent(kq, &[], &mut events, 0).unwrap(), 0);
+
+    close(kq).unwrap();
+}
+
+#[test]
+fn test_vnode_watcher() {
+    use nix::fcntl::{open, OFlag};
+    use nix::sys::stat::Mode;
+    use std::fs;
+    use std::io::Write;
+    use tempdir::TempDir;
+
+    let tempdir = TempDir::new("nix-test_vnode_watcher").unwrap();
+    let path = tempdir.path().join("file");
+    fs::File::create(&path).unwrap();
+
+    let fd = open(&path, OFlag::O_RDONLY, Mode::empty()).unwrap();
+    let watcher = VnodeWatcher::new().unwrap();
+    watcher.add(fd, VnodeEvents::NOTE_WRITE | VnodeEvents::NOTE_DELETE).unwrap();
+    assert_eq!(watcher.read_events(Some(TimeSpec::zero())).unwrap(), vec![]);
+
+    fs::OpenOptions::new().write(true).open(&path).unwrap().write_all(b"x").unwrap();
+    fs::remove_file(&path).unwrap();
+    let events = watcher.read_events(Some(TimeSpec::seconds(1))).unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].fd(), fd);
+    assert_eq!(events[0].events(), VnodeEvents::NOTE_WRITE | VnodeEvents::NOTE_DELETE);
+
+    watcher.remove(fd).unwrap();
+    close(fd).unwrap();
+}
+
+#[test]
+fn test_process_watcher() {
+    use libc::_exit;
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::{fork, pipe, read, write};
+    use nix::unistd::ForkResult::*;
+
+    #[allow(unused_variables)]
+    let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let (r, w) = pipe().unwrap();
+    // Safe: The child only calls `read` and `_exit`, which are async-signal-safe.
+    match fork().expect("Error: Fork Failed") {
+        Child => {
+            // Wait until the parent is watching
+            let mut buf = [0u8; 1];
+            let _ = read(r, &mut buf);
+            unsafe { _exit(3) }
+        },
+        Parent { child } => {
+            let watcher = ProcessWatcher::new().unwrap();
+            #[cfg(any(target_os = "macos", target_os = "ios"))]
+            let events = ProcEvents::NOTE_EXIT | ProcEvents::NOTE_EXITSTATUS;
+            #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+            let events = ProcEvents::NOTE_EXIT;
+            watcher.add(child, events).unwrap();
+            write(w, b"x").unwrap();
+
+            let events = watcher.read_events(Some(TimeSpec::seconds(10))).unwrap();
+            assert_eq!(events.len(), 1);
+            assert_eq!(events[0].pid(), child);
+            assert!(events[0].events().contains(ProcEvents::NOTE_EXIT));
+            assert_eq!(events[0].exit_status(), Some(WaitStatus::Exited(child, 3)));
+            assert_eq!(events[0].parent(), None);
+
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 3)));
+            close(r).unwrap();
+            close(w).unwrap();
+        },
+    }
+}
@@ -0,0 +1,43 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::sys::eventfd::{EfdFlags, EventFd};
+
+#[test]
+fn test_eventfd() {
+    let efd = EventFd::new(1, EfdFlags::EFD_CLOEXEC | EfdFlags::EFD_NONBLOCK).unwrap();
+    efd.write(41).unwrap();
+    assert_eq!(efd.read(), Ok(Some(42)));
+    assert_eq!(efd.read(), Ok(None));
+    assert_eq!(efd.write(u64::max_value()), Err(Error::Sys(Errno::EINVAL)));
+}
+
+#[test]
+fn test_eventfd_semaphore() {
+    let flags = EfdFlags::EFD_NONBLOCK | EfdFlags::EFD_SEMAPHORE;
+    let efd = EventFd::new(2, flags).unwrap();
+    assert_eq!(efd.read(), Ok(Some(1)));
+    assert_eq!(efd.read(), Ok(Some(1)));
+    assert_eq!(efd.read(), Ok(None));
+}
+
+#[test]
+fn test_eventfd_wakeup() {
+    use std::sync::Arc;
+    use std::thread;
+
+    let efd = Arc::new(EventFd::new(0, EfdFlags::empty()).unwrap());
+    let waker = efd.clone();
+    let child = thread::spawn(move || waker.write(1).unwrap());
+    assert_eq!(efd.read(), Ok(Some(1)));
+    child.join().unwrap();
+}
+
+#[test]
+fn test_eventfd_raw_fd() {
+    use std::os::unix::io::{FromRawFd, IntoRawFd};
+
+    let efd = EventFd::new(3, EfdFlags::EFD_NONBLOCK).unwrap();
+    let fd = efd.into_raw_fd();
+    let efd = unsafe { EventFd::from_raw_fd(fd) };
+    assert_eq!(efd.read(), Ok(Some(3)));
+}
@@ -0,0 +1,46 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::sys::extattr::*;
+use tempfile::NamedTempFile;
+use std::os::unix::io::AsRawFd;
+
+#[test]
+fn test_extattr_names() {
+    let list = b"\x06origin\x01a\x03xyz";
+    let names: Vec<&[u8]> = ExtattrNames::new(list).collect();
+    assert_eq!(names, vec![&b"origin"[..], &b"a"[..], &b"xyz"[..]]);
+
+    // A list truncated by a small buffer stops at the last whole name
+    let names: Vec<&[u8]> = ExtattrNames::new(&list[..9]).collect();
+    assert_eq!(names, vec![&b"origin"[..]]);
+}
+
+#[test]
+fn test_extattr() {
+    const NS: AttrNamespace = AttrNamespace::EXTATTR_NAMESPACE_USER;
+
+    let tmp = NamedTempFile::new().unwrap();
+    let path = tmp.path();
+    match extattr_set_file(path, NS, "origin", b"backup") {
+        // The file system doesn't support extended attributes
+        Err(Error::Sys(Errno::EOPNOTSUPP)) => return,
+        r => r.unwrap(),
+    }
+
+    let mut buf = [0u8; 64];
+    assert_eq!(extattr_get_file(path, NS, "origin", &mut []), Ok(6));
+    assert_eq!(extattr_get_file(path, NS, "origin", &mut buf), Ok(6));
+    assert_eq!(&buf[..6], b"backup");
+
+    let fd = tmp.as_raw_fd();
+    extattr_set_fd(fd, NS, "other", b"").unwrap();
+    let len = extattr_list_fd(fd, NS, &mut buf).unwrap();
+    let mut names: Vec<&[u8]> = ExtattrNames::new(&buf[..len]).collect();
+    names.sort();
+    assert_eq!(names, vec![&b"origin"[..], &b"other"[..]]);
+
+    extattr_delete_link(path, NS, "origin").unwrap();
+    extattr_delete_fd(fd, NS, "other").unwrap();
+    assert_eq!(extattr_list_file(path, NS, &mut buf), Ok(0));
+    assert_eq!(extattr_get_fd(fd, NS, "origin", &mut buf), Err(Error::Sys(Errno::ENOATTR)));
+}
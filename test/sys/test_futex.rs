@@ -0,0 +1,96 @@
+use libc;
+use nix::Error;
+use nix::errno::Errno;
+use nix::sys::futex::*;
+use nix::sys::time::{clock_gettime, ClockId, TimeSpec, TimeValLike};
+use nix::unistd::gettid;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn test_futex_wait_timeout() {
+    let word = 1u32;
+    let timeout = TimeSpec::milliseconds(10);
+
+    assert_eq!(futex_wait(&word, 0, Some(&timeout), FutexFlags::empty()),
+               Err(Error::Sys(Errno::EAGAIN)));
+    assert_eq!(futex_wait(&word, 1, Some(&timeout), FutexFlags::FUTEX_PRIVATE_FLAG),
+               Err(Error::Sys(Errno::ETIMEDOUT)));
+    assert_eq!(futex_wake(&word, 1, FutexFlags::empty()), Ok(0));
+}
+
+#[test]
+fn test_futex_wait_bitset_timeout() {
+    let word = 0u32;
+    let deadline = clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap() + TimeSpec::milliseconds(10);
+    assert_eq!(futex_wait_bitset(&word, 0, Some(&deadline), FUTEX_BITSET_MATCH_ANY,
+                                 FutexFlags::empty()),
+               Err(Error::Sys(Errno::ETIMEDOUT)));
+
+    let deadline = clock_gettime(ClockId::CLOCK_REALTIME).unwrap() + TimeSpec::milliseconds(10);
+    assert_eq!(futex_wait_bitset(&word, 0, Some(&deadline), 1, FutexFlags::FUTEX_CLOCK_REALTIME),
+               Err(Error::Sys(Errno::ETIMEDOUT)));
+    assert_eq!(futex_wait_bitset(&word, 0, None, 0, FutexFlags::empty()),
+               Err(Error::Sys(Errno::EINVAL)));
+    assert_eq!(futex_wake_bitset(&word, 1, 1, FutexFlags::empty()), Ok(0));
+}
+
+// Wake up the threads waiting on `word` until `n` of them have been woken up
+fn wake_all(word: &u32, n: i32) {
+    let mut woken = 0;
+    while woken < n {
+        woken += futex_wake(word, n - woken, FutexFlags::empty()).unwrap();
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn test_futex_wake() {
+    let word = Arc::new(0u32);
+    let waiter = {
+        let word = word.clone();
+        thread::spawn(move || futex_wait(&*word, 0, None, FutexFlags::empty()))
+    };
+
+    wake_all(&word, 1);
+    assert_eq!(waiter.join().unwrap(), Ok(()));
+}
+
+#[test]
+fn test_futex_cmp_requeue() {
+    let words = Arc::new([0u32, 0u32]);
+    let waiters: Vec<_> = (0..2).map(|_| {
+        let words = words.clone();
+        thread::spawn(move || futex_wait(&words[0], 0, None, FutexFlags::empty()))
+    }).collect();
+
+    assert_eq!(futex_cmp_requeue(&words[0], 0, &words[1], 2, 1, FutexFlags::empty()),
+               Err(Error::Sys(Errno::EAGAIN)));
+    // Move both waiters to the second futex once they're asleep
+    let mut moved = 0;
+    while moved < 2 {
+        moved += futex_cmp_requeue(&words[0], 0, &words[1], 2, 0, FutexFlags::empty()).unwrap();
+        thread::sleep(Duration::from_millis(1));
+    }
+    assert_eq!(futex_wake(&words[0], 2, FutexFlags::empty()), Ok(0));
+    wake_all(&words[1], 2);
+    for waiter in waiters {
+        assert_eq!(waiter.join().unwrap(), Ok(()));
+    }
+}
+
+#[test]
+fn test_futex_pi() {
+    let mut word = 0u32;
+    unsafe {
+        futex_trylock_pi(&mut word, FutexFlags::empty()).unwrap();
+        assert_eq!(word & FUTEX_TID_MASK, libc::pid_t::from(gettid()) as u32);
+        assert_eq!(futex_lock_pi(&mut word, None, FutexFlags::empty()),
+                   Err(Error::Sys(Errno::EDEADLK)));
+        futex_unlock_pi(&mut word, FutexFlags::empty()).unwrap();
+        assert_eq!(futex_unlock_pi(&mut word, FutexFlags::empty()),
+                   Err(Error::Sys(Errno::EPERM)));
+    }
+    assert_eq!(word, 0);
+}
@@ -0,0 +1,104 @@
+use libc;
+use nix::Error;
+use nix::errno::Errno;
+use nix::sys::io_uring::*;
+use nix::sys::socket::{bind, connect, getsockname, listen, socket, AddressFamily, InetAddr, SockAddr,
+                       SockFlag, SockType};
+use nix::sys::uio::IoVec;
+use nix::unistd::close;
+use std::net::SocketAddr;
+use std::os::unix::io::AsRawFd;
+use std::str::FromStr;
+use tempfile::tempfile;
+
+// io_uring may be missing from the kernel, or disabled by seccomp or sysctl
+fn ring(entries: u32) -> Option<IoUring> {
+    match IoUring::new(entries) {
+        Ok(ring) => Some(ring),
+        Err(Error::Sys(Errno::ENOSYS)) | Err(Error::Sys(Errno::EPERM)) => None,
+        Err(e) => panic!("io_uring_setup failed: {}", e),
+    }
+}
+
+#[test]
+fn test_nop() {
+    let mut ring = match ring(4) {
+        Some(ring) => ring,
+        None => return,
+    };
+
+    for i in 0..4 {
+        unsafe { ring.push(&Sqe::nop().user_data(i)) }.unwrap();
+    }
+    assert_eq!(unsafe { ring.push(&Sqe::nop()) }, Err(Error::Sys(Errno::EBUSY)));
+    assert_eq!(ring.submit_and_wait(4).unwrap(), 4);
+
+    let mut user_data: Vec<u64> = (0..4).map(|_| {
+        let cqe = ring.pop_completion().unwrap();
+        assert_eq!(cqe.result(), Ok(0));
+        cqe.user_data()
+    }).collect();
+    user_data.sort();
+    assert_eq!(user_data, vec![0, 1, 2, 3]);
+    assert_eq!(ring.pop_completion(), None);
+}
+
+#[test]
+fn test_writev_fsync_readv() {
+    let mut ring = match ring(4) {
+        Some(ring) => ring,
+        None => return,
+    };
+    let file = tempfile().unwrap();
+    let fd = file.as_raw_fd();
+
+    let wbufs = [IoVec::from_slice(b"abc"), IoVec::from_slice(b"defg")];
+    let writev = Sqe::writev(fd, &wbufs, 0).user_data(1).flags(SqeFlags::IOSQE_IO_LINK);
+    let fsync = Sqe::fsync(fd, FsyncFlags::IORING_FSYNC_DATASYNC).user_data(2);
+    unsafe {
+        ring.push(&writev).unwrap();
+        ring.push(&fsync).unwrap();
+    }
+    ring.submit_and_wait(2).unwrap();
+    let cqe = ring.pop_completion().unwrap();
+    assert_eq!((cqe.user_data(), cqe.result()), (1, Ok(7)));
+    let cqe = ring.pop_completion().unwrap();
+    assert_eq!((cqe.user_data(), cqe.result()), (2, Ok(0)));
+
+    let mut rbuf1 = [0u8; 4];
+    let mut rbuf2 = [0u8; 3];
+    {
+        let rbufs = [IoVec::from_mut_slice(&mut rbuf1), IoVec::from_mut_slice(&mut rbuf2)];
+        unsafe { ring.push(&Sqe::readv(fd, &rbufs, 0).user_data(3)) }.unwrap();
+        ring.submit_and_wait(1).unwrap();
+        let cqe = ring.pop_completion().unwrap();
+        assert_eq!((cqe.user_data(), cqe.result()), (3, Ok(7)));
+    }
+    assert_eq!(&rbuf1, b"abcd");
+    assert_eq!(&rbuf2, b"efg");
+}
+
+#[test]
+fn test_accept() {
+    let mut ring = match ring(4) {
+        Some(ring) => ring,
+        None => return,
+    };
+    let listener = socket(AddressFamily::Inet, SockType::Stream, SockFlag::empty(), None).unwrap();
+    let addr = SockAddr::new_inet(InetAddr::from_std(&SocketAddr::from_str("127.0.0.1:0").unwrap()));
+    bind(listener, &addr).unwrap();
+    listen(listener, 1).unwrap();
+    let addr = getsockname(listener).unwrap();
+
+    unsafe { ring.push(&Sqe::accept(listener, SockFlag::SOCK_CLOEXEC)) }.unwrap();
+    ring.submit().unwrap();
+    let client = socket(AddressFamily::Inet, SockType::Stream, SockFlag::empty(), None).unwrap();
+    connect(client, &addr).unwrap();
+    ring.submit_and_wait(1).unwrap();
+
+    let conn = ring.pop_completion().unwrap().result().unwrap() as libc::c_int;
+    assert!(conn >= 0);
+    close(conn).unwrap();
+    close(client).unwrap();
+    close(listener).unwrap();
+}
@@ -0,0 +1,11 @@
+use nix::sys::ipc::ftok;
+use tempfile::NamedTempFile;
+
+#[test]
+fn test_ftok() {
+    let file = NamedTempFile::new().unwrap();
+    let key1 = ftok(file.path(), b'a').unwrap();
+    assert_eq!(ftok(file.path(), b'a').unwrap(), key1);
+    assert!(ftok(file.path(), b'b').unwrap() != key1);
+    assert!(ftok("/nonexistent/nix-test_ftok", b'a').is_err());
+}
@@ -0,0 +1,46 @@
+use nix::sys::jail::*;
+use nix::unistd::Uid;
+use std::io::Write;
+
+#[test]
+fn test_jail_params() {
+    let mut params = JailParams::new();
+    params.string("name", "nix").unwrap()
+          .int("securelevel", 3).unwrap()
+          .flag("persist", false).unwrap()
+          .string_buffer("errmsg", 64).unwrap();
+    assert_eq!(params.get_string("name").unwrap().to_bytes(), b"nix");
+    assert_eq!(params.get_int("securelevel"), Some(3));
+    assert_eq!(params.get_string("errmsg").unwrap().to_bytes(), b"");
+    // Flags have no value
+    assert_eq!(params.get_bytes("nopersist"), None);
+    assert!(params.string("name", "n\0x").is_err());
+}
+
+#[test]
+fn test_jail_set_get_remove() {
+    if !Uid::current().is_root() {
+        let stderr = ::std::io::stderr();
+        let mut handle = stderr.lock();
+        writeln!(handle, "test_jail_set_get_remove requires root privileges. Skipping test.")
+            .unwrap();
+        return;
+    }
+
+    let mut params = JailParams::new();
+    params.string("name", "nix_test_jail").unwrap()
+          .string("path", "/").unwrap()
+          .string("host.hostname", "nix.test").unwrap()
+          .flag("persist", true).unwrap();
+    let jid = jail_set(&mut params, JailFlags::JAIL_CREATE).unwrap();
+
+    let mut query = JailParams::new();
+    query.int("jid", jid).unwrap()
+         .string_buffer("host.hostname", 256).unwrap()
+         .string_buffer("name", 256).unwrap();
+    assert_eq!(jail_get(&mut query, JailFlags::empty()), Ok(jid));
+    assert_eq!(query.get_string("host.hostname").unwrap().to_bytes(), b"nix.test");
+    assert_eq!(query.get_string("name").unwrap().to_bytes(), b"nix_test_jail");
+
+    jail_remove(jid).unwrap();
+}
@@ -0,0 +1,39 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::sys::keyctl::*;
+
+#[test]
+fn test_keyctl() {
+    // The thread keyring keeps the key away from the other tests
+    let key = match add_key("user", "nix:test_keyctl", b"secret", KEY_SPEC_THREAD_KEYRING) {
+        Ok(key) => key,
+        // No keyring support, or forbidden by a sandbox
+        Err(Error::Sys(Errno::ENOSYS)) | Err(Error::Sys(Errno::EPERM)) => return,
+        Err(e) => panic!("add_key failed: {:?}", e),
+    };
+    let keyring = get_keyring_id(KEY_SPEC_THREAD_KEYRING, false).unwrap();
+
+    assert_eq!(read(key).unwrap(), b"secret");
+    // The keyring only holds the key
+    assert_eq!(read(keyring).unwrap().len(), 4);
+    assert_eq!(search(keyring, "user", "nix:test_keyctl", None), Ok(key));
+    assert_eq!(request_key("user", "nix:test_keyctl", None), Ok(key));
+    assert_eq!(request_key("user", "nix:nonexistent", None), Err(Error::Sys(Errno::ENOKEY)));
+
+    // Adding a key with the same description updates it
+    let long = vec![42; 1000];
+    assert_eq!(add_key("user", "nix:test_keyctl", &long, KEY_SPEC_THREAD_KEYRING), Ok(key));
+    assert_eq!(read(key).unwrap(), long);
+    update(key, b"other").unwrap();
+    assert_eq!(read(key).unwrap(), b"other");
+
+    // Possessing the key, which requires finding it, is enough to read it
+    setperm(key, KeyPerm::KEY_POS_VIEW | KeyPerm::KEY_POS_READ | KeyPerm::KEY_POS_SEARCH |
+            KeyPerm::KEY_POS_SETATTR).unwrap();
+    assert_eq!(read(key).unwrap(), b"other");
+    assert_eq!(update(key, b"denied"), Err(Error::Sys(Errno::EACCES)));
+
+    revoke(key).unwrap();
+    assert_eq!(read(key), Err(Error::Sys(Errno::EKEYREVOKED)));
+    unlink(key, keyring).unwrap();
+}
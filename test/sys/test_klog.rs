@@ -0,0 +1,22 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::sys::klog::{klogctl, KlogAction};
+
+#[test]
+fn test_klogctl() {
+    let size = match klogctl(KlogAction::SizeBuffer) {
+        // Reading the log is restricted by dmesg_restrict
+        Err(Error::Sys(Errno::EPERM)) => return,
+        res => res.unwrap(),
+    };
+    assert!(size > 0);
+    assert!(klogctl(KlogAction::SizeUnread).unwrap() <= size);
+
+    let mut buf = vec![0; size];
+    let len = klogctl(KlogAction::ReadAll(&mut buf)).unwrap();
+    assert!(len <= size);
+    // Every message starts with its level
+    if len > 0 {
+        assert_eq!(buf[0], b'<');
+    }
+}
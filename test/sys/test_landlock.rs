@@ -0,0 +1,45 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::fcntl::{open, OFlag};
+use nix::sys::landlock::*;
+use nix::sys::stat::Mode;
+use nix::unistd::close;
+use std::env;
+use std::fs::File;
+use std::thread;
+use tempdir::TempDir;
+
+#[test]
+fn test_landlock() {
+    match landlock_abi_version() {
+        // The kernel lacks Landlock, or it was disabled
+        Err(Error::Sys(Errno::ENOSYS)) | Err(Error::Sys(Errno::EOPNOTSUPP)) => return,
+        res => assert!(res.unwrap() >= 1),
+    }
+    let tempdir = TempDir::new("nix-test_landlock").unwrap();
+    let path = tempdir.path().join("file");
+    File::create(&path).unwrap();
+    let outside = env::current_exe().unwrap();
+
+    // Restrictions only apply to the thread and its children
+    thread::spawn(move || {
+        let read = AccessFs::LANDLOCK_ACCESS_FS_READ_FILE;
+        let ruleset = landlock_create_ruleset(read, AccessNet::empty()).unwrap();
+        let dir = open(tempdir.path(), OFlag::O_PATH | OFlag::O_CLOEXEC, Mode::empty()).unwrap();
+        landlock_add_rule(ruleset, LandlockRule::PathBeneath(dir, read)).unwrap();
+        assert_eq!(landlock_add_rule(ruleset, LandlockRule::PathBeneath(dir,
+                                     AccessFs::LANDLOCK_ACCESS_FS_EXECUTE)),
+                   Err(Error::Sys(Errno::EINVAL)));
+        close(dir).unwrap();
+
+        match landlock_restrict_self(ruleset) {
+            // Requires no_new_privs without CAP_SYS_ADMIN
+            Err(Error::Sys(Errno::EPERM)) => return,
+            res => res.unwrap(),
+        }
+        close(ruleset).unwrap();
+        close(open(&path, OFlag::O_RDONLY, Mode::empty()).unwrap()).unwrap();
+        assert_eq!(open(&outside, OFlag::O_RDONLY, Mode::empty()),
+                   Err(Error::Sys(Errno::EACCES)));
+    }).join().unwrap();
+}
@@ -0,0 +1,58 @@
+use libc::c_int;
+use nix::Error;
+use nix::errno::Errno;
+use nix::sys::ipc::{IpcFlags, IPC_PRIVATE};
+use nix::sys::msg::*;
+use nix::sys::stat::Mode;
+use nix::unistd::{getpid, Uid};
+
+fn private_queue() -> c_int {
+    msgget(IPC_PRIVATE, IpcFlags::IPC_CREAT, Mode::S_IRUSR | Mode::S_IWUSR).unwrap()
+}
+
+#[test]
+fn test_msgsnd_msgrcv() {
+    let msqid = private_queue();
+    msgsnd(msqid, 2, b"second", MsgFlags::empty()).unwrap();
+    msgsnd(msqid, 1, b"first", MsgFlags::empty()).unwrap();
+
+    // Select by type
+    let mut buf = [0u8; 16];
+    let (mtype, len) = msgrcv(msqid, &mut buf, 1, MsgFlags::empty()).unwrap();
+    assert_eq!((mtype, &buf[..len]), (1, &b"first"[..]));
+
+    // Too long for the buffer, unless truncated
+    let mut short = [0u8; 3];
+    assert_eq!(msgrcv(msqid, &mut short, 0, MsgFlags::IPC_NOWAIT),
+               Err(Error::Sys(Errno::E2BIG)));
+    let (mtype, len) = msgrcv(msqid, &mut short, 0, MsgFlags::MSG_NOERROR).unwrap();
+    assert_eq!((mtype, &short[..len]), (2, &b"sec"[..]));
+
+    assert_eq!(msgrcv(msqid, &mut buf, 0, MsgFlags::IPC_NOWAIT),
+               Err(Error::Sys(Errno::ENOMSG)));
+    msgctl_rmid(msqid).unwrap();
+}
+
+#[test]
+fn test_msgctl() {
+    let msqid = private_queue();
+    msgsnd(msqid, 1, b"abc", MsgFlags::empty()).unwrap();
+
+    let mut ds = msgctl_stat(msqid).unwrap();
+    assert_eq!(ds.qnum(), 1);
+    assert_eq!(ds.lspid(), getpid());
+    assert_eq!(ds.perm().uid(), Uid::effective());
+    assert_eq!(ds.perm().mode(), Mode::S_IRUSR | Mode::S_IWUSR);
+
+    let mut perm = ds.perm();
+    perm.set_mode(Mode::S_IRUSR | Mode::S_IWUSR | Mode::S_IRGRP);
+    ds.set_perm(perm);
+    ds.set_qbytes(ds.qbytes() / 2);
+    msgctl_set(msqid, &ds).unwrap();
+    let new_ds = msgctl_stat(msqid).unwrap();
+    assert_eq!(new_ds.perm().mode(), Mode::S_IRUSR | Mode::S_IWUSR | Mode::S_IRGRP);
+    assert_eq!(new_ds.qbytes(), ds.qbytes());
+
+    msgctl_rmid(msqid).unwrap();
+    assert_eq!(msgctl_stat(msqid).err(), Some(Error::Sys(Errno::EINVAL)));
+}
@@ -0,0 +1,263 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::net::if_::InterfaceFlags;
+use nix::sys::net;
+use nix::sys::socket::{socket, AddressFamily, SockFlag, SockType};
+use nix::unistd::close;
+
+#[test]
+fn test_net_loopback() {
+    let fd = socket(AddressFamily::Inet, SockType::Datagram, SockFlag::empty(), None).unwrap();
+
+    let flags = net::flags(fd, "lo").unwrap();
+    assert!(flags.contains(InterfaceFlags::IFF_UP | InterfaceFlags::IFF_LOOPBACK));
+    assert!(net::mtu(fd, "lo").unwrap() > 0);
+    assert_eq!(net::addr(fd, "lo").unwrap().octets(), [127, 0, 0, 1]);
+    assert_eq!(net::netmask(fd, "lo").unwrap().octets(), [255, 0, 0, 0]);
+    assert_eq!(net::hwaddr(fd, "lo").unwrap(), net::MacAddress::from([0; 6]));
+
+    // Setting the current values doesn't change anything, but needs privileges
+    let mtu = net::mtu(fd, "lo").unwrap();
+    match net::set_mtu(fd, "lo", mtu) {
+        Ok(()) => net::set_flags(fd, "lo", flags).unwrap(),
+        Err(e) => assert_eq!(e, Error::Sys(Errno::EPERM)),
+    }
+
+    close(fd).unwrap();
+}
+
+#[test]
+fn test_net_bad_name() {
+    let fd = socket(AddressFamily::Inet, SockType::Datagram, SockFlag::empty(), None).unwrap();
+
+    assert_eq!(net::mtu(fd, "nonexistent0"), Err(Error::Sys(Errno::ENODEV)));
+    assert_eq!(net::mtu(fd, "a_name_too_long_for_ifreq"), Err(Error::Sys(Errno::ENAMETOOLONG)));
+
+    close(fd).unwrap();
+}
+
+#[test]
+fn test_tun_open() {
+    use nix::sys::net::tun::{self, TunFlags};
+    use nix::unistd::{getgid, getuid};
+
+    let (fd, name) = match tun::open("", TunFlags::IFF_TUN | TunFlags::IFF_NO_PI) {
+        Ok(tun) => tun,
+        // No TUN driver, or not privileged
+        Err(Error::Sys(Errno::ENOENT)) | Err(Error::Sys(Errno::EPERM)) |
+            Err(Error::Sys(Errno::EACCES)) => return,
+        Err(e) => panic!("tun::open failed: {:?}", e),
+    };
+    assert!(name.as_bytes().starts_with(b"tun"));
+
+    let sock = socket(AddressFamily::Inet, SockType::Datagram, SockFlag::empty(), None).unwrap();
+    assert!(net::flags(sock, name.as_c_str()).is_ok());
+    tun::set_owner(fd, getuid()).unwrap();
+    tun::set_group(fd, getgid()).unwrap();
+    tun::set_persist(fd, false).unwrap();
+
+    // The device goes away with its last descriptor
+    close(fd).unwrap();
+    assert_eq!(net::flags(sock, name.as_c_str()), Err(Error::Sys(Errno::ENODEV)));
+    close(sock).unwrap();
+}
+
+#[test]
+fn test_net_hwaddr() {
+    use nix::sys::net::MacAddress;
+    use nix::sys::net::tun::{self, TunFlags};
+
+    let mac = MacAddress::new(0x02, 0x00, 0x5e, 0x10, 0xab, 0xcd);
+    assert_eq!(mac.to_string(), "02:00:5e:10:ab:cd");
+
+    let (fd, name) = match tun::open("", TunFlags::IFF_TAP | TunFlags::IFF_NO_PI) {
+        Ok(tap) => tap,
+        // No TUN driver, or not privileged
+        Err(Error::Sys(Errno::ENOENT)) | Err(Error::Sys(Errno::EPERM)) |
+            Err(Error::Sys(Errno::EACCES)) => return,
+        Err(e) => panic!("tun::open failed: {:?}", e),
+    };
+
+    let sock = socket(AddressFamily::Inet, SockType::Datagram, SockFlag::empty(), None).unwrap();
+    net::set_hwaddr(sock, name.as_c_str(), mac).unwrap();
+    assert_eq!(net::hwaddr(sock, name.as_c_str()).unwrap(), mac);
+
+    close(fd).unwrap();
+    close(sock).unwrap();
+}
+
+#[test]
+fn test_ethtool() {
+    use nix::sys::net::ethtool::{self, Duplex};
+    use nix::sys::net::tun::{self, TunFlags};
+
+    let sock = socket(AddressFamily::Inet, SockType::Datagram, SockFlag::empty(), None).unwrap();
+    // The loopback interface is always up, but has no speed
+    assert_eq!(ethtool::link(sock, "lo"), Ok(true));
+    assert_eq!(ethtool::link_settings(sock, "lo"), Err(Error::Sys(Errno::EOPNOTSUPP)));
+
+    let (fd, name) = match tun::open("", TunFlags::IFF_TAP | TunFlags::IFF_NO_PI) {
+        Ok(tap) => tap,
+        // No TUN driver, or not privileged
+        Err(Error::Sys(Errno::ENOENT)) | Err(Error::Sys(Errno::EPERM)) |
+            Err(Error::Sys(Errno::EACCES)) => return close(sock).unwrap(),
+        Err(e) => panic!("tun::open failed: {:?}", e),
+    };
+
+    // TAP devices pretend to be full duplex links, at a speed depending on the
+    // kernel version
+    let settings = ethtool::link_settings(sock, name.as_c_str()).unwrap();
+    assert!(settings.speed.is_some());
+    assert_eq!(settings.duplex, Duplex::Full);
+    assert!(!settings.autoneg);
+
+    close(fd).unwrap();
+    close(sock).unwrap();
+}
+
+#[test]
+fn test_netns() {
+    use std::thread;
+    use nix::sched::{unshare, CloneFlags};
+    use nix::sys::net::netns;
+    use nix::unistd::getpid;
+
+    assert_eq!(netns::open("nonexistent"), Err(Error::Sys(Errno::ENOENT)));
+
+    // Namespaces belong to threads, so leave the other tests alone
+    thread::spawn(|| {
+        let orig = netns::open_pid(getpid()).unwrap();
+        match unshare(CloneFlags::CLONE_NEWNET) {
+            Ok(()) => (),
+            Err(Error::Sys(Errno::EPERM)) => return close(orig).unwrap(),
+            Err(e) => panic!("unshare failed: {:?}", e),
+        }
+
+        // The loopback interface of a new namespace is down
+        let is_up = || {
+            let fd = socket(AddressFamily::Inet, SockType::Datagram, SockFlag::empty(), None)
+                .unwrap();
+            let flags = net::flags(fd, "lo").unwrap();
+            close(fd).unwrap();
+            flags.contains(InterfaceFlags::IFF_UP)
+        };
+        assert!(!is_up());
+        {
+            let _guard = netns::enter(orig).unwrap();
+            assert!(is_up());
+        }
+        assert!(!is_up());
+        let guard = netns::enter(orig).unwrap();
+        assert!(is_up());
+        guard.leave().unwrap();
+        assert!(!is_up());
+
+        close(orig).unwrap();
+    }).join().unwrap();
+}
+
+#[test]
+fn test_arp() {
+    use nix::sys::net::MacAddress;
+    use nix::sys::net::arp::{self, ArpEntry, ArpFlags};
+    use nix::sys::net::tun::{self, TunFlags};
+    use nix::sys::socket::Ipv4Addr;
+
+    let sock = socket(AddressFamily::Inet, SockType::Datagram, SockFlag::empty(), None).unwrap();
+    let addr = Ipv4Addr::new(10, 201, 0, 2);
+    assert_eq!(arp::get(sock, "lo", addr), Err(Error::Sys(Errno::ENXIO)));
+
+    let (fd, name) = match tun::open("", TunFlags::IFF_TAP | TunFlags::IFF_NO_PI) {
+        Ok(tap) => tap,
+        // No TUN driver, or not privileged
+        Err(Error::Sys(Errno::ENOENT)) | Err(Error::Sys(Errno::EPERM)) |
+            Err(Error::Sys(Errno::EACCES)) => return close(sock).unwrap(),
+        Err(e) => panic!("tun::open failed: {:?}", e),
+    };
+    let name = name.as_c_str();
+
+    let entry = ArpEntry {
+        addr: addr,
+        hwaddr: MacAddress::new(0x02, 0x00, 0x5e, 0x10, 0xab, 0xcd),
+        flags: ArpFlags::ATF_PERM,
+    };
+    assert_eq!(arp::set(sock, "lo", &entry), Err(Error::Sys(Errno::EINVAL)));
+    arp::set(sock, name, &entry).unwrap();
+    let got = arp::get(sock, name, addr).unwrap();
+    assert_eq!(got.hwaddr, entry.hwaddr);
+    assert!(got.flags.contains(ArpFlags::ATF_COM | ArpFlags::ATF_PERM));
+
+    arp::delete(sock, name, addr).unwrap();
+    assert_eq!(arp::get(sock, name, addr), Err(Error::Sys(Errno::ENXIO)));
+    assert_eq!(arp::delete(sock, name, addr), Err(Error::Sys(Errno::ENXIO)));
+
+    close(fd).unwrap();
+    close(sock).unwrap();
+}
+
+#[test]
+fn test_bridge_vlan() {
+    use nix::net::if_::if_nametoindex;
+    use nix::sys::net::{bridge, vlan};
+    use nix::sys::net::tun::{self, TunFlags};
+
+    let (fd, name) = match tun::open("", TunFlags::IFF_TAP | TunFlags::IFF_NO_PI) {
+        Ok(tap) => tap,
+        // No TUN driver, or not privileged
+        Err(Error::Sys(Errno::ENOENT)) | Err(Error::Sys(Errno::EPERM)) |
+            Err(Error::Sys(Errno::EACCES)) => return,
+        Err(e) => panic!("tun::open failed: {:?}", e),
+    };
+    let name = name.to_str().unwrap();
+    let sock = socket(AddressFamily::Inet, SockType::Datagram, SockFlag::empty(), None).unwrap();
+
+    match vlan::add(sock, name, 5) {
+        Ok(()) => {
+            let vlan_name = format!("{}.5", name);
+            assert!(if_nametoindex(vlan_name.as_str()).is_ok());
+            assert_eq!(vlan::add(sock, name, 5), Err(Error::Sys(Errno::EEXIST)));
+            vlan::delete(sock, vlan_name.as_str()).unwrap();
+            assert_eq!(if_nametoindex(vlan_name.as_str()), Err(Error::Sys(Errno::ENODEV)));
+        },
+        // No VLAN support
+        Err(Error::Sys(Errno::ENOPKG)) => (),
+        Err(e) => panic!("vlan::add failed: {:?}", e),
+    }
+
+    match bridge::add(sock, "nixtestbr0") {
+        Ok(()) => {
+            bridge::add_interface(sock, "nixtestbr0", name).unwrap();
+            assert_eq!(bridge::add_interface(sock, "nixtestbr0", name),
+                       Err(Error::Sys(Errno::EBUSY)));
+            bridge::delete_interface(sock, "nixtestbr0", name).unwrap();
+            bridge::delete(sock, "nixtestbr0").unwrap();
+            assert_eq!(if_nametoindex("nixtestbr0"), Err(Error::Sys(Errno::ENODEV)));
+        },
+        // No bridge support
+        Err(Error::Sys(Errno::ENOPKG)) => (),
+        Err(e) => panic!("bridge::add failed: {:?}", e),
+    }
+
+    close(fd).unwrap();
+    close(sock).unwrap();
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_wireless_not_wireless() {
+    use nix::sys::net::wireless;
+
+    let fd = socket(AddressFamily::Inet, SockType::Datagram, SockFlag::empty(), None).unwrap();
+    let enotsup = Error::Sys(Errno::EOPNOTSUPP);
+    match wireless::name(fd, "lo") {
+        Err(e) if e == enotsup => (),
+        // No wireless extensions
+        Err(Error::Sys(Errno::ENOTTY)) => return close(fd).unwrap(),
+        res => panic!("unexpected result {:?}", res),
+    }
+    assert_eq!(wireless::essid(fd, "lo").unwrap_err(), enotsup);
+    assert_eq!(wireless::bitrate(fd, "lo").unwrap_err(), enotsup);
+    assert_eq!(wireless::quality(fd, "lo").unwrap_err(), enotsup);
+    assert_eq!(wireless::name(fd, "nonexistent0"), Err(Error::Sys(Errno::ENODEV)));
+    close(fd).unwrap();
+}
@@ -0,0 +1,42 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::sys::perf_event::*;
+use nix::unistd::{close, gettid};
+
+#[test]
+fn test_perf_event_counter() {
+    let attr = PerfEventAttr::software(SoftwareEvent::PERF_COUNT_SW_TASK_CLOCK).disabled(true);
+    let fd = match perf_event_open(&attr, Some(gettid()), None, None,
+                                   PerfFlags::PERF_FLAG_FD_CLOEXEC) {
+        // Restricted by perf_event_paranoid, or the kernel lacks perf events
+        Err(Error::Sys(Errno::EACCES)) | Err(Error::Sys(Errno::ENOENT)) |
+        Err(Error::Sys(Errno::ENOSYS)) => return,
+        res => res.unwrap(),
+    };
+    assert_eq!(read_count(fd).unwrap(), 0);
+
+    enable(fd, false).unwrap();
+    let mut x = 0u64;
+    for i in 0..100_000 {
+        x = x.wrapping_add(i);
+    }
+    assert!(x > 0);
+    disable(fd, false).unwrap();
+    let count = read_count(fd).unwrap();
+    assert!(count > 0);
+    // Disabled, so it stopped counting
+    assert_eq!(read_count(fd).unwrap(), count);
+
+    reset(fd, false).unwrap();
+    assert_eq!(read_count(fd).unwrap(), 0);
+    id(fd).unwrap();
+    close(fd).unwrap();
+}
+
+#[test]
+fn test_ring_buffer_len() {
+    let page_size = ring_buffer_len(0).unwrap();
+    assert!(page_size > 0);
+    assert_eq!(ring_buffer_len(8).unwrap(), 9 * page_size);
+    assert_eq!(ring_buffer_len(3), Err(Error::Sys(Errno::EINVAL)));
+}
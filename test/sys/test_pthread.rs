@@ -5,3 +5,157 @@ fn test_pthread_self() {
     let tid = pthread_self();
     assert!(tid != 0);
 }
+
+#[test]
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "linux"))]
+fn test_pthread_getcpuclockid() {
+    use nix::sys::time::{clock_gettime, TimeSpec, TimeValLike};
+    use std::sync::mpsc;
+    use std::thread;
+
+    // Measure the CPU time of a busy thread from the main thread
+    let (tx, rx) = mpsc::channel();
+    let (done_tx, done_rx) = mpsc::channel::<()>();
+    let child = thread::spawn(move || {
+        tx.send(pthread_self()).unwrap();
+        while done_rx.try_recv().is_err() {}
+    });
+    let clock = pthread_getcpuclockid(rx.recv().unwrap()).unwrap();
+    let mut elapsed = clock_gettime(clock).unwrap();
+    while elapsed < TimeSpec::milliseconds(10) {
+        elapsed = clock_gettime(clock).unwrap();
+    }
+    done_tx.send(()).unwrap();
+    child.join().unwrap();
+}
+
+#[test]
+#[cfg(any(target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos"))]
+fn test_pthread_setname_np() {
+    use std::thread;
+
+    // Name a new thread, so the test harness's own name for this one is kept
+    thread::spawn(|| {
+        pthread_setname_np("nix-worker").unwrap();
+        let mut buf = [0u8; 64];
+        let name = pthread_getname_np(pthread_self(), &mut buf).unwrap();
+        assert_eq!(name.to_bytes(), b"nix-worker");
+    }).join().unwrap();
+}
+
+#[test]
+fn test_pthread_atfork() {
+    use libc::_exit;
+    use nix::unistd::{fork, ForkResult};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use std::sync::atomic::{AtomicBool, Ordering, ATOMIC_BOOL_INIT};
+
+    static PREPARED: AtomicBool = ATOMIC_BOOL_INIT;
+    static IN_CHILD: AtomicBool = ATOMIC_BOOL_INIT;
+
+    extern fn prepare() {
+        PREPARED.store(true, Ordering::SeqCst);
+    }
+
+    extern fn child() {
+        IN_CHILD.store(true, Ordering::SeqCst);
+    }
+
+    #[allow(unused_variables)]
+    let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    pthread_atfork(Some(prepare), None, Some(child)).unwrap();
+    // Safe: Child only calls `_exit`, which is signal-safe
+    match fork().unwrap() {
+        ForkResult::Child => unsafe { _exit(IN_CHILD.load(Ordering::SeqCst) as i32) },
+        ForkResult::Parent { child } => {
+            assert!(PREPARED.load(Ordering::SeqCst));
+            assert!(!IN_CHILD.load(Ordering::SeqCst));
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 1)));
+        },
+    }
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_pthread_affinity_np() {
+    use nix::Error;
+    use nix::errno::Errno;
+    use std::thread;
+
+    // Pin a new thread to one of the CPUs it may run on
+    thread::spawn(|| {
+        let allowed = pthread_getaffinity_np(pthread_self()).unwrap();
+        let cpu = (0..).find(|&cpu| allowed.is_set(cpu).unwrap()).unwrap();
+
+        let mut cpuset = nix::sched::DynCpuSet::new(allowed.cpus());
+        cpuset.set(cpu).unwrap();
+        pthread_setaffinity_np(pthread_self(), &cpuset).unwrap();
+
+        let pinned = pthread_getaffinity_np(pthread_self()).unwrap();
+        assert!(pinned.is_set(cpu).unwrap());
+        assert_eq!(pinned.is_set(pinned.cpus()), Err(Error::Sys(Errno::EINVAL)));
+        for other in (0..pinned.cpus()).filter(|&other| other != cpu) {
+            assert!(!pinned.is_set(other).unwrap());
+        }
+    }).join().unwrap();
+}
+
+#[test]
+#[cfg(any(target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos"))]
+fn test_pthread_mutex_shared() {
+    use libc::_exit;
+    use nix::Error;
+    use nix::errno::Errno;
+    use nix::sys::mman::*;
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::{fork, ForkResult};
+    use std::ptr;
+
+    let len = 2 * PthreadMutex::size();
+    let region = unsafe {
+        mmap(ptr::null_mut(), len, ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+             MapFlags::MAP_SHARED | MapFlags::MAP_ANONYMOUS, -1, 0).unwrap()
+    };
+    let misaligned = unsafe { (region as *mut u8).offset(1) as *mut _ };
+    assert_eq!(unsafe { PthreadMutex::init(misaligned, true) }.err(),
+               Some(Error::Sys(Errno::EINVAL)));
+
+    let mutex = unsafe { PthreadMutex::init(region, true) }.unwrap();
+
+    {
+        #[allow(unused_variables)]
+        let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+        mutex.lock().unwrap();
+        assert_eq!(mutex.try_lock(), Err(Error::Sys(Errno::EBUSY)));
+        match fork().unwrap() {
+            ForkResult::Child => {
+                let mutex = unsafe { PthreadMutex::from_ptr(region) };
+                // Blocks until the parent releases the lock
+                let ok = mutex.lock().is_ok() && mutex.unlock().is_ok();
+                unsafe { _exit(if ok { 0 } else { 1 }) };
+            }
+            ForkResult::Parent { child } => {
+                mutex.unlock().unwrap();
+                assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+            }
+        }
+    }
+    mutex.try_lock().unwrap();
+    mutex.unlock().unwrap();
+
+    unsafe {
+        mutex.destroy().unwrap();
+        munmap(region, len).unwrap();
+    }
+}
@@ -86,3 +86,549 @@ fn test_ptrace_cont() {
         },
     }
 }
+
+#[cfg(all(target_os = "linux",
+          any(target_arch = "x86_64", target_arch = "aarch64"),
+          any(target_env = "gnu", target_env = "musl")))]
+#[test]
+fn test_ptrace_regs() {
+    use nix::sys::ptrace;
+    use nix::sys::signal::{raise, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+
+    let err = ptrace::attach(getpid()).unwrap_err();
+    if err == Error::Sys(Errno::ENOSYS) {
+        return;
+    }
+
+    // A register that isn't clobbered while the tracee is stopped
+    #[cfg(target_arch = "x86_64")]
+    fn reg(regs: &mut ptrace::user_regs_struct) -> &mut u64 {
+        &mut regs.r15
+    }
+    #[cfg(target_arch = "aarch64")]
+    fn reg(regs: &mut ptrace::user_regs_struct) -> &mut u64 {
+        &mut regs.regs[19]
+    }
+
+    #[allow(unused_variables)]
+    let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    match fork().expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            loop {
+                raise(Signal::SIGTRAP).unwrap();
+            }
+        },
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGTRAP)));
+
+            let mut regs = ptrace::getregs(child).unwrap();
+            let old = *reg(&mut regs);
+            *reg(&mut regs) = 0xdead_beef;
+            ptrace::setregs(child, regs).unwrap();
+            assert_eq!(*reg(&mut ptrace::getregs(child).unwrap()), 0xdead_beef);
+            *reg(&mut regs) = old;
+            ptrace::setregs(child, regs).unwrap();
+
+            ptrace::cont(child, Signal::SIGKILL).unwrap();
+            match waitpid(child, None) {
+                Ok(WaitStatus::Signaled(pid, Signal::SIGKILL, _)) if pid == child => {}
+                _ => panic!("The process should have been killed"),
+            }
+        },
+    }
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64",
+          any(target_env = "gnu", target_env = "musl")))]
+#[test]
+fn test_ptrace_regset() {
+    use libc::{user_fpregs_struct, user_regs_struct};
+    use nix::sys::ptrace::{self, RegisterSet};
+    use nix::sys::signal::{raise, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+    use std::ptr;
+
+    let err = ptrace::attach(getpid()).unwrap_err();
+    if err == Error::Sys(Errno::ENOSYS) {
+        return;
+    }
+
+    #[allow(unused_variables)]
+    let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    match fork().expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            loop {
+                raise(Signal::SIGTRAP).unwrap();
+            }
+        },
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGTRAP)));
+
+            // The length is reduced to the size of the set
+            let mut buf = [0u8; 4096];
+            let len = ptrace::getregset(child, RegisterSet::NT_PRSTATUS, &mut buf).unwrap();
+            assert_eq!(len, mem::size_of::<user_regs_struct>());
+            let regs = ptrace::getregs(child).unwrap();
+            let rip = unsafe { ptr::read_unaligned(buf[16 * 8..].as_ptr() as *const u64) };
+            assert_eq!(rip, regs.rip);
+            assert_eq!(ptrace::setregset(child, RegisterSet::NT_PRSTATUS, &buf[..len]), Ok(len));
+
+            let len = ptrace::getregset(child, RegisterSet::NT_PRFPREG, &mut buf).unwrap();
+            assert_eq!(len, mem::size_of::<user_fpregs_struct>());
+            assert!(ptrace::getregset(child, RegisterSet::NT_X86_XSTATE, &mut buf).unwrap() > len);
+            assert_eq!(ptrace::getregset(child, RegisterSet::from_raw(0x7fff_ffff), &mut buf),
+                       Err(Error::Sys(Errno::EINVAL)));
+
+            ptrace::cont(child, Signal::SIGKILL).unwrap();
+            match waitpid(child, None) {
+                Ok(WaitStatus::Signaled(pid, Signal::SIGKILL, _)) if pid == child => {}
+                _ => panic!("The process should have been killed"),
+            }
+        },
+    }
+}
+
+#[cfg(not(any(target_os = "android", target_arch = "mips", target_arch = "mips64")))]
+#[test]
+fn test_ptrace_seize() {
+    use nix::sys::ptrace::{self, Event};
+    use nix::sys::signal::{kill, Signal};
+    use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+    use nix::unistd::{fork, sleep};
+    use nix::unistd::ForkResult::*;
+
+    let err = ptrace::attach(getpid()).unwrap_err();
+    if err == Error::Sys(Errno::ENOSYS) {
+        return;
+    }
+
+    #[allow(unused_variables)]
+    let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    match fork().expect("Error: Fork Failed") {
+        Child => {
+            loop {
+                sleep(1);
+            }
+        },
+        Parent { child } => {
+            // The tracee keeps running
+            ptrace::seize(child, Options::PTRACE_O_EXITKILL).unwrap();
+            assert_eq!(waitpid(child, Some(WaitPidFlag::WNOHANG)), Ok(WaitStatus::StillAlive));
+
+            ptrace::interrupt(child).unwrap();
+            assert_eq!(waitpid(child, None),
+                       Ok(WaitStatus::PtraceEvent(child, Signal::SIGTRAP,
+                                                  Event::PTRACE_EVENT_STOP as i32)));
+
+            // A group-stop is reported as an event too
+            kill(child, Signal::SIGSTOP).unwrap();
+            ptrace::cont(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGSTOP)));
+            ptrace::cont(child, Signal::SIGSTOP).unwrap();
+            assert_eq!(waitpid(child, None),
+                       Ok(WaitStatus::PtraceEvent(child, Signal::SIGSTOP,
+                                                  Event::PTRACE_EVENT_STOP as i32)));
+            ptrace::listen(child).unwrap();
+
+            kill(child, Signal::SIGKILL).unwrap();
+            match waitpid(child, None) {
+                Ok(WaitStatus::Signaled(pid, Signal::SIGKILL, _)) if pid == child => {}
+                _ => panic!("The process should have been killed"),
+            }
+        },
+    }
+}
+
+#[test]
+fn test_ptrace_memory() {
+    use nix::sys::ptrace;
+    use nix::sys::signal::{raise, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+
+    // Read-only memory, which process_vm_writev can't write to
+    static RODATA: [u8; 32] = *b"0123456789abcdefghijklmnopqrstuv";
+
+    let err = ptrace::attach(getpid()).unwrap_err();
+    if err == Error::Sys(Errno::ENOSYS) {
+        return;
+    }
+
+    let mut data = RODATA.to_vec();
+
+    #[allow(unused_variables)]
+    let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    match fork().expect("Error: Fork Failed") {
+        Child => {
+            data.reverse();
+            ptrace::traceme().unwrap();
+            loop {
+                raise(Signal::SIGTRAP).unwrap();
+            }
+        },
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGTRAP)));
+
+            // The child has its own copy, at the same address
+            let mut buf = [0u8; 13];
+            ptrace::read_memory(child, data.as_ptr() as usize + 3, &mut buf).unwrap();
+            assert_eq!(&buf, b"srqponmlkjihg");
+
+            let rodata = RODATA.as_ptr() as usize;
+            ptrace::write_memory(child, rodata + 5, b"ABCDEFGHIJK").unwrap();
+            let mut buf = [0u8; 32];
+            ptrace::read_memory(child, rodata, &mut buf).unwrap();
+            assert_eq!(&buf[..], &b"01234ABCDEFGHIJKghijklmnopqrstuv"[..]);
+
+            let mut buf = [0u8; 8];
+            assert!(ptrace::read_memory(child, 0, &mut buf).is_err());
+            assert!(ptrace::write_memory(child, 0, &buf).is_err());
+
+            ptrace::cont(child, Signal::SIGKILL).unwrap();
+            match waitpid(child, None) {
+                Ok(WaitStatus::Signaled(pid, Signal::SIGKILL, _)) if pid == child => {}
+                _ => panic!("The process should have been killed"),
+            }
+        },
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_ptrace_get_syscall_info() {
+    use nix::sys::ptrace::{self, SyscallStop};
+    use nix::sys::signal::{raise, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+
+    let err = ptrace::attach(getpid()).unwrap_err();
+    if err == Error::Sys(Errno::ENOSYS) {
+        return;
+    }
+
+    #[allow(unused_variables)]
+    let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    match fork().expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            loop {
+                raise(Signal::SIGTRAP).unwrap();
+            }
+        },
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGTRAP)));
+            let info = match ptrace::get_syscall_info(child) {
+                // Before Linux 5.3
+                Err(Error::Sys(Errno::EIO)) => {
+                    ptrace::cont(child, Signal::SIGKILL).unwrap();
+                    waitpid(child, None).unwrap();
+                    return;
+                }
+                info => info.unwrap(),
+            };
+            assert_eq!(info.stop, SyscallStop::None);
+            assert!(info.instruction_pointer != 0);
+
+            ptrace::setoptions(child, Options::PTRACE_O_TRACESYSGOOD).unwrap();
+            ptrace::syscall(child).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::PtraceSyscall(child)));
+            let nr = match ptrace::get_syscall_info(child).unwrap().stop {
+                SyscallStop::Entry { nr, .. } => nr,
+                stop => panic!("unexpected stop {:?}", stop),
+            };
+            #[cfg(all(target_arch = "x86_64", any(target_env = "gnu", target_env = "musl")))]
+            assert_eq!(nr, ptrace::getregs(child).unwrap().orig_rax);
+
+            ptrace::syscall(child).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::PtraceSyscall(child)));
+            match ptrace::get_syscall_info(child).unwrap().stop {
+                SyscallStop::Exit { .. } => {}
+                stop => panic!("unexpected stop {:?} after system call {}", stop, nr),
+            }
+
+            ptrace::cont(child, Signal::SIGKILL).unwrap();
+            match waitpid(child, None) {
+                Ok(WaitStatus::Signaled(pid, Signal::SIGKILL, _)) if pid == child => {}
+                _ => panic!("The process should have been killed"),
+            }
+        },
+    }
+}
+
+#[test]
+fn test_ptrace_getsiginfo_fault() {
+    use nix::sys::signal::Signal;
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+    use std::ptr;
+
+    const FAULT_ADDR: usize = 0x1000;
+
+    let err = ptrace::attach(getpid()).unwrap_err();
+    if err == Error::Sys(Errno::ENOSYS) {
+        return;
+    }
+
+    #[allow(unused_variables)]
+    let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    match fork().expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            loop {
+                unsafe { ptr::write_volatile(FAULT_ADDR as *mut u8, 0) };
+            }
+        },
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGSEGV)));
+
+            let info = ptrace::getsiginfo(child).unwrap();
+            assert_eq!(info.signal(), Ok(Signal::SIGSEGV));
+            assert!(info.code() > 0);
+            assert_eq!(info.addr(), Some(FAULT_ADDR as *mut _));
+            assert_eq!(info.pid(), None);
+
+            ptrace::cont(child, Signal::SIGKILL).unwrap();
+            match waitpid(child, None) {
+                Ok(WaitStatus::Signaled(pid, Signal::SIGKILL, _)) if pid == child => {}
+                _ => panic!("The process should have been killed"),
+            }
+        },
+    }
+}
+
+#[test]
+fn test_ptrace_setsiginfo_modify() {
+    use libc::{self, c_int, c_void, siginfo_t};
+    use nix::sys::signal::{raise, sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+
+    // Exits with the code the tracer put in the siginfo
+    extern fn handler(_: c_int, info: *mut siginfo_t, _: *mut c_void) {
+        unsafe { libc::_exit(-(*info).si_code) };
+    }
+
+    let err = ptrace::attach(getpid()).unwrap_err();
+    if err == Error::Sys(Errno::ENOSYS) {
+        return;
+    }
+
+    #[allow(unused_variables)]
+    let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    match fork().expect("Error: Fork Failed") {
+        Child => {
+            let sa = SigAction::new(SigHandler::SigAction(handler), SaFlags::SA_SIGINFO,
+                                    SigSet::empty());
+            unsafe { sigaction(Signal::SIGUSR1, &sa) }.unwrap();
+            ptrace::traceme().unwrap();
+            raise(Signal::SIGUSR1).unwrap();
+            unsafe { libc::_exit(0) };
+        },
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGUSR1)));
+
+            let mut info = ptrace::getsiginfo(child).unwrap();
+            assert_eq!(info.signal(), Ok(Signal::SIGUSR1));
+            assert!(info.code() < 0);
+            assert_eq!(info.pid(), Some(child));
+            assert_eq!(info.addr(), None);
+
+            info.set_code(libc::SI_QUEUE);
+            ptrace::setsiginfo(child, &info).unwrap();
+            assert_eq!(ptrace::getsiginfo(child).unwrap().code(), libc::SI_QUEUE);
+
+            ptrace::cont(child, Signal::SIGUSR1).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, -libc::SI_QUEUE)));
+        },
+    }
+}
+
+#[test]
+fn test_ptrace_seccomp_get_filter() {
+    use libc::{self, sock_filter, sock_fprog};
+    use nix::sys::signal::{raise, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+
+    // BPF_RET | BPF_K, SECCOMP_RET_ALLOW
+    let mut filter = [sock_filter { code: 0x06, jt: 0, jf: 0, k: 0x7fff_0000 }];
+
+    let err = ptrace::attach(getpid()).unwrap_err();
+    if err == Error::Sys(Errno::ENOSYS) {
+        return;
+    }
+
+    #[allow(unused_variables)]
+    let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    match fork().expect("Error: Fork Failed") {
+        Child => {
+            let prog = sock_fprog { len: filter.len() as u16, filter: filter.as_mut_ptr() };
+            unsafe {
+                assert_eq!(libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0), 0);
+                assert_eq!(libc::prctl(libc::PR_SET_SECCOMP, libc::SECCOMP_MODE_FILTER,
+                                       &prog as *const sock_fprog), 0);
+            }
+            ptrace::traceme().unwrap();
+            loop {
+                raise(Signal::SIGTRAP).unwrap();
+            }
+        },
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGTRAP)));
+
+            match ptrace::seccomp_get_filter(child, 0) {
+                Ok(installed) => {
+                    assert_eq!(installed.len(), 1);
+                    assert_eq!((installed[0].code, installed[0].k), (filter[0].code, filter[0].k));
+                    assert_eq!(ptrace::seccomp_get_filter(child, 1).unwrap_err(),
+                               Error::Sys(Errno::ENOENT));
+                    match ptrace::seccomp_get_metadata(child, 0) {
+                        Ok(metadata) => assert_eq!(metadata.flags, 0),
+                        // Linux before 4.16
+                        Err(Error::Sys(Errno::EIO)) => {}
+                        Err(e) => panic!("seccomp_get_metadata failed: {:?}", e),
+                    }
+                }
+                // Not privileged, or not supported by the kernel
+                Err(Error::Sys(Errno::EPERM)) | Err(Error::Sys(Errno::EIO)) => {}
+                Err(e) => panic!("seccomp_get_filter failed: {:?}", e),
+            }
+
+            ptrace::cont(child, Signal::SIGKILL).unwrap();
+            match waitpid(child, None) {
+                Ok(WaitStatus::Signaled(pid, Signal::SIGKILL, _)) if pid == child => {}
+                _ => panic!("The process should have been killed"),
+            }
+        },
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn test_ptrace_hw_breakpoint() {
+    use nix::sys::ptrace::{HwBreakpoint, HwBreakpointCondition};
+    use nix::sys::signal::{raise, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+    use std::ptr;
+
+    static mut WATCHED: u64 = 0;
+
+    let err = ptrace::attach(getpid()).unwrap_err();
+    if err == Error::Sys(Errno::ENOSYS) {
+        return;
+    }
+
+    #[allow(unused_variables)]
+    let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    match fork().expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            raise(Signal::SIGTRAP).unwrap();
+            let mut n = 1;
+            loop {
+                unsafe { ptr::write_volatile(&mut WATCHED, n) };
+                n += 1;
+            }
+        },
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGTRAP)));
+
+            let addr = unsafe { &WATCHED as *const u64 as usize };
+            let bad = HwBreakpoint { addr: addr + 1, len: 8, condition: HwBreakpointCondition::Write };
+            assert_eq!(ptrace::set_hw_breakpoint(child, 1, &bad), Err(Error::invalid_argument()));
+            let bp = HwBreakpoint { addr: addr, len: 8, condition: HwBreakpointCondition::Write };
+            assert_eq!(ptrace::set_hw_breakpoint(child, 4, &bp), Err(Error::invalid_argument()));
+            ptrace::set_hw_breakpoint(child, 1, &bp).unwrap();
+
+            // The tracee stops after each write
+            for n in 1..3 {
+                ptrace::cont(child, None).unwrap();
+                assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGTRAP)));
+                assert_eq!(ptrace::hw_breakpoint_hit(child), Ok(Some(1)));
+                let mut buf = [0u8; 8];
+                ptrace::read_memory(child, addr, &mut buf).unwrap();
+                assert_eq!(u64::from_le(unsafe { ptr::read_unaligned(buf.as_ptr() as *const u64) }), n);
+            }
+
+            ptrace::clear_hw_breakpoint(child, 1, HwBreakpointCondition::Write).unwrap();
+            ptrace::cont(child, Signal::SIGKILL).unwrap();
+            match waitpid(child, None) {
+                Ok(WaitStatus::Signaled(pid, Signal::SIGKILL, _)) if pid == child => {}
+                _ => panic!("The process should have been killed"),
+            }
+        },
+    }
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64",
+          any(target_env = "gnu", target_env = "musl")))]
+#[test]
+fn test_ptrace_fpregs() {
+    use nix::sys::signal::{raise, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+
+    let err = ptrace::attach(getpid()).unwrap_err();
+    if err == Error::Sys(Errno::ENOSYS) {
+        return;
+    }
+
+    #[allow(unused_variables)]
+    let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    match fork().expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            loop {
+                raise(Signal::SIGTRAP).unwrap();
+            }
+        },
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGTRAP)));
+
+            // The low 32 bits of xmm15
+            let mut regs = ptrace::getfpregs(child).unwrap();
+            regs.xmm_space[60] = 0xdead_beef;
+            ptrace::setfpregs(child, regs).unwrap();
+            assert_eq!(ptrace::getfpregs(child).unwrap().xmm_space[60], 0xdead_beef);
+
+            // The legacy area of the XSAVE state holds the same registers
+            let mut xstate = ptrace::getxstate(child).unwrap();
+            assert!(xstate.len() > 512);
+            assert_eq!(&xstate[160 + 15 * 16..160 + 15 * 16 + 4], &[0xef, 0xbe, 0xad, 0xde]);
+            xstate[160 + 15 * 16] = 0xaa;
+            ptrace::setxstate(child, &xstate).unwrap();
+            assert_eq!(ptrace::getfpregs(child).unwrap().xmm_space[60], 0xdead_beaa);
+
+            ptrace::cont(child, Signal::SIGKILL).unwrap();
+            match waitpid(child, None) {
+                Ok(WaitStatus::Signaled(pid, Signal::SIGKILL, _)) if pid == child => {}
+                _ => panic!("The process should have been killed"),
+            }
+        },
+    }
+}
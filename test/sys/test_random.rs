@@ -0,0 +1,31 @@
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_getrandom() {
+    use nix::sys::random::{getrandom, GetRandomFlags};
+
+    // Bigger than what the kernel returns at once with GRND_RANDOM on old
+    // kernels
+    let mut buf = [0u8; 1024];
+    getrandom(&mut buf, GetRandomFlags::empty()).unwrap();
+    // The odds of this failing are negligible
+    assert!(buf.iter().any(|&b| b != 0));
+
+    let mut empty = [];
+    getrandom(&mut empty, GetRandomFlags::GRND_NONBLOCK).unwrap();
+}
+
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+#[test]
+fn test_getentropy() {
+    use nix::sys::random::getentropy;
+
+    // More than one call
+    let mut buf = [0u8; 1024];
+    getentropy(&mut buf).unwrap();
+    assert!(buf[768..].iter().any(|&b| b != 0));
+}
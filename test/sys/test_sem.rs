@@ -0,0 +1,88 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::sys::ipc::{IpcFlags, IPC_PRIVATE};
+use nix::sys::sem::*;
+use nix::sys::stat::Mode;
+use nix::sys::wait::{waitpid, WaitStatus};
+use nix::unistd::{fork, getpid, ForkResult};
+use libc::_exit;
+
+fn new_set(nsems: i32) -> i32 {
+    semget(IPC_PRIVATE, nsems, IpcFlags::IPC_CREAT, Mode::S_IRUSR | Mode::S_IWUSR).unwrap()
+}
+
+#[test]
+fn test_semop() {
+    let semid = new_set(1);
+    assert_eq!(semctl_getval(semid, 0).unwrap(), 0);
+    semctl_setval(semid, 0, 2).unwrap();
+
+    semop(semid, &[SemBuf::new(0, -2, SemFlags::empty())]).unwrap();
+    assert_eq!(semctl_getval(semid, 0).unwrap(), 0);
+    assert_eq!(semctl_getpid(semid, 0).unwrap(), getpid());
+    assert_eq!(semctl_getncnt(semid, 0).unwrap(), 0);
+    assert_eq!(semctl_getzcnt(semid, 0).unwrap(), 0);
+
+    let op = SemBuf::new(0, -1, SemFlags::IPC_NOWAIT);
+    assert_eq!(op.sem_num(), 0);
+    assert_eq!(op.sem_op(), -1);
+    assert_eq!(op.flags(), SemFlags::IPC_NOWAIT);
+    assert_eq!(semop(semid, &[op]), Err(Error::Sys(Errno::EAGAIN)));
+
+    semctl_rmid(semid).unwrap();
+    assert_eq!(semctl_getval(semid, 0), Err(Error::Sys(Errno::EINVAL)));
+}
+
+#[test]
+fn test_semop_undo() {
+    #[allow(unused_variables)]
+    let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let semid = new_set(1);
+    semctl_setval(semid, 0, 1).unwrap();
+
+    match fork().unwrap() {
+        ForkResult::Child => {
+            // The kernel adds the value back when the child exits
+            let ok = semop(semid, &[SemBuf::new(0, -1, SemFlags::SEM_UNDO)]).is_ok() &&
+                semctl_getval(semid, 0) == Ok(0);
+            unsafe { _exit(if ok { 0 } else { 1 }) };
+        }
+        ForkResult::Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+            assert_eq!(semctl_getval(semid, 0).unwrap(), 1);
+        }
+    }
+
+    semctl_rmid(semid).unwrap();
+}
+
+#[cfg(target_env = "gnu")]
+#[test]
+fn test_semctl_all() {
+    let semid = new_set(3);
+    semctl_setall(semid, &[1, 2, 3]).unwrap();
+
+    let mut values = [0; 3];
+    semctl_getall(semid, &mut values).unwrap();
+    assert_eq!(values, [1, 2, 3]);
+    assert_eq!(semctl_getall(semid, &mut [0; 2]), Err(Error::Sys(Errno::EINVAL)));
+    assert_eq!(semctl_setall(semid, &[0; 4]), Err(Error::Sys(Errno::EINVAL)));
+
+    // Operations on several semaphores are applied together, or not at all
+    let ops = [SemBuf::new(0, -1, SemFlags::IPC_NOWAIT), SemBuf::new(1, -3, SemFlags::IPC_NOWAIT)];
+    assert_eq!(semop(semid, &ops), Err(Error::Sys(Errno::EAGAIN)));
+    semctl_getall(semid, &mut values).unwrap();
+    assert_eq!(values, [1, 2, 3]);
+
+    let mut ds = semctl_stat(semid).unwrap();
+    assert_eq!(ds.nsems(), 3);
+    let mut perm = ds.perm();
+    assert_eq!(perm.mode(), Mode::S_IRUSR | Mode::S_IWUSR);
+    perm.set_mode(Mode::S_IRUSR);
+    ds.set_perm(perm);
+    semctl_set(semid, &ds).unwrap();
+    assert_eq!(semctl_stat(semid).unwrap().perm().mode(), Mode::S_IRUSR);
+
+    semctl_rmid(semid).unwrap();
+}
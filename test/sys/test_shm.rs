@@ -0,0 +1,42 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::sys::ipc::IPC_PRIVATE;
+use nix::sys::shm::*;
+use nix::sys::stat::Mode;
+use nix::unistd::getpid;
+
+#[test]
+fn test_shm_attach() {
+    let shmid = shmget(IPC_PRIVATE, 100, ShmgetFlags::IPC_CREAT, Mode::S_IRUSR | Mode::S_IWUSR)
+        .unwrap();
+    let ds = shmctl_stat(shmid).unwrap();
+    assert_eq!(ds.segsz(), 100);
+    assert_eq!(ds.nattch(), 0);
+    assert_eq!(ds.cpid(), getpid());
+
+    {
+        let mut shm = shmat(shmid, ShmatFlags::empty()).unwrap();
+        let ro = shmat(shmid, ShmatFlags::SHM_RDONLY).unwrap();
+        assert_eq!(shm.len(), 100);
+        assert!(shm.as_ptr() != ro.as_ptr());
+        assert_eq!(shmctl_stat(shmid).unwrap().nattch(), 2);
+
+        // Both attachments map the same zero-initialized memory
+        unsafe {
+            assert!(ro.as_slice().iter().all(|&b| b == 0));
+            shm.as_mut_slice()[..5].copy_from_slice(b"hello");
+            assert_eq!(&ro.as_slice()[..5], b"hello");
+        }
+    }
+    assert_eq!(shmctl_stat(shmid).unwrap().nattch(), 0);
+
+    let mut ds = shmctl_stat(shmid).unwrap();
+    let mut perm = ds.perm();
+    perm.set_mode(Mode::S_IRUSR);
+    ds.set_perm(perm);
+    shmctl_set(shmid, &ds).unwrap();
+    assert_eq!(shmctl_stat(shmid).unwrap().perm().mode(), Mode::S_IRUSR);
+
+    shmctl_rmid(shmid).unwrap();
+    assert_eq!(shmat(shmid, ShmatFlags::empty()).err(), Some(Error::Sys(Errno::EINVAL)));
+}
@@ -47,3 +47,47 @@ fn test_sigprocmask() {
     sigprocmask(SigmaskHow::SIG_UNBLOCK, Some(&signal_set), None)
         .expect("expect to be able to block signals");
 }
+
+#[test]
+fn test_pthread_sigmask_query() {
+    use std::thread;
+
+    // A new thread, so the test harness's own mask can't interfere
+    thread::spawn(|| {
+        const SIGNAL: Signal = Signal::SIGUSR2;
+
+        let mut old_signal_set = SigSet::all();
+        pthread_sigmask(SigmaskHow::SIG_BLOCK, None, Some(&mut old_signal_set))
+            .expect("expect to be able to retrieve the signal mask");
+        assert!(!old_signal_set.contains(SIGNAL));
+
+        let mut signal_set = SigSet::empty();
+        signal_set.add(SIGNAL);
+        signal_set.thread_block().unwrap();
+        assert!(SigSet::thread_get_mask().unwrap().contains(SIGNAL));
+    }).join().unwrap();
+}
+
+#[test]
+fn test_pthread_kill() {
+    use nix::sys::pthread::pthread_self;
+    use std::sync::mpsc;
+    use std::thread;
+
+    #[allow(unused_variables)]
+    let m = ::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+
+    // Route SIGUSR2 to a thread that blocks it and waits for it
+    let (tx, rx) = mpsc::channel();
+    let child = thread::spawn(move || {
+        let mut signal_set = SigSet::empty();
+        signal_set.add(Signal::SIGUSR2);
+        signal_set.thread_block().unwrap();
+        tx.send(pthread_self()).unwrap();
+        signal_set.wait().unwrap()
+    });
+    let thread = rx.recv().unwrap();
+    pthread_kill(thread, None).expect("the thread should exist");
+    pthread_kill(thread, Signal::SIGUSR2).unwrap();
+    assert_eq!(child.join().unwrap(), Signal::SIGUSR2);
+}
@@ -0,0 +1,20 @@
+use libc::{CTL_KERN, KERN_MAXPROC, KERN_OSTYPE};
+use nix::sys::sysctl::*;
+
+#[test]
+fn test_sysctl() {
+    let ostype = sysctl(&[CTL_KERN, KERN_OSTYPE]).unwrap();
+    assert!(!ostype.as_c_str().unwrap().to_bytes().is_empty());
+    assert!(sysctl(&[CTL_KERN, KERN_MAXPROC]).unwrap().as_int().unwrap() > 0);
+    // A node, not a value
+    assert!(sysctl(&[CTL_KERN]).is_err());
+}
+
+#[test]
+#[cfg(not(target_os = "openbsd"))]
+fn test_sysctlbyname() {
+    let ostype = sysctlbyname("kern.ostype").unwrap();
+    assert_eq!(ostype, sysctl(&[CTL_KERN, KERN_OSTYPE]).unwrap());
+    assert!(sysctlbyname("kern.maxproc").unwrap().as_uint().unwrap() > 0);
+    assert!(sysctlbyname("nix.does.not.exist").is_err());
+}
@@ -0,0 +1,15 @@
+use nix::sys::sysinfo::sysinfo;
+
+#[test]
+fn test_sysinfo() {
+    let info = sysinfo().unwrap();
+
+    let (load1, load5, load15) = info.load_average();
+    assert!(load1 >= 0.0 && load5 >= 0.0 && load15 >= 0.0);
+    // At least this thread and the main one
+    assert!(info.process_count() >= 2);
+    assert!(info.ram_total() > 0);
+    assert!(info.ram_unused() <= info.ram_total());
+    assert!(info.ram_buffered() <= info.ram_total());
+    assert!(info.swap_free() <= info.swap_total());
+}
@@ -136,3 +136,216 @@ fn test_cfmakeraw() {
     let mut termios = unsafe { Termios::default_uninit() };
     termios::cfmakeraw(&mut termios);
 }
+
+// Test getting and setting the window size of a pty
+#[test]
+fn test_tcgetwinsize_tcsetwinsize() {
+    use nix::sys::termios::{tcgetwinsize, tcsetwinsize, Winsize};
+
+    // openpty uses ptname(3) internally
+    #[allow(unused_variables)]
+    let m = ::PTSNAME_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let winsize = Winsize { ws_row: 24, ws_col: 80, ws_xpixel: 0, ws_ypixel: 0 };
+    let pty = openpty(&winsize, None).expect("openpty failed");
+    let got = tcgetwinsize(pty.slave).expect("tcgetwinsize failed");
+    assert_eq!((got.ws_row, got.ws_col), (24, 80));
+
+    let winsize = Winsize { ws_row: 50, ws_col: 132, ws_xpixel: 0, ws_ypixel: 0 };
+    tcsetwinsize(pty.master, &winsize).expect("tcsetwinsize failed");
+    let got = tcgetwinsize(pty.slave).expect("tcgetwinsize failed");
+    assert_eq!((got.ws_row, got.ws_col), (50, 132));
+
+    close(pty.master).expect("closing the master failed");
+    close(pty.slave).expect("closing the slave failed");
+}
+
+// Test tcgetwinsize on something that isn't a terminal
+#[test]
+fn test_tcgetwinsize_enotty() {
+    let file = tempfile().unwrap();
+    assert_eq!(termios::tcgetwinsize(file.as_raw_fd()).err(),
+               Some(Error::Sys(Errno::ENOTTY)));
+}
+
+// Test setting a baud rate that has no Bnnn constant
+#[test]
+#[cfg(all(target_os = "linux",
+          not(any(target_arch = "powerpc", target_arch = "powerpc64"))))]
+fn test_set_custom_speed() {
+    use libc;
+    use nix::sys::termios::{cfgetispeed, cfgetospeed, tcsetattr, ControlFlags, SetArg};
+
+    // openpty uses ptname(3) internally
+    #[allow(unused_variables)]
+    let m = ::PTSNAME_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let pty = openpty(None, None).expect("openpty failed");
+    let mut termios = tcgetattr(pty.slave).unwrap();
+    assert!(cfgetospeed(&termios).is_ok());
+    termios.set_custom_speed(250_000).unwrap();
+    // The speed has no BaudRate
+    assert_eq!(cfgetospeed(&termios), Err(Error::Sys(Errno::EINVAL)));
+    tcsetattr(pty.slave, SetArg::TCSANOW, &termios).unwrap();
+
+    // The custom speed must survive a round trip through tcgetattr
+    let termios = tcgetattr(pty.slave).unwrap();
+    assert_eq!(termios.control_flags & ControlFlags::CBAUD, ControlFlags::CBAUDEX);
+    assert_eq!(cfgetispeed(&termios), Err(Error::Sys(Errno::EINVAL)));
+    tcsetattr(pty.slave, SetArg::TCSADRAIN, &termios).unwrap();
+
+    let mut termios2: libc::termios2 = unsafe { ::std::mem::zeroed() };
+    assert_eq!(unsafe { libc::ioctl(pty.slave, libc::TCGETS2, &mut termios2) }, 0);
+    assert_eq!(termios2.c_ospeed, 250_000);
+    assert_eq!(termios2.c_ispeed, 250_000);
+
+    close(pty.master).expect("closing the master failed");
+    close(pty.slave).expect("closing the slave failed");
+}
+
+// Test that RawModeGuard applies raw mode and restores the original settings
+#[test]
+fn test_raw_mode_guard() {
+    use nix::sys::termios::RawModeGuard;
+
+    // openpty uses ptname(3) internally
+    #[allow(unused_variables)]
+    let m = ::PTSNAME_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let pty = openpty(None, None).expect("openpty failed");
+    let original = tcgetattr(pty.slave).unwrap();
+    assert!(original.local_flags.contains(LocalFlags::ECHO | LocalFlags::ICANON));
+
+    {
+        let guard = RawModeGuard::with(pty.slave, |termios| {
+            termios.local_flags.insert(LocalFlags::ISIG);
+        }).unwrap();
+        let raw = tcgetattr(pty.slave).unwrap();
+        assert!(!raw.local_flags.intersects(LocalFlags::ECHO | LocalFlags::ICANON));
+        assert!(raw.local_flags.contains(LocalFlags::ISIG));
+        assert_eq!(guard.original().local_flags, original.local_flags);
+    }
+
+    let restored = tcgetattr(pty.slave).unwrap();
+    assert_eq!(restored.local_flags, original.local_flags);
+    assert_eq!(restored.input_flags, original.input_flags);
+    assert_eq!(restored.output_flags, original.output_flags);
+
+    close(pty.master).expect("closing the master failed");
+    close(pty.slave).expect("closing the slave failed");
+}
+
+// Test acquiring and giving up a controlling terminal in a new session
+#[test]
+fn test_tcsetctty() {
+    use libc::_exit;
+    use nix::sys::termios::{tcgetsid, tcsetctty};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::{fork, getpid, setsid, ForkResult};
+
+    #[allow(unused_variables)]
+    let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+    // openpty uses ptname(3) internally
+    #[allow(unused_variables)]
+    let m2 = ::PTSNAME_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let pty = openpty(None, None).expect("openpty failed");
+    match fork().unwrap() {
+        ForkResult::Child => {
+            // The caller must be a session leader without a controlling terminal
+            let ok = setsid().is_ok() &&
+                tcsetctty(pty.slave, false).is_ok() &&
+                tcgetsid(pty.slave) == Ok(getpid());
+            #[cfg(any(target_os = "dragonfly",
+                      target_os = "freebsd",
+                      target_os = "ios",
+                      target_os = "linux",
+                      target_os = "macos"))]
+            let ok = ok && {
+                use nix::sys::signal::{sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal};
+                use nix::sys::termios::tcnotty;
+
+                // Detaching as session leader hangs up our own process group
+                let ign = SigAction::new(SigHandler::SigIgn, SaFlags::empty(), SigSet::empty());
+                unsafe { sigaction(Signal::SIGHUP, &ign) }.is_ok() &&
+                    tcnotty(pty.slave).is_ok() &&
+                    tcgetsid(pty.slave).is_err()
+            };
+            unsafe { _exit(if ok { 0 } else { 1 }) };
+        },
+        ForkResult::Parent { child } => {
+            assert_eq!(waitpid(child, None).unwrap(), WaitStatus::Exited(child, 0));
+            close(pty.master).expect("closing the master failed");
+            close(pty.slave).expect("closing the slave failed");
+        },
+    }
+}
+
+// Test getting and setting the line discipline of a pty
+#[test]
+#[cfg(target_os = "linux")]
+fn test_tcgetldisc() {
+    use nix::sys::termios::{tcgetldisc, tcsetldisc, LineDiscipline};
+
+    // openpty uses ptname(3) internally
+    #[allow(unused_variables)]
+    let m = ::PTSNAME_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let pty = openpty(None, None).expect("openpty failed");
+    assert_eq!(tcgetldisc(pty.slave).unwrap(), LineDiscipline::N_TTY);
+    tcsetldisc(pty.slave, LineDiscipline::N_TTY).unwrap();
+    assert_eq!(tcgetldisc(pty.slave).unwrap(), LineDiscipline::N_TTY);
+    close(pty.master).expect("closing the master failed");
+    close(pty.slave).expect("closing the slave failed");
+}
+
+// Test the modem control line ioctls on something that isn't a serial port
+#[test]
+fn test_tcgetmodembits_enotty() {
+    use nix::sys::termios::{tcaddmodembits, tcclearmodembits, tcgetmodembits, tcsetmodembits,
+                            ModemBits};
+
+    let file = tempfile().unwrap();
+    let fd = file.as_raw_fd();
+    assert_eq!(tcgetmodembits(fd).err(), Some(Error::Sys(Errno::ENOTTY)));
+    assert_eq!(tcsetmodembits(fd, ModemBits::TIOCM_DTR).err(), Some(Error::Sys(Errno::ENOTTY)));
+    assert_eq!(tcaddmodembits(fd, ModemBits::TIOCM_RTS).err(), Some(Error::Sys(Errno::ENOTTY)));
+    assert_eq!(tcclearmodembits(fd, ModemBits::TIOCM_RTS).err(), Some(Error::Sys(Errno::ENOTTY)));
+}
+
+// Test querying the length of the input and output queues
+#[test]
+fn test_tcinq() {
+    use nix::sys::termios::tcinq;
+    use nix::unistd::pipe;
+
+    let (rd, wr) = pipe().unwrap();
+    assert_eq!(tcinq(rd).unwrap(), 0);
+    write_all(wr, b"abc");
+    assert_eq!(tcinq(rd).unwrap(), 3);
+    close(rd).unwrap();
+    close(wr).unwrap();
+}
+
+#[test]
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos"))]
+fn test_tcoutq() {
+    use nix::sys::termios::tcoutq;
+
+    // openpty uses ptname(3) internally
+    #[allow(unused_variables)]
+    let m = ::PTSNAME_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let pty = openpty(None, None).expect("openpty failed");
+    assert!(tcoutq(pty.slave).is_ok());
+    close(pty.master).expect("closing the master failed");
+    close(pty.slave).expect("closing the slave failed");
+
+    let file = tempfile().unwrap();
+    assert_eq!(tcoutq(file.as_raw_fd()).err(), Some(Error::Sys(Errno::ENOTTY)));
+}
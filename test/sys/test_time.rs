@@ -0,0 +1,150 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::sys::time::{clock_getres, clock_gettime, clock_settime, ClockId, TimeSpec, TimeValLike};
+use nix::unistd::Uid;
+
+#[test]
+fn test_clock_gettime() {
+    let a = clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap();
+    let b = clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap();
+    assert!(b >= a);
+
+    // The realtime clock should be well past the epoch
+    assert!(clock_gettime(ClockId::CLOCK_REALTIME).unwrap() > TimeSpec::seconds(1_000_000_000));
+}
+
+#[test]
+fn test_clock_getres() {
+    let res = clock_getres(ClockId::CLOCK_MONOTONIC).unwrap();
+    assert!(res > TimeSpec::zero());
+    assert!(res <= TimeSpec::seconds(1));
+}
+
+#[test]
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "openbsd"))]
+fn test_clock_cputime() {
+    let start = clock_gettime(ClockId::CLOCK_THREAD_CPUTIME_ID).unwrap();
+    assert!(clock_gettime(ClockId::CLOCK_THREAD_CPUTIME_ID).unwrap() >= start);
+    assert!(clock_gettime(ClockId::CLOCK_PROCESS_CPUTIME_ID).unwrap() > TimeSpec::zero());
+}
+
+#[test]
+fn test_clock_settime_monotonic() {
+    // Monotonic clocks can't be set, not even by root
+    let now = clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap();
+    assert_eq!(clock_settime(ClockId::CLOCK_MONOTONIC, &now), Err(Error::Sys(Errno::EINVAL)));
+}
+
+#[test]
+fn test_clock_settime_realtime_unprivileged() {
+    if Uid::current().is_root() {
+        // Don't mess with the system clock while running as root
+        return;
+    }
+    let now = clock_gettime(ClockId::CLOCK_REALTIME).unwrap();
+    assert_eq!(clock_settime(ClockId::CLOCK_REALTIME, &now), Err(Error::Sys(Errno::EPERM)));
+}
+
+#[test]
+fn test_gettimeofday() {
+    use nix::sys::time::{gettimeofday, TimeVal};
+
+    let before = clock_gettime(ClockId::CLOCK_REALTIME).unwrap();
+    let (tv, _) = gettimeofday().unwrap();
+    let after = clock_gettime(ClockId::CLOCK_REALTIME).unwrap();
+    // gettimeofday truncates to microseconds
+    assert!(tv >= TimeVal::microseconds(before.num_microseconds()));
+    assert!(tv <= TimeVal::microseconds(after.num_microseconds()));
+}
+
+#[test]
+fn test_settimeofday_unprivileged() {
+    use nix::sys::time::{gettimeofday, settimeofday};
+
+    if Uid::current().is_root() {
+        // Don't mess with the system clock while running as root
+        return;
+    }
+    let (now, tz) = gettimeofday().unwrap();
+    assert_eq!(settimeofday(&now, None), Err(Error::Sys(Errno::EPERM)));
+    assert_eq!(settimeofday(&now, Some(&tz)), Err(Error::Sys(Errno::EPERM)));
+}
+
+#[test]
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd"))]
+fn test_clock_nanosleep() {
+    use nix::sys::time::{clock_nanosleep, ClockNanosleepFlags};
+
+    let start = clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap();
+    let interval = TimeSpec::milliseconds(10);
+    assert_eq!(clock_nanosleep(ClockId::CLOCK_MONOTONIC, ClockNanosleepFlags::empty(), &interval),
+               Ok(None));
+    let deadline = clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap() + interval;
+    assert_eq!(clock_nanosleep(ClockId::CLOCK_MONOTONIC, ClockNanosleepFlags::TIMER_ABSTIME,
+                               &deadline),
+               Ok(None));
+    let end = clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap();
+    assert!(end >= deadline);
+    assert!(end - start >= interval * 2);
+
+    // A deadline in the past returns immediately
+    assert_eq!(clock_nanosleep(ClockId::CLOCK_MONOTONIC, ClockNanosleepFlags::TIMER_ABSTIME,
+                               &start),
+               Ok(None));
+    assert_eq!(clock_nanosleep(ClockId::CLOCK_MONOTONIC, ClockNanosleepFlags::empty(),
+                               &TimeSpec::nanoseconds(-1)),
+               Err(Error::Sys(Errno::EINVAL)));
+}
+
+#[test]
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+fn test_setitimer() {
+    use nix::sys::time::{getitimer, setitimer, IntervalTimer, ItimerSpec, TimeVal};
+
+    // The timer counts process CPU time, so this long a timer never expires during the test
+    let spec = ItimerSpec {
+        interval: TimeVal::seconds(1000),
+        value: TimeVal::seconds(1000),
+    };
+    let disarmed = ItimerSpec { interval: TimeVal::zero(), value: TimeVal::zero() };
+    assert_eq!(setitimer(IntervalTimer::ITIMER_PROF, &spec), Ok(disarmed));
+
+    let current = getitimer(IntervalTimer::ITIMER_PROF).unwrap();
+    assert_eq!(current.interval, spec.interval);
+    assert!(current.value > TimeVal::zero());
+    // The kernel may round the value up to its clock tick
+    assert!(current.value <= spec.value + TimeVal::seconds(1));
+
+    let old = setitimer(IntervalTimer::ITIMER_PROF, &disarmed).unwrap();
+    assert_eq!(old.interval, spec.interval);
+    assert_eq!(getitimer(IntervalTimer::ITIMER_PROF), Ok(disarmed));
+}
+
+#[test]
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "linux"))]
+fn test_clock_getcpuclockid() {
+    use nix::sys::time::clock_getcpuclockid;
+    use nix::unistd::getpid;
+
+    let clock = clock_getcpuclockid(getpid()).unwrap();
+    assert!(clock_gettime(clock).unwrap() > TimeSpec::zero());
+}
@@ -0,0 +1,73 @@
+use libc::c_int;
+use nix::sys::signal::{sigaction, SaFlags, SigAction, SigevNotify, SigHandler, SigSet, Signal};
+use nix::sys::time::{clock_gettime, ClockId, TimeSpec, TimeValLike};
+use nix::sys::timer::{Expiration, Timer, TimerSetTimeFlags};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{thread, time};
+
+#[test]
+fn test_timer_get_set() {
+    // Some kernels keep reporting the old expiration of SIGEV_NONE timers after they're disarmed,
+    // so use a signal even though the timer never fires.
+    let notify = SigevNotify::SigevSignal { signal: Signal::SIGALRM, si_value: 0 };
+    let mut timer = Timer::new(ClockId::CLOCK_MONOTONIC, notify).unwrap();
+    assert_eq!(timer.get().unwrap(), None);
+
+    timer.set(Expiration::OneShot(TimeSpec::seconds(100)), TimerSetTimeFlags::empty()).unwrap();
+    match timer.get().unwrap() {
+        Some(Expiration::OneShot(t)) => assert!(t > TimeSpec::zero() && t <= TimeSpec::seconds(100)),
+        e => panic!("unexpected expiration {:?}", e),
+    }
+
+    timer.set(Expiration::IntervalDelayed(TimeSpec::seconds(100), TimeSpec::seconds(5)),
+              TimerSetTimeFlags::empty()).unwrap();
+    match timer.get().unwrap() {
+        Some(Expiration::IntervalDelayed(t, i)) => {
+            assert!(t > TimeSpec::seconds(5) && t <= TimeSpec::seconds(100));
+            assert_eq!(i, TimeSpec::seconds(5));
+        },
+        e => panic!("unexpected expiration {:?}", e),
+    }
+
+    // An absolute deadline is reported relative to now
+    let deadline = clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap() + TimeSpec::seconds(100);
+    timer.set(Expiration::OneShot(deadline), TimerSetTimeFlags::TIMER_ABSTIME).unwrap();
+    match timer.get().unwrap() {
+        Some(Expiration::OneShot(t)) => assert!(t <= TimeSpec::seconds(100)),
+        e => panic!("unexpected expiration {:?}", e),
+    }
+
+    timer.unset().unwrap();
+    assert_eq!(timer.get().unwrap(), None);
+    assert_eq!(timer.overruns().unwrap(), 0);
+}
+
+lazy_static! {
+    pub static ref ALARMED: AtomicBool = AtomicBool::new(false);
+}
+
+extern fn handle_sigalrm(_: c_int) {
+    ALARMED.store(true, Ordering::Relaxed);
+}
+
+// Test a timer with expiration delivered by a signal
+#[test]
+fn test_timer_signal() {
+    #[allow(unused_variables)]
+    let m = ::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let sa = SigAction::new(SigHandler::Handler(handle_sigalrm),
+                            SaFlags::SA_RESETHAND,
+                            SigSet::empty());
+    ALARMED.store(false, Ordering::Relaxed);
+    unsafe { sigaction(Signal::SIGALRM, &sa) }.unwrap();
+
+    let notify = SigevNotify::SigevSignal { signal: Signal::SIGALRM, si_value: 0 };
+    let mut timer = Timer::new(ClockId::CLOCK_MONOTONIC, notify).unwrap();
+    timer.set(Expiration::OneShot(TimeSpec::milliseconds(1)), TimerSetTimeFlags::empty()).unwrap();
+    while !ALARMED.load(Ordering::Relaxed) {
+        thread::sleep(time::Duration::from_millis(10));
+    }
+    // A one-shot timer is disarmed once it has expired
+    assert_eq!(timer.get().unwrap(), None);
+}
@@ -0,0 +1,44 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::sys::time::{clock_gettime, ClockId, TimeSpec, TimeValLike};
+use nix::sys::timerfd::{Expiration, TimerFd, TimerFlags, TimerSetTimeFlags};
+
+#[test]
+fn test_timerfd_oneshot() {
+    let mut timer = TimerFd::new(ClockId::CLOCK_MONOTONIC, TimerFlags::TFD_NONBLOCK).unwrap();
+    assert_eq!(timer.get().unwrap(), None);
+    assert_eq!(timer.read_expirations().unwrap(), None);
+
+    let deadline = clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap() + TimeSpec::milliseconds(1);
+    timer.set(Expiration::OneShot(deadline), TimerSetTimeFlags::TFD_TIMER_ABSTIME).unwrap();
+    while timer.read_expirations().unwrap().is_none() {}
+    assert!(clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap() >= deadline);
+    // A one-shot timer must be disarmed once it has expired
+    assert_eq!(timer.get().unwrap(), None);
+}
+
+#[test]
+fn test_timerfd_interval() {
+    let mut timer = TimerFd::new(ClockId::CLOCK_MONOTONIC, TimerFlags::TFD_CLOEXEC).unwrap();
+    timer.set(Expiration::Interval(TimeSpec::milliseconds(1)), TimerSetTimeFlags::empty())
+        .unwrap();
+    match timer.get().unwrap() {
+        Some(Expiration::Interval(i)) | Some(Expiration::IntervalDelayed(_, i)) =>
+            assert_eq!(i, TimeSpec::milliseconds(1)),
+        e => panic!("unexpected expiration {:?}", e),
+    }
+
+    let mut expirations = 0;
+    while expirations < 3 {
+        expirations += timer.read_expirations().unwrap().unwrap();
+    }
+
+    timer.unset().unwrap();
+    assert_eq!(timer.get().unwrap(), None);
+}
+
+#[test]
+fn test_timerfd_bad_clock() {
+    assert_eq!(TimerFd::new(ClockId::CLOCK_PROCESS_CPUTIME_ID, TimerFlags::empty()),
+               Err(Error::Sys(Errno::EINVAL)));
+}
@@ -0,0 +1,56 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::sys::time::{ClockId, TimeSpec, TimeValLike};
+use nix::sys::timex::{adjtimex, clock_adjtime, AdjFlags, Timex, TimexStatus};
+use nix::unistd::Uid;
+
+#[test]
+fn test_adjtimex_read() {
+    let mut timex = Timex::default();
+    adjtimex(&mut timex).unwrap();
+    // The kernel fills in the current time and its tick length
+    assert!(timex.time() > TimeSpec::seconds(1_000_000_000));
+    assert!(timex.tick() > 0);
+
+    let mut timex = Timex::default();
+    clock_adjtime(ClockId::CLOCK_REALTIME, &mut timex).unwrap();
+    assert!(timex.time() > TimeSpec::seconds(1_000_000_000));
+}
+
+#[test]
+fn test_timex_time_units() {
+    let mut timex = Timex::default();
+    timex.as_mut().time.tv_sec = 1;
+    timex.as_mut().time.tv_usec = 500_000;
+    assert_eq!(timex.time(), TimeSpec::milliseconds(1500));
+
+    // With STA_NANO, the same field holds nanoseconds
+    timex.as_mut().status = TimexStatus::STA_NANO.bits();
+    timex.as_mut().time.tv_usec = 500_000_000;
+    assert_eq!(timex.time(), TimeSpec::milliseconds(1500));
+}
+
+#[test]
+fn test_timex_setters() {
+    let mut timex = Timex::default();
+    assert_eq!(timex.modes(), AdjFlags::empty());
+    timex.set_freq(-42);
+    timex.set_maxerror(16_000);
+    assert_eq!(timex.freq(), -42);
+    assert_eq!(timex.maxerror(), 16_000);
+    assert_eq!(timex.modes(), AdjFlags::ADJ_FREQUENCY | AdjFlags::ADJ_MAXERROR);
+}
+
+#[test]
+fn test_adjtimex_unprivileged() {
+    if Uid::current().is_root() {
+        // Don't mess with the system clock while running as root
+        return;
+    }
+    let mut timex = Timex::default();
+    adjtimex(&mut timex).unwrap();
+    let esterror = timex.esterror();
+    let mut timex = Timex::default();
+    timex.set_esterror(esterror);
+    assert_eq!(adjtimex(&mut timex), Err(Error::Sys(Errno::EPERM)));
+}
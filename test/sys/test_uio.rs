@@ -96,6 +96,31 @@ fn test_readv() {
     assert!(close_res.is_ok());
 }
 
+#[test]
+#[cfg(io_slice)]
+fn test_io_slices() {
+    use std::io::{IoSlice, IoSliceMut};
+
+    let (reader, writer) = pipe().unwrap();
+    let bufs = [IoSlice::new(b"abc"), IoSlice::new(b"defgh")];
+    assert_eq!(Ok(8), writev(writer, IoVec::from_io_slices(&bufs)));
+
+    let mut first = [0u8; 5];
+    let mut second = [0u8; 3];
+    {
+        let mut bufs = [IoSliceMut::new(&mut first), IoSliceMut::new(&mut second)];
+        assert_eq!(Ok(8), readv(reader, IoVec::from_io_slices_mut(&mut bufs)));
+    }
+    assert_eq!(&first, b"abcde");
+    assert_eq!(&second, b"fgh");
+
+    let iov: IoVec<&[u8]> = IoSlice::new(b"ijk").into();
+    assert_eq!(iov.as_slice(), b"ijk");
+
+    close(reader).unwrap();
+    close(writer).unwrap();
+}
+
 #[test]
 fn test_pwrite() {
     use std::io::Read;
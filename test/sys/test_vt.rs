@@ -0,0 +1,18 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::sys::vt::{self, ConsoleMode};
+use std::os::unix::io::AsRawFd;
+use tempfile::tempfile;
+
+// Console ioctls must be rejected on anything that isn't a virtual console.
+#[test]
+fn test_vt_regular_file() {
+    let tmp = tempfile().unwrap();
+    let fd = tmp.as_raw_fd();
+
+    assert_eq!(vt::get_state(fd), Err(Error::Sys(Errno::ENOTTY)));
+    assert_eq!(vt::activate(fd, 1), Err(Error::Sys(Errno::ENOTTY)));
+    assert_eq!(vt::set_mode(fd, ConsoleMode::KD_TEXT), Err(Error::Sys(Errno::ENOTTY)));
+    assert_eq!(vt::get_mode(fd), Err(Error::Sys(Errno::ENOTTY)));
+    assert_eq!(vt::get_keyboard_mode(fd), Err(Error::Sys(Errno::ENOTTY)));
+}
@@ -88,7 +88,7 @@ mod ptrace {
         assert_eq!(waitpid(child, None), Ok(WaitStatus::PtraceSyscall(child)));
         // Then get the ptrace event for the process exiting
         assert!(ptrace::cont(child, None).is_ok());
-        assert_eq!(waitpid(child, None), Ok(WaitStatus::PtraceEvent(child, SIGTRAP, Event::PTRACE_EVENT_EXIT as i32)));
+        assert_eq!(waitpid(child, None), Ok(WaitStatus::PtraceExit(child, 0)));
         // Finally get the normal wait() result, now that the process has exited
         assert!(ptrace::cont(child, None).is_ok());
         assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
@@ -104,4 +104,61 @@ mod ptrace {
             Parent { child } => ptrace_parent(child),
         }
     }
+
+    #[test]
+    fn test_wait_ptrace_from_raw() {
+        // The event message can't be read, since the process isn't traced
+        let pid = Pid::from_raw(1);
+        let status = (Event::PTRACE_EVENT_EXIT as i32) << 16 | (SIGTRAP as i32) << 8 | 0x7f;
+        assert_eq!(WaitStatus::from_raw(pid, status),
+                   Ok(WaitStatus::PtraceEvent(pid, SIGTRAP, Event::PTRACE_EVENT_EXIT as i32)));
+    }
+
+    fn ptrace_fork_child() -> ! {
+        use std::ffi::CString;
+
+        ptrace::traceme().unwrap();
+        raise(SIGTRAP).unwrap();
+        if let Ok(Child) = fork() {
+            unsafe { _exit(0) }
+        }
+        let path = CString::new("/bin/true").unwrap();
+        execv(&path, &[path.clone()]).unwrap();
+        unsafe { _exit(1) }
+    }
+
+    fn ptrace_fork_parent(child: Pid) {
+        assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, SIGTRAP)));
+        ptrace::setoptions(child, Options::PTRACE_O_TRACEFORK | Options::PTRACE_O_TRACEEXEC)
+            .unwrap();
+
+        // The new child starts traced and stopped
+        ptrace::cont(child, None).unwrap();
+        let grandchild = match waitpid(child, None) {
+            Ok(WaitStatus::PtraceFork(pid, grandchild)) if pid == child => grandchild,
+            status => panic!("Expected a fork event, got {:?}", status),
+        };
+        assert_eq!(waitpid(grandchild, None), Ok(WaitStatus::Stopped(grandchild, SIGSTOP)));
+        ptrace::cont(grandchild, None).unwrap();
+        assert_eq!(waitpid(grandchild, None), Ok(WaitStatus::Exited(grandchild, 0)));
+
+        // The child is notified of the exit first
+        ptrace::cont(child, None).unwrap();
+        assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, SIGCHLD)));
+        ptrace::cont(child, None).unwrap();
+        assert_eq!(waitpid(child, None), Ok(WaitStatus::PtraceExec(child, child)));
+        ptrace::cont(child, None).unwrap();
+        assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+    }
+
+    #[test]
+    fn test_wait_ptrace_fork() {
+        #[allow(unused_variables)]
+        let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+        match fork().expect("Error: Fork Failed") {
+            Child => ptrace_fork_child(),
+            Parent { child } => ptrace_fork_parent(child),
+        }
+    }
 }
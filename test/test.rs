@@ -11,7 +11,10 @@ extern crate tempdir;
 extern crate tempfile;
 
 mod sys;
+mod test_errno;
 mod test_fcntl;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod test_kmod;
 #[cfg(any(target_os = "dragonfly",
           target_os = "freebsd",
           target_os = "fushsia",
@@ -23,6 +26,9 @@ mod test_nix_path;
 mod test_poll;
 mod test_pty;
 #[cfg(any(target_os = "linux", target_os = "android"))]
+mod test_sched;
+mod test_semaphore;
+#[cfg(any(target_os = "linux", target_os = "android"))]
 mod test_sendfile;
 mod test_stat;
 mod test_unistd;
@@ -0,0 +1,30 @@
+use std::io;
+
+use nix::Error;
+use nix::errno::Errno;
+
+#[test]
+fn test_io_error_roundtrip() {
+    let err: io::Error = Error::Sys(Errno::ENOENT).into();
+    assert_eq!(err.raw_os_error(), Some(Errno::ENOENT as i32));
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert_eq!(Error::from(err), Error::Sys(Errno::ENOENT));
+
+    let err: io::Error = Error::InvalidUtf8.into();
+    assert_eq!(Errno::from(err), Errno::EILSEQ);
+}
+
+#[test]
+fn test_io_error_kind() {
+    let err = io::Error::new(io::ErrorKind::PermissionDenied, "denied");
+    assert_eq!(Errno::from(err), Errno::EACCES);
+    let err = io::Error::new(io::ErrorKind::Other, "other");
+    assert_eq!(Error::from(err).as_errno(), Errno::EIO);
+}
+
+#[test]
+fn test_display() {
+    let message = Error::Sys(Errno::EPERM).to_string();
+    assert!(message.starts_with("EPERM: "));
+    assert!(message.len() > "EPERM: ".len());
+}
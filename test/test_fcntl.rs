@@ -1,10 +1,13 @@
-use nix::fcntl::{openat, open, OFlag, readlink, readlinkat};
+use nix::fcntl::{openat, openat_owned, open, open_owned, OFlag, readlink, readlinkat, readlink_owned,
+                 readlinkat_owned, fcntl, set_cloexec, set_nonblocking, FcntlArg, FdFlag};
+use libc;
 use nix::sys::stat::Mode;
 use nix::unistd::{close, read};
 use tempdir::TempDir;
 use tempfile::NamedTempFile;
 use std::io::prelude::*;
 use std::os::unix::fs;
+use std::os::unix::io::AsRawFd;
 
 #[test]
 fn test_openat() {
@@ -28,6 +31,23 @@ fn test_openat() {
     close(dirfd).unwrap();
 }
 
+#[test]
+fn test_openat_owned() {
+    const CONTENTS: &[u8] = b"abcd";
+    let mut tmp = NamedTempFile::new().unwrap();
+    tmp.write_all(CONTENTS).unwrap();
+
+    let dir = open_owned(tmp.path().parent().unwrap(), OFlag::empty(), Mode::empty()).unwrap();
+    let fd = openat_owned(dir.as_raw_fd(),
+                          tmp.path().file_name().unwrap(),
+                          OFlag::O_RDONLY,
+                          Mode::empty()).unwrap();
+
+    let mut buf = [0u8; 1024];
+    assert_eq!(4, read(fd.as_raw_fd(), &mut buf).unwrap());
+    assert_eq!(CONTENTS, &buf[0..4]);
+}
+
 #[test]
 fn test_readlink() {
     let tempdir = TempDir::new("nix-test_readdir")
@@ -45,11 +65,62 @@ fn test_readlink() {
                src.to_str().unwrap());
     assert_eq!(readlinkat(dirfd, "b", &mut buf).unwrap().to_str().unwrap(),
                src.to_str().unwrap());
+
+    // A buffer that's too small must be reported rather than truncated.
+    let mut short = vec![0; 2];
+    assert!(readlink(&dst, &mut short).is_err());
+
+    assert_eq!(readlink_owned(&dst).unwrap(), src.as_os_str());
+    assert_eq!(readlinkat_owned(dirfd, "b").unwrap(), src.as_os_str());
+}
+
+#[test]
+fn test_readlink_owned_long_target() {
+    let tempdir = TempDir::new("nix-test_readlink_owned")
+        .unwrap_or_else(|e| panic!("tempdir failed: {}", e));
+    // Symlink targets don't have to exist, so use the longest one the OS
+    // allows rather than a real path.
+    let target: String = "x/".chars().cycle().take(libc::PATH_MAX as usize - 1).collect();
+    let dst = tempdir.path().join("long");
+    fs::symlink(&target, &dst).unwrap();
+
+    assert_eq!(readlink_owned(&dst).unwrap().to_str().unwrap(), target);
+}
+
+#[test]
+fn test_set_nonblocking() {
+    let tmp = NamedTempFile::new().unwrap();
+    let fd = open(tmp.path(), OFlag::O_WRONLY | OFlag::O_APPEND, Mode::empty()).unwrap();
+    let status = || OFlag::from_bits_truncate(fcntl(fd, FcntlArg::F_GETFL).unwrap());
+
+    set_nonblocking(fd, true).unwrap();
+    assert!(status().contains(OFlag::O_NONBLOCK | OFlag::O_APPEND));
+    set_nonblocking(fd, true).unwrap();
+    assert!(status().contains(OFlag::O_NONBLOCK));
+    set_nonblocking(fd, false).unwrap();
+    assert!(!status().contains(OFlag::O_NONBLOCK));
+    assert!(status().contains(OFlag::O_APPEND));
+    close(fd).unwrap();
+}
+
+#[test]
+fn test_set_cloexec() {
+    let tmp = NamedTempFile::new().unwrap();
+    let fd = open(tmp.path(), OFlag::O_RDONLY, Mode::empty()).unwrap();
+    let flags = || FdFlag::from_bits_truncate(fcntl(fd, FcntlArg::F_GETFD).unwrap());
+
+    assert!(!flags().contains(FdFlag::FD_CLOEXEC));
+    set_cloexec(fd, true).unwrap();
+    assert!(flags().contains(FdFlag::FD_CLOEXEC));
+    set_cloexec(fd, false).unwrap();
+    assert!(!flags().contains(FdFlag::FD_CLOEXEC));
+    close(fd).unwrap();
 }
 
 #[cfg(any(target_os = "linux", target_os = "android"))]
 mod linux_android {
     use std::io::prelude::*;
+    use std::os::unix::fs::symlink;
     use std::os::unix::prelude::*;
 
     use libc::loff_t;
@@ -131,6 +202,35 @@ mod linux_android {
         close(wr).unwrap();
     }
 
+    #[test]
+    fn test_open_path() {
+        use libc::{S_IFMT, S_IFLNK};
+        use nix::Error;
+        use nix::errno::Errno;
+        use nix::fcntl::{open_path, openat_path, readlinkat, AtFlags, OFlag};
+        use nix::sys::stat::fstatat;
+        use tempdir::TempDir;
+
+        let tempdir = TempDir::new("nix-test_open_path").unwrap();
+        symlink("target", tempdir.path().join("link")).unwrap();
+
+        let dirfd = open_path(tempdir.path(), OFlag::O_DIRECTORY | OFlag::O_CLOEXEC).unwrap();
+        let linkfd = openat_path(dirfd, "link", OFlag::O_NOFOLLOW).unwrap();
+
+        let mut buf = [0u8; 32];
+        assert_eq!(readlinkat(linkfd, "", &mut buf).unwrap(), "target");
+        let st = fstatat(linkfd, "", AtFlags::AT_EMPTY_PATH).unwrap();
+        assert_eq!(st.st_mode & S_IFMT, S_IFLNK);
+
+        // The file isn't actually open, so I/O isn't possible
+        assert_eq!(read(linkfd, &mut buf), Err(Error::Sys(Errno::EBADF)));
+        // Access modes make no sense with O_PATH
+        assert_eq!(openat_path(dirfd, "link", OFlag::O_RDWR), Err(Error::Sys(Errno::EINVAL)));
+
+        close(linkfd).unwrap();
+        close(dirfd).unwrap();
+    }
+
     #[test]
     fn test_fallocate() {
         let tmp = NamedTempFile::new().unwrap();
@@ -143,3 +243,88 @@ mod linux_android {
         assert_eq!(100, read(fd, &mut buf).unwrap());
     }
 }
+
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+mod apple {
+    use libc;
+    use nix::Error;
+    use nix::errno::Errno;
+    use nix::fcntl::*;
+    use nix::unistd::write;
+    use tempdir::TempDir;
+    use tempfile::tempfile;
+    use std::fs::File;
+    use std::io::prelude::*;
+    use std::os::unix::io::AsRawFd;
+    use std::path::Path;
+
+    fn read_all(path: &Path) -> Vec<u8> {
+        let mut buf = Vec::new();
+        File::open(path).unwrap().read_to_end(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_fullfsync() {
+        let tmp = tempfile().unwrap();
+        let fd = tmp.as_raw_fd();
+        write(fd, b"durable").unwrap();
+
+        fcntl(fd, FcntlArg::F_FULLFSYNC).unwrap();
+    }
+
+    #[test]
+    fn test_preallocate() {
+        let tmp = tempfile().unwrap();
+
+        let mut fstore = libc::fstore_t {
+            fst_flags: libc::F_ALLOCATEALL,
+            fst_posmode: libc::F_PEOFPOSMODE,
+            fst_offset: 0,
+            fst_length: 1 << 20,
+            fst_bytesalloc: 0,
+        };
+        fcntl(tmp.as_raw_fd(), FcntlArg::F_PREALLOCATE(&mut fstore)).unwrap();
+        assert!(fstore.fst_bytesalloc >= 1 << 20);
+        // The size of the file is unchanged
+        assert_eq!(tmp.metadata().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_clonefileat() {
+        let tempdir = TempDir::new("nix-test_clonefileat").unwrap();
+        let src = tempdir.path().join("src");
+        File::create(&src).unwrap().write_all(b"cloned").unwrap();
+
+        let dst = tempdir.path().join("dst");
+        match clonefileat(libc::AT_FDCWD, &src, libc::AT_FDCWD, &dst, CloneFileFlags::empty()) {
+            // The temporary directory isn't on APFS
+            Err(Error::Sys(Errno::ENOTSUP)) => return,
+            r => r.unwrap(),
+        }
+        assert_eq!(read_all(&dst), b"cloned");
+
+        // The destination must not exist
+        let f = File::open(&src).unwrap();
+        assert_eq!(fclonefileat(f.as_raw_fd(), libc::AT_FDCWD, &dst, CloneFileFlags::empty()),
+                   Err(Error::Sys(Errno::EEXIST)));
+    }
+
+    #[test]
+    fn test_copyfile() {
+        let tempdir = TempDir::new("nix-test_copyfile").unwrap();
+        let src = tempdir.path().join("src");
+        File::create(&src).unwrap().write_all(b"copied").unwrap();
+
+        let dst = tempdir.path().join("dst");
+        copyfile(&src, &dst, CopyFileFlags::COPYFILE_ALL).unwrap();
+        assert_eq!(read_all(&dst), b"copied");
+        assert_eq!(copyfile(&src, &dst, CopyFileFlags::COPYFILE_ALL | CopyFileFlags::COPYFILE_EXCL),
+                   Err(Error::Sys(Errno::EEXIST)));
+
+        let from = File::open(&src).unwrap();
+        let to = tempfile().unwrap();
+        fcopyfile(from.as_raw_fd(), to.as_raw_fd(), CopyFileFlags::COPYFILE_DATA).unwrap();
+        assert_eq!(to.metadata().unwrap().len(), 6);
+    }
+}
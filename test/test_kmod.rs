@@ -0,0 +1,20 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::kmod::*;
+
+#[test]
+fn test_init_module_invalid() {
+    match init_module(&b"not an ELF image"[..], "") {
+        // Unprivileged, or the kernel doesn't support modules
+        Err(Error::Sys(Errno::EPERM)) | Err(Error::Sys(Errno::ENOSYS)) => (),
+        res => assert_eq!(res, Err(Error::Sys(Errno::ENOEXEC))),
+    }
+}
+
+#[test]
+fn test_delete_module_missing() {
+    match delete_module("nix_does_not_exist", DeleteModuleFlags::O_NONBLOCK) {
+        Err(Error::Sys(Errno::EPERM)) | Err(Error::Sys(Errno::ENOSYS)) => (),
+        res => assert_eq!(res, Err(Error::Sys(Errno::ENOENT))),
+    }
+}
@@ -38,6 +38,38 @@ fn test_mq_send_and_receive() {
     assert_eq!(msg_to_send, str::from_utf8(&buf[0..len]).unwrap());
 }
 
+#[test]
+fn test_mq_timedsend_and_timedreceive() {
+    use nix::mqueue::{mq_timedreceive, mq_timedsend};
+    use nix::sys::time::{clock_gettime, ClockId, TimeValLike, TimeSpec};
+
+    const MSG_SIZE: c_long =  32;
+    let attr =  MqAttr::new(0, 1, MSG_SIZE, 0);
+    let mq_name = &CString::new(b"/mq_timed_test".as_ref()).unwrap();
+    let oflag = MQ_OFlag::O_CREAT | MQ_OFlag::O_RDWR;
+    let mode = Mode::S_IWUSR | Mode::S_IRUSR | Mode::S_IRGRP | Mode::S_IROTH;
+    let r = mq_open(mq_name, oflag, mode, Some(&attr));
+    if let Err(Sys(ENOSYS)) = r {
+        println!("message queues not supported or module not loaded?");
+        return;
+    };
+    let mqd = r.unwrap();
+    mq_unlink(mq_name).unwrap();
+    let deadline = || clock_gettime(ClockId::CLOCK_REALTIME).unwrap() + TimeSpec::milliseconds(10);
+
+    let mut buf = [0u8; 32];
+    let mut prio = 0u32;
+    assert_eq!(mq_timedreceive(mqd, &mut buf, &mut prio, &deadline()), Err(Sys(ETIMEDOUT)));
+
+    mq_timedsend(mqd, b"msg_1", 3, &deadline()).unwrap();
+    // The queue only holds one message
+    assert_eq!(mq_timedsend(mqd, b"msg_2", 1, &deadline()), Err(Sys(ETIMEDOUT)));
+
+    let len = mq_timedreceive(mqd, &mut buf, &mut prio, &deadline()).unwrap();
+    assert_eq!(&buf[0..len], b"msg_1");
+    assert_eq!(prio, 3);
+    mq_close(mqd).unwrap();
+}
 
 #[test]
 fn test_mq_getattr() {
@@ -142,3 +174,132 @@ fn test_mq_unlink() {
     let res_unlink_after_close = mq_unlink(mq_name_opened);
     assert!(res_unlink_after_close == Err(Sys(ENOENT)) );
 }
+
+#[test]
+fn test_mqd() {
+    use nix::mqueue::MqD;
+    use nix::unistd::getpid;
+
+    const MSG_SIZE: c_long =  32;
+    let attr =  MqAttr::new(0, 10, MSG_SIZE, 0);
+    // The queue is created with O_EXCL, so a queue left behind by an aborted
+    // run mustn't have the same name
+    let mq_name = &CString::new(format!("/mqd_test_{}", getpid())).unwrap();
+    let mode = Mode::S_IWUSR | Mode::S_IRUSR;
+    let r = MqD::create(mq_name, MQ_OFlag::O_RDWR, mode, &attr);
+    if let Err(Sys(ENOSYS)) = r {
+        println!("message queues not supported or module not loaded?");
+        return;
+    };
+    let mut mq = r.unwrap();
+    assert_eq!(MqD::create(mq_name, MQ_OFlag::O_RDWR, mode, &attr).unwrap_err(), Sys(EEXIST));
+
+    {
+        let other = MqD::open(mq_name, MQ_OFlag::O_WRONLY, mode, None).unwrap();
+        other.send(b"msg_1", 1).unwrap();
+    }
+    let read_attr = mq.getattr().unwrap();
+    assert_eq!(read_attr.maxmsg(), 10);
+    assert_eq!(read_attr.msgsize(), MSG_SIZE);
+    assert_eq!(read_attr.curmsgs(), 1);
+
+    let mut buf = [0u8; 32];
+    let mut prio = 0u32;
+    let len = mq.receive(&mut buf, &mut prio).unwrap();
+    assert_eq!(&buf[0..len], b"msg_1");
+
+    mq.set_nonblock(true).unwrap();
+    assert_eq!(mq.getattr().unwrap().flags(), MQ_OFlag::O_NONBLOCK.bits() as c_long);
+    assert_eq!(mq.receive(&mut buf, &mut prio), Err(Sys(EAGAIN)));
+    mq.set_nonblock(false).unwrap();
+    assert_eq!(mq.getattr().unwrap().flags(), 0);
+
+    mq.unlink_on_drop();
+    drop(mq);
+    assert_eq!(mq_unlink(mq_name), Err(Sys(ENOENT)));
+}
+
+#[cfg(any(all(target_os = "linux", target_env = "gnu"),
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "netbsd"))]
+mod notify {
+    use libc::{c_int, c_long};
+
+    use std::ffi::CString;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::{thread, time};
+
+    use nix::errno::Errno::*;
+    use nix::Error::Sys;
+    use nix::mqueue::{mq_open, mq_close, mq_notify, mq_send, mq_unlink};
+    use nix::mqueue::{MqAttr, MQ_OFlag};
+    use nix::sys::signal::{sigaction, SaFlags, SigAction, SigevNotify, SigHandler, SigSet, Signal};
+    use nix::sys::stat::Mode;
+
+    #[test]
+    fn test_mq_notify_registration() {
+        const MSG_SIZE: c_long =  32;
+        let attr =  MqAttr::new(0, 10, MSG_SIZE, 0);
+        let mq_name = &CString::new(b"/mq_notify_registration_test".as_ref()).unwrap();
+        let oflag = MQ_OFlag::O_CREAT | MQ_OFlag::O_RDWR;
+        let mode = Mode::S_IWUSR | Mode::S_IRUSR | Mode::S_IRGRP | Mode::S_IROTH;
+        let r = mq_open(mq_name, oflag, mode, Some(&attr));
+        if let Err(Sys(ENOSYS)) = r {
+            println!("message queues not supported or module not loaded?");
+            return;
+        };
+        let mqd = r.unwrap();
+        mq_unlink(mq_name).unwrap();
+
+        // Only one registration is allowed, until it's removed
+        mq_notify(mqd, Some(SigevNotify::SigevNone)).unwrap();
+        assert_eq!(mq_notify(mqd, Some(SigevNotify::SigevNone)), Err(Sys(EBUSY)));
+        mq_notify(mqd, None).unwrap();
+        mq_notify(mqd, Some(SigevNotify::SigevNone)).unwrap();
+        mq_close(mqd).unwrap();
+    }
+
+    lazy_static! {
+        pub static ref NOTIFIED: AtomicBool = AtomicBool::new(false);
+    }
+
+    extern fn notify_handler(_: c_int) {
+        NOTIFIED.store(true, Ordering::Relaxed);
+    }
+
+    #[test]
+    fn test_mq_notify_signal() {
+        #[allow(unused_variables)]
+        let m = ::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+        const MSG_SIZE: c_long =  32;
+        let attr =  MqAttr::new(0, 10, MSG_SIZE, 0);
+        let mq_name = &CString::new(b"/mq_notify_signal_test".as_ref()).unwrap();
+        let oflag = MQ_OFlag::O_CREAT | MQ_OFlag::O_RDWR;
+        let mode = Mode::S_IWUSR | Mode::S_IRUSR | Mode::S_IRGRP | Mode::S_IROTH;
+        let r = mq_open(mq_name, oflag, mode, Some(&attr));
+        if let Err(Sys(ENOSYS)) = r {
+            println!("message queues not supported or module not loaded?");
+            return;
+        };
+        let mqd = r.unwrap();
+        mq_unlink(mq_name).unwrap();
+
+        let sa = SigAction::new(SigHandler::Handler(notify_handler),
+                                SaFlags::SA_RESETHAND,
+                                SigSet::empty());
+        NOTIFIED.store(false, Ordering::Relaxed);
+        unsafe { sigaction(Signal::SIGUSR2, &sa) }.unwrap();
+
+        let notification = SigevNotify::SigevSignal { signal: Signal::SIGUSR2, si_value: 0 };
+        mq_notify(mqd, Some(notification)).unwrap();
+        mq_send(mqd, b"msg", 1).unwrap();
+        while !NOTIFIED.load(Ordering::Relaxed) {
+            thread::sleep(time::Duration::from_millis(10));
+        }
+
+        // The registration was removed by the notification
+        mq_notify(mqd, Some(SigevNotify::SigevNone)).unwrap();
+        mq_close(mqd).unwrap();
+    }
+}
@@ -10,3 +10,20 @@ const LOOPBACK: &[u8] = b"lo0";
 fn test_if_nametoindex() {
     assert!(if_nametoindex(&LOOPBACK[..]).is_ok());
 }
+
+#[test]
+fn test_if_indextoname() {
+    let index = if_nametoindex(&LOOPBACK[..]).unwrap();
+    assert_eq!(if_indextoname(index).unwrap().as_bytes(), LOOPBACK);
+    assert!(if_indextoname(0).is_err());
+}
+
+#[test]
+fn test_if_nameindex() {
+    let interfaces = if_nameindex().unwrap();
+    let loopback = interfaces.iter().find(|i| i.name().to_bytes() == LOOPBACK).unwrap();
+    assert_eq!(loopback.index(), if_nametoindex(&LOOPBACK[..]).unwrap());
+    for interface in &interfaces {
+        assert_eq!(if_nametoindex(interface.name()), Ok(interface.index()));
+    }
+}
@@ -0,0 +1,33 @@
+use std::ffi::{CString, OsString};
+use std::path::PathBuf;
+
+use libc::PATH_MAX;
+use nix::{Error, NixPath};
+use nix::errno::Errno;
+use nix::sys::stat::{stat, Mode};
+use nix::fcntl::{open, OFlag};
+
+#[test]
+fn test_nul() {
+    let res = "a\0b".with_nix_path(|_| ());
+    assert_eq!(res, Err(Error::InteriorNul));
+    assert_eq!(Error::InteriorNul.as_errno(), Errno::EINVAL);
+}
+
+#[test]
+fn test_too_long() {
+    // Too long for the buffer on the stack, but passed to the OS anyway
+    let path = vec![b'a'; PATH_MAX as usize * 2];
+    let len = path.with_nix_path(|cstr| cstr.to_bytes().len()).unwrap();
+    assert_eq!(len, path.len());
+    assert_eq!(open(&path, OFlag::O_RDONLY, Mode::empty()), Err(Error::Sys(Errno::ENAMETOOLONG)));
+}
+
+#[test]
+fn test_owned() {
+    assert!(stat(&CString::new("/").unwrap()).is_ok());
+    assert!(stat(&String::from("/")).is_ok());
+    assert!(stat(&OsString::from("/")).is_ok());
+    assert!(stat(&PathBuf::from("/")).is_ok());
+    assert!(stat(&b"/".to_vec()).is_ok());
+}
@@ -21,13 +21,49 @@ fn test_poll() {
     assert!(fds[0].revents().unwrap().contains(EventFlags::POLLIN));
 }
 
+#[test]
+fn test_pollfd_set_events() {
+    let (r, w) = pipe().unwrap();
+    let mut fds = [PollFd::new(r, EventFlags::POLLIN)];
+    assert_eq!(fds[0].fd(), r);
+    assert_eq!(fds[0].events(), EventFlags::POLLIN);
+
+    write(w, b".").unwrap();
+    assert_eq!(poll(&mut fds, 100).unwrap(), 1);
+
+    // A pipe's read end never becomes writable, so the data is now ignored
+    fds[0].set_events(EventFlags::POLLOUT);
+    assert_eq!(fds[0].events(), EventFlags::POLLOUT);
+    assert_eq!(fds[0].revents(), Some(EventFlags::empty()));
+    assert_eq!(poll(&mut fds, 0).unwrap(), 0);
+}
+
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "linux"))]
+#[test]
+fn test_pollrdhup() {
+    use nix::sys::socket::{shutdown, socketpair, AddressFamily, Shutdown, SockFlag, SockType};
+
+    let (fd1, fd2) = socketpair(AddressFamily::Unix, SockType::Stream, None, SockFlag::empty())
+        .unwrap();
+    let mut fds = [PollFd::new(fd1, EventFlags::POLLRDHUP)];
+    assert_eq!(poll(&mut fds, 0).unwrap(), 0);
+
+    shutdown(fd2, Shutdown::Write).unwrap();
+    assert_eq!(poll(&mut fds, 100).unwrap(), 1);
+    assert!(fds[0].revents().unwrap().contains(EventFlags::POLLRDHUP));
+}
+
 // ppoll(2) is the same as poll except for how it handles timeouts and signals.
 // Repeating the test for poll(2) should be sufficient to check that our
 // bindings are correct.
 #[cfg(any(target_os = "android",
           target_os = "dragonfly",
           target_os = "freebsd",
-          target_os = "linux"))]
+          target_os = "linux",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
 #[test]
 fn test_ppoll() {
     use nix::poll::ppoll;
@@ -36,14 +72,14 @@ fn test_ppoll() {
     let mut fds = [PollFd::new(r, EventFlags::POLLIN)];
 
     // Poll an idle pipe.  Should timeout
-    let nfds = ppoll(&mut fds, timeout, SigSet::empty()).unwrap();
+    let nfds = ppoll(&mut fds, Some(timeout), SigSet::empty()).unwrap();
     assert_eq!(nfds, 0);
     assert!(!fds[0].revents().unwrap().contains(EventFlags::POLLIN));
 
     write(w, b".").unwrap();
 
-    // Poll a readable pipe.  Should return an event.
-    let nfds = ppoll(&mut fds, timeout, SigSet::empty()).unwrap();
+    // Poll a readable pipe.  Should return an event, even without a timeout.
+    let nfds = ppoll(&mut fds, None, SigSet::empty()).unwrap();
     assert_eq!(nfds, 1);
     assert!(fds[0].revents().unwrap().contains(EventFlags::POLLIN));
 }
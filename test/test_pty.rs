@@ -207,3 +207,64 @@ fn test_openpty_with_termios() {
     close(pty.master).unwrap();
     close(pty.slave).unwrap();
 }
+
+#[test]
+fn test_forkpty() {
+    use nix::unistd::ForkResult::*;
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use libc::_exit;
+
+    // forkpty calls openpty which uses ptname(3) internally.
+    #[allow(unused_variables)]
+    let m0 = ::PTSNAME_MTX.lock().expect("Mutex got poisoned by another test");
+    #[allow(unused_variables)]
+    let m1 = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let string = "naninani\n";
+    // Output processing on the slave turns newlines into CRLF
+    let translated_string = "naninani\r\n";
+    let pty = forkpty(None, None).unwrap();
+    match pty.fork_result {
+        // Safe: Child only calls `write` and `_exit`, which are signal-safe
+        Child => {
+            write(1, string.as_bytes()).unwrap();
+            unsafe { _exit(0) };
+        },
+        Parent { child } => {
+            let mut buf = [0u8; 10];
+            let child_raw: ::libc::pid_t = child.into();
+            assert!(child_raw > 0);
+            ::read_exact(pty.master, &mut buf);
+            assert_eq!(waitpid(child, None).unwrap(), WaitStatus::Exited(child, 0));
+            assert_eq!(&buf, translated_string.as_bytes());
+            close(pty.master).unwrap();
+        },
+    }
+}
+
+/// Test the whole allocation sequence performed by `PtyMaster::open`
+#[test]
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd"))]
+fn test_ptymaster_open() {
+    let master = PtyMaster::open(OFlag::O_RDWR | OFlag::O_NOCTTY).unwrap();
+    assert!(master.as_raw_fd() > 0);
+
+    let slave_name = master.slave_name().unwrap();
+    assert!(slave_name.starts_with("/dev"));
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    assert_eq!(slave_name.to_str().unwrap(), ptsname_r(&master).unwrap());
+
+    let slave = master.open_slave(OFlag::O_RDWR | OFlag::O_NOCTTY).unwrap();
+    let string = "foofoofoo\n";
+    let mut buf = [0u8; 10];
+    write(master.as_raw_fd(), string.as_bytes()).unwrap();
+    ::read_exact(slave, &mut buf);
+    assert_eq!(&buf, string.as_bytes());
+
+    close(slave).unwrap();
+}
@@ -0,0 +1,15 @@
+use nix::sched::*;
+use nix::sys::time::{TimeSpec, TimeValLike};
+use nix::unistd::Pid;
+
+#[test]
+fn test_sched_yield() {
+    sched_yield().expect("sched_yield failed");
+}
+
+#[test]
+fn test_sched_rr_get_interval() {
+    let interval = sched_rr_get_interval(Pid::from_raw(0))
+        .expect("sched_rr_get_interval failed");
+    assert!(interval >= TimeSpec::zero());
+}
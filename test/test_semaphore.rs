@@ -0,0 +1,119 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::fcntl::OFlag;
+use nix::semaphore::*;
+use nix::sys::stat::Mode;
+use nix::sys::wait::{waitpid, WaitStatus};
+use nix::unistd::{fork, getpid, ForkResult};
+use libc::_exit;
+
+fn name(test: &str) -> String {
+    format!("/nix-test-{}-{}", test, getpid())
+}
+
+#[test]
+fn test_named_semaphore() {
+    let name = name("named");
+    let mode = Mode::S_IRUSR | Mode::S_IWUSR;
+    let mut sem = NamedSemaphore::create(&name[..], mode, 1).unwrap();
+    sem.unlink_on_drop();
+    assert_eq!(NamedSemaphore::create(&name[..], mode, 1).err(), Some(Error::Sys(Errno::EEXIST)));
+
+    // Another handle refers to the same semaphore
+    let other = NamedSemaphore::open(&name[..], OFlag::empty(), Mode::empty(), 0).unwrap();
+    other.try_wait().unwrap();
+    assert_eq!(sem.try_wait(), Err(Error::Sys(Errno::EAGAIN)));
+    sem.post().unwrap();
+    #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+    assert_eq!(other.value(), Ok(1));
+
+    drop(sem);
+    assert_eq!(NamedSemaphore::open(&name[..], OFlag::empty(), Mode::empty(), 0).err(),
+               Some(Error::Sys(Errno::ENOENT)));
+    // The semaphore lives on while it's open
+    other.try_wait().unwrap();
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+#[test]
+fn test_named_semaphore_timed_wait() {
+    use nix::sys::time::{clock_gettime, ClockId, TimeValLike, TimeSpec};
+
+    let name = name("timed");
+    let sem = NamedSemaphore::create(&name[..], Mode::S_IRUSR | Mode::S_IWUSR, 0).unwrap();
+    sem_unlink(&name[..]).unwrap();
+
+    let deadline = || clock_gettime(ClockId::CLOCK_REALTIME).unwrap() + TimeSpec::milliseconds(10);
+    assert_eq!(sem.timed_wait(&deadline()), Err(Error::Sys(Errno::ETIMEDOUT)));
+    sem.post().unwrap();
+    sem.timed_wait(&deadline()).unwrap();
+}
+
+#[test]
+fn test_named_semaphore_fork() {
+    #[allow(unused_variables)]
+    let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let name = name("fork");
+    let mut sem = NamedSemaphore::create(&name[..], Mode::S_IRUSR | Mode::S_IWUSR, 0).unwrap();
+    sem.unlink_on_drop();
+
+    match fork().unwrap() {
+        ForkResult::Child => {
+            let ok = NamedSemaphore::open(&name[..], OFlag::empty(), Mode::empty(), 0)
+                .and_then(|sem| sem.post())
+                .is_ok();
+            unsafe { _exit(if ok { 0 } else { 1 }) };
+        }
+        ForkResult::Parent { child } => {
+            sem.wait().unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+#[test]
+fn test_unnamed_semaphore() {
+    use nix::sys::mman::*;
+    use std::ptr;
+
+    let len = 2 * UnnamedSemaphore::size();
+    let region = unsafe {
+        mmap(ptr::null_mut(), len, ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+             MapFlags::MAP_SHARED | MapFlags::MAP_ANONYMOUS, -1, 0).unwrap()
+    };
+    let misaligned = unsafe { (region as *mut u8).offset(1) as *mut _ };
+    assert_eq!(unsafe { UnnamedSemaphore::init(misaligned, true, 0) }.err(),
+               Some(Error::Sys(Errno::EINVAL)));
+
+    // Used as a mutex between two processes
+    let sem = unsafe { UnnamedSemaphore::init(region, true, 1) }.unwrap();
+    assert_eq!(sem.value(), Ok(1));
+
+    {
+        #[allow(unused_variables)]
+        let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+        sem.wait().unwrap();
+        match fork().unwrap() {
+            ForkResult::Child => {
+                let sem = unsafe { UnnamedSemaphore::from_ptr(region) };
+                // Blocks until the parent releases the lock
+                let ok = sem.wait().is_ok() && sem.post().is_ok();
+                unsafe { _exit(if ok { 0 } else { 1 }) };
+            }
+            ForkResult::Parent { child } => {
+                assert_eq!(sem.try_wait(), Err(Error::Sys(Errno::EAGAIN)));
+                sem.post().unwrap();
+                assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+            }
+        }
+    }
+    assert_eq!(sem.value(), Ok(1));
+
+    unsafe {
+        sem.destroy().unwrap();
+        munmap(region, len).unwrap();
+    }
+}
@@ -0,0 +1,76 @@
+extern crate nix;
+#[macro_use]
+extern crate serde;
+extern crate serde_json;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use nix::errno::Errno;
+use nix::sys::signal::Signal;
+use nix::sys::socket::{InetAddr, SockAddr, UnixAddr};
+use nix::sys::stat::{stat, FileStat, Mode};
+use nix::sys::time::{TimeSpec, TimeValLike};
+use nix::sys::wait::WaitStatus;
+use nix::unistd::Pid;
+
+fn round_trip<T: Serialize + DeserializeOwned>(value: &T) -> T {
+    serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+}
+
+#[test]
+fn test_enums() {
+    assert_eq!(round_trip(&Signal::SIGTERM), Signal::SIGTERM);
+    assert_eq!(round_trip(&Errno::ENOENT), Errno::ENOENT);
+
+    let status = WaitStatus::Signaled(Pid::from_raw(42), Signal::SIGKILL, true);
+    assert_eq!(round_trip(&status), status);
+}
+
+#[test]
+fn test_bitflags() {
+    let mode = Mode::S_IRUSR | Mode::S_IWUSR;
+    assert_eq!(round_trip(&mode), mode);
+}
+
+#[test]
+fn test_time() {
+    assert_eq!(serde_json::to_string(&TimeSpec::milliseconds(1500)).unwrap(),
+               r#"{"tv_sec":1,"tv_nsec":500000000}"#);
+
+    let ts = TimeSpec::nanoseconds(-1_500_000_000);
+    assert_eq!(round_trip(&ts), ts);
+}
+
+#[test]
+fn test_sockaddr() {
+    let inet = SockAddr::new_inet(InetAddr::from_std(&"127.0.0.1:80".parse().unwrap()));
+    assert_eq!(serde_json::to_string(&inet).unwrap(), r#"{"Inet":"127.0.0.1:80"}"#);
+    assert_eq!(round_trip(&inet), inet);
+
+    let unix = SockAddr::Unix(UnixAddr::new("/tmp/sock").unwrap());
+    assert_eq!(round_trip(&unix), unix);
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_abstract_sockaddr() {
+    let unix = SockAddr::Unix(UnixAddr::new_abstract(b"nix").unwrap());
+    assert_eq!(round_trip(&unix), unix);
+}
+
+#[derive(Serialize, Deserialize)]
+struct Stat {
+    #[serde(with = "nix::sys::stat::serde_file_stat")]
+    stat: FileStat,
+}
+
+#[test]
+fn test_file_stat() {
+    let st = stat("/").unwrap();
+    let copy = round_trip(&Stat { stat: st }).stat;
+    assert_eq!(copy.st_ino, st.st_ino);
+    assert_eq!(copy.st_mode, st.st_mode);
+    assert_eq!(copy.st_mtime, st.st_mtime);
+    assert_eq!(copy.st_mtime_nsec, st.st_mtime_nsec);
+}
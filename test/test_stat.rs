@@ -1,11 +1,28 @@
-use std::fs::File;
+use std::fs::{self, File};
 use std::os::unix::fs::symlink;
 use std::os::unix::prelude::AsRawFd;
+use std::time::{Duration, UNIX_EPOCH};
 
 use libc::{S_IFMT, S_IFLNK};
 
 use nix::fcntl;
-use nix::sys::stat::{self, stat, fstat, lstat};
+use nix::sys::stat::{self, stat, fstat, lstat, utimes};
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+use nix::sys::stat::futimes;
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd"))]
+use nix::sys::stat::lutimes;
+use nix::sys::time::{TimeVal, TimeValLike};
 use nix::sys::stat::FileStat;
 use nix::Result;
 use tempdir::TempDir;
@@ -102,3 +119,87 @@ fn test_stat_fstat_lstat() {
     let fstat_result = fstat(link.as_raw_fd());
     assert_stat_results(fstat_result);
 }
+
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+#[test]
+fn test_chflags() {
+    use nix::sys::stat::{chflags, fchflags, FileFlag};
+
+    let tempdir = TempDir::new("nix-test_chflags").unwrap();
+    let filename = tempdir.path().join("foo.txt");
+    let file = File::create(&filename).unwrap();
+
+    // UF_NODUMP is settable by the file owner on every platform
+    chflags(&filename, FileFlag::UF_NODUMP).unwrap();
+    let flags = FileFlag::from_bits_truncate(stat(&filename).unwrap().st_flags as _);
+    assert!(flags.contains(FileFlag::UF_NODUMP));
+
+    fchflags(file.as_raw_fd(), FileFlag::empty()).unwrap();
+    let flags = FileFlag::from_bits_truncate(fstat(file.as_raw_fd()).unwrap().st_flags as _);
+    assert!(!flags.contains(FileFlag::UF_NODUMP));
+}
+
+fn assert_times_eq(exp_atime_sec: u64, exp_mtime_sec: u64, attr: &fs::Metadata) {
+    assert_eq!(
+        Duration::new(exp_atime_sec, 0),
+        attr.accessed().unwrap().duration_since(UNIX_EPOCH).unwrap());
+    assert_eq!(
+        Duration::new(exp_mtime_sec, 0),
+        attr.modified().unwrap().duration_since(UNIX_EPOCH).unwrap());
+}
+
+#[test]
+fn test_utimes() {
+    let tempdir = TempDir::new("nix-test_utimes").unwrap();
+    let fullpath = tempdir.path().join("file");
+    drop(File::create(&fullpath).unwrap());
+
+    utimes(&fullpath, &TimeVal::seconds(9990), &TimeVal::seconds(5550)).unwrap();
+    assert_times_eq(9990, 5550, &fs::metadata(&fullpath).unwrap());
+}
+
+#[test]
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+fn test_futimes() {
+    let tempdir = TempDir::new("nix-test_futimes").unwrap();
+    let fullpath = tempdir.path().join("file");
+    let file = File::create(&fullpath).unwrap();
+
+    futimes(file.as_raw_fd(), &TimeVal::seconds(10), &TimeVal::seconds(20)).unwrap();
+    assert_times_eq(10, 20, &fs::metadata(&fullpath).unwrap());
+}
+
+#[test]
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd"))]
+fn test_lutimes() {
+    let tempdir = TempDir::new("nix-test_lutimes").unwrap();
+    let target = tempdir.path().join("target");
+    let fullpath = tempdir.path().join("symlink");
+    drop(File::create(&target).unwrap());
+    symlink(&target, &fullpath).unwrap();
+
+    let exp_target_metadata = fs::symlink_metadata(&target).unwrap();
+    lutimes(&fullpath, &TimeVal::seconds(4560), &TimeVal::seconds(1230)).unwrap();
+    assert_times_eq(4560, 1230, &fs::symlink_metadata(&fullpath).unwrap());
+
+    // The target of the link must be untouched
+    let target_metadata = fs::symlink_metadata(&target).unwrap();
+    assert_eq!(exp_target_metadata.accessed().unwrap(), target_metadata.accessed().unwrap());
+    assert_eq!(exp_target_metadata.modified().unwrap(), target_metadata.modified().unwrap());
+}
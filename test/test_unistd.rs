@@ -1,5 +1,7 @@
 extern crate tempdir;
 
+use nix::Error;
+use nix::errno::Errno;
 use nix::fcntl::{fcntl, FcntlArg, FdFlag, OFlag};
 use nix::unistd::*;
 use nix::unistd::ForkResult::*;
@@ -287,6 +289,68 @@ fn test_fchdir() {
     assert!(close(tmpdir_fd).is_ok());
 }
 
+#[test]
+fn test_isatty_ttyname() {
+    use nix::Error;
+    use nix::errno::Errno;
+    use nix::pty::openpty;
+
+    // openpty uses ptname(3) internally
+    #[allow(unused_variables)]
+    let m = ::PTSNAME_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let file = tempfile().unwrap();
+    assert_eq!(isatty(file.as_raw_fd()), Ok(false));
+    assert_eq!(ttyname(file.as_raw_fd()), Err(Error::Sys(Errno::ENOTTY)));
+
+    let pty = openpty(None, None).unwrap();
+    assert_eq!(isatty(pty.slave), Ok(true));
+    let name = ttyname(pty.slave).unwrap();
+    assert!(name.to_str().unwrap().starts_with("/dev/"));
+    close(pty.master).unwrap();
+    close(pty.slave).unwrap();
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_vhangup() {
+    use nix::Error;
+    use nix::errno::Errno;
+    use nix::pty::openpty;
+    use nix::sys::signal::{sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal};
+    use nix::sys::termios::{tcgetsid, tcsetctty};
+
+    #[allow(unused_variables)]
+    let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+    // openpty uses ptname(3) internally
+    #[allow(unused_variables)]
+    let m2 = ::PTSNAME_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let pty = openpty(None, None).unwrap();
+    match fork().unwrap() {
+        Child => {
+            // The hangup is delivered to our own process group too
+            let ign = SigAction::new(SigHandler::SigIgn, SaFlags::empty(), SigSet::empty());
+            let ok = unsafe { sigaction(Signal::SIGHUP, &ign) }.is_ok() &&
+                setsid().is_ok() &&
+                tcsetctty(pty.slave, false).is_ok() &&
+                tcgetsid(pty.slave) == Ok(getpid()) &&
+                match vhangup() {
+                    // Our descriptor for the terminal must be useless afterwards
+                    Ok(()) => write(pty.slave, b"x") == Err(Error::Sys(Errno::EIO)),
+                    // Unprivileged users aren't allowed to hang up terminals
+                    Err(e) => e == Error::Sys(Errno::EPERM),
+                };
+            unsafe { _exit(if ok { 0 } else { 1 }) };
+        },
+        Parent { child } => {
+            assert_eq!(waitpid(child, None).unwrap(), WaitStatus::Exited(child, 0));
+            close(pty.master).unwrap();
+            close(pty.slave).unwrap();
+        },
+    }
+}
+
 #[test]
 fn test_getcwd() {
     // chdir changes the process's cwd
@@ -400,3 +464,117 @@ fn test_pipe2() {
     let f1 = FdFlag::from_bits_truncate(fcntl(fd1, FcntlArg::F_GETFD).unwrap());
     assert!(f1.contains(FdFlag::FD_CLOEXEC));
 }
+
+#[test]
+fn test_fd() {
+    let (reader, writer) = pipe().unwrap();
+    let writer = unsafe { Fd::from_raw_fd(writer) };
+
+    let clone = writer.try_clone().unwrap();
+    assert!(clone.as_raw_fd() != writer.as_raw_fd());
+    let flags = FdFlag::from_bits_truncate(fcntl(clone.as_raw_fd(), FcntlArg::F_GETFD).unwrap());
+    assert!(flags.contains(FdFlag::FD_CLOEXEC));
+
+    // Dropping one copy of the write end leaves the pipe open through the other
+    drop(writer);
+    assert_eq!(write(clone.as_raw_fd(), b"x"), Ok(1));
+    // Closing the last copy closes the pipe, so the reader then sees end of file
+    let fd = clone.into_raw_fd();
+    close(fd).unwrap();
+    let mut buf = [0u8; 2];
+    assert_eq!(read(reader, &mut buf), Ok(1));
+    assert_eq!(read(reader, &mut buf), Ok(0));
+    close(reader).unwrap();
+}
+
+#[test]
+fn test_pipe_struct() {
+    let pipe = Pipe::new(OFlag::O_CLOEXEC | OFlag::O_NONBLOCK).unwrap();
+    for fd in &[pipe.read.as_raw_fd(), pipe.write.as_raw_fd()] {
+        let fdflags = FdFlag::from_bits_truncate(fcntl(*fd, FcntlArg::F_GETFD).unwrap());
+        assert!(fdflags.contains(FdFlag::FD_CLOEXEC));
+        let flags = OFlag::from_bits_truncate(fcntl(*fd, FcntlArg::F_GETFL).unwrap());
+        assert!(flags.contains(OFlag::O_NONBLOCK));
+    }
+
+    let mut buf = [0u8; 8];
+    assert_eq!(pipe.read(&mut buf), Err(Error::Sys(Errno::EAGAIN)));
+    assert_eq!(pipe.write(b"abc"), Ok(3));
+    assert_eq!(pipe.read(&mut buf), Ok(3));
+    assert_eq!(&buf[..3], b"abc");
+
+    // Without its write end, the pipe reads as empty
+    let Pipe { read, write } = pipe;
+    drop(write);
+    assert_eq!(::nix::unistd::read(read.as_raw_fd(), &mut buf), Ok(0));
+}
+
+#[cfg(target_os = "openbsd")]
+mod openbsd {
+    use libc::_exit;
+    use nix::Error;
+    use nix::errno::Errno;
+    use nix::fcntl::{open, OFlag};
+    use nix::sys::signal::Signal;
+    use nix::sys::stat::Mode;
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::*;
+    use nix::unistd::ForkResult::*;
+
+    #[test]
+    fn test_pledge() {
+        #[allow(unused_variables)]
+        let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+        // Safe: Child only calls `pledge`, `open` and `_exit`
+        match fork().unwrap() {
+            Child => {
+                pledge(Some(Promises::STDIO | Promises::ERROR), None).unwrap();
+                // Without rpath, opening a file is forbidden
+                let res = open("/etc/passwd", OFlag::O_RDONLY, Mode::empty());
+                unsafe { _exit((res == Err(Error::Sys(Errno::ENOSYS))) as i32) }
+            },
+            Parent { child } => {
+                assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 1)));
+            },
+        }
+
+        match fork().unwrap() {
+            Child => {
+                pledge(Some(Promises::STDIO), None).unwrap();
+                let _ = open("/etc/passwd", OFlag::O_RDONLY, Mode::empty());
+                unsafe { _exit(0) }
+            },
+            Parent { child } => {
+                match waitpid(child, None) {
+                    // Whether a core is dumped depends on the resource limits
+                    Ok(WaitStatus::Signaled(pid, Signal::SIGABRT, _)) => assert_eq!(pid, child),
+                    status => panic!("Child wasn't killed by SIGABRT: {:?}", status),
+                }
+            },
+        }
+    }
+
+    #[test]
+    fn test_unveil() {
+        #[allow(unused_variables)]
+        let m = ::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+        // Safe: Child only calls `unveil`, `open` and `_exit`
+        match fork().unwrap() {
+            Child => {
+                unveil("/etc", UnveilPermissions::READ).unwrap();
+                unveil_lock().unwrap();
+                let visible = open("/etc/passwd", OFlag::O_RDONLY, Mode::empty()).is_ok();
+                let hidden = open("/bin/sh", OFlag::O_RDONLY, Mode::empty())
+                    == Err(Error::Sys(Errno::ENOENT));
+                let locked = unveil("/bin", UnveilPermissions::READ)
+                    == Err(Error::Sys(Errno::EPERM));
+                unsafe { _exit((visible && hidden && locked) as i32) }
+            },
+            Parent { child } => {
+                assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 1)));
+            },
+        }
+    }
+}